@@ -1,11 +1,22 @@
 pub mod adjust;
+pub mod archive;
 pub mod begin;
 pub mod docs;
 pub mod end;
+pub mod export;
+pub mod history;
 pub mod hold;
+pub mod import;
+pub mod inbox;
+pub mod invoice;
+pub mod migrate;
 pub mod now;
+pub mod pomo;
 pub mod reflect;
 pub mod resume;
+pub mod sync;
+pub mod tag;
+pub mod tasks;
 
 use getset::Getters;
 use pace_time::date_time::PaceDateTime;
@@ -14,7 +14,7 @@ use crate::{
     config::PaceConfig,
     error::{ActivityLogErrorKind, PaceResult, UserMessage},
     service::activity_store::ActivityStore,
-    storage::{get_storage_from_config, ActivityQuerying, ActivityWriteOps, SyncStorage},
+    storage::{ActivityQuerying, ActivityWriteOps, SyncStorage},
 };
 
 /// `adjust` subcommand options
@@ -160,7 +160,7 @@ impl AdjustCommandOptions {
 
         debug!("Parsed time: {date_time:?}");
 
-        let activity_store = ActivityStore::with_storage(get_storage_from_config(config)?)?;
+        let activity_store = ActivityStore::from_config(config)?;
 
         let activity_item = activity_store
             .most_recent_active_activity()?
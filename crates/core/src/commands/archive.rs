@@ -0,0 +1,111 @@
+#[cfg(feature = "clap")]
+use clap::Parser;
+use getset::Getters;
+use tracing::debug;
+
+use crate::{
+    config::PaceConfig,
+    error::{PaceResult, UserMessage},
+    service::{activity_archive::ActivityArchive, activity_store::ActivityStore},
+    storage::SyncStorage,
+};
+
+/// `archive` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters, Default)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct ArchiveCommandOptions {
+    /// Archive activities that have ended this many days ago
+    ///
+    /// Defaults to `archive-after-days` in the `auto-archival` section of the configuration.
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Days"))]
+    days: Option<u32>,
+}
+
+impl ArchiveCommandOptions {
+    /// Handles the `archive` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities could not be archived
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the amount of archived activities
+    #[tracing::instrument(skip(self))]
+    pub fn handle_archive(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let days = self.days.unwrap_or_else(|| {
+            *config
+                .auto_archival()
+                .clone()
+                .unwrap_or_default()
+                .archive_after_days()
+        });
+
+        let archive = ActivityArchive::from_config(config);
+
+        let count = archive_activities(config, &archive, days)?;
+
+        if count == 0 {
+            return Ok(UserMessage::new("No activities to archive."));
+        }
+
+        Ok(UserMessage::new(format!(
+            "Archived {count} activities to {}.",
+            archive.path().display()
+        )))
+    }
+
+    /// Archive activities automatically, if it is enabled in the configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities could not be archived
+    ///
+    /// # Returns
+    ///
+    /// Returns the amount of archived activities, `None` if auto-archival is disabled
+    #[tracing::instrument]
+    pub fn handle_auto_archive(config: &PaceConfig) -> PaceResult<Option<usize>> {
+        let Some(auto_archival) = config
+            .auto_archival()
+            .as_ref()
+            .filter(|auto_archival| *auto_archival.enabled())
+        else {
+            debug!("Auto-archival is disabled");
+
+            return Ok(None);
+        };
+
+        let archive = ActivityArchive::from_config(config);
+
+        archive_activities(config, &archive, *auto_archival.archive_after_days()).map(Some)
+    }
+}
+
+/// Archive the activities that have ended a number of days ago and sync the storage
+fn archive_activities(
+    config: &PaceConfig,
+    archive: &ActivityArchive,
+    days: u32,
+) -> PaceResult<usize> {
+    let activity_store = ActivityStore::from_config(config)?;
+
+    let count = archive.archive_activities(&activity_store, days)?;
+
+    if count > 0 {
+        activity_store.sync()?;
+    }
+
+    debug!("Archived {count} activities");
+
+    Ok(count)
+}
@@ -11,9 +11,9 @@ use tracing::debug;
 use crate::{
     config::PaceConfig,
     domain::activity::{Activity, ActivityKind},
-    error::{PaceResult, UserMessage},
-    service::activity_store::ActivityStore,
-    storage::{get_storage_from_config, ActivityStateManagement, SyncStorage},
+    error::{PaceErrorKind, PaceResult, UserMessage},
+    service::{activity_store::ActivityStore, project_store::ProjectStore, task_store::TaskStore},
+    storage::{ActivityStateManagement, SyncStorage},
 };
 
 /// `begin` subcommand options
@@ -38,8 +38,17 @@ pub struct BeginCommandOptions {
     at: Option<NaiveTime>,
 
     /// The description of the activity you want to start
-    #[cfg_attr(feature = "clap", clap(value_name = "Activity Description"))]
-    description: String,
+    ///
+    /// Defaults to the title of the task, if the activity is tracked for a task.
+    #[cfg_attr(
+        feature = "clap",
+        clap(value_name = "Activity Description", required_unless_present = "task")
+    )]
+    description: Option<String>,
+
+    /// The id of the task you want to track time for, or the beginning of it
+    #[cfg_attr(feature = "clap", clap(long, value_name = "Task Id"))]
+    task: Option<String>,
 
     /// The tags you want to associate with the activity, separated by a comma
     #[cfg_attr(
@@ -54,10 +63,15 @@ pub struct BeginCommandOptions {
     )]
     tags: Option<Vec<String>>,
 
-    /// TODO: The project you want to start tracking time for
-    /// FIXME: involves parsing the project configuration first
-    #[cfg_attr(feature = "clap", clap(skip))]
-    _projects: Option<Vec<String>>,
+    /// The name or id of the project you want to track time for
+    ///
+    /// Defaults to the project of the current directory, if there is a
+    /// `projects.pace.toml` file in it or in one of its parent directories.
+    #[cfg_attr(
+        feature = "clap",
+        clap(short, long, value_name = "Project", visible_alias = "proj")
+    )]
+    project: Option<String>,
 
     /// Time zone to use for the activity, e.g., "Europe/Amsterdam"
     #[cfg_attr(
@@ -108,9 +122,10 @@ impl BeginCommandOptions {
             at,
             description,
             tags,
+            task,
+            project,
             time_zone,
             time_zone_offset,
-            .. // TODO: exclude projects for now
         } = self;
 
         let date_time = PaceDateTime::try_from((
@@ -129,17 +144,69 @@ impl BeginCommandOptions {
 
         debug!("Parsed tags: {tags:?}");
 
+        let task = task
+            .as_ref()
+            .map(|task_id| -> PaceResult<_> {
+                let task_store = TaskStore::from_config(config)?;
+
+                let task_id = task_store.task_list().find_id(task_id)?;
+
+                let title = task_store
+                    .task_list()
+                    .get(&task_id)
+                    .map(|task| task.title().clone())
+                    .unwrap_or_default();
+
+                Ok((task_id, title))
+            })
+            .transpose()?;
+
+        debug!("Parsed task: {task:?}");
+
+        let description = description
+            .clone()
+            .or_else(|| task.as_ref().map(|(_, title)| title.clone()))
+            .ok_or(PaceErrorKind::NoActivityDescription)?;
+
+        let project_store = ProjectStore::discover(std::env::current_dir()?)?;
+
+        let projects = match (&project_store, project) {
+            (Some(project_store), Some(project)) => Some(
+                project_store
+                    .project_list()
+                    .parent_chain(project_store.project_list().find_id(project)?),
+            ),
+            (Some(project_store), None) => project_store
+                .project_for_directory(std::env::current_dir()?)
+                .map(|project_id| project_store.project_list().parent_chain(project_id)),
+            (None, Some(project)) => {
+                return Err(PaceErrorKind::ProjectNotFound(project.clone()).into())
+            }
+            (None, None) => None,
+        }
+        .filter(|projects| !projects.is_empty());
+
+        debug!("Parsed projects: {projects:?}");
+
         // TODO: Parse categories and subcategories from string
+        let category = category.clone().or_else(|| {
+            project_store
+                .as_ref()
+                .and_then(|project_store| project_store.project_list().default_category())
+                .map(ToString::to_string)
+        });
 
         let activity = Activity::builder()
-            .description(description.clone())
+            .description(description)
             .begin(date_time)
             .kind(ActivityKind::default())
-            .category(category.clone())
+            .category(category)
             .tags(tags)
+            .task_id(task.map(|(task_id, _)| task_id))
+            .projects(projects)
             .build();
 
-        let activity_store = ActivityStore::with_storage(get_storage_from_config(config)?)?;
+        let activity_store = ActivityStore::from_config(config)?;
 
         let activity_item = activity_store.begin_activity(activity)?;
 
@@ -12,7 +12,7 @@ use crate::{
     config::PaceConfig,
     error::{PaceResult, UserMessage},
     service::activity_store::ActivityStore,
-    storage::{get_storage_from_config, ActivityStateManagement, SyncStorage},
+    storage::{ActivityStateManagement, SyncStorage},
 };
 
 /// `end` subcommand options
@@ -91,7 +91,7 @@ impl EndCommandOptions {
 
         debug!("Parsed date time: {:?}", date_time);
 
-        let activity_store = ActivityStore::with_storage(get_storage_from_config(config)?)?;
+        let activity_store = ActivityStore::from_config(config)?;
 
         let end_opts = EndOptions::builder().end_time(date_time).build();
 
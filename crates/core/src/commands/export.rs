@@ -0,0 +1,154 @@
+#[cfg(feature = "clap")]
+use clap::Parser;
+use getset::Getters;
+use pace_time::{
+    flags::{DateFlags, TimeFlags},
+    time_frame::PaceTimeFrame,
+    time_range::TimeRangeOptions,
+    time_zone::PaceTimeZoneKind,
+};
+use std::path::PathBuf;
+use tracing::debug;
+
+use crate::{
+    config::PaceConfig,
+    domain::{
+        activity::ActivityKind,
+        export::{ActivityExport, ExportFormatKind},
+        filter::FilterOptions,
+    },
+    error::{PaceResult, UserMessage},
+    service::activity_store::ActivityStore,
+    storage::get_storage_from_config,
+};
+
+/// `export` subcommand options
+#[derive(Debug, Getters)]
+#[getset(get = "pub")]
+#[cfg_attr(feature = "clap", derive(Parser))]
+pub struct ExportCommandOptions {
+    /// Filter by activity kind (e.g., activity, intermission)
+    #[cfg_attr(
+        feature = "clap",
+        clap(short, long, value_name = "Activity Kind", visible_alias = "kind")
+    )]
+    activity_kind: Option<ActivityKind>,
+
+    /// Filter by category name, wildcard supported
+    #[cfg_attr(
+        feature = "clap",
+        clap(short, long, value_name = "Category", visible_alias = "cat")
+    )]
+    category: Option<String>,
+
+    /// Case sensitive category filter
+    #[cfg_attr(
+        feature = "clap",
+        clap(short = 'i', long, value_name = "Case Sensitive")
+    )]
+    case_sensitive: bool,
+
+    /// Specify output format for the export
+    #[cfg_attr(
+        feature = "clap",
+        clap(short, long, value_name = "Output Format", visible_alias = "format")
+    )]
+    output_format: Option<ExportFormatKind>,
+
+    /// Write the export to a specified file instead of the standard output
+    #[cfg_attr(
+        feature = "clap",
+        clap(short, long, value_name = "Export File", visible_alias = "export")
+    )]
+    export_file: Option<PathBuf>,
+
+    /// Time flags
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            rename_all = "kebab-case",
+            value_name = "Time Flags",
+            next_help_heading = "Flags for specifying time periods"
+        )
+    )]
+    time_flags: Option<TimeFlags>,
+
+    /// Date flags
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            flatten,
+            next_help_heading = "Date flags for specifying custom date ranges or specific dates"
+        )
+    )]
+    date_flags: Option<DateFlags>,
+}
+
+impl ExportCommandOptions {
+    /// Handles the `export` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the time frame or the configured time format is invalid,
+    /// the activities could not be read or the export could not be written
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the exported activities or the information
+    /// where they have been exported to
+    #[tracing::instrument(skip(self))]
+    pub fn handle_export(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let Self {
+            export_file,
+            time_flags,
+            date_flags,
+            output_format,
+            ..
+        } = self;
+
+        // Validate the time and time zone as early as possible
+        let time_frame = PaceTimeFrame::try_from((
+            time_flags.as_ref(),
+            date_flags.as_ref(),
+            PaceTimeZoneKind::NotSet,
+            PaceTimeZoneKind::from(config.general().default_time_zone().as_ref()),
+        ))?;
+
+        let export_config = config.export().clone().unwrap_or_default();
+
+        let activity_store = ActivityStore::with_storage(get_storage_from_config(config)?)?;
+
+        debug!("Exporting activities for time frame: {}", time_frame);
+
+        let Some(activity_items) = activity_store.list_activity_items_for_time_range(
+            &FilterOptions::from(self),
+            TimeRangeOptions::try_from(time_frame)?,
+        )?
+        else {
+            return Ok(UserMessage::new(
+                "No activities found for the specified time frame",
+            ));
+        };
+
+        let export = ActivityExport::with_config(&activity_items, &export_config)?;
+
+        let rendered = export.render(output_format.unwrap_or_default())?;
+
+        // write to file if export file is specified
+        if let Some(export_file) = export_file {
+            std::fs::write(export_file, rendered)?;
+
+            return Ok(UserMessage::new(format!(
+                "Exported {} activities to {}",
+                export.activities().len(),
+                export_file.display()
+            )));
+        }
+
+        Ok(UserMessage::new(rendered))
+    }
+}
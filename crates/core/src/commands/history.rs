@@ -0,0 +1,204 @@
+use chrono::Local;
+#[cfg(feature = "clap")]
+use clap::Parser;
+use getset::Getters;
+
+use crate::{
+    config::PaceConfig,
+    domain::{activity::ActivityGuid, history::HistoryEntry},
+    error::{PaceErrorKind, PaceResult, UserMessage},
+    service::{activity_history::ActivityHistory, activity_store::ActivityStore},
+    storage::{ActivityQuerying, SyncStorage},
+};
+
+/// `undo` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+pub struct UndoCommandOptions {}
+
+impl UndoCommandOptions {
+    /// Handles the `undo` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an activity has been changed in the meantime
+    /// or the activity log could not be written
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the undone change
+    #[tracing::instrument(skip(self))]
+    pub fn handle_undo(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let activity_store = ActivityStore::from_config(config)?;
+
+        let Some(entry) = activity_store.undo()? else {
+            return Ok(UserMessage::new("Nothing to undo."));
+        };
+
+        activity_store.sync()?;
+
+        Ok(UserMessage::new(format!("Undid {}.", describe(&entry))))
+    }
+}
+
+/// `redo` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+pub struct RedoCommandOptions {}
+
+impl RedoCommandOptions {
+    /// Handles the `redo` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an activity has been changed in the meantime
+    /// or the activity log could not be written
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the redone change
+    #[tracing::instrument(skip(self))]
+    pub fn handle_redo(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let activity_store = ActivityStore::from_config(config)?;
+
+        let Some(entry) = activity_store.redo()? else {
+            return Ok(UserMessage::new("Nothing to redo."));
+        };
+
+        activity_store.sync()?;
+
+        Ok(UserMessage::new(format!("Redid {}.", describe(&entry))))
+    }
+}
+
+/// `log` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct LogCommandOptions {
+    /// Show every change to the activity with this id, or the beginning of it
+    #[cfg_attr(feature = "clap", clap(long, value_name = "Activity Id"))]
+    history: Option<String>,
+
+    /// The number of most recent changes to show, if no activity is given
+    #[cfg_attr(
+        feature = "clap",
+        clap(short, long, value_name = "Count", default_value_t = 10)
+    )]
+    limit: usize,
+}
+
+impl LogCommandOptions {
+    /// Handles the `log` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history or the activity log could not be read
+    /// or the activity could not be found
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the changes, oldest first
+    #[tracing::instrument(skip(self))]
+    pub fn handle_log(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let activity_store = ActivityStore::from_config(config)?;
+
+        let history = activity_store
+            .history()
+            .as_deref()
+            .map(ActivityHistory::history)
+            .ok_or(PaceErrorKind::NoHistory)?;
+
+        let Some(id_or_prefix) = self.history.as_deref() else {
+            let entries = history.entries();
+
+            if entries.is_empty() {
+                return Ok(UserMessage::new("No changes recorded yet."));
+            }
+
+            let lines = entries
+                .iter()
+                .skip(entries.len().saturating_sub(self.limit))
+                .map(|entry| {
+                    format!(
+                        "{}  {:<9}  {}",
+                        timestamp(entry),
+                        entry.operation(),
+                        entry.descriptions().join(", ")
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            return Ok(UserMessage::new(lines.join("\n")));
+        };
+
+        // Deleted activities are only found in the history
+        let mut activity_ids = history.activity_ids();
+        activity_ids.extend(
+            activity_store
+                .list_activities_by_id()?
+                .unwrap_or_default()
+                .into_keys(),
+        );
+
+        let activity_id = ActivityGuid::find_by_prefix(&activity_ids, id_or_prefix)?;
+
+        let changes = history.changes_of(activity_id);
+
+        if changes.is_empty() {
+            return Ok(UserMessage::new(format!(
+                "No changes recorded for activity {activity_id}."
+            )));
+        }
+
+        let mut lines = vec![format!("History of activity {activity_id}:")];
+
+        for (entry, change) in changes {
+            lines.push(format!(
+                "{}  {:<9}  {}",
+                timestamp(entry),
+                entry.operation(),
+                change.summary()?
+            ));
+        }
+
+        Ok(UserMessage::new(lines.join("\n")))
+    }
+}
+
+/// The time of a history entry in the local time zone
+fn timestamp(entry: &HistoryEntry) -> String {
+    entry
+        .at()
+        .and_local_timezone(&Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+/// Describe a history entry for the user, e.g. `the begin of "Coding"`
+fn describe(entry: &HistoryEntry) -> String {
+    let descriptions = entry
+        .descriptions()
+        .iter()
+        .map(|description| format!("\"{description}\""))
+        .collect::<Vec<_>>();
+
+    format!(
+        "the {} of {} from {}",
+        entry.operation(),
+        descriptions.join(", "),
+        timestamp(entry)
+    )
+}
@@ -13,7 +13,7 @@ use crate::{
     domain::intermission::IntermissionAction,
     error::{PaceResult, UserMessage},
     service::activity_store::ActivityStore,
-    storage::{get_storage_from_config, ActivityStateManagement, SyncStorage},
+    storage::{ActivityStateManagement, SyncStorage},
 };
 
 /// `hold` subcommand options
@@ -112,7 +112,7 @@ impl HoldCommandOptions {
 
         debug!("Hold options: {hold_opts:?}");
 
-        let activity_store = ActivityStore::with_storage(get_storage_from_config(config)?)?;
+        let activity_store = ActivityStore::from_config(config)?;
 
         let user_message =
             if let Some(activity) = activity_store.hold_most_recent_active_activity(hold_opts)? {
@@ -0,0 +1,140 @@
+#[cfg(feature = "clap")]
+use clap::Parser;
+use getset::Getters;
+use std::path::PathBuf;
+use tracing::debug;
+
+use crate::{
+    config::PaceConfig,
+    domain::{
+        export::validate_time_format,
+        import::{
+            csv::{CsvColumnMapping, CsvParser},
+            timewarrior::TimewarriorParser,
+            watson::WatsonParser,
+            ActivityParser, ActivityParserKind, ImportFormatKind,
+        },
+    },
+    error::{PaceResult, UserMessage},
+    service::activity_store::ActivityStore,
+    storage::{ActivityQuerying, SyncStorage},
+};
+
+/// `import` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct ImportCommandOptions {
+    /// The format of the file you want to import
+    #[cfg_attr(
+        feature = "clap",
+        clap(short, long, value_name = "Import Format", visible_alias = "from")
+    )]
+    format: ImportFormatKind,
+
+    /// The file you want to import
+    #[cfg_attr(feature = "clap", clap(value_name = "Import File"))]
+    file: PathBuf,
+
+    /// Map the columns of a CSV file onto the fields of an activity, e.g. `begin=Start,end=Stop,description=Task`
+    ///
+    /// The fields are begin, end, description, category, tags, and kind. By default,
+    /// the columns are named like the fields, as in the CSV files of `pace export`.
+    #[cfg_attr(feature = "clap", clap(long, value_name = "Column Mapping"))]
+    columns: Option<CsvColumnMapping>,
+
+    /// The format of date-times without a time zone in a CSV file, e.g. `%Y-%m-%d %H:%M`
+    ///
+    /// Defaults to the time format of exports, date-times in RFC 3339 format are always accepted.
+    #[cfg_attr(feature = "clap", clap(long, value_name = "Time Format"))]
+    time_format: Option<String>,
+
+    /// Only show what would be imported, without changing the activity log
+    #[cfg_attr(feature = "clap", clap(long))]
+    dry_run: bool,
+}
+
+impl ImportCommandOptions {
+    /// Handles the `import` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or parsed, or the imported
+    /// activities can't be stored
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the information about the imported activities
+    /// and the skipped entries that can be displayed to the user
+    #[tracing::instrument(skip(self))]
+    pub fn handle_import(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let content = std::fs::read_to_string(&self.file)?;
+
+        let parsed = self.parser(config)?.parse(&content)?;
+
+        let activity_store = ActivityStore::from_config(config)?;
+
+        let existing_activities = activity_store.list_activities_by_id()?.unwrap_or_default();
+
+        let (activities, skipped) = parsed
+            .without_duplicates(existing_activities.values())
+            .into_parts();
+
+        debug!("Importing activities: {:?}", activities);
+
+        if !self.dry_run {
+            _ = activity_store.import_activities(activities.iter().cloned())?;
+
+            activity_store.sync()?;
+        }
+
+        let mut msgs = vec![format!(
+            "{} {} activities from {}.",
+            if self.dry_run {
+                "Would import"
+            } else {
+                "Imported"
+            },
+            activities.len(),
+            self.file.display()
+        )];
+
+        if !skipped.is_empty() {
+            msgs.push(format!("Skipped {} entries:", skipped.len()));
+
+            for entry in &skipped {
+                msgs.push(format!("  #{}: {}", entry.position(), entry.reason()));
+            }
+        }
+
+        Ok(UserMessage::new(msgs.join("\n")))
+    }
+
+    /// Get the parser for the format of the imported file
+    fn parser(&self, config: &PaceConfig) -> PaceResult<ActivityParserKind> {
+        let parser = match self.format {
+            ImportFormatKind::Timewarrior => TimewarriorParser.into(),
+            ImportFormatKind::Watson => WatsonParser.into(),
+            ImportFormatKind::Csv => {
+                let time_format = self.time_format.clone().unwrap_or_else(|| {
+                    config
+                        .export()
+                        .clone()
+                        .unwrap_or_default()
+                        .time_format()
+                        .clone()
+                });
+
+                validate_time_format(&time_format)?;
+
+                CsvParser::new(self.columns.clone().unwrap_or_default(), time_format).into()
+            }
+        };
+
+        Ok(parser)
+    }
+}
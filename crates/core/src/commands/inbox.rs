@@ -0,0 +1,263 @@
+use std::collections::HashSet;
+
+#[cfg(feature = "clap")]
+use clap::Parser;
+use getset::Getters;
+use pace_time::{date_time::PaceDateTime, time_zone::PaceTimeZoneKind, Validate};
+use tabled::{
+    builder::Builder,
+    settings::{Padding, Style},
+};
+use tracing::debug;
+
+use crate::{
+    config::PaceConfig,
+    domain::{
+        activity::{Activity, ActivityKind},
+        priority::ItemPriorityKind,
+        task::Task,
+    },
+    error::{PaceResult, UserMessage},
+    service::{activity_store::ActivityStore, inbox_store::InboxStore, task_store::TaskStore},
+    storage::{ActivityStateManagement, SyncStorage},
+};
+
+/// `inbox add` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct AddInboxItemCommandOptions {
+    /// The title of the item you want to capture
+    #[cfg_attr(feature = "clap", clap(value_name = "Title"))]
+    title: String,
+
+    /// The priority of the item, defaults to the inbox priority in the configuration
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Priority"))]
+    priority: Option<ItemPriorityKind>,
+
+    /// The tags you want to associate with the item, separated by a comma
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            short,
+            long,
+            value_name = "Tags",
+            visible_alias = "tag",
+            value_delimiter = ','
+        )
+    )]
+    tags: Option<Vec<String>>,
+}
+
+impl AddInboxItemCommandOptions {
+    /// Handles the `inbox add` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inbox is full or the inbox file could not be
+    /// read or written
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the id of the captured item
+    #[tracing::instrument(skip(self))]
+    pub fn handle_add_inbox_item(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let mut inbox_store = InboxStore::from_config(config)?;
+
+        inbox_store.lock_for_changes()?;
+
+        // Archive stale items first, so they don't count towards the size limit
+        _ = inbox_store.archive_stale(chrono::Local::now().naive_local());
+
+        let task = Task::builder()
+            .title(self.title.clone())
+            .priority(
+                self.priority
+                    .unwrap_or_else(|| *inbox_store.config().default_priority()),
+            )
+            .tags(self.tags.clone().unwrap_or_default())
+            .build();
+
+        let max_size = inbox_store.max_size();
+
+        let item_id = inbox_store.inbox_mut().add(task, max_size)?;
+
+        debug!("Captured inbox item: {item_id}");
+
+        inbox_store.sync()?;
+
+        Ok(UserMessage::new(format!(
+            "Captured \"{}\" with id {item_id}.",
+            self.title
+        )))
+    }
+}
+
+/// `inbox list` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct ListInboxCommandOptions {
+    /// Only show items with this priority
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Priority"))]
+    priority: Option<ItemPriorityKind>,
+}
+
+impl ListInboxCommandOptions {
+    /// Handles the `inbox list` subcommand
+    ///
+    /// Items are listed by their priority, older items first.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inbox file could not be read
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with a table of the items in the inbox
+    #[tracing::instrument(skip(self))]
+    pub fn handle_list_inbox(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let inbox_store = InboxStore::from_config(config)?;
+
+        let mut items = inbox_store
+            .inbox()
+            .sorted()
+            .into_iter()
+            .filter(|item| {
+                self.priority
+                    .map_or(true, |priority| *item.task().priority() == priority)
+            })
+            .peekable();
+
+        if items.peek().is_none() {
+            return Ok(UserMessage::new("The inbox is empty."));
+        }
+
+        let mut builder = Builder::new();
+
+        builder.push_record(vec!["Id", "Title", "Priority", "Tags", "Captured"]);
+
+        for item in items {
+            builder.push_record(vec![
+                item.id().to_string(),
+                item.task().title().clone(),
+                item.task().priority().to_string(),
+                item.task().tags().join(", "),
+                item.task()
+                    .created_at()
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+            ]);
+        }
+
+        let table = builder
+            .build()
+            .with(Style::modern_rounded())
+            .with(Padding::new(1, 1, 0, 0))
+            .to_string();
+
+        Ok(UserMessage::new(table))
+    }
+}
+
+/// `inbox promote` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct PromoteInboxItemCommandOptions {
+    /// The id of the item, or the beginning of it
+    #[cfg_attr(feature = "clap", clap(value_name = "Item Id"))]
+    item_id: String,
+
+    /// Start an activity for the item right away, instead of adding it to the tasks
+    #[cfg_attr(feature = "clap", clap(short, long))]
+    begin: bool,
+
+    /// The category of the activity, when starting it with `--begin`
+    #[cfg_attr(
+        feature = "clap",
+        clap(short, long, value_name = "Category", requires = "begin")
+    )]
+    category: Option<String>,
+}
+
+impl PromoteInboxItemCommandOptions {
+    /// Handles the `inbox promote` subcommand
+    ///
+    /// The item is moved to the tasks and keeps its id, or an activity is started for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the item could not be found, the activity could not be
+    /// started or the files could not be read or written
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the task or activity the item has been promoted to
+    #[tracing::instrument(skip(self))]
+    pub fn handle_promote_inbox_item(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let mut inbox_store = InboxStore::from_config(config)?;
+
+        inbox_store.lock_for_changes()?;
+
+        _ = inbox_store.archive_stale(chrono::Local::now().naive_local());
+
+        let (item_id, task) = inbox_store.inbox_mut().take(&self.item_id)?.into_parts();
+
+        let message = if self.begin {
+            let date_time = PaceDateTime::try_from((
+                None,
+                PaceTimeZoneKind::NotSet,
+                PaceTimeZoneKind::from(config.general().default_time_zone().as_ref()),
+            ))?
+            .validate()?;
+
+            let tags = (!task.tags().is_empty())
+                .then(|| task.tags().iter().cloned().collect::<HashSet<_>>());
+
+            let activity = Activity::builder()
+                .description(task.title().clone())
+                .begin(date_time)
+                .kind(ActivityKind::default())
+                .category(self.category.clone())
+                .tags(tags)
+                .build();
+
+            let activity_store = ActivityStore::from_config(config)?;
+
+            let activity_item = activity_store.begin_activity(activity)?;
+
+            debug!("Started Activity: {:?}", activity_item);
+
+            activity_store.sync()?;
+
+            format!("{}", activity_item.activity())
+        } else {
+            let mut task_store = TaskStore::from_config(config)?;
+
+            let title = task.title().clone();
+
+            _ = task_store.task_list_mut().insert(item_id, task);
+
+            task_store.sync()?;
+
+            format!("Promoted \"{title}\" to a task with id {item_id}.")
+        };
+
+        inbox_store.sync()?;
+
+        Ok(UserMessage::new(message))
+    }
+}
@@ -0,0 +1,212 @@
+#[cfg(feature = "clap")]
+use clap::Parser;
+use getset::Getters;
+use pace_time::{
+    flags::{DateFlags, TimeFlags},
+    time_frame::PaceTimeFrame,
+    time_range::TimeRangeOptions,
+    time_zone::PaceTimeZoneKind,
+};
+use std::path::PathBuf;
+use tracing::debug;
+
+use crate::{
+    config::PaceConfig,
+    domain::{billing::Invoice, filter::FilterOptions, reflection::ReflectionSummary},
+    error::{PaceResult, UserMessage},
+    service::{activity_store::ActivityStore, project_store::ProjectStore},
+    template::{PaceReflectionTemplate, PaceTemplates},
+};
+
+/// The bundled template invoices are rendered with, if no other template is given
+pub const DEFAULT_INVOICE_TEMPLATE_NAME: &str = "invoice.md";
+
+/// `invoice` subcommand options
+#[derive(Debug, Getters)]
+#[getset(get = "pub")]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[cfg_attr(
+        feature = "clap", clap(group = clap::ArgGroup::new("period").multiple(false).required(false)))]
+pub struct InvoiceCommandOptions {
+    /// The client to bill, the category of its activities, e.g. `acme` for `acme::website`
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Client"))]
+    client: Option<String>,
+
+    /// Bill the activities of the previous month, the default without a period
+    #[cfg_attr(
+        feature = "clap",
+        clap(long, group = "period", conflicts_with = "date-flag")
+    )]
+    last_month: bool,
+
+    /// Bill the activities of the current month
+    #[cfg_attr(
+        feature = "clap",
+        clap(long, group = "period", conflicts_with = "date-flag")
+    )]
+    current_month: bool,
+
+    /// Date flags
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            flatten,
+            next_help_heading = "Date flags for specifying custom date ranges or specific dates"
+        )
+    )]
+    date_flags: Option<DateFlags>,
+
+    /// Use this template file for rendering the invoice
+    #[cfg_attr(
+        feature = "clap",
+        clap(short, long, value_name = "Template File", visible_alias = "tpl")
+    )]
+    template_file: Option<PathBuf>,
+
+    /// Use the bundled or user template with this name for rendering the invoice
+    ///
+    /// User templates are read from the `templates` directory within the reflections
+    /// directory of the configuration. Defaults to `invoice.md`.
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            value_name = "Template Name",
+            visible_alias = "tpl-name",
+            conflicts_with = "template_file"
+        )
+    )]
+    template_name: Option<String>,
+
+    /// Write the invoice to a specified file instead of the standard output
+    #[cfg_attr(
+        feature = "clap",
+        clap(short, long, value_name = "Export File", visible_alias = "export")
+    )]
+    export_file: Option<PathBuf>,
+}
+
+impl InvoiceCommandOptions {
+    /// Handles the `invoice` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the time frame is invalid, the activities could not be read,
+    /// a project with a rate doesn't exist or the invoice could not be rendered or written
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the rendered invoice or the information
+    /// where it has been written to
+    #[tracing::instrument(skip(self))]
+    pub fn handle_invoice(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let Self {
+            client,
+            current_month,
+            date_flags,
+            template_file,
+            template_name,
+            export_file,
+            ..
+        } = self;
+
+        // Invoices are usually written for the previous month
+        let time_flags = if *current_month {
+            Some(TimeFlags::CurrentMonth)
+        } else if date_flags.is_none() {
+            Some(TimeFlags::LastMonth)
+        } else {
+            None
+        };
+
+        let time_frame = PaceTimeFrame::try_from((
+            time_flags.as_ref(),
+            date_flags.as_ref(),
+            PaceTimeZoneKind::NotSet,
+            PaceTimeZoneKind::from(config.general().default_time_zone().as_ref()),
+        ))?;
+
+        debug!("Billing time frame: {}", time_frame);
+
+        let time_range = TimeRangeOptions::try_from(time_frame)?;
+
+        let activity_store = ActivityStore::from_config(config)?;
+
+        let mut summary_groups = activity_store
+            .summary_groups_by_category_for_time_range(FilterOptions::default(), time_range)?
+            .unwrap_or_default();
+
+        if let Some(client) = client {
+            summary_groups.retain(|(category, _), _| category.eq_ignore_ascii_case(client));
+        }
+
+        if summary_groups.is_empty() {
+            return Ok(UserMessage::new(client.as_ref().map_or_else(
+                || "No activities found for the specified time frame".to_string(),
+                |client| format!("No activities of {client} found for the specified time frame"),
+            )));
+        }
+
+        let summary = ReflectionSummary::new(time_range, summary_groups);
+
+        let project_store = ProjectStore::discover(std::env::current_dir()?)?;
+
+        let invoice = Invoice::new(
+            &summary,
+            &config.billing().clone().unwrap_or_default(),
+            project_store.as_ref().map(ProjectStore::project_list),
+            client.clone(),
+            config.general().category_separator().as_deref(),
+        );
+
+        if invoice.line_items().is_empty() {
+            return Ok(UserMessage::new(
+                "None of the activities have a rate, set the rates in the `[billing]` section of your configuration",
+            ));
+        }
+
+        let context = PaceReflectionTemplate::from(summary)
+            .with_time_zone(*config.general().default_time_zone())
+            .with_invoice(&invoice)
+            .into_context();
+
+        let templates = PaceTemplates::from_config(config)?;
+
+        let rendered = if let Some(template_file) = template_file {
+            templates.render_file(template_file, &context)?
+        } else {
+            templates.render(
+                template_name
+                    .as_deref()
+                    .unwrap_or(DEFAULT_INVOICE_TEMPLATE_NAME),
+                &context,
+            )?
+        };
+
+        let Some(export_file) = export_file else {
+            return Ok(UserMessage::new(rendered));
+        };
+
+        std::fs::write(export_file, rendered)?;
+
+        let mut msgs = vec![format!(
+            "Invoice generated: {} ({} {})",
+            export_file.display(),
+            invoice.amount(),
+            invoice.currency()
+        )];
+
+        if !invoice.unbilled_duration().is_zero() {
+            msgs.push(format!(
+                "⚠️  {} of activities without a rate have not been billed.",
+                invoice.unbilled_duration().human_readable()
+            ));
+        }
+
+        Ok(UserMessage::new(msgs.join("\n")))
+    }
+}
@@ -0,0 +1,89 @@
+#[cfg(feature = "clap")]
+use clap::Parser;
+use getset::Getters;
+
+use crate::{
+    config::{ActivityLogStorageKind, PaceConfig},
+    error::{PaceResult, UserMessage},
+};
+
+#[cfg(not(feature = "sqlite"))]
+use crate::error::PaceErrorKind;
+#[cfg(feature = "sqlite")]
+use crate::storage::{get_sqlite_storage_from_config, sqlite::migrations::LATEST_SCHEMA_VERSION};
+
+/// `migrate` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct MigrateCommandOptions {
+    /// Only show the migrations that would be applied, without changing the database
+    #[cfg_attr(feature = "clap", clap(long))]
+    dry_run: bool,
+}
+
+impl MigrateCommandOptions {
+    /// Handles the `migrate` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database can't be opened, has a newer schema version
+    /// than this version of pace supports or a migration fails
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the information about the (pending) migrations
+    /// that can be displayed to the user
+    #[tracing::instrument(skip(self))]
+    pub fn handle_migrate(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        match config.general().activity_log_options().storage_kind() {
+            ActivityLogStorageKind::Database => self.migrate_database(config),
+            _ => Ok(UserMessage::new(
+                "The activity log is not stored in a database, there is nothing to migrate.",
+            )),
+        }
+    }
+
+    /// Migrate the configured database to the latest schema version
+    #[cfg(feature = "sqlite")]
+    fn migrate_database(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let storage = get_sqlite_storage_from_config(config)?;
+
+        let current_version = storage.schema_version()?;
+
+        let migrations = storage.migrate(self.dry_run)?;
+
+        if migrations.is_empty() {
+            return Ok(UserMessage::new(format!(
+                "The database is up to date (schema version {current_version})."
+            )));
+        }
+
+        let mut msgs = vec![if self.dry_run {
+            format!(
+                "The following migrations would be applied to migrate the database from schema version {current_version} to {LATEST_SCHEMA_VERSION}:"
+            )
+        } else {
+            format!(
+                "Migrated the database from schema version {current_version} to {LATEST_SCHEMA_VERSION}:"
+            )
+        }];
+
+        for migration in &migrations {
+            msgs.push(format!("  {:04} {}", migration.version(), migration.name()));
+        }
+
+        Ok(UserMessage::new(msgs.join("\n")))
+    }
+
+    /// Migrate the configured database to the latest schema version
+    #[cfg(not(feature = "sqlite"))]
+    #[allow(clippy::unused_self)]
+    fn migrate_database(&self, _config: &PaceConfig) -> PaceResult<UserMessage> {
+        Err(PaceErrorKind::DatabaseStorageNotImplemented.into())
+    }
+}
@@ -1,12 +1,16 @@
 #[cfg(feature = "clap")]
 use clap::Parser;
+use pace_time::{date_time::PaceDateTime, time_frame::PaceTimeFrame};
 use tracing::debug;
 
 use crate::{
     config::PaceConfig,
-    domain::{activity::ActivityItem, filter::ActivityFilterKind},
+    domain::{
+        activity::{ActivityGuid, ActivityItem},
+        filter::ActivityFilterKind,
+    },
     error::{PaceResult, UserMessage},
-    service::activity_store::ActivityStore,
+    service::{activity_store::ActivityStore, activity_tracker::ActivityTracker},
     storage::{get_storage_from_config, ActivityQuerying, ActivityReadOps},
 };
 
@@ -28,32 +32,89 @@ impl NowCommandOptions {
     ///
     /// # Returns
     ///
-    /// Returns a `UserMessage` with the information about the current activities that can be displayed to the user
+    /// Returns a `UserMessage` with the information about the current activities and the
+    /// progress towards the goals of today and this week that can be displayed to the user
     #[tracing::instrument(skip(self))]
     pub fn handle_now(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
         let activity_store = ActivityStore::with_storage(get_storage_from_config(config)?)?;
 
-        let user_message = (activity_store.list_current_activities(ActivityFilterKind::Active)?)
-            .map_or_else(
-                || "No activities are currently running.".to_string(),
-                |activities| {
-                    debug!("Current Activities: {:?}", activities);
+        let mut msgs = vec![];
 
-                    // Get the activity items
-                    let activity_items = activities
-                        .iter()
-                        .flat_map(|activity_id| activity_store.read_activity(*activity_id))
-                        .collect::<Vec<ActivityItem>>();
+        if let Some(activities) =
+            activity_store.list_current_activities(ActivityFilterKind::Active)?
+        {
+            debug!("Current Activities: {:?}", activities);
 
-                    let mut msgs = vec![];
-                    for activity in &activity_items {
-                        msgs.push(format!("{}", activity.activity()));
-                    }
+            msgs.extend(Self::current_activity_lines(
+                &activity_store,
+                &activities,
+                config,
+            )?);
+        } else {
+            msgs.push("No activities are currently running.".to_string());
+        }
 
-                    msgs.join("\n")
-                },
-            );
+        let goals = ActivityTracker::with_activity_store(activity_store)
+            .goal_progress(config.goals(), PaceTimeFrame::Today)?;
 
-        Ok(UserMessage::new(user_message))
+        if !goals.is_empty() {
+            msgs.push(String::new());
+            msgs.push("Goals:".to_string());
+            msgs.extend(goals.iter().map(|goal| format!("  {goal}")));
+        }
+
+        Ok(UserMessage::new(msgs.join("\n")))
+    }
+
+    /// The lines describing the current activities and their pomodoro phases
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_store` - The activity store the activities are read from
+    /// * `activities` - The ids of the current activities
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pomodoro phase of an activity could not be determined
+    fn current_activity_lines(
+        activity_store: &ActivityStore,
+        activities: &[ActivityGuid],
+        config: &PaceConfig,
+    ) -> PaceResult<Vec<String>> {
+        // Get the activity items
+        let activity_items = activities
+            .iter()
+            .flat_map(|activity_id| activity_store.read_activity(*activity_id))
+            .collect::<Vec<ActivityItem>>();
+
+        let pomodoro_config = config.pomodoro().unwrap_or_default();
+
+        let now = PaceDateTime::now();
+
+        let mut msgs = vec![];
+        for activity in &activity_items {
+            msgs.push(format!("{}", activity.activity()));
+
+            // Show the remaining time of pomodoro phases, as they might not be counted down in the foreground
+            if let Some(phase) =
+                activity_store.pomodoro_phase(activity.activity(), &pomodoro_config)?
+            {
+                let begin = activity.activity().begin();
+
+                let remaining = phase.remaining(begin, &now);
+
+                if remaining.is_zero() {
+                    msgs.push(format!(
+                        "   ⏰ The {phase} is over since {}, run `pace pomo` to continue.",
+                        phase.overtime(begin, &now)
+                    ));
+                } else {
+                    msgs.push(format!("   ⏳ {remaining} left of the {phase}."));
+                }
+            }
+        }
+
+        Ok(msgs)
     }
 }
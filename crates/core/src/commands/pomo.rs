@@ -0,0 +1,214 @@
+use std::collections::HashSet;
+
+#[cfg(feature = "clap")]
+use clap::Parser;
+use getset::Getters;
+use pace_time::date_time::PaceDateTime;
+use tracing::debug;
+
+use crate::{
+    commands::EndOptions,
+    config::{PaceConfig, PomodoroConfig},
+    domain::{activity::ActivityItem, pomodoro::PomodoroPhase},
+    error::{PaceErrorKind, PaceResult, UserMessage},
+    service::activity_store::ActivityStore,
+    storage::{ActivityStateManagement, SyncStorage},
+};
+
+/// `pomo` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct PomoCommandOptions {
+    /// The description of the pomodoro session you want to start
+    ///
+    /// If it is left out, the running pomodoro session continues with its next phase.
+    #[cfg_attr(feature = "clap", clap(value_name = "Session Description"))]
+    description: Option<String>,
+
+    /// The Category of the pomodoro session you want to start
+    ///
+    /// You can use the separator you setup in the configuration file
+    /// to specify a subcategory.
+    #[cfg_attr(feature = "clap", clap(short, long, name = "Category"))]
+    category: Option<String>,
+
+    /// The tags you want to associate with the work sessions, separated by a comma
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            short,
+            long,
+            value_name = "Tags",
+            visible_alias = "tag",
+            value_delimiter = ','
+        )
+    )]
+    tags: Option<Vec<String>>,
+
+    /// The number of work sessions to count down, each followed by its break
+    ///
+    /// Defaults to the number of work sessions before a long break.
+    #[cfg_attr(
+        feature = "clap",
+        clap(short = 'n', long, value_name = "Work Sessions")
+    )]
+    cycles: Option<usize>,
+
+    /// Only begin the next phase instead of counting down in the foreground
+    ///
+    /// Use `pace now` to see the remaining time of the phase, and run `pace pomo`
+    /// again to continue with the next phase.
+    #[cfg_attr(feature = "clap", clap(short, long, visible_alias = "no-wait"))]
+    detach: bool,
+}
+
+impl PomoCommandOptions {
+    /// Handles the `pomo` subcommand
+    ///
+    /// Every phase is tracked as its own activity. In the foreground, the next
+    /// phase begins as soon as the countdown of the current phase returns, until
+    /// the requested number of work sessions and their breaks are done.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    /// * `countdown` - Waits until the planned duration of a phase is over, not called if detached
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a phase could not be started or ended, if there is
+    /// no pomodoro session to continue, or if the countdown fails
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the information about the pomodoro session
+    /// that can be displayed to the user
+    #[tracing::instrument(skip(self, countdown))]
+    pub fn handle_pomo(
+        &self,
+        config: &PaceConfig,
+        mut countdown: impl FnMut(&ActivityItem, &PomodoroPhase) -> PaceResult<()>,
+    ) -> PaceResult<UserMessage> {
+        let pomodoro_config = config.pomodoro().unwrap_or_default();
+
+        let activity_store = ActivityStore::from_config(config)?;
+
+        if self.detach {
+            let (activity_item, phase) =
+                self.begin_next_phase(&activity_store, &pomodoro_config)?;
+
+            return Ok(UserMessage::new(format!(
+                "{}\nStarted {phase}, it ends in {}.",
+                activity_item.activity(),
+                phase.duration()
+            )));
+        }
+
+        let cycles = self
+            .cycles
+            .unwrap_or_else(|| *pomodoro_config.sessions_before_long_break() as usize)
+            .max(1);
+
+        let mut started_work_sessions = 0;
+
+        loop {
+            let (activity_item, phase) =
+                self.begin_next_phase(&activity_store, &pomodoro_config)?;
+
+            if !phase.kind().is_break() {
+                started_work_sessions += 1;
+            }
+
+            countdown(&activity_item, &phase)?;
+
+            if phase.kind().is_break() && started_work_sessions >= cycles {
+                let _ =
+                    activity_store.end_activity(*activity_item.guid(), EndOptions::default())?;
+
+                activity_store.sync()?;
+
+                return Ok(UserMessage::new(format!(
+                    "Finished the pomodoro session \"{}\" after work session {}.",
+                    activity_item.activity().description(),
+                    phase.cycle()
+                )));
+            }
+        }
+    }
+
+    /// Begin the next phase of the pomodoro session
+    ///
+    /// The running pomodoro session is continued, unless a description for
+    /// another session is given. A new session starts with its first work session.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_store` - The activity store to track the phase in
+    /// * `config` - The pomodoro configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the phase could not be started, or if there is
+    /// no pomodoro session to continue and no description is given
+    ///
+    /// # Returns
+    ///
+    /// The activity item of the started phase and the phase itself
+    pub fn begin_next_phase(
+        &self,
+        activity_store: &ActivityStore,
+        config: &PomodoroConfig,
+    ) -> PaceResult<(ActivityItem, PomodoroPhase)> {
+        let running =
+            activity_store
+                .running_pomodoro_phase(config)?
+                .filter(|(_, work_session, _)| {
+                    self.description.as_ref().map_or(true, |description| {
+                        work_session.activity().description() == description
+                    })
+                });
+
+        debug!("Running pomodoro phase: {:?}", running);
+
+        let activity = match (running, &self.description) {
+            (Some((_, work_session, phase)), _) => {
+                let next_phase = phase.next(config);
+
+                let parent_id = next_phase.kind().is_break().then(|| *work_session.guid());
+
+                let work_session = work_session.activity();
+
+                next_phase.to_activity(
+                    work_session.description().clone(),
+                    work_session.category().clone(),
+                    work_session.tags().clone(),
+                    PaceDateTime::now(),
+                    parent_id,
+                )
+            }
+            (None, Some(description)) => PomodoroPhase::work(1, config).to_activity(
+                description.clone(),
+                self.category.clone(),
+                self.tags
+                    .as_ref()
+                    .map(|tags| tags.iter().cloned().collect::<HashSet<String>>()),
+                PaceDateTime::now(),
+                None,
+            ),
+            (None, None) => return Err(PaceErrorKind::NoPomodoroSessionFound.into()),
+        };
+
+        let activity_item = activity_store.begin_activity(activity)?;
+
+        debug!("Started pomodoro phase: {:?}", activity_item);
+
+        activity_store.sync()?;
+
+        let phase = activity_store
+            .pomodoro_phase(activity_item.activity(), config)?
+            .ok_or(PaceErrorKind::NoPomodoroSessionFound)?;
+
+        Ok((activity_item, phase))
+    }
+}
@@ -4,20 +4,33 @@ use getset::{Getters, MutGetters, Setters};
 use pace_time::{
     flags::{DateFlags, TimeFlags},
     time_frame::PaceTimeFrame,
+    time_range::TimeRangeOptions,
     time_zone::PaceTimeZoneKind,
 };
 use serde_derive::Serialize;
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 use tracing::debug;
 use typed_builder::TypedBuilder;
 
 use crate::{
     config::PaceConfig,
-    domain::{activity::ActivityKind, filter::FilterOptions, reflection::ReflectionsFormatKind},
-    error::{PaceResult, TemplatingErrorKind, UserMessage},
-    service::{activity_store::ActivityStore, activity_tracker::ActivityTracker},
-    storage::get_storage_from_config,
-    template::{PaceReflectionTemplate, TEMPLATES},
+    domain::{
+        activity::ActivityKind,
+        filter::FilterOptions,
+        project::ProjectFilter,
+        reflection::{ReflectionGroupByKind, ReflectionSummary, ReflectionsFormatKind},
+        rounding::{RoundingModeKind, RoundingOptions, RoundingScopeKind},
+    },
+    error::{PaceErrorKind, PaceResult, UserMessage},
+    service::{
+        activity_archive::ActivityArchive, activity_store::ActivityStore,
+        activity_tracker::ActivityTracker, project_store::ProjectStore, task_store::TaskStore,
+    },
+    storage::{
+        get_storage_from_config, in_memory::InMemoryActivityStorage, ActivityQuerying,
+        ActivityStorage,
+    },
+    template::{PaceReflectionTemplate, PaceTemplates, DEFAULT_TEMPLATE_NAME},
 };
 
 /// `reflect` subcommand options
@@ -48,6 +61,50 @@ pub struct ReflectCommandOptions {
     )]
     case_sensitive: bool,
 
+    /// Only reflect on the project with this name or id, including its subprojects
+    ///
+    /// The project is looked up in the nearest `projects.pace.toml` file, from
+    /// the current directory upwards.
+    #[cfg_attr(
+        feature = "clap",
+        clap(long, value_name = "Project", visible_alias = "proj")
+    )]
+    project: Option<String>,
+
+    /// Only reflect on activities with any of these tags, separated by a comma, wildcards supported
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long = "tag",
+            value_name = "Tags",
+            visible_alias = "tags",
+            value_delimiter = ','
+        )
+    )]
+    tags: Option<Vec<String>>,
+
+    /// Group the activities by category or by tag
+    #[cfg_attr(feature = "clap", clap(long, value_name = "Group By"))]
+    group_by: Option<ReflectionGroupByKind>,
+
+    /// Round the durations to this interval (in minutes) and show them next to the tracked ones
+    ///
+    /// Overrides `minutes` in the `[rounding]` section of the configuration, `0` disables rounding.
+    #[cfg_attr(feature = "clap", clap(long, value_name = "Minutes"))]
+    round_to: Option<u64>,
+
+    /// How the durations are rounded, overrides `mode` in the `[rounding]` section of the configuration
+    #[cfg_attr(feature = "clap", clap(long, value_name = "Rounding Mode"))]
+    rounding_mode: Option<RoundingModeKind>,
+
+    /// What durations are rounded on their own, overrides `scope` in the `[rounding]` section of the configuration
+    #[cfg_attr(feature = "clap", clap(long, value_name = "Rounding Scope"))]
+    rounding_scope: Option<RoundingScopeKind>,
+
+    /// Also include the activities that have been moved to the archive
+    #[cfg_attr(feature = "clap", clap(long, visible_alias = "archived"))]
+    include_archived: bool,
+
     /// Specify output format for the reflection
     #[cfg_attr(
         feature = "clap",
@@ -63,6 +120,22 @@ pub struct ReflectCommandOptions {
     )]
     template_file: Option<PathBuf>,
 
+    /// Use the bundled or user template with this name for rendering the reflection, e.g. `basic.md`
+    ///
+    /// User templates are read from the `templates` directory within the reflections
+    /// directory of the configuration. Defaults to `basic.md` if the reflection is
+    /// exported to a markdown file, otherwise to `basic.html`.
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            value_name = "Template Name",
+            visible_alias = "tpl-name",
+            conflicts_with = "template_file"
+        )
+    )]
+    template_name: Option<String>,
+
     /// Export the reflections to a specified file
     #[cfg_attr(
         feature = "clap",
@@ -125,11 +198,11 @@ impl ReflectCommandOptions {
     #[tracing::instrument(skip(self))]
     pub fn handle_reflect(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
         let Self {
-            export_file,
             time_flags,
             date_flags,
-            template_file,
-            output_format,
+            expensive_flags,
+            project,
+            include_archived,
             // time_zone,
             // time_zone_offset,
             .. // TODO: ignore the rest of the fields for now,
@@ -140,89 +213,216 @@ impl ReflectCommandOptions {
             time_flags.as_ref(),
             date_flags.as_ref(),
             PaceTimeZoneKind::NotSet,
-            PaceTimeZoneKind::NotSet,
+            PaceTimeZoneKind::from(config.general().default_time_zone().as_ref()),
         ))?;
 
-        let activity_store = ActivityStore::with_storage(get_storage_from_config(config)?)?;
+        let project_store = ProjectStore::discover(std::env::current_dir()?)?;
+
+        let mut filter_opts = FilterOptions::from(self);
+
+        let project_id = project
+            .as_ref()
+            .map(|project| -> PaceResult<_> {
+                let project_store = project_store
+                    .as_ref()
+                    .ok_or_else(|| PaceErrorKind::ProjectNotFound(project.clone()))?;
+
+                let project_id = project_store.project_list().find_id(project)?;
+
+                _ = filter_opts.set_project(Some(ProjectFilter::new(
+                    project_store.project_list(),
+                    project_id,
+                )));
+
+                Ok(project_id)
+            })
+            .transpose()?;
+
+        let mut storage = get_storage_from_config(config)?;
+
+        if *include_archived {
+            storage.setup_storage()?;
+
+            let mut time_range = TimeRangeOptions::try_from(time_frame.clone())?;
+
+            // The previous period is compared against, so its archived activities are needed as well
+            if *expensive_flags.comparative() {
+                time_range = TimeRangeOptions::builder()
+                    .start(*time_frame.previous_time_range()?.start())
+                    .end(*time_range.end())
+                    .build();
+            }
+
+            let mut activity_log =
+                ActivityArchive::from_config(config).load_time_range(&time_range)?;
+
+            activity_log.extend(storage.list_activities_by_id()?.unwrap_or_default());
+
+            debug!("Including {} archived activities", activity_log.len());
+
+            storage = Arc::new(InMemoryActivityStorage::from(activity_log).into());
+        }
+
+        let activity_store = ActivityStore::with_storage(storage)?;
 
         let activity_tracker = ActivityTracker::with_activity_store(activity_store);
 
         debug!("Displaying reflection for time frame: {}", time_frame);
 
-        let Some(reflection) =
-            activity_tracker.generate_reflection(FilterOptions::from(self), time_frame)?
+        let Some(mut reflection) = activity_tracker.generate_reflection(
+            filter_opts,
+            time_frame.clone(),
+            expensive_flags,
+            &config.duration_range_options()?,
+        )?
         else {
             return Ok(UserMessage::new(
                 "No activities found for the specified time frame",
             ));
         };
 
-        match output_format {
-            Some(ReflectionsFormatKind::Console) | None => {
-                return Ok(UserMessage::new(reflection.to_string()));
+        if !reflection.summary_groups_by_task().is_empty() {
+            reflection.set_task_titles(TaskStore::from_config(config)?.task_list());
+        }
+
+        if let Some(project_store) = &project_store {
+            let project_list = project_store.project_list();
+
+            // Only show the project and its subprojects, not the projects above it
+            if let Some(project_id) = project_id {
+                let subprojects = project_list.with_subprojects(project_id);
+
+                reflection
+                    .summary_groups_by_project_mut()
+                    .retain(|project_id, _| subprojects.contains(project_id));
             }
-            Some(ReflectionsFormatKind::Json) => {
-                let json = serde_json::to_string_pretty(&reflection)?;
 
-                debug!("Reflection: {}", json);
+            reflection.set_project_names(project_list);
+        }
 
-                // write to file if export file is specified
-                if let Some(export_file) = export_file {
-                    std::fs::write(export_file, json)?;
+        self.complete_reflection(config, &activity_tracker, &mut reflection, time_frame)?;
 
-                    return Ok(UserMessage::new(format!(
-                        "Reflection generated: {}",
-                        export_file.display()
-                    )));
-                }
+        self.render_reflection(config, reflection)
+    }
 
-                return Ok(UserMessage::new(json));
-            }
+    /// Round the durations of the reflection and add the progress towards the goals
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The pace configuration
+    /// * `activity_tracker` - The activity tracker the reflection has been generated with
+    /// * `reflection` - The reflection to complete
+    /// * `time_frame` - The time frame of the reflection
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the progress towards the goals cannot be evaluated
+    fn complete_reflection(
+        &self,
+        config: &PaceConfig,
+        activity_tracker: &ActivityTracker,
+        reflection: &mut ReflectionSummary,
+        time_frame: PaceTimeFrame,
+    ) -> PaceResult<()> {
+        let Self {
+            round_to,
+            rounding_mode,
+            rounding_scope,
+            ..
+        } = self;
 
-            Some(ReflectionsFormatKind::Template) => {
-                let context = PaceReflectionTemplate::from(reflection).into_context();
+        let rounding = config.rounding().unwrap_or_default();
 
-                let templated = if template_file.is_none() {
-                    TEMPLATES
-                        .render("base.html", &context)
-                        .map_err(TemplatingErrorKind::RenderingToTemplateFailed)?
-                } else {
-                    let Some(user_tpl) = template_file.as_ref() else {
-                        return Err(TemplatingErrorKind::TemplateFileNotSpecified.into());
-                    };
+        reflection.apply_rounding(
+            RoundingOptions::builder()
+                .minutes(round_to.unwrap_or(*rounding.minutes()))
+                .mode(rounding_mode.unwrap_or(*rounding.mode()))
+                .scope(rounding_scope.unwrap_or(*rounding.scope()))
+                .build(),
+        );
 
-                    let user_tpl = std::fs::read_to_string(user_tpl)
-                        .map_err(TemplatingErrorKind::FailedToReadTemplateFile)?;
+        _ = reflection.set_goals(activity_tracker.goal_progress(config.goals(), time_frame)?);
 
-                    tera::Tera::one_off(&user_tpl, &context, true)
-                        .map_err(TemplatingErrorKind::RenderingToTemplateFailed)?
-                };
+        Ok(())
+    }
 
-                debug!("Reflection: {}", templated);
+    /// Render the reflection in the requested output format
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The pace configuration
+    /// * `reflection` - The reflection to render
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reflection cannot be rendered or exported
+    ///
+    /// # Returns
+    ///
+    /// The rendered reflection, or a message if it has been exported to a file
+    fn render_reflection(
+        &self,
+        config: &PaceConfig,
+        reflection: ReflectionSummary,
+    ) -> PaceResult<UserMessage> {
+        let rendered = match self.output_format {
+            Some(ReflectionsFormatKind::Console) | None => {
+                return Ok(UserMessage::new(reflection.to_string()));
+            }
+            Some(ReflectionsFormatKind::Json) => serde_json::to_string_pretty(&reflection)?,
+            Some(ReflectionsFormatKind::Template) => {
+                let context = PaceReflectionTemplate::from(reflection)
+                    .with_time_zone(*config.general().default_time_zone())
+                    .into_context();
 
-                // write to file if export file is specified
-                if let Some(export_file) = export_file {
-                    std::fs::write(export_file, templated)?;
+                let templates = PaceTemplates::from_config(config)?;
 
-                    return Ok(UserMessage::new(format!(
-                        "Reflection generated: {}",
-                        export_file.display()
-                    )));
-                }
+                if let Some(template_file) = &self.template_file {
+                    templates.render_file(template_file, &context)?
+                } else {
+                    let default_template_name = match self
+                        .export_file
+                        .as_ref()
+                        .and_then(|export_file| export_file.extension())
+                    {
+                        Some(extension) if extension == "md" => "basic.md",
+                        _ => DEFAULT_TEMPLATE_NAME,
+                    };
 
-                return Ok(UserMessage::new(templated));
+                    templates.render(
+                        self.template_name
+                            .as_deref()
+                            .unwrap_or(default_template_name),
+                        &context,
+                    )?
+                }
             }
-            Some(ReflectionsFormatKind::Csv) => unimplemented!("CSV format not yet supported"),
+            Some(ReflectionsFormatKind::Csv) => reflection.to_csv(*self.expensive_flags.detailed()),
+        };
+
+        debug!("Reflection: {}", rendered);
+
+        // write to file if export file is specified
+        if let Some(export_file) = &self.export_file {
+            std::fs::write(export_file, rendered)?;
+
+            return Ok(UserMessage::new(format!(
+                "Reflection generated: {}",
+                export_file.display()
+            )));
         }
+
+        Ok(UserMessage::new(rendered))
     }
 }
 
 #[derive(
     Debug, TypedBuilder, Serialize, Getters, Setters, MutGetters, Clone, Eq, PartialEq, Default,
 )]
+#[getset(get = "pub")]
 #[cfg_attr(feature = "clap", derive(Parser))]
 pub struct ExpensiveFlags {
-    /// Include detailed time logs in the reflection
+    /// Include detailed time logs in the reflection, e.g. one row per session in CSV
     #[cfg_attr(feature = "clap", clap(long))]
     detailed: bool,
 
@@ -0,0 +1,295 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+#[cfg(feature = "clap")]
+use clap::Parser;
+use getset::Getters;
+
+use crate::{
+    config::PaceConfig,
+    domain::{
+        activity::{Activity, ActivityGuid},
+        sync::ActivityLogMerge,
+    },
+    error::{PaceErrorKind, PaceResult, UserMessage},
+    service::{activity_history::ActivityHistory, activity_store::ActivityStore},
+    storage::{
+        get_storage_from_path,
+        git::{GitActivityStorage, GitPull},
+        ActivityQuerying, StorageKind, SyncStorage,
+    },
+};
+
+/// `sync merge` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct MergeSyncCommandOptions {
+    /// The activity log to merge into yours, e.g. a conflicting copy created by a file sync tool
+    #[cfg_attr(feature = "clap", clap(value_name = "Other Activity Log"))]
+    other_log: PathBuf,
+
+    /// The history of the other activity log, to tell which changes to an activity have been made last
+    #[cfg_attr(feature = "clap", clap(long, value_name = "Other History"))]
+    other_history: Option<PathBuf>,
+
+    /// Only report what would be merged, without changing your activity log
+    #[cfg_attr(feature = "clap", clap(short = 'n', long))]
+    dry_run: bool,
+}
+
+impl MergeSyncCommandOptions {
+    /// Handles the `sync merge` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if one of the activity logs could not be read
+    /// or the merged activity log could not be written
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the merged activities and the resolved conflicts
+    #[tracing::instrument(skip(self))]
+    pub fn handle_merge(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let other = get_storage_from_path(&self.other_log)?
+            .list_activities_by_id()?
+            .unwrap_or_default();
+
+        let activity_store = ActivityStore::from_config(config)?;
+
+        let local = activity_store.list_activities_by_id()?.unwrap_or_default();
+
+        let local_history = activity_store
+            .history()
+            .as_ref()
+            .map(|history| history.history());
+
+        let other_history = self
+            .other_history
+            .as_ref()
+            .map(ActivityHistory::new)
+            .transpose()?
+            .map(|history| history.history());
+
+        let merge = ActivityLogMerge::by_change_times(
+            &local,
+            &other,
+            local_history.as_ref(),
+            other_history.as_ref(),
+        )?;
+
+        let mut msgs = vec![];
+
+        if self.dry_run {
+            msgs.push(format!(
+                "Would merge {} new activities from {}.",
+                merge.added().len(),
+                self.other_log.display()
+            ));
+        } else {
+            let count = activity_store.apply_merge(&merge)?;
+
+            activity_store.sync()?;
+
+            msgs.push(format!(
+                "Merged {} new activities from {}, {count} activities have changed.",
+                merge.added().len(),
+                self.other_log.display()
+            ));
+        }
+
+        for conflict in merge.conflicts() {
+            msgs.push(format!("⚠️  {conflict}."));
+        }
+
+        Ok(UserMessage::new(msgs.join("\n")))
+    }
+}
+
+/// The remote repository options shared by `sync pull` and `sync push`
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct GitRemoteOptions {
+    /// The URL or path of the remote repository, overrides `remote` in the `[git]` section of the configuration
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Remote"))]
+    remote: Option<String>,
+
+    /// The branch of the remote repository, overrides `branch` in the `[git]` section of the configuration
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Branch"))]
+    branch: Option<String>,
+}
+
+impl GitRemoteOptions {
+    /// The remote repository and branch, taken from the options or the configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no remote repository in the options nor the configuration
+    fn remote_and_branch(&self, config: &PaceConfig) -> PaceResult<(String, String)> {
+        let git_config = config.git().clone().unwrap_or_default();
+
+        let remote = self
+            .remote
+            .clone()
+            .or_else(|| git_config.remote().clone())
+            .ok_or(PaceErrorKind::GitRemoteNotConfigured)?;
+
+        let branch = self
+            .branch
+            .clone()
+            .unwrap_or_else(|| git_config.branch().clone());
+
+        Ok((remote, branch))
+    }
+}
+
+/// `sync pull` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct PullSyncCommandOptions {
+    #[cfg_attr(feature = "clap", clap(flatten))]
+    remote_opts: GitRemoteOptions,
+}
+
+impl PullSyncCommandOptions {
+    /// Handles the `sync pull` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activity log is not stored in git, the remote
+    /// activity log cannot be pulled or the merged activity log cannot be committed
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with how the activity log has been pulled and the resolved conflicts
+    #[tracing::instrument(skip(self))]
+    pub fn handle_pull(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let (remote, branch) = self.remote_opts.remote_and_branch(config)?;
+
+        let activity_store = ActivityStore::from_config(config)?;
+
+        let git = git_storage(&activity_store)?;
+
+        let activities = match git.pull(&remote, &branch)? {
+            GitPull::NothingToPull => {
+                return Ok(UserMessage::new(format!(
+                    "There is no activity log on {branch} of {remote} yet, push yours with `pace sync push`."
+                )))
+            }
+            GitPull::UpToDate => {
+                return Ok(UserMessage::new(format!(
+                    "Your activity log is up to date with {branch} of {remote}."
+                )))
+            }
+            GitPull::FastForwarded => {
+                return Ok(UserMessage::new(format!(
+                    "Pulled the activity log from {branch} of {remote}."
+                )))
+            }
+            GitPull::Diverged { activities } => activities,
+        };
+
+        let merged = merge_pulled(&activity_store, &activities);
+
+        let (merge, count) = match merged {
+            Ok(merged) => merged,
+            Err(err) => {
+                git.abort_merge()?;
+
+                return Err(err);
+            }
+        };
+
+        let mut msgs = vec![format!(
+            "Merged {} new activities from {branch} of {remote}, {count} activities have changed.",
+            merge.added().len()
+        )];
+
+        for conflict in merge.conflicts() {
+            msgs.push(format!("⚠️  {conflict}."));
+        }
+
+        Ok(UserMessage::new(msgs.join("\n")))
+    }
+}
+
+/// `sync push` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct PushSyncCommandOptions {
+    #[cfg_attr(feature = "clap", clap(flatten))]
+    remote_opts: GitRemoteOptions,
+}
+
+impl PushSyncCommandOptions {
+    /// Handles the `sync push` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activity log is not stored in git or cannot be pushed,
+    /// e.g. because the remote activity log has changes that need to be pulled first
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the remote repository the activity log has been pushed to
+    #[tracing::instrument(skip(self))]
+    pub fn handle_push(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let (remote, branch) = self.remote_opts.remote_and_branch(config)?;
+
+        let activity_store = ActivityStore::from_config(config)?;
+
+        git_storage(&activity_store)?.push(&remote, &branch)?;
+
+        Ok(UserMessage::new(format!(
+            "Pushed your activity log to {branch} of {remote}."
+        )))
+    }
+}
+
+/// The git storage of the activity store
+///
+/// # Errors
+///
+/// Returns an error if the activity log is not stored in git
+fn git_storage(activity_store: &ActivityStore) -> PaceResult<&GitActivityStorage> {
+    match activity_store.storage().as_ref() {
+        StorageKind::GitActivityStorage(git) => Ok(git),
+        _ => Err(PaceErrorKind::GitStorageNotConfigured.into()),
+    }
+}
+
+/// Merge the pulled activities into the activity log and commit the merge
+///
+/// # Returns
+///
+/// The merge and the number of activities that have been added or changed
+fn merge_pulled(
+    activity_store: &ActivityStore,
+    activities: &BTreeMap<ActivityGuid, Activity>,
+) -> PaceResult<(ActivityLogMerge, usize)> {
+    let local = activity_store.list_activities_by_id()?.unwrap_or_default();
+
+    // The history isn't part of the repository, so the activities themselves tell which changes are newer
+    let merge = ActivityLogMerge::by_change_times(&local, activities, None, None)?;
+
+    let count = activity_store.apply_merge(&merge)?;
+
+    // Commits the merge
+    activity_store.sync()?;
+
+    Ok((merge, count))
+}
@@ -0,0 +1,281 @@
+#[cfg(feature = "clap")]
+use clap::Parser;
+use getset::Getters;
+use tabled::{
+    builder::Builder,
+    settings::{object::Columns, Alignment, Modify, Padding, Style},
+};
+use tracing::debug;
+use wildmatch::WildMatch;
+
+use crate::{
+    config::PaceConfig,
+    domain::{activity::ActivityGuid, tag::normalize_tag},
+    error::{PaceErrorKind, PaceResult, UserMessage},
+    service::activity_store::ActivityStore,
+    storage::{
+        get_storage_from_config, ActivityQuerying, ActivityReadOps, ActivityTagging, SyncStorage,
+    },
+};
+
+/// `tag add` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct AddTagCommandOptions {
+    /// The tags you want to add, separated by a comma
+    #[cfg_attr(
+        feature = "clap",
+        clap(value_name = "Tags", value_delimiter = ',', required = true)
+    )]
+    tags: Vec<String>,
+
+    /// The id of the activity, or the beginning of it, defaults to the currently active activity
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Activity Id"))]
+    activity: Option<String>,
+}
+
+impl AddTagCommandOptions {
+    /// Handles the `tag add` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a tag is invalid, the activity could not be found
+    /// or the activity log could not be written
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the tagged activity
+    #[tracing::instrument(skip(self))]
+    pub fn handle_add_tag(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let tags = normalize_tags(&self.tags)?;
+
+        let activity_store = ActivityStore::from_config(config)?;
+
+        let activity_id = find_activity_id(&activity_store, self.activity.as_deref())?;
+
+        let mut activity_item = activity_store.read_activity(activity_id)?;
+
+        for tag in &tags {
+            activity_item = activity_store.add_tag_to_activity(activity_id, tag)?;
+        }
+
+        debug!("Tagged activity: {:?}", activity_item);
+
+        activity_store.sync()?;
+
+        Ok(UserMessage::new(format!(
+            "Tagged \"{}\" with {}.",
+            activity_item.activity().description(),
+            tags.join(", ")
+        )))
+    }
+}
+
+/// `tag rm` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct RemoveTagCommandOptions {
+    /// The tags you want to remove, separated by a comma
+    #[cfg_attr(
+        feature = "clap",
+        clap(value_name = "Tags", value_delimiter = ',', required = true)
+    )]
+    tags: Vec<String>,
+
+    /// The id of the activity, or the beginning of it, defaults to the currently active activity
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Activity Id"))]
+    activity: Option<String>,
+}
+
+impl RemoveTagCommandOptions {
+    /// Handles the `tag rm` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activity could not be found, doesn't have one of
+    /// the tags or the activity log could not be written
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the untagged activity
+    #[tracing::instrument(skip(self))]
+    pub fn handle_remove_tag(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let tags = normalize_tags(&self.tags)?;
+
+        let activity_store = ActivityStore::from_config(config)?;
+
+        let activity_id = find_activity_id(&activity_store, self.activity.as_deref())?;
+
+        let mut activity_item = activity_store.read_activity(activity_id)?;
+
+        for tag in &tags {
+            activity_item = activity_store.remove_tag_from_activity(activity_id, tag)?;
+        }
+
+        debug!("Untagged activity: {:?}", activity_item);
+
+        activity_store.sync()?;
+
+        Ok(UserMessage::new(format!(
+            "Removed {} from \"{}\".",
+            tags.join(", "),
+            activity_item.activity().description()
+        )))
+    }
+}
+
+/// `tag rename` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct RenameTagCommandOptions {
+    /// The tag you want to rename
+    #[cfg_attr(feature = "clap", clap(value_name = "Old Tag"))]
+    old_tag: String,
+
+    /// The new name of the tag
+    #[cfg_attr(feature = "clap", clap(value_name = "New Tag"))]
+    new_tag: String,
+}
+
+impl RenameTagCommandOptions {
+    /// Handles the `tag rename` subcommand
+    ///
+    /// The tag is renamed on all activities, if an activity already has the new
+    /// tag, both are merged.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new tag is invalid or the activity log could not be written
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the amount of renamed tags
+    #[tracing::instrument(skip(self))]
+    pub fn handle_rename_tag(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let old_tag = self.old_tag.trim();
+
+        let new_tag = normalize_tag(&self.new_tag)?;
+
+        let activity_store = ActivityStore::from_config(config)?;
+
+        let count = activity_store.rename_tag(old_tag, &new_tag)?;
+
+        if count == 0 {
+            return Ok(UserMessage::new(format!(
+                "No activities are tagged with {old_tag}."
+            )));
+        }
+
+        activity_store.sync()?;
+
+        Ok(UserMessage::new(format!(
+            "Renamed {old_tag} to {new_tag} on {count} activities."
+        )))
+    }
+}
+
+/// `tag list` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct ListTagsCommandOptions {
+    /// Only show tags matching this pattern, wildcards supported
+    #[cfg_attr(feature = "clap", clap(value_name = "Pattern"))]
+    pattern: Option<String>,
+}
+
+impl ListTagsCommandOptions {
+    /// Handles the `tag list` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activity log could not be read
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with a table of the tags and how many activities use them
+    #[tracing::instrument(skip(self))]
+    pub fn handle_list_tags(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let activity_store = ActivityStore::with_storage(get_storage_from_config(config)?)?;
+
+        let mut tags = activity_store
+            .list_tags()?
+            .into_iter()
+            .filter(|(tag, _)| {
+                self.pattern.as_ref().map_or(true, |pattern| {
+                    WildMatch::new(&pattern.to_lowercase()).matches(&tag.to_lowercase())
+                })
+            })
+            .peekable();
+
+        if tags.peek().is_none() {
+            return Ok(UserMessage::new("No tags found."));
+        }
+
+        let mut builder = Builder::new();
+
+        builder.push_record(vec!["Tag", "Activities"]);
+
+        for (tag, count) in tags {
+            builder.push_record(vec![tag, count.to_string()]);
+        }
+
+        let table = builder
+            .build()
+            .with(Style::modern_rounded())
+            .with(Padding::new(1, 1, 0, 0))
+            .with(Modify::new(Columns::new(1..)).with(Alignment::right()))
+            .to_string();
+
+        Ok(UserMessage::new(table))
+    }
+}
+
+/// Check the tags given by the user and remove duplicates, keeping their order
+fn normalize_tags(tags: &[String]) -> PaceResult<Vec<String>> {
+    let mut normalized: Vec<String> = vec![];
+
+    for tag in tags {
+        let tag = normalize_tag(tag)?;
+
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+
+    Ok(normalized)
+}
+
+/// Find the activity to tag by its id or the beginning of it, or the currently active activity
+fn find_activity_id(
+    activity_store: &ActivityStore,
+    id_or_prefix: Option<&str>,
+) -> PaceResult<ActivityGuid> {
+    if let Some(id_or_prefix) = id_or_prefix {
+        return activity_store.find_activity_id(id_or_prefix);
+    }
+
+    activity_store
+        .most_recent_active_activity()?
+        .map(|activity_item| *activity_item.guid())
+        .ok_or_else(|| PaceErrorKind::NoActivityToTag.into())
+}
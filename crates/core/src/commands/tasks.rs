@@ -0,0 +1,381 @@
+#[cfg(feature = "clap")]
+use clap::Parser;
+use getset::Getters;
+use tabled::{
+    builder::Builder,
+    settings::{Padding, Style},
+};
+use tracing::debug;
+
+use crate::{
+    config::PaceConfig,
+    domain::{
+        priority::ItemPriorityKind,
+        status::TaskStatus,
+        task::{Task, TaskFilter},
+    },
+    error::{PaceErrorKind, PaceResult, UserMessage},
+    service::task_store::TaskStore,
+    storage::SyncStorage,
+};
+
+/// `tasks add` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct AddTaskCommandOptions {
+    /// The title of the task
+    #[cfg_attr(feature = "clap", clap(value_name = "Title"))]
+    title: String,
+
+    /// A longer description of the task
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Description"))]
+    description: Option<String>,
+
+    /// The priority of the task, defaults to the priority in the configuration
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Priority"))]
+    priority: Option<ItemPriorityKind>,
+
+    /// The tags you want to associate with the task, separated by a comma
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            short,
+            long,
+            value_name = "Tags",
+            visible_alias = "tag",
+            value_delimiter = ','
+        )
+    )]
+    tags: Option<Vec<String>>,
+}
+
+impl AddTaskCommandOptions {
+    /// Handles the `tasks add` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tasks file could not be read or written
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the id of the added task
+    #[tracing::instrument(skip(self))]
+    pub fn handle_add_task(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let mut task_store = TaskStore::from_config(config)?;
+
+        let task = Task::builder()
+            .title(self.title.clone())
+            .description(self.description.clone().unwrap_or_default())
+            .priority(
+                self.priority
+                    .or_else(|| *config.general().default_priority())
+                    .unwrap_or_default(),
+            )
+            .tags(self.tags.clone().unwrap_or_default())
+            .build();
+
+        let task_id = task_store.task_list_mut().add(task);
+
+        debug!("Added task: {task_id}");
+
+        task_store.sync()?;
+
+        Ok(UserMessage::new(format!(
+            "Added task \"{}\" with id {task_id}.",
+            self.title
+        )))
+    }
+}
+
+/// `tasks list` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct ListTasksCommandOptions {
+    /// Only show tasks with this status
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Status"))]
+    status: Option<TaskStatus>,
+
+    /// Only show tasks with this priority
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Priority"))]
+    priority: Option<ItemPriorityKind>,
+
+    /// Only show tasks that have all of these tags, separated by a comma
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            short,
+            long,
+            value_name = "Tags",
+            visible_alias = "tag",
+            value_delimiter = ','
+        )
+    )]
+    tags: Option<Vec<String>>,
+
+    /// Also show completed tasks
+    #[cfg_attr(feature = "clap", clap(short, long))]
+    all: bool,
+}
+
+impl ListTasksCommandOptions {
+    /// Handles the `tasks list` subcommand
+    ///
+    /// Completed tasks are only shown with `--all` or when filtering by their status.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tasks file could not be read
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with a table of the matching tasks
+    #[tracing::instrument(skip(self))]
+    pub fn handle_list_tasks(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let task_store = TaskStore::from_config(config)?;
+
+        let filter = TaskFilter::builder()
+            .status(self.status)
+            .priority(self.priority)
+            .tags(self.tags.clone().unwrap_or_default())
+            .include_completed(self.all)
+            .build();
+
+        let mut tasks = task_store.task_list().filter(&filter).peekable();
+
+        if tasks.peek().is_none() {
+            return Ok(UserMessage::new("No tasks found."));
+        }
+
+        let mut builder = Builder::new();
+
+        builder.push_record(vec!["Id", "Title", "Status", "Priority", "Tags"]);
+
+        for (task_id, task) in tasks {
+            builder.push_record(vec![
+                task_id.to_string(),
+                task.title().clone(),
+                task.status().to_string(),
+                task.priority().to_string(),
+                task.tags().join(", "),
+            ]);
+        }
+
+        let table = builder
+            .build()
+            .with(Style::modern_rounded())
+            .with(Padding::new(1, 1, 0, 0))
+            .to_string();
+
+        Ok(UserMessage::new(table))
+    }
+}
+
+/// `tasks done` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct DoneTaskCommandOptions {
+    /// The id of the task, or the beginning of it
+    #[cfg_attr(feature = "clap", clap(value_name = "Task Id"))]
+    task_id: String,
+}
+
+impl DoneTaskCommandOptions {
+    /// Handles the `tasks done` subcommand
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task could not be found or the tasks file
+    /// could not be read or written
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the title of the completed task
+    #[tracing::instrument(skip(self))]
+    pub fn handle_done_task(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let mut task_store = TaskStore::from_config(config)?;
+
+        let task_id = task_store.task_list().find_id(&self.task_id)?;
+
+        let task = task_store
+            .task_list_mut()
+            .get_mut(&task_id)
+            .ok_or_else(|| PaceErrorKind::TaskNotFound(self.task_id.clone()))?;
+
+        if task.is_completed() {
+            return Ok(UserMessage::new(format!(
+                "Task \"{}\" has already been completed.",
+                task.title()
+            )));
+        }
+
+        task.complete();
+
+        let message = format!("Completed task \"{}\".", task.title());
+
+        task_store.sync()?;
+
+        Ok(UserMessage::new(message))
+    }
+}
+
+/// `tasks edit` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct EditTaskCommandOptions {
+    /// The id of the task, or the beginning of it
+    #[cfg_attr(feature = "clap", clap(value_name = "Task Id"))]
+    task_id: String,
+
+    /// The new title of the task
+    #[cfg_attr(feature = "clap", clap(long, value_name = "Title"))]
+    title: Option<String>,
+
+    /// The new description of the task
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Description"))]
+    description: Option<String>,
+
+    /// The new priority of the task
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Priority"))]
+    priority: Option<ItemPriorityKind>,
+
+    /// The new status of the task
+    #[cfg_attr(feature = "clap", clap(short, long, value_name = "Status"))]
+    status: Option<TaskStatus>,
+
+    /// The new tags of the task, separated by a comma, replacing the existing ones
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            short,
+            long,
+            value_name = "Tags",
+            visible_alias = "tag",
+            value_delimiter = ','
+        )
+    )]
+    tags: Option<Vec<String>>,
+}
+
+impl EditTaskCommandOptions {
+    /// Handles the `tasks edit` subcommand
+    ///
+    /// Only the given fields are changed. Setting the status to `completed`
+    /// marks the task as finished now, any other status reopens it.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task could not be found or the tasks file
+    /// could not be read or written
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the title of the edited task
+    #[tracing::instrument(skip(self))]
+    pub fn handle_edit_task(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let mut task_store = TaskStore::from_config(config)?;
+
+        let task_id = task_store.task_list().find_id(&self.task_id)?;
+
+        let task = task_store
+            .task_list_mut()
+            .get_mut(&task_id)
+            .ok_or_else(|| PaceErrorKind::TaskNotFound(self.task_id.clone()))?;
+
+        if let Some(title) = &self.title {
+            _ = task.set_title(title.clone());
+        }
+
+        if let Some(description) = &self.description {
+            _ = task.set_description(description.clone());
+        }
+
+        if let Some(priority) = self.priority {
+            _ = task.set_priority(priority);
+        }
+
+        if let Some(tags) = &self.tags {
+            _ = task.set_tags(tags.clone());
+        }
+
+        match self.status {
+            Some(TaskStatus::Completed) if !task.is_completed() => task.complete(),
+            Some(TaskStatus::Completed) | None => {}
+            Some(status) => {
+                _ = task.set_status(status).set_finished_at(None);
+            }
+        }
+
+        let message = format!("Edited task \"{}\".", task.title());
+
+        task_store.sync()?;
+
+        Ok(UserMessage::new(message))
+    }
+}
+
+/// `tasks rm` subcommand options
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[getset(get = "pub")]
+pub struct RemoveTaskCommandOptions {
+    /// The id of the task, or the beginning of it
+    #[cfg_attr(feature = "clap", clap(value_name = "Task Id"))]
+    task_id: String,
+}
+
+impl RemoveTaskCommandOptions {
+    /// Handles the `tasks rm` subcommand
+    ///
+    /// Activities that have been tracked for the task keep its id.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task could not be found or the tasks file
+    /// could not be read or written
+    ///
+    /// # Returns
+    ///
+    /// Returns a `UserMessage` with the title of the removed task
+    #[tracing::instrument(skip(self))]
+    pub fn handle_remove_task(&self, config: &PaceConfig) -> PaceResult<UserMessage> {
+        let mut task_store = TaskStore::from_config(config)?;
+
+        let task_id = task_store.task_list().find_id(&self.task_id)?;
+
+        let task = task_store
+            .task_list_mut()
+            .remove(&task_id)
+            .ok_or_else(|| PaceErrorKind::TaskNotFound(self.task_id.clone()))?;
+
+        task_store.sync()?;
+
+        Ok(UserMessage::new(format!(
+            "Removed task \"{}\".",
+            task.title()
+        )))
+    }
+}
@@ -1,17 +1,28 @@
 //! Pace Config
 
 use std::path::{Path, PathBuf};
-use std::{fmt::Display, fs};
+use std::{collections::BTreeMap, fmt::Display, fs};
 
 use chrono_tz::Tz;
 use getset::{Getters, MutGetters};
 use serde_derive::{Deserialize, Serialize};
 
 use directories::ProjectDirs;
+use pace_time::duration::{PaceDuration, PaceDurationRangeOptions};
 use strum_macros::EnumString;
 
 use crate::{
-    domain::{priority::ItemPriorityKind, reflection::ReflectionsFormatKind},
+    constants::{
+        PACE_ARCHIVE_DIRNAME, PACE_HISTORY_FILENAME, PACE_INBOX_FILENAME, PACE_TASKS_FILENAME,
+    },
+    domain::{
+        billing::Amount,
+        event::PaceEventKind,
+        goal::Goal,
+        priority::ItemPriorityKind,
+        reflection::ReflectionsFormatKind,
+        rounding::{RoundingModeKind, RoundingOptions},
+    },
     error::{PaceErrorKind, PaceResult},
 };
 
@@ -56,6 +67,41 @@ pub struct PaceConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[getset(get = "pub", get_mut = "pub")]
     auto_archival: Option<AutoArchivalConfig>,
+
+    /// The boundaries between short, medium and long activity sessions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", get_mut = "pub")]
+    duration_ranges: Option<PaceDurationRangeOptions>,
+
+    /// The rounding of the durations shown in reflections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", get_mut = "pub")]
+    rounding: Option<RoundingOptions>,
+
+    /// Local server configuration for the pace application
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", get_mut = "pub")]
+    server: Option<ServerConfig>,
+
+    /// Event subscriber configuration for the pace application
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", get_mut = "pub")]
+    events: Option<EventsConfig>,
+
+    /// Git configuration for the pace application
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", get_mut = "pub")]
+    git: Option<GitConfig>,
+
+    /// Billing configuration for the pace application
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", get_mut = "pub")]
+    billing: Option<BillingConfig>,
+
+    /// Targets for the time spent on categories per day or per week
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[getset(get = "pub", get_mut = "pub")]
+    goals: Vec<Goal>,
 }
 
 impl PaceConfig {
@@ -72,6 +118,72 @@ impl PaceConfig {
     pub fn set_time_zone(&mut self, time_zone: Tz) {
         *self.general_mut().default_time_zone_mut() = Some(time_zone);
     }
+
+    /// Get the path to the tasks file
+    ///
+    /// If no path is configured, the tasks file is stored next to the activity log file
+    #[must_use]
+    pub fn tasks_path(&self) -> PathBuf {
+        self.general.tasks_path.clone().unwrap_or_else(|| {
+            self.general
+                .activity_log_options
+                .path
+                .with_file_name(PACE_TASKS_FILENAME)
+        })
+    }
+
+    /// Get the path to the directory archived activities are stored in
+    ///
+    /// If no path is configured, the archive directory is located next to the activity log file
+    #[must_use]
+    pub fn archive_path(&self) -> PathBuf {
+        self.auto_archival
+            .as_ref()
+            .and_then(|auto_archival| auto_archival.archive_path.clone())
+            .unwrap_or_else(|| {
+                self.general
+                    .activity_log_options
+                    .path
+                    .with_file_name(PACE_ARCHIVE_DIRNAME)
+            })
+    }
+
+    /// Get the boundaries between short, medium and long activity sessions
+    ///
+    /// If no boundaries are configured, the defaults are used
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configured boundary for long sessions is below the one for short sessions
+    pub fn duration_range_options(&self) -> PaceResult<PaceDurationRangeOptions> {
+        Ok(self.duration_ranges.unwrap_or_default().validate()?)
+    }
+
+    /// Get the path to the inbox file
+    ///
+    /// If no path is configured, the inbox file is stored next to the activity log file
+    #[must_use]
+    pub fn inbox_path(&self) -> PathBuf {
+        self.general.inbox_path.clone().unwrap_or_else(|| {
+            self.general
+                .activity_log_options
+                .path
+                .with_file_name(PACE_INBOX_FILENAME)
+        })
+    }
+
+    /// Get the path to the history file
+    ///
+    /// If no path is configured, the history file is stored next to the activity log file
+    #[must_use]
+    pub fn history_path(&self) -> PathBuf {
+        self.general.history_path.clone().unwrap_or_else(|| {
+            self.general
+                .activity_log_options
+                .path
+                .with_file_name(PACE_HISTORY_FILENAME)
+        })
+    }
 }
 
 /// The general configuration for the pace application
@@ -103,6 +215,21 @@ pub struct GeneralConfig {
     #[getset(get = "pub", get_mut = "pub", set = "pub")]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     default_time_zone: Option<Tz>,
+
+    /// The path to the tasks file
+    /// Default: `tasks.pace.toml` next to the activity log file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tasks_path: Option<PathBuf>,
+
+    /// The path to the inbox file
+    /// Default: `inbox.pace.toml` next to the activity log file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inbox_path: Option<PathBuf>,
+
+    /// The path to the file the history of changes to the activity log is stored in
+    /// Default: `history.pace.jsonl` next to the activity log file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    history_path: Option<PathBuf>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Getters, MutGetters, Clone, Default)]
@@ -123,24 +250,48 @@ pub struct ActivityLogOptions {
     /// The storage type for the activity log
     /// Default: `file`
     storage_kind: ActivityLogStorageKind,
+
+    /// The number of entries after which a `journal` activity log is compacted to a snapshot
+    /// Default: `500`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    journal_compaction_threshold: Option<usize>,
 }
 
 /// The kind of activity log format
 /// Default: `toml`
 ///
-/// Options: `toml`, `json`, `yaml`
+/// Options: `toml`, `journal`
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, EnumString)]
 #[serde(rename_all = "lowercase")]
 #[non_exhaustive]
 pub enum ActivityLogFormatKind {
+    /// The whole activity log as a TOML file
     #[default]
     Toml,
+
+    /// An append-only journal of the changes to the activity log, one JSON entry per line
+    Journal,
+}
+
+impl ActivityLogFormatKind {
+    /// The format of the activity log at the given path, journals are stored as JSON lines
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "jsonl")
+        {
+            Self::Journal
+        } else {
+            Self::Toml
+        }
+    }
 }
 
 /// The kind of log storage
 /// Default: `file`
 ///
-/// Options: `file`, `database`
+/// Options: `file`, `database`, `git`
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, Default)]
 #[serde(rename_all = "kebab-case")]
 #[non_exhaustive]
@@ -148,6 +299,9 @@ pub enum ActivityLogStorageKind {
     #[default]
     File,
     Database,
+
+    /// A TOML file in a git repository, that is committed after every change
+    Git,
     #[cfg(test)]
     InMemory,
 }
@@ -160,6 +314,9 @@ impl Default for GeneralConfig {
             default_priority: Some(ItemPriorityKind::default()),
             most_recent_count: Some(9),
             default_time_zone: Some(Tz::UTC),
+            tasks_path: None,
+            inbox_path: None,
+            history_path: None,
         }
     }
 }
@@ -177,20 +334,33 @@ pub struct ReflectionsConfig {
 }
 
 /// The export configuration for the pace application
-#[derive(Debug, Deserialize, Default, Serialize, Getters, Clone)]
+#[derive(Debug, Deserialize, Serialize, Getters, Clone)]
 #[getset(get = "pub")]
-#[serde(rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case", default)]
 pub struct ExportConfig {
     /// If the export should include descriptions
+    /// Default: `true`
     include_descriptions: bool,
 
     /// If the export should include tags
+    /// Default: `true`
     include_tags: bool,
 
     /// The time format within the export
+    /// Default: `%Y-%m-%d %H:%M`
     time_format: String,
 }
 
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            include_descriptions: true,
+            include_tags: true,
+            time_format: "%Y-%m-%d %H:%M".to_string(),
+        }
+    }
+}
+
 /// The kind of database engine
 /// Default: `sqlite`
 ///
@@ -252,35 +422,204 @@ impl Default for PomodoroConfig {
 }
 
 /// The inbox configuration for the pace application
-#[derive(Debug, Deserialize, Default, Serialize, Getters, Clone)]
+#[derive(Debug, Deserialize, Serialize, Getters, Clone, Copy)]
 #[getset(get = "pub")]
-#[serde(rename_all = "kebab-case")]
+#[serde(default, rename_all = "kebab-case")]
 pub struct InboxConfig {
-    /// The default time to auto-archive items in the inbox (in days)
+    /// The time after which items in the inbox are archived (in days), `0` disables it
+    /// Default: `30`
     auto_archive_after_days: u32,
 
-    /// The default category for items in the inbox
-    default_priority: String,
+    /// The default priority for items in the inbox
+    /// Default: `medium`
+    default_priority: ItemPriorityKind,
 
     /// The maximum items the inbox should hold
+    /// Default: `100`
     max_size: u32,
 }
 
+impl Default for InboxConfig {
+    fn default() -> Self {
+        Self {
+            auto_archive_after_days: 30,
+            default_priority: ItemPriorityKind::default(),
+            max_size: 100,
+        }
+    }
+}
+
 /// The auto-archival configuration for the pace application
-#[derive(Debug, Deserialize, Default, Serialize, Getters, Clone)]
+#[derive(Debug, Deserialize, Serialize, Getters, Clone)]
 #[getset(get = "pub")]
-#[serde(rename_all = "kebab-case")]
+#[serde(default, rename_all = "kebab-case")]
 pub struct AutoArchivalConfig {
-    /// The default auto-archival time after which items should be archived (in days)
+    /// The time after which ended activities are archived (in days)
+    /// Default: `90`
     archive_after_days: u32,
 
-    /// The path to the archive file
-    archive_path: String,
+    /// The directory the archived activities are stored in, one activity log per year
+    /// Default: `archive` next to the activity log file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archive_path: Option<PathBuf>,
 
-    /// If auto-archival is enabled
+    /// If activities are archived automatically on startup
+    /// Default: `false`
     enabled: bool,
 }
 
+impl Default for AutoArchivalConfig {
+    fn default() -> Self {
+        Self {
+            archive_after_days: 90,
+            archive_path: None,
+            enabled: false,
+        }
+    }
+}
+
+/// The local server configuration for the pace application
+#[derive(Debug, Deserialize, Serialize, Getters, Clone)]
+#[getset(get = "pub")]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ServerConfig {
+    /// The port the server listens on, it only ever binds to the loopback interface
+    /// Default: `7878`
+    port: u16,
+
+    /// The token clients have to send as `Authorization: Bearer <token>`
+    /// Default: not set, no token is required
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            port: 7878,
+            token: None,
+        }
+    }
+}
+
+/// The git configuration for the pace application
+///
+/// Only used if the activity log is stored in git, see [`ActivityLogStorageKind::Git`]
+#[derive(Debug, Deserialize, Serialize, Getters, Clone)]
+#[getset(get = "pub")]
+#[serde(default, rename_all = "kebab-case")]
+pub struct GitConfig {
+    /// The URL or path of the repository `pace sync pull` and `pace sync push` use
+    /// Default: not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remote: Option<String>,
+
+    /// The branch of the remote repository to pull from and push to
+    /// Default: `main`
+    branch: String,
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        Self {
+            remote: None,
+            branch: "main".to_string(),
+        }
+    }
+}
+
+/// The billing configuration for the pace application
+///
+/// The rates are hourly rates in the currency, an activity is billed at the rate of its
+/// tags, its project, its category or the default rate, whichever is found first.
+#[derive(Debug, Deserialize, Serialize, Getters, Clone)]
+#[getset(get = "pub")]
+#[serde(default, rename_all = "kebab-case")]
+pub struct BillingConfig {
+    /// The currency of the rates, e.g. `EUR` or `USD`
+    /// Default: `EUR`
+    currency: String,
+
+    /// The rate of activities without a more specific rate, activities without a rate aren't billed
+    /// Default: not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_rate: Option<Amount>,
+
+    /// The rates by category, e.g. `acme` or `acme::support`
+    /// Default: `{}`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    category_rates: BTreeMap<String, Amount>,
+
+    /// The rates by project name or id, subprojects are billed at the rate of their parent
+    /// Default: `{}`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    project_rates: BTreeMap<String, Amount>,
+
+    /// The rates by tag, the highest rate is used for activities with several of them
+    /// Default: `{}`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    tag_rates: BTreeMap<String, Amount>,
+
+    /// The interval the billed time is rounded to (in minutes), `0` disables rounding
+    /// Default: `0`
+    rounding_minutes: u64,
+
+    /// How the billed time is rounded
+    /// Default: `up`
+    rounding_mode: RoundingModeKind,
+}
+
+impl BillingConfig {
+    /// The interval the billed time is rounded to
+    #[must_use]
+    pub const fn rounding_interval(&self) -> PaceDuration {
+        PaceDuration::new(self.rounding_minutes * 60)
+    }
+}
+
+impl Default for BillingConfig {
+    fn default() -> Self {
+        Self {
+            currency: "EUR".to_string(),
+            default_rate: None,
+            category_rates: BTreeMap::new(),
+            project_rates: BTreeMap::new(),
+            tag_rates: BTreeMap::new(),
+            rounding_minutes: 0,
+            rounding_mode: RoundingModeKind::default(),
+        }
+    }
+}
+
+/// The event configuration for the pace application
+#[derive(Debug, Deserialize, Serialize, Getters, Clone, Default)]
+#[getset(get = "pub")]
+#[serde(default, rename_all = "kebab-case")]
+pub struct EventsConfig {
+    /// The subscribers that are notified, when the state of an activity changes
+    /// Default: `[]`
+    subscribers: Vec<EventSubscriberConfig>,
+}
+
+/// A shell command that is run for the events it subscribed to
+#[derive(Debug, Deserialize, Serialize, Getters, Clone)]
+#[getset(get = "pub")]
+#[serde(rename_all = "kebab-case")]
+pub struct EventSubscriberConfig {
+    /// The kinds of events to run the command for, all events if empty
+    /// Default: `[]`
+    #[serde(default)]
+    events: Vec<PaceEventKind>,
+
+    /// The command to run with the shell, it receives the event as JSON on stdin
+    command: String,
+
+    /// The seconds to wait for the command to finish, before it is stopped
+    /// Default: `10`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timeout_secs: Option<u64>,
+}
+
 /// Get the current directory and then search upwards in the directory hierarchy for a file name
 ///
 /// # Arguments
@@ -6,17 +6,50 @@ pub mod activity;
 /// A log file  of activities
 pub mod activity_log;
 
+/// Billable rates and invoices
+pub mod billing;
+
 /// A category for activities
 pub mod category;
 
+/// Events published when the state of an activity changes
+pub mod event;
+
+/// Export of activities into other formats
+pub mod export;
+
 /// A filter for activities
 pub mod filter;
+
+/// Targets for the time spent per day or per week
+pub mod goal;
+
+/// The history of changes to the activity log, to undo and redo them
+pub mod history;
+
+/// Import of activities from other time trackers
+pub mod import;
 pub mod inbox;
 pub mod intermission;
+
+/// An append-only journal of the changes to the activity log
+pub mod journal;
+
+/// Pomodoro sessions with work and break phases
+pub mod pomodoro;
 pub mod priority;
 pub mod project;
 pub mod reflection;
+
+/// Rounding of the tracked time
+pub mod rounding;
+
+/// Statistics and comparisons for reflections
+pub mod statistics;
 pub mod status;
+
+/// Merging the activity logs of several devices
+pub mod sync;
 pub mod tag;
 pub mod task;
 
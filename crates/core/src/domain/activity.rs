@@ -9,19 +9,32 @@ use pace_time::{
     duration::{calculate_duration, duration_to_str, PaceDuration},
 };
 use serde_derive::{Deserialize, Serialize};
-use std::{collections::HashSet, fmt::Display};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fmt::Display,
+};
 use strum_macros::EnumString;
 use tracing::debug;
 use typed_builder::TypedBuilder;
 use ulid::Ulid;
 
 use crate::{
-    domain::status::ActivityStatusKind,
-    error::{ActivityLogErrorKind, PaceResult},
+    domain::{project::ProjectGuid, status::ActivityStatusKind, task::TaskGuid},
+    error::{ActivityLogErrorKind, PaceErrorKind, PaceResult},
 };
 
 #[derive(
-    Debug, TypedBuilder, Serialize, Getters, Setters, MutGetters, Clone, Eq, PartialEq, Default,
+    Debug,
+    TypedBuilder,
+    Serialize,
+    Deserialize,
+    Getters,
+    Setters,
+    MutGetters,
+    Clone,
+    Eq,
+    PartialEq,
+    Default,
 )]
 #[getset(get = "pub", get_mut = "pub", set = "pub")]
 pub struct ActivityItem {
@@ -81,8 +94,10 @@ impl From<(ActivityGuid, Activity)> for ActivityItem {
     PartialOrd,
     Ord,
     EnumString,
+    strum_macros::Display,
 )]
 #[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
 // #[serde(untagged)]
 pub enum ActivityKind {
     /// A generic activity
@@ -158,11 +173,10 @@ impl ActivityKind {
 }
 
 /// The cycle of pomodoro activity a user can track
-// TODO!: Optional: Track Pomodoro work/break cycles
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
-enum PomodoroCycle {
-    /// A work session
-    Work(usize), // usize could represent the work session number in a sequence
+pub enum PomodoroCycle {
+    /// A work session with its number within the pomodoro session
+    Work(usize),
 
     // A break
     #[default]
@@ -243,6 +257,18 @@ pub struct Activity {
     #[merge(strategy = crate::util::overwrite_left_with_right)]
     pomodoro_cycle_options: Option<PomodoroCycle>,
 
+    /// The task the activity is tracked for
+    #[builder(default, setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::util::overwrite_left_with_right)]
+    task_id: Option<TaskGuid>,
+
+    /// The project the activity is tracked for, followed by its parent projects
+    #[builder(default, setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::util::overwrite_left_with_right)]
+    projects: Option<Vec<ProjectGuid>>,
+
     #[serde(default)]
     #[builder(default)]
     #[merge(strategy = crate::util::overwrite_left_with_right)]
@@ -319,6 +345,41 @@ impl Default for ActivityGuid {
     }
 }
 
+impl ActivityGuid {
+    /// Find an id by the id itself or a unique prefix of it
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_ids` - The ids to search in
+    /// * `id_or_prefix` - The id of the activity or the beginning of it, case insensitive
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no id or more than one id matches
+    ///
+    /// # Returns
+    ///
+    /// The matching id
+    pub fn find_by_prefix<'a>(
+        activity_ids: impl IntoIterator<Item = &'a Self>,
+        id_or_prefix: &str,
+    ) -> PaceResult<Self> {
+        let prefix = id_or_prefix.trim().to_uppercase();
+
+        let mut matches = activity_ids.into_iter().filter(|activity_id| {
+            !prefix.is_empty() && activity_id.to_string().starts_with(&prefix)
+        });
+
+        match (matches.next(), matches.next()) {
+            (Some(activity_id), None) => Ok(*activity_id),
+            (Some(_), Some(_)) => {
+                Err(PaceErrorKind::AmbiguousActivityId(id_or_prefix.to_string()).into())
+            }
+            (None, _) => Err(PaceErrorKind::ActivityIdNotFound(id_or_prefix.to_string()).into()),
+        }
+    }
+}
+
 impl Display for Activity {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let time = self.begin.and_local_timezone(&Local);
@@ -353,6 +414,8 @@ impl Activity {
             .activity_kind_options(self.activity_kind_options.clone())
             .pomodoro_cycle_options(self.pomodoro_cycle_options)
             .tags(self.tags.clone())
+            .task_id(self.task_id)
+            .projects(self.projects.clone())
             .build()
     }
 
@@ -385,6 +448,84 @@ impl Activity {
         self.status = ActivityStatusKind::Created;
     }
 
+    /// Check if the activity has a tag
+    #[must_use]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.as_ref().is_some_and(|tags| tags.contains(tag))
+    }
+
+    /// Add a tag to the activity
+    ///
+    /// Returns `true` if the activity didn't have the tag before
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        self.tags
+            .get_or_insert_with(HashSet::new)
+            .insert(tag.to_string())
+    }
+
+    /// Remove a tag from the activity
+    ///
+    /// Returns `true` if the activity had the tag
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let Some(tags) = self.tags.as_mut() else {
+            return false;
+        };
+
+        let removed = tags.remove(tag);
+
+        if tags.is_empty() {
+            self.tags = None;
+        }
+
+        removed
+    }
+
+    /// Rename a tag of the activity, merging it with the new tag if it already exists
+    ///
+    /// Returns `true` if the activity had the tag
+    pub fn rename_tag(&mut self, old_tag: &str, new_tag: &str) -> bool {
+        if !self.remove_tag(old_tag) {
+            return false;
+        }
+
+        _ = self.add_tag(new_tag);
+
+        true
+    }
+
+    /// The names of the fields that differ from another activity, e.g. `end` or `tags`
+    ///
+    /// The activities are compared by their serialized form, so differences
+    /// that don't survive being written to the activity log are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The activity to compare with
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be serialized to compare them
+    pub fn changed_fields(&self, other: &Self) -> PaceResult<Vec<String>> {
+        let fields = |activity: &Self| -> PaceResult<BTreeMap<String, serde_json::Value>> {
+            Ok(match serde_json::to_value(activity)? {
+                serde_json::Value::Object(fields) => fields.into_iter().collect(),
+                _ => BTreeMap::new(),
+            })
+        };
+
+        let own = fields(self)?;
+        let other = fields(other)?;
+
+        Ok(own
+            .keys()
+            .chain(other.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter(|field| own.get(*field) != other.get(*field))
+            .cloned()
+            .collect())
+    }
+
     /// Archive the activity
     /// This is only possible if the activity is not active and has ended
     pub fn archive(&mut self) {
@@ -522,6 +663,19 @@ impl Activity {
 
         Ok(end_opts.duration)
     }
+
+    /// Get the duration of the activity, or the time elapsed since its begin,
+    /// if the activity has not ended yet
+    ///
+    /// # Result
+    ///
+    /// Returns the duration of the activity
+    #[must_use]
+    pub fn duration_or_elapsed(&self) -> PaceDuration {
+        self.duration().unwrap_or_else(|_| {
+            calculate_duration(&self.begin, &PaceDateTime::now()).unwrap_or_default()
+        })
+    }
 }
 
 #[derive(
@@ -557,7 +711,7 @@ impl ActivitySession {
 
         Self {
             description: root_activity.activity().description().to_owned(),
-            adjusted_duration: root_activity.activity().duration().unwrap_or_default(),
+            adjusted_duration: root_activity.activity().duration_or_elapsed(),
             root_activity,
             ..Default::default()
         }
@@ -568,8 +722,13 @@ impl ActivitySession {
 
         debug!("Intermission: {:#?}", intermission.activity());
 
-        self.intermission_duration += intermission.activity().duration().unwrap_or_default();
-        self.adjusted_duration -= intermission.activity().duration().unwrap_or_default();
+        self.intermission_duration += intermission.activity().duration_or_elapsed();
+
+        // Pomodoro breaks follow their work session instead of interrupting it
+        if !intermission.activity().kind().is_pomodoro_intermission() {
+            self.adjusted_duration -= intermission.activity().duration_or_elapsed();
+        }
+
         self.intermissions.push(intermission);
     }
 
@@ -604,6 +763,12 @@ pub struct ActivityGroup {
 
     /// Activity sessions within the activity group
     activity_sessions: Vec<ActivitySession>,
+
+    /// The duration rounded with the rounding rule of the reflection, if any
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[getset(set = "pub")]
+    rounded_duration: Option<PaceDuration>,
 }
 
 impl ActivityGroup {
@@ -645,6 +810,7 @@ impl ActivityGroup {
             intermission_duration,
             intermission_count,
             activity_sessions,
+            rounded_duration: None,
         }
     }
 
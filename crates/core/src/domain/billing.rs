@@ -0,0 +1,414 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+    iter::Sum,
+    ops::{Add, AddAssign},
+};
+
+use getset::Getters;
+use pace_time::{duration::PaceDuration, time_range::TimeRangeOptions};
+use serde::{Deserializer, Serializer};
+use serde_derive::Serialize;
+use tracing::warn;
+
+use crate::{
+    config::BillingConfig,
+    domain::{
+        activity::Activity,
+        category::split_category_by_category_separator,
+        project::{ProjectGuid, ProjectList},
+        reflection::ReflectionSummary,
+    },
+};
+
+/// The minor units in a major unit of a currency, e.g. cents in a dollar
+const MINOR_UNITS_PER_MAJOR_UNIT: i64 = 100;
+
+/// The seconds in an hour, the unit of the rates
+const SECONDS_PER_HOUR: i128 = 3600;
+
+/// An amount of money in the minor unit of its currency, e.g. cents
+///
+/// Amounts are read and written as decimal numbers of the major unit, e.g. `85.5`,
+/// but computed in whole minor units, so summing them doesn't accumulate rounding errors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Amount(i64);
+
+impl Amount {
+    /// Create an amount from minor units, e.g. `8550` for `85.50`
+    #[must_use]
+    pub const fn from_minor_units(minor_units: i64) -> Self {
+        Self(minor_units)
+    }
+
+    /// Create an amount from a decimal number of major units, rounded to the nearest minor unit
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn from_major_units(major_units: f64) -> Self {
+        // Saturates for amounts out of range, which are no real amounts of money anyway
+        Self((major_units * 100.0).round() as i64)
+    }
+
+    /// The amount in minor units
+    #[must_use]
+    pub const fn minor_units(&self) -> i64 {
+        self.0
+    }
+
+    /// The amount as a decimal number of major units, e.g. for templates
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn as_major_units(&self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    /// The amount billed for a duration at this hourly rate, rounded to the nearest minor unit
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - The billed duration
+    #[must_use]
+    pub fn for_duration(self, duration: PaceDuration) -> Self {
+        let amount = i128::from(self.0) * i128::from(duration.as_secs());
+
+        // Round half away from zero, the division truncates towards zero
+        let rounded = (amount + amount.signum() * SECONDS_PER_HOUR / 2) / SECONDS_PER_HOUR;
+
+        Self(i64::try_from(rounded).unwrap_or(if rounded < 0 { i64::MIN } else { i64::MAX }))
+    }
+}
+
+impl Display for Amount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let minor_units = self.0.unsigned_abs();
+        let per_major_unit = MINOR_UNITS_PER_MAJOR_UNIT.unsigned_abs();
+
+        write!(
+            f,
+            "{sign}{}.{:02}",
+            minor_units / per_major_unit,
+            minor_units % per_major_unit
+        )
+    }
+}
+
+impl Add for Amount {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign for Amount {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sum for Amount {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl serde::Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.as_major_units())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <f64 as serde::Deserialize>::deserialize(deserializer).map(Self::from_major_units)
+    }
+}
+
+/// A line of an invoice, the sessions of an activity group that are billed at the same rate
+#[derive(Debug, Clone, Getters, Serialize, PartialEq, Eq)]
+#[getset(get = "pub")]
+pub struct InvoiceLineItem {
+    /// The category of the activities, e.g. `acme::website`
+    category: String,
+
+    /// The description of the activities
+    description: String,
+
+    /// The hourly rate the activities are billed at
+    rate: Amount,
+
+    /// The amount of activity sessions
+    session_count: usize,
+
+    /// The time spent on the activity sessions without their breaks
+    duration: PaceDuration,
+
+    /// The time that is billed, the duration rounded with the rounding rule of the billing configuration
+    billable_duration: PaceDuration,
+
+    /// The billed amount, the billable duration in hours times the rate, rounded to the minor unit
+    amount: Amount,
+}
+
+/// An invoice for the time spent within a time range
+///
+/// The line items are built from the activity groups of the summary. Sessions
+/// of an activity group with different rates are billed in separate line items.
+/// Each line item is rounded on its own, so rounding up to 15 minutes adds at
+/// most 15 minutes per line item, not per session.
+#[derive(Debug, Clone, Getters, Serialize, PartialEq, Eq)]
+#[getset(get = "pub")]
+pub struct Invoice {
+    /// The client that is billed, the category the activities belong to
+    client: Option<String>,
+
+    /// The currency of the rates and amounts, e.g. `EUR`
+    currency: String,
+
+    /// The time range that is billed
+    time_range: TimeRangeOptions,
+
+    /// The line items, ordered by category and description
+    line_items: Vec<InvoiceLineItem>,
+
+    /// The time spent on the billed sessions
+    duration: PaceDuration,
+
+    /// The time that is billed
+    billable_duration: PaceDuration,
+
+    /// The total amount of the line items
+    amount: Amount,
+
+    /// The time spent on sessions without a rate, which are not billed
+    unbilled_duration: PaceDuration,
+}
+
+impl Invoice {
+    /// Compute the invoice for the activities of a reflection summary
+    ///
+    /// # Arguments
+    ///
+    /// * `summary` - The reflection summary of the time range, grouped by category
+    /// * `billing` - The rates and the rounding rule
+    /// * `project_list` - The projects the project rates are looked up in, if any
+    /// * `client` - The client that is billed
+    /// * `category_separator` - The separator between category and subcategory, `::` if not set
+    ///
+    /// # Returns
+    ///
+    /// The invoice with a line item per activity group and rate, rates of projects
+    /// that can't be found in the project list are skipped
+    #[must_use]
+    pub fn new(
+        summary: &ReflectionSummary,
+        billing: &BillingConfig,
+        project_list: Option<&ProjectList>,
+        client: Option<String>,
+        category_separator: Option<&str>,
+    ) -> Self {
+        let project_rates = project_list
+            .map(|project_list| {
+                billing
+                    .project_rates()
+                    .iter()
+                    .filter_map(|(project, rate)| match project_list.find_id(project) {
+                        Ok(project_id) => Some((project_id, *rate)),
+                        Err(err) => {
+                            warn!("Skipping the rate of project '{project}' in `billing.project-rates`: {err}");
+                            None
+                        }
+                    })
+                    .collect::<HashMap<_, _>>()
+            })
+            .unwrap_or_default();
+
+        let rates = BillingRates {
+            billing,
+            project_rates,
+            category_separator,
+        };
+
+        let mut line_items = vec![];
+        let mut unbilled_duration = PaceDuration::default();
+
+        for ((category, subcategory), summary_group) in summary.summary_groups_by_category() {
+            let category = if subcategory.is_empty() {
+                category.clone()
+            } else {
+                format!(
+                    "{category}{}{subcategory}",
+                    category_separator.unwrap_or("::")
+                )
+            };
+
+            for (description, activity_group) in summary_group.activity_groups_by_description() {
+                // The sessions of the activity group by their rate, in the order they are found
+                let mut sessions_by_rate = Vec::<(Amount, usize, PaceDuration)>::new();
+
+                for session in activity_group.activity_sessions() {
+                    let duration = *session.adjusted_duration();
+
+                    let Some(rate) = rates.rate(session.root_activity().activity()) else {
+                        unbilled_duration += duration;
+                        continue;
+                    };
+
+                    if let Some((_, session_count, total)) = sessions_by_rate
+                        .iter_mut()
+                        .find(|(other_rate, ..)| *other_rate == rate)
+                    {
+                        *session_count += 1;
+                        *total += duration;
+                    } else {
+                        sessions_by_rate.push((rate, 1, duration));
+                    }
+                }
+
+                for (rate, session_count, duration) in sessions_by_rate {
+                    let billable_duration = billing
+                        .rounding_mode()
+                        .round(duration, billing.rounding_interval());
+
+                    line_items.push(InvoiceLineItem {
+                        category: category.clone(),
+                        description: description.clone(),
+                        rate,
+                        session_count,
+                        duration,
+                        billable_duration,
+                        amount: rate.for_duration(billable_duration),
+                    });
+                }
+            }
+        }
+
+        let mut invoice = Self {
+            client,
+            currency: billing.currency().clone(),
+            time_range: *summary.time_range(),
+            line_items: vec![],
+            duration: PaceDuration::default(),
+            billable_duration: PaceDuration::default(),
+            amount: Amount::default(),
+            unbilled_duration,
+        };
+
+        for line_item in line_items {
+            invoice.duration += line_item.duration;
+            invoice.billable_duration += line_item.billable_duration;
+            invoice.amount += line_item.amount;
+            invoice.line_items.push(line_item);
+        }
+
+        invoice
+    }
+}
+
+/// The rates of the billing configuration, with the projects looked up by their id
+struct BillingRates<'a> {
+    billing: &'a BillingConfig,
+    project_rates: HashMap<ProjectGuid, Amount>,
+    category_separator: Option<&'a str>,
+}
+
+impl BillingRates<'_> {
+    /// The hourly rate an activity is billed at, `None` if it isn't billed
+    ///
+    /// The most specific rate is used: the highest rate of the tags of the activity,
+    /// the rate of its project or the closest parent project, of its category,
+    /// of the category without the subcategory and finally the default rate.
+    fn rate(&self, activity: &Activity) -> Option<Amount> {
+        let tag_rate = activity
+            .tags()
+            .iter()
+            .flatten()
+            .filter_map(|tag| self.billing.tag_rates().get(tag).copied())
+            .max();
+
+        let project_rate = || {
+            activity
+                .projects()
+                .iter()
+                .flatten()
+                .find_map(|project_id| self.project_rates.get(project_id).copied())
+        };
+
+        let category_rate = || {
+            let category = activity.category().as_deref()?;
+
+            self.billing
+                .category_rates()
+                .get(category)
+                .copied()
+                .or_else(|| {
+                    let (category, subcategory) =
+                        split_category_by_category_separator(category, self.category_separator);
+
+                    // Only look up the category without the subcategory, if there is one
+                    subcategory.and_then(|_| self.billing.category_rates().get(&category).copied())
+                })
+        };
+
+        tag_rate
+            .or_else(project_rate)
+            .or_else(category_rate)
+            .or_else(|| *self.billing.default_rate())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use serde_derive::Deserialize;
+
+    use crate::error::TestResult;
+
+    use super::*;
+
+    #[test]
+    fn test_amount_for_duration_rounds_to_minor_units_passes() {
+        let rate = Amount::from_major_units(85.1);
+
+        assert_eq!(rate.minor_units(), 8510);
+        assert_eq!(
+            rate.for_duration(PaceDuration::from_seconds(15 * 60)),
+            Amount::from_minor_units(2128),
+            "A quarter of 85.10 is 21.275, which rounds up."
+        );
+        assert_eq!(
+            Amount::from_minor_units(1).for_duration(PaceDuration::from_seconds(1799)),
+            Amount::default()
+        );
+    }
+
+    #[test]
+    fn test_amount_sums_without_rounding_errors_passes() {
+        let total = [0.1, 0.2, 0.7]
+            .into_iter()
+            .map(Amount::from_major_units)
+            .sum::<Amount>();
+
+        assert_eq!(total, Amount::from_minor_units(100));
+        assert_eq!(total.to_string(), "1.00");
+        assert_eq!(Amount::from_minor_units(-1205).to_string(), "-12.05");
+    }
+
+    #[test]
+    fn test_amount_deserializes_from_major_units_passes() -> TestResult<()> {
+        #[derive(Deserialize)]
+        struct Rates {
+            decimal: Amount,
+            integer: Amount,
+        }
+
+        let rates = toml::from_str::<Rates>("decimal = 85.5\ninteger = 30")?;
+
+        assert_eq!(rates.decimal, Amount::from_minor_units(8550));
+        assert_eq!(rates.integer, Amount::from_minor_units(3000));
+        assert_eq!(serde_json::to_string(&rates.decimal)?, "85.5");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use serde_derive::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+use crate::domain::activity::{ActivityGuid, ActivityItem};
+
+/// An event published when the state of an activity changes
+///
+/// The event carries the activity item after the change, so subscribers
+/// don't need to read the activity log themselves.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum PaceEvent {
+    /// An activity has begun
+    ActivityBegan(ActivityItem),
+
+    /// An activity has ended
+    ActivityEnded(ActivityItem),
+
+    /// An activity has been held for an intermission
+    ActivityHeld(ActivityItem),
+
+    /// A held activity has been resumed
+    ActivityResumed(ActivityItem),
+
+    /// The details of an activity have been adjusted
+    ActivityAdjusted(ActivityItem),
+}
+
+impl PaceEvent {
+    /// The kind of the event
+    #[must_use]
+    pub const fn kind(&self) -> PaceEventKind {
+        match self {
+            Self::ActivityBegan(_) => PaceEventKind::ActivityBegan,
+            Self::ActivityEnded(_) => PaceEventKind::ActivityEnded,
+            Self::ActivityHeld(_) => PaceEventKind::ActivityHeld,
+            Self::ActivityResumed(_) => PaceEventKind::ActivityResumed,
+            Self::ActivityAdjusted(_) => PaceEventKind::ActivityAdjusted,
+        }
+    }
+
+    /// The activity item the event is about
+    #[must_use]
+    pub const fn activity_item(&self) -> &ActivityItem {
+        match self {
+            Self::ActivityBegan(activity_item)
+            | Self::ActivityEnded(activity_item)
+            | Self::ActivityHeld(activity_item)
+            | Self::ActivityResumed(activity_item)
+            | Self::ActivityAdjusted(activity_item) => activity_item,
+        }
+    }
+
+    /// The id of the activity the event is about
+    #[must_use]
+    pub fn activity_id(&self) -> ActivityGuid {
+        *self.activity_item().guid()
+    }
+}
+
+/// The kind of a [`PaceEvent`], used to subscribe to events
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Display,
+    EnumString,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum PaceEventKind {
+    /// See [`PaceEvent::ActivityBegan`]
+    ActivityBegan,
+
+    /// See [`PaceEvent::ActivityEnded`]
+    ActivityEnded,
+
+    /// See [`PaceEvent::ActivityHeld`]
+    ActivityHeld,
+
+    /// See [`PaceEvent::ActivityResumed`]
+    ActivityResumed,
+
+    /// See [`PaceEvent::ActivityAdjusted`]
+    ActivityAdjusted,
+}
+
+#[cfg(test)]
+mod tests {
+
+    use serde_json::json;
+
+    use crate::{domain::activity::Activity, error::TestResult};
+
+    use super::*;
+
+    #[test]
+    fn test_pace_event_serializes_with_kind_passes() -> TestResult<()> {
+        let activity_item = ActivityItem::from(
+            Activity::builder()
+                .description("Test activity")
+                .category(Some("development::pace".to_string()))
+                .build(),
+        );
+
+        let event = PaceEvent::ActivityBegan(activity_item.clone());
+
+        let value = serde_json::to_value(&event)?;
+
+        assert_eq!(value["event"], json!("activity-began"));
+        assert_eq!(value["guid"], json!(activity_item.guid().to_string()));
+        assert_eq!(value["activity"]["description"], json!("Test activity"));
+        assert_eq!(event.kind().to_string(), "activity-began");
+        assert_eq!(event.activity_id(), *activity_item.guid());
+
+        Ok(())
+    }
+}
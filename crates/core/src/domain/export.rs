@@ -0,0 +1,527 @@
+//! Export of activities into formats other tools understand
+
+use chrono::{
+    format::{Item, StrftimeItems},
+    Utc,
+};
+use getset::Getters;
+use pace_time::date_time::PaceDateTime;
+use serde_derive::{Deserialize, Serialize};
+use strum_macros::EnumString;
+
+use crate::{
+    config::ExportConfig,
+    constants::{PACE_APP_NAME, PACE_CORE_VERSION},
+    domain::{
+        activity::{ActivityGuid, ActivityItem, ActivityKind},
+        status::ActivityStatusKind,
+    },
+    error::{PaceErrorKind, PaceResult},
+    util::{escape_csv_field, to_plain_text},
+};
+
+/// The maximum length of a content line in an iCalendar file in octets, excluding the line break
+const ICS_MAX_LINE_OCTETS: usize = 75;
+
+/// The format of date-time values in UTC within an iCalendar file
+const ICS_DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// The kind of export format
+/// Default: `json`
+///
+/// Options: `json`, `csv`, `ics`
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, EnumString, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum ExportFormatKind {
+    #[default]
+    Json,
+    Csv,
+    /// iCalendar, every activity becomes a `VEVENT`
+    #[cfg_attr(feature = "clap", value(alias = "ical"))]
+    Ics,
+}
+
+/// A single activity as it is exported
+#[derive(Debug, Serialize, Getters, Clone, Eq, PartialEq)]
+#[getset(get = "pub")]
+#[serde(rename_all = "kebab-case")]
+pub struct ExportedActivity {
+    /// The identifier of the activity
+    id: ActivityGuid,
+
+    /// The kind of the activity
+    kind: ActivityKind,
+
+    /// The status of the activity
+    status: ActivityStatusKind,
+
+    /// The category of the activity
+    category: Option<String>,
+
+    /// The description of the activity, `None` if descriptions are excluded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+
+    /// The start of the activity, formatted with the configured time format
+    begin: String,
+
+    /// The end of the activity, formatted with the configured time format
+    end: Option<String>,
+
+    /// The duration of the activity in seconds
+    duration: Option<u64>,
+
+    /// The sorted tags of the activity, `None` if tags are excluded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<String>>,
+
+    /// The parent activity of an intermission
+    parent_id: Option<ActivityGuid>,
+
+    /// The unformatted start of the activity
+    #[serde(skip)]
+    begin_time: PaceDateTime,
+
+    /// The unformatted end of the activity
+    #[serde(skip)]
+    end_time: Option<PaceDateTime>,
+}
+
+impl ExportedActivity {
+    /// Create an exported activity from an activity item
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_item` - The activity item to export
+    /// * `config` - The export configuration, the time format needs to be valid
+    #[must_use]
+    pub fn with_config(activity_item: &ActivityItem, config: &ExportConfig) -> Self {
+        let activity = activity_item.activity();
+
+        let time_format = config.time_format();
+
+        let end_options = activity.activity_end_options().as_ref();
+
+        let tags = config.include_tags().then(|| {
+            let mut tags = activity
+                .tags()
+                .iter()
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>();
+
+            tags.sort();
+
+            tags
+        });
+
+        Self {
+            id: *activity_item.guid(),
+            kind: *activity.kind(),
+            status: *activity.status(),
+            category: activity.category().clone(),
+            description: config
+                .include_descriptions()
+                .then(|| activity.description().clone()),
+            begin: activity.begin().inner().format(time_format).to_string(),
+            end: end_options.map(|opts| opts.end().inner().format(time_format).to_string()),
+            duration: end_options.map(|opts| opts.duration().inner()),
+            tags,
+            parent_id: activity.parent_id(),
+            begin_time: *activity.begin(),
+            end_time: end_options.map(|opts| *opts.end()),
+        }
+    }
+
+    /// The summary of the activity within a calendar
+    ///
+    /// Falls back to the category or the kind of the activity, if there
+    /// is no description to show.
+    fn summary(&self) -> PaceResult<String> {
+        if let Some(description) = self.description.as_ref().filter(|desc| !desc.is_empty()) {
+            return Ok(description.clone());
+        }
+
+        self.category.as_ref().map_or_else(
+            || to_plain_text(&self.kind),
+            |category| Ok(category.clone()),
+        )
+    }
+}
+
+/// Activities prepared for an export
+#[derive(Debug, Serialize, Getters, Clone, Eq, PartialEq)]
+#[getset(get = "pub")]
+#[serde(transparent)]
+pub struct ActivityExport {
+    /// The activities to export
+    activities: Vec<ExportedActivity>,
+
+    /// If the descriptions are included in the export
+    #[serde(skip)]
+    include_descriptions: bool,
+
+    /// If the tags are included in the export
+    #[serde(skip)]
+    include_tags: bool,
+}
+
+impl ActivityExport {
+    /// Prepare activity items for an export
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_items` - The activity items to export
+    /// * `config` - The export configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configured time format is invalid
+    ///
+    /// # Returns
+    ///
+    /// The activities prepared for an export
+    pub fn with_config(activity_items: &[ActivityItem], config: &ExportConfig) -> PaceResult<Self> {
+        validate_time_format(config.time_format())?;
+
+        Ok(Self {
+            activities: activity_items
+                .iter()
+                .map(|item| ExportedActivity::with_config(item, config))
+                .collect(),
+            include_descriptions: *config.include_descriptions(),
+            include_tags: *config.include_tags(),
+        })
+    }
+
+    /// Render the activities in the given format
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The format to render the activities in
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities could not be serialized
+    ///
+    /// # Returns
+    ///
+    /// The rendered activities
+    pub fn render(&self, format: ExportFormatKind) -> PaceResult<String> {
+        match format {
+            ExportFormatKind::Json => Ok(serde_json::to_string_pretty(self)?),
+            ExportFormatKind::Csv => self.to_csv(),
+            ExportFormatKind::Ics => self.to_ics(),
+        }
+    }
+
+    /// Render the activities as CSV with a header row
+    ///
+    /// The description and tags columns are only present if they are included
+    /// in the export. Tags are separated by a semicolon.
+    fn to_csv(&self) -> PaceResult<String> {
+        let mut header = vec!["id", "kind", "status", "category"];
+
+        if self.include_descriptions {
+            header.push("description");
+        }
+
+        header.extend(["begin", "end", "duration"]);
+
+        if self.include_tags {
+            header.push("tags");
+        }
+
+        header.push("parent-id");
+
+        let mut lines = vec![header.join(",")];
+
+        for activity in &self.activities {
+            let mut fields = vec![
+                activity.id.to_string(),
+                to_plain_text(&activity.kind)?,
+                to_plain_text(&activity.status)?,
+                activity.category.clone().unwrap_or_default(),
+            ];
+
+            if let Some(description) = &activity.description {
+                fields.push(description.clone());
+            }
+
+            fields.extend([
+                activity.begin.clone(),
+                activity.end.clone().unwrap_or_default(),
+                activity
+                    .duration
+                    .map(|duration| duration.to_string())
+                    .unwrap_or_default(),
+            ]);
+
+            if let Some(tags) = &activity.tags {
+                fields.push(tags.join(";"));
+            }
+
+            fields.push(
+                activity
+                    .parent_id
+                    .map(|parent_id| parent_id.to_string())
+                    .unwrap_or_default(),
+            );
+
+            lines.push(
+                fields
+                    .iter()
+                    .map(|field| escape_csv_field(field))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+        }
+
+        let mut csv = lines.join("\n");
+        csv.push('\n');
+
+        Ok(csv)
+    }
+
+    /// Render the activities as an iCalendar file as described in RFC 5545
+    ///
+    /// Every activity becomes a `VEVENT`, activities that are still active
+    /// have no `DTEND`.
+    fn to_ics(&self) -> PaceResult<String> {
+        let timestamp = Utc::now().format(ICS_DATE_TIME_FORMAT).to_string();
+
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!("PRODID:-//pace-rs//{PACE_APP_NAME} {PACE_CORE_VERSION}//EN"),
+            "CALSCALE:GREGORIAN".to_string(),
+        ];
+
+        for activity in &self.activities {
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}@pace.cli.rs", activity.id));
+            lines.push(format!("DTSTAMP:{timestamp}"));
+            lines.push(format!(
+                "DTSTART:{}",
+                format_ics_date_time(&activity.begin_time)
+            ));
+
+            if let Some(end_time) = &activity.end_time {
+                lines.push(format!("DTEND:{}", format_ics_date_time(end_time)));
+            }
+
+            lines.push(format!("SUMMARY:{}", escape_ics_text(&activity.summary()?)));
+
+            let mut categories = activity
+                .category
+                .iter()
+                .chain(activity.tags.iter().flatten())
+                .map(|category| escape_ics_text(category))
+                .collect::<Vec<_>>();
+
+            categories.dedup();
+
+            if !categories.is_empty() {
+                lines.push(format!("CATEGORIES:{}", categories.join(",")));
+            }
+
+            if let Some(parent_id) = activity.parent_id {
+                lines.push(format!("RELATED-TO:{parent_id}@pace.cli.rs"));
+            }
+
+            lines.push("END:VEVENT".to_string());
+        }
+
+        lines.push("END:VCALENDAR".to_string());
+
+        Ok(lines
+            .iter()
+            .map(|line| fold_ics_line(line) + "\r\n")
+            .collect())
+    }
+}
+
+/// Validate a time format, so formatting a date-time with it doesn't fail
+///
+/// # Arguments
+///
+/// * `time_format` - The time format to validate
+///
+/// # Errors
+///
+/// Returns an error if the time format contains an invalid specifier
+pub fn validate_time_format(time_format: &str) -> PaceResult<()> {
+    if StrftimeItems::new(time_format).any(|item| matches!(item, Item::Error)) {
+        return Err(PaceErrorKind::InvalidTimeFormat(time_format.to_string()).into());
+    }
+
+    Ok(())
+}
+
+/// Format a date-time in UTC as used within an iCalendar file
+fn format_ics_date_time(date_time: &PaceDateTime) -> String {
+    date_time
+        .inner()
+        .with_timezone(&Utc)
+        .format(ICS_DATE_TIME_FORMAT)
+        .to_string()
+}
+
+/// Escape a text value within an iCalendar file
+fn escape_ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line of an iCalendar file, so no line is longer than 75 octets
+///
+/// Continuation lines start with a single space. Lines are only folded
+/// between characters, so multi-byte characters are never split.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_octets = 0;
+
+    for character in line.chars() {
+        let character_octets = character.len_utf8();
+
+        if line_octets + character_octets > ICS_MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            line_octets = 1;
+        }
+
+        folded.push(character);
+        line_octets += character_octets;
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::collections::HashSet;
+
+    use chrono::DateTime;
+    use pace_time::duration::PaceDuration;
+
+    use super::*;
+    use crate::{
+        domain::activity::{Activity, ActivityEndOptions},
+        error::TestResult,
+    };
+
+    fn test_activity_items() -> TestResult<Vec<ActivityItem>> {
+        let begin = PaceDateTime::from(DateTime::parse_from_rfc3339("2024-03-01T09:00:00+01:00")?);
+
+        let end = PaceDateTime::from(DateTime::parse_from_rfc3339("2024-03-01T10:30:00+01:00")?);
+
+        let ended = Activity::builder()
+            .description("Review, then merge \"export\"")
+            .category("dev::pace".to_string())
+            .begin(begin)
+            .activity_end_options(Some(ActivityEndOptions::new(end, PaceDuration::new(5400))))
+            .tags(Some(HashSet::from(["rust".to_string(), "oss".to_string()])))
+            .status(ActivityStatusKind::Completed)
+            .build();
+
+        let active = Activity::builder()
+            .description("Writing docs")
+            .begin(end)
+            .status(ActivityStatusKind::InProgress)
+            .build();
+
+        Ok(vec![ActivityItem::from(ended), ActivityItem::from(active)])
+    }
+
+    #[test]
+    fn test_export_json_passes() -> TestResult<()> {
+        let export =
+            ActivityExport::with_config(&test_activity_items()?, &ExportConfig::default())?;
+
+        let json: serde_json::Value =
+            serde_json::from_str(&export.render(ExportFormatKind::Json)?)?;
+
+        assert_eq!(json[0]["begin"], "2024-03-01 09:00");
+        assert_eq!(json[0]["end"], "2024-03-01 10:30");
+        assert_eq!(json[0]["duration"], 5400);
+        assert_eq!(json[0]["tags"], serde_json::json!(["oss", "rust"]));
+        assert_eq!(json[1]["end"], serde_json::Value::Null);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_csv_passes() -> TestResult<()> {
+        let items = test_activity_items()?;
+
+        let export = ActivityExport::with_config(&items, &ExportConfig::default())?;
+
+        let csv = export.render(ExportFormatKind::Csv)?;
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            lines[0],
+            "id,kind,status,category,description,begin,end,duration,tags,parent-id"
+        );
+        assert_eq!(
+            lines[1],
+            format!(
+                "{},activity,completed,dev::pace,\"Review, then merge \"\"export\"\"\",2024-03-01 09:00,2024-03-01 10:30,5400,oss;rust,",
+                items[0].guid()
+            )
+        );
+        assert_eq!(
+            lines[2],
+            format!(
+                "{},activity,in-progress,,Writing docs,2024-03-01 10:30,,,,",
+                items[1].guid()
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_ics_passes() -> TestResult<()> {
+        let items = test_activity_items()?;
+
+        let export = ActivityExport::with_config(&items, &ExportConfig::default())?;
+
+        let ics = export.render(ExportFormatKind::Ics)?;
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert_eq!(ics.matches("DTEND:").count(), 1);
+        assert!(ics.contains(&format!("UID:{}@pace.cli.rs\r\n", items[0].guid())));
+        assert!(ics.contains("SUMMARY:Review\\, then merge \"export\"\r\n"));
+        assert!(ics.contains("DTSTART:20240301T080000Z\r\n"));
+        assert!(ics.contains("DTEND:20240301T093000Z\r\n"));
+        assert!(ics.contains("CATEGORIES:dev::pace,oss,rust\r\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fold_ics_line_passes() {
+        let line = format!("SUMMARY:{}", "ä".repeat(80));
+
+        let folded = fold_ics_line(&line);
+
+        assert!(folded
+            .split("\r\n")
+            .all(|line| line.len() <= ICS_MAX_LINE_OCTETS));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn test_invalid_time_format_fails() {
+        assert!(validate_time_format("%Y-%m-%d %H:%M").is_ok());
+        assert!(validate_time_format("%Y-%Q").is_err());
+    }
+}
@@ -3,8 +3,16 @@ use pace_time::time_range::TimeRangeOptions;
 use serde_derive::Serialize;
 use strum::EnumIter;
 use typed_builder::TypedBuilder;
+use wildmatch::WildMatch;
 
-use crate::{commands::reflect::ReflectCommandOptions, domain::activity::ActivityGuid};
+use crate::{
+    commands::{export::ExportCommandOptions, reflect::ReflectCommandOptions},
+    domain::{
+        activity::{Activity, ActivityGuid, ActivityKind},
+        project::ProjectFilter,
+        reflection::ReflectionGroupByKind,
+    },
+};
 
 /// Filter for activities
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter)]
@@ -31,7 +39,7 @@ pub enum ActivityFilterKind {
     /// Activities that are held
     Held,
 
-    /// Intermission
+    /// Intermissions, including pomodoro breaks
     Intermission,
 
     /// Time range
@@ -94,21 +102,161 @@ impl FilteredActivities {
 pub struct FilterOptions {
     category: Option<String>,
     case_sensitive: bool,
+    #[builder(default)]
+    activity_kind: Option<ActivityKind>,
+    #[builder(default)]
+    #[getset(set = "pub")]
+    project: Option<ProjectFilter>,
+    #[builder(default)]
+    tags: Option<Vec<String>>,
+    #[builder(default)]
+    group_by: ReflectionGroupByKind,
+}
+
+impl FilterOptions {
+    /// Check if a category matches the category filter
+    ///
+    /// The category filter supports wildcards, e.g. `dev::*`. If no category
+    /// filter is set, every category matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `category` - The category to check
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the category matches the filter, `false` otherwise
+    #[must_use]
+    pub fn matches_category(&self, category: &str) -> bool {
+        let Some(filter_category) = self.category.as_ref() else {
+            return true;
+        };
+
+        if self.case_sensitive {
+            WildMatch::new(filter_category).matches(category)
+        } else {
+            WildMatch::new(&filter_category.to_lowercase()).matches(&category.to_lowercase())
+        }
+    }
+
+    /// Check if a tag matches the tag filter
+    ///
+    /// The tag filter supports wildcards, e.g. `client-*`, and follows the
+    /// case sensitivity of the category filter. If no tag filter is set, every
+    /// tag matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The tag to check
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the tag matches any of the tags in the filter, `false` otherwise
+    #[must_use]
+    pub fn matches_tag(&self, tag: &str) -> bool {
+        let Some(filter_tags) = self.tags.as_ref() else {
+            return true;
+        };
+
+        filter_tags.iter().any(|filter_tag| {
+            if self.case_sensitive {
+                WildMatch::new(filter_tag).matches(tag)
+            } else {
+                WildMatch::new(&filter_tag.to_lowercase()).matches(&tag.to_lowercase())
+            }
+        })
+    }
+
+    /// Check if an activity has a tag that matches the tag filter
+    ///
+    /// If no tag filter is set, every activity matches, even untagged ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity` - The activity to check
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the activity matches the filter, `false` otherwise
+    #[must_use]
+    pub fn matches_tags(&self, activity: &Activity) -> bool {
+        if self.tags.is_none() {
+            return true;
+        }
+
+        activity
+            .tags()
+            .iter()
+            .flatten()
+            .any(|tag| self.matches_tag(tag))
+    }
+
+    /// Check if an activity kind matches the activity kind filter
+    ///
+    /// If no activity kind filter is set, every kind matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The activity kind to check
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the activity kind matches the filter, `false` otherwise
+    #[must_use]
+    pub fn matches_activity_kind(&self, kind: ActivityKind) -> bool {
+        self.activity_kind
+            .map_or(true, |filter_kind| filter_kind == kind)
+    }
+
+    /// Check if an activity belongs to the project of the project filter
+    ///
+    /// If no project filter is set, every activity matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity` - The activity to check
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the activity matches the filter, `false` otherwise
+    #[must_use]
+    pub fn matches_project(&self, activity: &Activity) -> bool {
+        self.project.as_ref().map_or(true, |project_filter| {
+            project_filter.matches(
+                activity.projects().as_deref(),
+                activity.category().as_deref().unwrap_or("Uncategorized"),
+            )
+        })
+    }
 }
 
 impl From<ReflectCommandOptions> for FilterOptions {
     fn from(options: ReflectCommandOptions) -> Self {
+        Self::from(&options)
+    }
+}
+impl From<&ReflectCommandOptions> for FilterOptions {
+    fn from(options: &ReflectCommandOptions) -> Self {
         Self {
             category: options.category().clone(),
             case_sensitive: *options.case_sensitive(),
+            activity_kind: None,
+            project: None,
+            tags: options.tags().clone(),
+            group_by: options.group_by().unwrap_or_default(),
         }
     }
 }
-impl From<&ReflectCommandOptions> for FilterOptions {
-    fn from(options: &ReflectCommandOptions) -> Self {
+
+impl From<&ExportCommandOptions> for FilterOptions {
+    fn from(options: &ExportCommandOptions) -> Self {
         Self {
             category: options.category().clone(),
             case_sensitive: *options.case_sensitive(),
+            activity_kind: *options.activity_kind(),
+            project: None,
+            tags: None,
+            group_by: ReflectionGroupByKind::default(),
         }
     }
 }
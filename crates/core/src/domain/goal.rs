@@ -0,0 +1,573 @@
+use std::collections::BTreeMap;
+
+use chrono::{Days, NaiveDate, Weekday};
+use getset::Getters;
+use pace_time::{date::PaceDate, duration::PaceDuration, time_range::TimeRangeOptions};
+use serde_derive::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+use typed_builder::TypedBuilder;
+
+use crate::domain::{activity::ActivitySession, filter::FilterOptions};
+
+/// The width of the progress bar of a goal, in characters
+const PROGRESS_BAR_WIDTH: u64 = 20;
+
+/// The period the target of a goal is set for
+///
+/// Options: `day`, `week`
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, EnumString, Display, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum GoalPeriodKind {
+    /// Every calendar day
+    Day,
+
+    /// Every calendar week, beginning on Monday
+    Week,
+}
+
+impl GoalPeriodKind {
+    /// The first day of the period a date belongs to
+    #[must_use]
+    pub const fn first_day(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Week => date.week(Weekday::Mon).first_day(),
+        }
+    }
+
+    /// The last day of the period beginning on a date
+    #[must_use]
+    pub fn last_day(self, first_day: NaiveDate) -> NaiveDate {
+        first_day
+            .checked_add_days(Days::new(self.days() - 1))
+            .unwrap_or(NaiveDate::MAX)
+    }
+
+    /// The first day of the period before the period beginning on a date
+    const fn previous(self, first_day: NaiveDate) -> Option<NaiveDate> {
+        first_day.checked_sub_days(Days::new(self.days()))
+    }
+
+    /// The first day of the period after the period beginning on a date
+    const fn next(self, first_day: NaiveDate) -> Option<NaiveDate> {
+        first_day.checked_add_days(Days::new(self.days()))
+    }
+
+    /// The amount of days of the period
+    const fn days(self) -> u64 {
+        match self {
+            Self::Day => 1,
+            Self::Week => 7,
+        }
+    }
+}
+
+/// A target for the time spent on a category per day or per week, e.g. 32 hours of `work::*` per week
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Getters, TypedBuilder)]
+#[getset(get = "pub")]
+#[serde(rename_all = "kebab-case")]
+pub struct Goal {
+    /// The name the goal is shown with, the category if not set
+    #[builder(default, setter(strip_option, into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+
+    /// The category the time counts towards the goal for, wildcards supported, e.g. `work::*`
+    #[builder(setter(into))]
+    category: String,
+
+    /// Whether the category is matched case sensitive
+    /// Default: `false`
+    #[builder(default)]
+    #[serde(default)]
+    case_sensitive: bool,
+
+    /// The hours of the target
+    /// Default: `0`
+    #[builder(default)]
+    #[serde(default)]
+    hours: u64,
+
+    /// The minutes of the target, in addition to the hours
+    /// Default: `0`
+    #[builder(default)]
+    #[serde(default)]
+    minutes: u64,
+
+    /// The period the target is set for
+    period: GoalPeriodKind,
+}
+
+impl Goal {
+    /// The name the goal is shown with, the category if no name is set
+    #[must_use]
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.category)
+    }
+
+    /// The time to spend per period
+    #[must_use]
+    pub const fn target(&self) -> PaceDuration {
+        PaceDuration::new((self.hours * 60 + self.minutes) * 60)
+    }
+
+    /// Check if the time spent on a category counts towards the goal
+    ///
+    /// The category is matched the same way as the category filter of reflections.
+    ///
+    /// # Arguments
+    ///
+    /// * `category` - The category to check, `Uncategorized` for activities without one
+    #[must_use]
+    pub fn matches_category(&self, category: &str) -> bool {
+        FilterOptions::builder()
+            .category(Some(self.category.clone()))
+            .case_sensitive(self.case_sensitive)
+            .build()
+            .matches_category(category)
+    }
+
+    /// The time spent per day on the activity sessions that count towards the goal
+    ///
+    /// Sessions count towards the day they began on.
+    ///
+    /// # Arguments
+    ///
+    /// * `sessions` - The activity sessions to sum up
+    #[must_use]
+    pub fn time_spent_by_day<'a>(
+        &self,
+        sessions: impl IntoIterator<Item = &'a ActivitySession>,
+    ) -> BTreeMap<PaceDate, PaceDuration> {
+        let mut days = BTreeMap::<PaceDate, PaceDuration>::new();
+
+        for session in sessions {
+            let activity = session.root_activity().activity();
+
+            if !self.matches_category(activity.category().as_deref().unwrap_or("Uncategorized")) {
+                continue;
+            }
+
+            *days.entry(PaceDate::from(activity.begin())).or_default() +=
+                *session.adjusted_duration();
+        }
+
+        days
+    }
+
+    /// Evaluate the progress towards the goal within a time range
+    ///
+    /// The time range is extended to whole periods, e.g. a weekly goal is evaluated
+    /// for the whole week when reflecting on today. The streak counts the periods
+    /// in a row the target has been reached in, up to the last period of the time
+    /// range. A period that is still in progress doesn't break the streak.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_spent_by_day` - The time spent per day, see [`Goal::time_spent_by_day`]
+    /// * `time_range` - The time range to evaluate the goal for
+    /// * `today` - The current date, periods ending before it are over
+    /// * `counted_since` - The first day the time spent has been counted since,
+    ///   the streak is only counted back to the first period beginning on or after it
+    ///
+    /// # Returns
+    ///
+    /// The progress towards the goal
+    #[must_use]
+    pub fn progress(
+        &self,
+        time_spent_by_day: &BTreeMap<PaceDate, PaceDuration>,
+        time_range: &TimeRangeOptions,
+        today: PaceDate,
+        counted_since: PaceDate,
+    ) -> GoalProgress {
+        let target = self.target();
+
+        let time_spent_in = |first_day: NaiveDate| {
+            let mut time_spent = PaceDuration::default();
+
+            for duration in time_spent_by_day
+                .range(PaceDate::new(first_day)..=PaceDate::new(self.period.last_day(first_day)))
+                .map(|(_, duration)| duration)
+            {
+                time_spent += *duration;
+            }
+
+            time_spent
+        };
+
+        let first_period = self
+            .period
+            .first_day(*PaceDate::from(time_range.start()).inner());
+
+        let last_period = self
+            .period
+            .first_day(*PaceDate::from(time_range.end()).inner());
+
+        let last_day = self.period.last_day(last_period);
+
+        let mut progress = GoalProgress {
+            goal: self.clone(),
+            first_day: PaceDate::new(first_period),
+            last_day: PaceDate::new(last_day),
+            in_progress: *today.inner() <= last_day,
+            period_count: 0,
+            periods_reached: 0,
+            target: PaceDuration::default(),
+            time_spent: PaceDuration::default(),
+            remaining: PaceDuration::default(),
+            percentage: 0,
+            streak: 0,
+            streak_ended: true,
+        };
+
+        let mut period = Some(first_period);
+
+        while let Some(first_day) = period.filter(|first_day| *first_day <= last_period) {
+            let time_spent = time_spent_in(first_day);
+
+            progress.period_count += 1;
+            progress.target += target;
+            progress.time_spent += time_spent;
+
+            if time_spent >= target {
+                progress.periods_reached += 1;
+            }
+
+            period = self.period.next(first_day);
+        }
+
+        progress.remaining = progress.target - progress.time_spent;
+
+        progress.percentage = if progress.target.is_zero() {
+            100
+        } else {
+            progress.time_spent.as_secs() * 100 / progress.target.as_secs()
+        };
+
+        let mut period = if progress.in_progress && time_spent_in(last_period) < target {
+            self.period.previous(last_period)
+        } else {
+            Some(last_period)
+        };
+
+        progress.streak_ended = loop {
+            let Some(first_day) = period else {
+                break true;
+            };
+
+            // The time spent before isn't known, the streak might go on
+            if first_day < *counted_since.inner() {
+                break false;
+            }
+
+            if time_spent_in(first_day) < target {
+                break true;
+            }
+
+            progress.streak += 1;
+
+            period = self.period.previous(first_day);
+        };
+
+        progress
+    }
+}
+
+/// The progress towards a goal within a time range
+#[derive(Debug, Serialize, Getters, Clone, PartialEq, Eq)]
+#[getset(get = "pub")]
+pub struct GoalProgress {
+    /// The goal
+    goal: Goal,
+
+    /// The first day of the first period of the time range
+    first_day: PaceDate,
+
+    /// The last day of the last period of the time range
+    last_day: PaceDate,
+
+    /// Whether the last period is still in progress
+    in_progress: bool,
+
+    /// The amount of periods within the time range
+    period_count: usize,
+
+    /// The amount of periods the target has been reached in
+    periods_reached: usize,
+
+    /// The target of all periods together
+    target: PaceDuration,
+
+    /// The time spent on the goal within the periods
+    time_spent: PaceDuration,
+
+    /// The time left to reach the target
+    remaining: PaceDuration,
+
+    /// The time spent in percent of the target, can exceed 100
+    percentage: u64,
+
+    /// The amount of periods in a row the target has been reached in
+    streak: usize,
+
+    /// Whether the streak ended within the days the time spent has been counted for,
+    /// otherwise it might reach further back
+    #[serde(skip)]
+    streak_ended: bool,
+}
+
+impl GoalProgress {
+    /// If the target has been reached
+    #[must_use]
+    pub fn is_reached(&self) -> bool {
+        self.time_spent >= self.target
+    }
+
+    /// A bar showing the time spent in relation to the target, e.g. `██████░░░░`
+    #[must_use]
+    pub fn progress_bar(&self) -> String {
+        let filled = self.percentage.min(100) * PROGRESS_BAR_WIDTH / 100;
+
+        (0..PROGRESS_BAR_WIDTH)
+            .map(|position| if position < filled { '█' } else { '░' })
+            .collect()
+    }
+
+    /// The period of the goal, in plural for more than one
+    fn periods(&self, count: usize) -> String {
+        if count == 1 {
+            self.goal.period.to_string()
+        } else {
+            format!("{}s", self.goal.period)
+        }
+    }
+}
+
+impl std::fmt::Display for GoalProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {:>3}% {} of {}",
+            self.goal.display_name(),
+            self.progress_bar(),
+            self.percentage,
+            self.time_spent,
+            self.target
+        )?;
+
+        if self.is_reached() {
+            write!(f, ", reached")?;
+        } else if self.in_progress {
+            write!(f, ", {} left", self.remaining)?;
+        } else {
+            write!(f, ", {} short", self.remaining)?;
+        }
+
+        if self.period_count > 1 {
+            write!(
+                f,
+                ", reached in {} of {} {}",
+                self.periods_reached,
+                self.period_count,
+                self.periods(self.period_count)
+            )?;
+        }
+
+        if self.streak > 0 {
+            write!(
+                f,
+                ", 🔥 {} {} in a row",
+                self.streak,
+                self.periods(self.streak)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use chrono::NaiveTime;
+    use rstest::rstest;
+
+    use pace_time::date_time::PaceDateTime;
+
+    use super::*;
+    use crate::error::TestResult;
+
+    fn date(day: &str) -> TestResult<PaceDate> {
+        Ok(day.parse::<PaceDate>()?)
+    }
+
+    fn time_range(start: &str, end: &str) -> TestResult<TimeRangeOptions> {
+        Ok(TimeRangeOptions::builder()
+            .start(PaceDateTime::try_from((
+                *date(start)?.inner(),
+                NaiveTime::MIN,
+            ))?)
+            .end(PaceDateTime::try_from((
+                *date(end)?.inner(),
+                NaiveTime::from_hms_opt(23, 59, 59).ok_or("Invalid time")?,
+            ))?)
+            .build())
+    }
+
+    fn time_spent_by_day(days: &[(&str, u64)]) -> TestResult<BTreeMap<PaceDate, PaceDuration>> {
+        days.iter()
+            .map(|(day, minutes)| Ok((date(day)?, PaceDuration::new(minutes * 60))))
+            .collect()
+    }
+
+    #[rstest]
+    #[case("work::*", "work::meetings", true)]
+    #[case("work::*", "Work::Meetings", true)]
+    #[case("work::*", "learning::rust", false)]
+    #[case("Uncategorized", "Uncategorized", true)]
+    fn test_goal_matches_category_passes(
+        #[case] goal_category: &str,
+        #[case] category: &str,
+        #[case] expected: bool,
+    ) {
+        let goal = Goal::builder()
+            .category(goal_category)
+            .minutes(30)
+            .period(GoalPeriodKind::Day)
+            .build();
+
+        assert_eq!(goal.matches_category(category), expected);
+    }
+
+    #[test]
+    fn test_daily_goal_progress_counts_streak_passes() -> TestResult<()> {
+        let goal = Goal::builder()
+            .name("Learning")
+            .category("learning")
+            .minutes(30)
+            .period(GoalPeriodKind::Day)
+            .build();
+
+        let time_spent = time_spent_by_day(&[
+            ("2024-03-10", 45),
+            ("2024-03-12", 30),
+            ("2024-03-13", 40),
+            ("2024-03-14", 10),
+        ])?;
+
+        // Today is still in progress, so it doesn't break the streak of the two days before
+        let progress = goal.progress(
+            &time_spent,
+            &time_range("2024-03-14", "2024-03-14")?,
+            date("2024-03-14")?,
+            date("2024-03-01")?,
+        );
+
+        assert_eq!(*progress.time_spent(), PaceDuration::new(10 * 60));
+        assert_eq!(*progress.remaining(), PaceDuration::new(20 * 60));
+        assert_eq!(*progress.percentage(), 33);
+        assert_eq!(*progress.streak(), 2);
+        assert!(*progress.streak_ended());
+        assert!(!progress.is_reached());
+        assert_eq!(
+            progress.progress_bar(),
+            format!("{}{}", "█".repeat(6), "░".repeat(14))
+        );
+        assert_eq!(
+            progress.to_string(),
+            format!(
+                "Learning {}{}  33% 10m of 30m, 20m left, 🔥 2 days in a row",
+                "█".repeat(6),
+                "░".repeat(14)
+            )
+        );
+
+        // Once the day is over, it breaks the streak
+        let progress = goal.progress(
+            &time_spent,
+            &time_range("2024-03-14", "2024-03-14")?,
+            date("2024-03-15")?,
+            date("2024-03-01")?,
+        );
+
+        assert_eq!(*progress.streak(), 0);
+        assert!(progress.to_string().ends_with(", 20m short"));
+
+        // Without the time spent before the streak, it might go on
+        let progress = goal.progress(
+            &time_spent,
+            &time_range("2024-03-14", "2024-03-14")?,
+            date("2024-03-14")?,
+            date("2024-03-13")?,
+        );
+
+        assert_eq!(*progress.streak(), 1);
+        assert!(!progress.streak_ended());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_weekly_goal_progress_extends_to_whole_weeks_passes() -> TestResult<()> {
+        let goal = Goal::builder()
+            .name("Work")
+            .category("work::*")
+            .hours(2)
+            .period(GoalPeriodKind::Week)
+            .build();
+
+        // Monday 2024-03-04 to Sunday 2024-03-17 and the week before
+        let time_spent = time_spent_by_day(&[
+            ("2024-02-26", 120),
+            ("2024-03-04", 60),
+            ("2024-03-10", 60),
+            ("2024-03-11", 30),
+        ])?;
+
+        let progress = goal.progress(
+            &time_spent,
+            &time_range("2024-03-06", "2024-03-12")?,
+            date("2024-03-12")?,
+            date("2024-02-01")?,
+        );
+
+        assert_eq!(*progress.first_day(), date("2024-03-04")?);
+        assert_eq!(*progress.last_day(), date("2024-03-17")?);
+        assert_eq!(*progress.period_count(), 2);
+        assert_eq!(*progress.periods_reached(), 1);
+        assert_eq!(*progress.target(), PaceDuration::new(4 * 3600));
+        assert_eq!(*progress.time_spent(), PaceDuration::new(150 * 60));
+        assert_eq!(*progress.percentage(), 62);
+        assert_eq!(*progress.streak(), 2);
+        assert!(progress
+            .to_string()
+            .ends_with(", reached in 1 of 2 weeks, 🔥 2 weeks in a row"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_goal_progress_without_time_spent_passes() -> TestResult<()> {
+        let goal = Goal::builder()
+            .category("work::*")
+            .hours(32)
+            .period(GoalPeriodKind::Week)
+            .build();
+
+        let progress = goal.progress(
+            &BTreeMap::new(),
+            &time_range("2024-03-14", "2024-03-14")?,
+            date("2024-03-14")?,
+            date("2024-03-01")?,
+        );
+
+        assert_eq!(*progress.remaining(), PaceDuration::new(32 * 3600));
+        assert_eq!(*progress.percentage(), 0);
+        assert_eq!(*progress.streak(), 0);
+        assert!(progress.to_string().starts_with("work::* ░░░░"));
+
+        Ok(())
+    }
+}
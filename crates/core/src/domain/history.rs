@@ -0,0 +1,395 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use getset::Getters;
+use pace_time::date_time::PaceDateTime;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        activity::{Activity, ActivityGuid},
+        journal::JournalOperationKind,
+    },
+    error::PaceResult,
+};
+
+/// The change of a single activity
+#[derive(Debug, Clone, Serialize, Deserialize, Getters, PartialEq, Eq)]
+#[getset(get = "pub")]
+#[serde(rename_all = "kebab-case")]
+pub struct ActivityChange {
+    /// The id of the changed activity
+    guid: ActivityGuid,
+
+    /// The activity before the change, `None` if it has been created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    before: Option<Activity>,
+
+    /// The activity after the change, `None` if it has been deleted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    after: Option<Activity>,
+}
+
+impl ActivityChange {
+    /// The description of the changed activity
+    #[must_use]
+    pub fn description(&self) -> Option<&str> {
+        self.after
+            .as_ref()
+            .or(self.before.as_ref())
+            .map(|activity| activity.description().as_str())
+    }
+
+    /// The names of the fields that have been changed, e.g. `end` or `tags`
+    ///
+    /// Empty if the activity has been created or deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be serialized to compare them
+    pub fn changed_fields(&self) -> PaceResult<Vec<String>> {
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => before.changed_fields(after),
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// A short summary of the change, e.g. `created` or `changed end, tags`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be serialized to compare them
+    pub fn summary(&self) -> PaceResult<String> {
+        Ok(match (&self.before, &self.after) {
+            (None, _) => "created".to_string(),
+            (_, None) => "deleted".to_string(),
+            _ => format!("changed {}", self.changed_fields()?.join(", ")),
+        })
+    }
+
+    /// Check if the activity is in the same state as before the change
+    ///
+    /// The activities are compared by their serialized form, so differences
+    /// that don't survive being written to the activity log are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity` - The current state of the activity, `None` if it doesn't exist
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be serialized to compare them
+    pub fn applies_to(&self, activity: Option<&Activity>) -> PaceResult<bool> {
+        Ok(serde_json::to_value(activity)? == serde_json::to_value(self.before.as_ref())?)
+    }
+
+    /// The change in the opposite direction
+    #[must_use]
+    fn inverted(&self) -> Self {
+        Self {
+            guid: self.guid,
+            before: self.after.clone(),
+            after: self.before.clone(),
+        }
+    }
+}
+
+/// An entry of the history of the activity log
+///
+/// An entry holds the changes of all activities affected by one command,
+/// together with their state before and after it, so they can be undone.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters, PartialEq, Eq)]
+#[getset(get = "pub")]
+#[serde(rename_all = "kebab-case")]
+pub struct HistoryEntry {
+    /// When the changes have been made
+    at: PaceDateTime,
+
+    /// The operation that made the changes
+    operation: JournalOperationKind,
+
+    /// The changes of the affected activities
+    changes: Vec<ActivityChange>,
+}
+
+impl HistoryEntry {
+    /// Record the changes an operation made to the activities
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation that changed the activities
+    /// * `before` - The activities before the operation
+    /// * `after` - The activities after the operation
+    ///
+    /// # Returns
+    ///
+    /// The history entry, or `None` if the operation didn't change anything
+    #[must_use]
+    pub fn from_changes(
+        operation: JournalOperationKind,
+        before: &BTreeMap<ActivityGuid, Activity>,
+        after: &BTreeMap<ActivityGuid, Activity>,
+    ) -> Option<Self> {
+        let changes = before
+            .keys()
+            .chain(after.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter(|guid| before.get(guid) != after.get(guid))
+            .map(|guid| ActivityChange {
+                guid: *guid,
+                before: before.get(guid).cloned(),
+                after: after.get(guid).cloned(),
+            })
+            .collect::<Vec<_>>();
+
+        if changes.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            at: PaceDateTime::now(),
+            operation,
+            changes,
+        })
+    }
+
+    /// Add the changes of a later operation of the same command to this entry
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The entry of the later operation
+    pub fn merge(&mut self, other: Self) {
+        for change in other.changes {
+            match self
+                .changes
+                .iter_mut()
+                .find(|existing| existing.guid == change.guid)
+            {
+                Some(existing) => existing.after = change.after,
+                None => self.changes.push(change),
+            }
+        }
+
+        self.changes.retain(|change| change.before != change.after);
+    }
+
+    /// An entry reverting the changes of this entry
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation reverting the changes, e.g. [`JournalOperationKind::Undo`]
+    #[must_use]
+    pub fn inverted(&self, operation: JournalOperationKind) -> Self {
+        Self {
+            at: PaceDateTime::now(),
+            operation,
+            changes: self.changes.iter().map(ActivityChange::inverted).collect(),
+        }
+    }
+
+    /// An entry making the changes of this entry again
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation making the changes again, e.g. [`JournalOperationKind::Redo`]
+    #[must_use]
+    pub fn repeated(&self, operation: JournalOperationKind) -> Self {
+        Self {
+            at: PaceDateTime::now(),
+            operation,
+            changes: self.changes.clone(),
+        }
+    }
+
+    /// The change of the activity with the given id, if it has been changed
+    #[must_use]
+    pub fn change_of(&self, activity_id: ActivityGuid) -> Option<&ActivityChange> {
+        self.changes
+            .iter()
+            .find(|change| change.guid == activity_id)
+    }
+
+    /// The descriptions of the changed activities
+    #[must_use]
+    pub fn descriptions(&self) -> Vec<&str> {
+        self.changes
+            .iter()
+            .filter_map(ActivityChange::description)
+            .collect()
+    }
+}
+
+/// The history of the activity log
+///
+/// Undoing and redoing are entries of the history themselves, so the history
+/// is a complete audit trail. Which entries can be undone or redone follows
+/// from replaying it like an undo and a redo stack.
+#[derive(Debug, Clone, Default, Getters, PartialEq, Eq)]
+#[getset(get = "pub")]
+pub struct History {
+    /// The entries of the history, oldest first
+    entries: Vec<HistoryEntry>,
+}
+
+impl From<Vec<HistoryEntry>> for History {
+    fn from(entries: Vec<HistoryEntry>) -> Self {
+        Self { entries }
+    }
+}
+
+impl History {
+    /// Add an entry to the history
+    pub fn push(&mut self, entry: HistoryEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Replay the history into an undo and a redo stack
+    fn stacks(&self) -> (Vec<&HistoryEntry>, Vec<&HistoryEntry>) {
+        let mut undo_stack = vec![];
+        let mut redo_stack = vec![];
+
+        for entry in &self.entries {
+            match entry.operation {
+                JournalOperationKind::Undo => redo_stack.extend(undo_stack.pop()),
+                JournalOperationKind::Redo => undo_stack.extend(redo_stack.pop()),
+                _ => {
+                    undo_stack.push(entry);
+                    redo_stack.clear();
+                }
+            }
+        }
+
+        (undo_stack, redo_stack)
+    }
+
+    /// The entry that is undone next, if there is one
+    #[must_use]
+    pub fn next_undo(&self) -> Option<&HistoryEntry> {
+        self.stacks().0.pop()
+    }
+
+    /// The entry that is redone next, if there is one
+    #[must_use]
+    pub fn next_redo(&self) -> Option<&HistoryEntry> {
+        self.stacks().1.pop()
+    }
+
+    /// The ids of all activities in the history
+    #[must_use]
+    pub fn activity_ids(&self) -> BTreeSet<ActivityGuid> {
+        self.entries
+            .iter()
+            .flat_map(|entry| entry.changes.iter().map(|change| change.guid))
+            .collect()
+    }
+
+    /// All changes of an activity, oldest first
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_id` - The id of the activity
+    #[must_use]
+    pub fn changes_of(&self, activity_id: ActivityGuid) -> Vec<(&HistoryEntry, &ActivityChange)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.change_of(activity_id).map(|change| (entry, change)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{domain::activity::ActivityItem, error::TestResult};
+
+    use super::*;
+
+    fn entry(
+        operation: JournalOperationKind,
+        before: &[&ActivityItem],
+        after: &[&ActivityItem],
+    ) -> Option<HistoryEntry> {
+        let to_map = |items: &[&ActivityItem]| {
+            items
+                .iter()
+                .map(|item| (*item.guid(), item.activity().clone()))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        HistoryEntry::from_changes(operation, &to_map(before), &to_map(after))
+    }
+
+    #[test]
+    fn test_history_undo_and_redo_stacks_passes() {
+        let first = ActivityItem::from(Activity::builder().description("First").build());
+        let second = ActivityItem::from(Activity::builder().description("Second").build());
+
+        let mut history = History::default();
+
+        let created = entry(JournalOperationKind::Create, &[], &[&first]);
+        let begun = entry(JournalOperationKind::Begin, &[&first], &[&first, &second]);
+
+        history.entries.extend(created.clone());
+        history.entries.extend(begun.clone());
+
+        assert_eq!(history.next_undo(), begun.as_ref());
+        assert_eq!(history.next_redo(), None);
+
+        let undo = begun
+            .as_ref()
+            .map(|e| e.inverted(JournalOperationKind::Undo));
+        history.entries.extend(undo.clone());
+
+        assert_eq!(history.next_undo(), created.as_ref());
+        assert_eq!(history.next_redo(), begun.as_ref());
+
+        assert_eq!(
+            undo.iter()
+                .flat_map(HistoryEntry::changes)
+                .map(|change| change.after().is_none())
+                .collect::<Vec<_>>(),
+            vec![true],
+            "Undoing the begin should remove the second activity"
+        );
+
+        history.entries.extend(
+            begun
+                .as_ref()
+                .map(|e| e.repeated(JournalOperationKind::Redo)),
+        );
+
+        assert_eq!(history.next_undo(), begun.as_ref());
+        assert_eq!(history.next_redo(), None);
+
+        assert_eq!(history.changes_of(*first.guid()).len(), 1);
+        assert_eq!(history.changes_of(*second.guid()).len(), 3);
+    }
+
+    #[test]
+    fn test_history_entry_merge_keeps_first_before_state_passes() -> TestResult<()> {
+        let activity = ActivityItem::from(Activity::builder().description("Original").build());
+
+        let mut adjusted = activity.clone();
+        adjusted
+            .activity_mut()
+            .set_description("Adjusted".to_string());
+
+        let mut merged =
+            entry(JournalOperationKind::Create, &[], &[&activity]).ok_or("nothing created")?;
+
+        merged.merge(
+            entry(JournalOperationKind::Update, &[&activity], &[&adjusted])
+                .ok_or("nothing updated")?,
+        );
+
+        let change = merged
+            .change_of(*activity.guid())
+            .ok_or("activity not changed")?;
+
+        assert_eq!(*change.before(), None);
+        assert_eq!(change.after().as_ref(), Some(adjusted.activity()));
+        assert_eq!(change.summary()?, "created");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,339 @@
+//! Import of activities from other time trackers
+//!
+//! Every supported format has its own parser, which maps the entries of the
+//! format onto [`Activity`]s. Entries that can't be mapped are skipped and
+//! reported, instead of failing the whole import.
+
+pub mod csv;
+pub mod timewarrior;
+pub mod watson;
+
+use std::collections::{BTreeSet, HashSet};
+
+use enum_dispatch::enum_dispatch;
+use getset::Getters;
+use pace_time::{date_time::PaceDateTime, duration::calculate_duration};
+use serde_derive::{Deserialize, Serialize};
+use strum_macros::EnumString;
+
+use crate::{
+    domain::{
+        activity::{Activity, ActivityEndOptions, ActivityKind},
+        status::ActivityStatusKind,
+    },
+    error::PaceResult,
+};
+
+use self::{csv::CsvParser, timewarrior::TimewarriorParser, watson::WatsonParser};
+
+/// The kind of import format
+///
+/// Options: `timewarrior`, `watson`, `csv`
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, EnumString, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum ImportFormatKind {
+    /// Timewarrior data files (`*.data`) or the output of `timew export`
+    #[cfg_attr(feature = "clap", value(alias = "timew"))]
+    Timewarrior,
+
+    /// The `frames` file of Watson
+    Watson,
+
+    /// CSV with a header row, the columns can be mapped to the fields of an activity
+    Csv,
+}
+
+/// The reason why an entry was skipped during an import
+#[derive(Debug, Clone, PartialEq, Eq, displaydoc::Display)]
+pub enum SkipReasonKind {
+    /// an activity with the same begin and description already exists
+    Duplicate,
+
+    /// the entry has no end, it is probably still being tracked
+    StillActive,
+
+    /// the entry ends before it begins
+    EndBeforeBegin,
+
+    /// entries of kind `{0}` are not supported
+    UnsupportedKind(String),
+
+    /// the entry is invalid: {0}
+    Invalid(String),
+}
+
+/// An entry that was skipped during an import
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct SkippedEntry {
+    /// The position of the entry within the imported file
+    ///
+    /// This is the line for Timewarrior data files, the row for CSV files
+    /// (the header being row `1`), and the index starting at `1` for JSON formats.
+    position: usize,
+
+    /// The reason why the entry was skipped
+    reason: SkipReasonKind,
+}
+
+/// The activities parsed from a file and the entries that were skipped
+#[derive(Debug, Clone, Default, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct ParsedActivities {
+    /// The activities together with the position of their entry within the imported file
+    activities: Vec<(usize, Activity)>,
+
+    /// The entries that were skipped
+    skipped: Vec<SkippedEntry>,
+}
+
+impl ParsedActivities {
+    /// Add the result of parsing a single entry
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position of the entry within the imported file
+    /// * `result` - The parsed activity or the reason why the entry was skipped
+    pub fn push(&mut self, position: usize, result: Result<Activity, SkipReasonKind>) {
+        match result {
+            Ok(activity) => self.activities.push((position, activity)),
+            Err(reason) => self.skipped.push(SkippedEntry { position, reason }),
+        }
+    }
+
+    /// Skip all activities that are duplicates of existing activities or of each other
+    ///
+    /// Activities are considered duplicates if they begin at the same time
+    /// and have the same description.
+    ///
+    /// # Arguments
+    ///
+    /// * `existing` - The activities that already exist in the activity log
+    ///
+    /// # Returns
+    ///
+    /// The parsed activities without duplicates
+    #[must_use]
+    pub fn without_duplicates<'a>(self, existing: impl IntoIterator<Item = &'a Activity>) -> Self {
+        let mut seen = existing
+            .into_iter()
+            .map(|activity| (*activity.begin(), activity.description().clone()))
+            .collect::<HashSet<_>>();
+
+        let mut skipped = self.skipped;
+        let mut activities = vec![];
+
+        for (position, activity) in self.activities {
+            if seen.insert((*activity.begin(), activity.description().clone())) {
+                activities.push((position, activity));
+            } else {
+                skipped.push(SkippedEntry {
+                    position,
+                    reason: SkipReasonKind::Duplicate,
+                });
+            }
+        }
+
+        skipped.sort_by_key(|entry| entry.position);
+
+        Self {
+            activities,
+            skipped,
+        }
+    }
+
+    /// Consume the parsed activities and return the activities and the skipped entries
+    #[must_use]
+    pub fn into_parts(self) -> (Vec<Activity>, Vec<SkippedEntry>) {
+        (
+            self.activities
+                .into_iter()
+                .map(|(_, activity)| activity)
+                .collect(),
+            self.skipped,
+        )
+    }
+}
+
+/// A parser for a format of another time tracker
+#[enum_dispatch(ActivityParserKind)]
+pub trait ActivityParser {
+    /// Parse the content of a file into activities
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The content of the file to import
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file as a whole can't be parsed, single invalid
+    /// entries are skipped instead
+    ///
+    /// # Returns
+    ///
+    /// The parsed activities and the skipped entries
+    fn parse(&self, content: &str) -> PaceResult<ParsedActivities>;
+}
+
+/// The available parsers
+#[enum_dispatch]
+#[derive(Debug, Clone)]
+pub enum ActivityParserKind {
+    TimewarriorParser,
+    WatsonParser,
+    CsvParser,
+}
+
+/// Create a completed activity from an imported entry
+///
+/// # Arguments
+///
+/// * `description` - The description of the activity
+/// * `category` - The category of the activity
+/// * `begin` - The begin of the activity
+/// * `end` - The end of the activity, `None` if the entry is still active
+/// * `tags` - The tags of the activity
+///
+/// # Errors
+///
+/// Returns the reason why the entry needs to be skipped, if it has no end,
+/// ends before it begins or has no description
+pub fn completed_activity(
+    description: String,
+    category: Option<String>,
+    begin: PaceDateTime,
+    end: Option<PaceDateTime>,
+    tags: BTreeSet<String>,
+) -> Result<Activity, SkipReasonKind> {
+    if description.trim().is_empty() {
+        return Err(SkipReasonKind::Invalid(
+            "the description is empty".to_string(),
+        ));
+    }
+
+    let Some(end) = end else {
+        return Err(SkipReasonKind::StillActive);
+    };
+
+    if end < begin {
+        return Err(SkipReasonKind::EndBeforeBegin);
+    }
+
+    let duration =
+        calculate_duration(&begin, &end).map_err(|err| SkipReasonKind::Invalid(err.to_string()))?;
+
+    Ok(Activity::builder()
+        .description(description)
+        .category(category)
+        .begin(begin)
+        .activity_end_options(Some(ActivityEndOptions::new(end, duration)))
+        .kind(ActivityKind::Activity)
+        .tags((!tags.is_empty()).then(|| tags.into_iter().collect()))
+        .status(ActivityStatusKind::Completed)
+        .build())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use chrono::DateTime;
+
+    use super::*;
+    use crate::error::TestResult;
+
+    fn date_time(rfc3339: &str) -> TestResult<PaceDateTime> {
+        Ok(PaceDateTime::from(DateTime::parse_from_rfc3339(rfc3339)?))
+    }
+
+    #[test]
+    fn test_completed_activity_passes() -> TestResult<()> {
+        let activity = completed_activity(
+            "Write docs".to_string(),
+            Some("dev".to_string()),
+            date_time("2024-03-01T09:00:00+01:00")?,
+            Some(date_time("2024-03-01T09:30:00+01:00")?),
+            BTreeSet::from(["docs".to_string()]),
+        )
+        .map_err(|reason| reason.to_string())?;
+
+        assert_eq!(activity.duration()?.inner(), 1800);
+        assert_eq!(activity.status(), &ActivityStatusKind::Completed);
+        assert_eq!(activity.tags().as_ref().map(HashSet::len), Some(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_completed_activity_without_end_fails() -> TestResult<()> {
+        let begin = date_time("2024-03-01T09:00:00+01:00")?;
+
+        assert_eq!(
+            completed_activity("Write docs".to_string(), None, begin, None, BTreeSet::new()),
+            Err(SkipReasonKind::StillActive)
+        );
+
+        assert_eq!(
+            completed_activity(
+                "Write docs".to_string(),
+                None,
+                begin,
+                Some(date_time("2024-03-01T08:00:00+01:00")?),
+                BTreeSet::new()
+            ),
+            Err(SkipReasonKind::EndBeforeBegin)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_without_duplicates_passes() -> TestResult<()> {
+        let begin = date_time("2024-03-01T09:00:00+01:00")?;
+        let end = Some(date_time("2024-03-01T10:00:00+01:00")?);
+
+        let existing =
+            completed_activity("Write docs".to_string(), None, begin, end, BTreeSet::new())
+                .map_err(|reason| reason.to_string())?;
+
+        let mut parsed = ParsedActivities::default();
+
+        // the same begin in another time zone is still a duplicate
+        parsed.push(
+            1,
+            completed_activity(
+                "Write docs".to_string(),
+                None,
+                date_time("2024-03-01T08:00:00Z")?,
+                end,
+                BTreeSet::new(),
+            ),
+        );
+        parsed.push(
+            2,
+            completed_activity("Review".to_string(), None, begin, end, BTreeSet::new()),
+        );
+        parsed.push(
+            3,
+            completed_activity("Review".to_string(), None, begin, end, BTreeSet::new()),
+        );
+
+        let (activities, skipped) = parsed.without_duplicates([&existing]).into_parts();
+
+        assert_eq!(activities.len(), 1);
+        assert_eq!(activities[0].description(), "Review");
+        assert_eq!(
+            skipped
+                .iter()
+                .map(|entry| entry.position)
+                .collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert!(skipped
+            .iter()
+            .all(|entry| entry.reason == SkipReasonKind::Duplicate));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,335 @@
+//! Parser for CSV files with a header row
+//!
+//! The columns of the file are mapped onto the fields of an activity by their
+//! name in the header row. By default, the names match the columns of `pace export`,
+//! so exported activities can be imported again.
+
+use std::{collections::BTreeSet, str::FromStr};
+
+use chrono::{DateTime, NaiveDateTime};
+use getset::Getters;
+use pace_time::date_time::PaceDateTime;
+
+use crate::{
+    config::ExportConfig,
+    domain::{
+        activity::Activity,
+        import::{completed_activity, ActivityParser, ParsedActivities, SkipReasonKind},
+    },
+    error::{PaceErrorKind, PaceResult},
+    util::parse_csv_records,
+};
+
+/// The separator between multiple tags within a single field
+const TAG_SEPARATOR: char = ';';
+
+/// The mapping of CSV columns onto the fields of an activity
+///
+/// The `begin`, `end` and `description` columns are required. The optional
+/// columns are only used if the file contains them.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct CsvColumnMapping {
+    /// The column containing the begin of an activity
+    begin: String,
+
+    /// The column containing the end of an activity
+    end: String,
+
+    /// The column containing the description of an activity
+    description: String,
+
+    /// The column containing the category of an activity
+    category: Option<String>,
+
+    /// The column containing the tags of an activity, separated by a semicolon
+    tags: Option<String>,
+
+    /// The column containing the kind of an activity, only activities are imported
+    kind: Option<String>,
+}
+
+impl Default for CsvColumnMapping {
+    fn default() -> Self {
+        Self {
+            begin: "begin".to_string(),
+            end: "end".to_string(),
+            description: "description".to_string(),
+            category: Some("category".to_string()),
+            tags: Some("tags".to_string()),
+            kind: Some("kind".to_string()),
+        }
+    }
+}
+
+impl FromStr for CsvColumnMapping {
+    type Err = PaceErrorKind;
+
+    /// Parse a mapping like `begin=Start,end=Stop,description=Task`
+    ///
+    /// Fields that are not mentioned keep their default column. Optional
+    /// columns can be ignored by mapping them to nothing, e.g. `kind=`.
+    fn from_str(mapping: &str) -> Result<Self, Self::Err> {
+        let mut columns = Self::default();
+
+        for pair in mapping.split(',').filter(|pair| !pair.trim().is_empty()) {
+            let Some((field, column)) = pair.split_once('=') else {
+                return Err(PaceErrorKind::InvalidCsvColumnMapping(pair.to_string()));
+            };
+
+            let column = column.trim().to_string();
+
+            let optional_column = (!column.is_empty()).then(|| column.clone());
+
+            match field.trim() {
+                "begin" if !column.is_empty() => columns.begin = column,
+                "end" if !column.is_empty() => columns.end = column,
+                "description" if !column.is_empty() => columns.description = column,
+                "category" => columns.category = optional_column,
+                "tags" => columns.tags = optional_column,
+                "kind" => columns.kind = optional_column,
+                _ => return Err(PaceErrorKind::InvalidCsvColumnMapping(pair.to_string())),
+            }
+        }
+
+        Ok(columns)
+    }
+}
+
+/// Parser for CSV files with a header row
+#[derive(Debug, Clone, Getters)]
+#[getset(get = "pub")]
+pub struct CsvParser {
+    /// The mapping of the columns onto the fields of an activity
+    columns: CsvColumnMapping,
+
+    /// The format of date-times without a time zone, they are interpreted in the local time zone
+    ///
+    /// Date-times in RFC 3339 format are always accepted.
+    time_format: String,
+}
+
+impl Default for CsvParser {
+    fn default() -> Self {
+        Self {
+            columns: CsvColumnMapping::default(),
+            time_format: ExportConfig::default().time_format().clone(),
+        }
+    }
+}
+
+/// The positions of the mapped columns within a record
+#[derive(Debug, Clone, Copy)]
+struct ColumnPositions {
+    begin: usize,
+    end: usize,
+    description: usize,
+    category: Option<usize>,
+    tags: Option<usize>,
+    kind: Option<usize>,
+}
+
+impl CsvParser {
+    /// Create a new parser for CSV files
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - The mapping of the columns onto the fields of an activity
+    /// * `time_format` - The format of date-times without a time zone
+    #[must_use]
+    pub const fn new(columns: CsvColumnMapping, time_format: String) -> Self {
+        Self {
+            columns,
+            time_format,
+        }
+    }
+
+    /// Parse a date-time either in RFC 3339 format or in the configured time format
+    fn parse_date_time(&self, date_time: &str) -> Result<PaceDateTime, SkipReasonKind> {
+        if let Ok(date_time) = DateTime::parse_from_rfc3339(date_time) {
+            return Ok(PaceDateTime::from(date_time));
+        }
+
+        let naive = NaiveDateTime::parse_from_str(date_time, &self.time_format)
+            .map_err(|err| SkipReasonKind::Invalid(format!("`{date_time}`: {err}")))?;
+
+        PaceDateTime::try_from(naive)
+            .map_err(|err| SkipReasonKind::Invalid(format!("`{date_time}`: {err}")))
+    }
+
+    /// Find the positions of the mapped columns within the header row
+    fn column_positions(&self, header: &[String]) -> PaceResult<ColumnPositions> {
+        let required_column = |column: &String| {
+            header
+                .iter()
+                .position(|name| name.trim() == column)
+                .ok_or_else(|| PaceErrorKind::CsvColumnNotFound(column.clone()))
+        };
+
+        let optional_column = |column: &Option<String>| {
+            column
+                .as_ref()
+                .and_then(|column| header.iter().position(|name| name.trim() == column))
+        };
+
+        Ok(ColumnPositions {
+            begin: required_column(&self.columns.begin)?,
+            end: required_column(&self.columns.end)?,
+            description: required_column(&self.columns.description)?,
+            category: optional_column(&self.columns.category),
+            tags: optional_column(&self.columns.tags),
+            kind: optional_column(&self.columns.kind),
+        })
+    }
+
+    /// Map a record onto an activity
+    fn parse_record(
+        &self,
+        record: &[String],
+        columns: ColumnPositions,
+    ) -> Result<Activity, SkipReasonKind> {
+        let field = |column: usize| record.get(column).map_or("", |field| field.trim());
+
+        let optional_field = |column: Option<usize>| {
+            column
+                .map(field)
+                .filter(|field| !field.is_empty())
+                .map(ToString::to_string)
+        };
+
+        if let Some(kind) = optional_field(columns.kind).filter(|kind| kind != "activity") {
+            return Err(SkipReasonKind::UnsupportedKind(kind));
+        }
+
+        let begin = self.parse_date_time(field(columns.begin))?;
+
+        let end = optional_field(Some(columns.end))
+            .map(|end| self.parse_date_time(&end))
+            .transpose()?;
+
+        let tags = optional_field(columns.tags)
+            .iter()
+            .flat_map(|tags| tags.split(TAG_SEPARATOR))
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(ToString::to_string)
+            .collect::<BTreeSet<_>>();
+
+        completed_activity(
+            field(columns.description).to_string(),
+            optional_field(columns.category),
+            begin,
+            end,
+            tags,
+        )
+    }
+}
+
+impl ActivityParser for CsvParser {
+    fn parse(&self, content: &str) -> PaceResult<ParsedActivities> {
+        let mut records = parse_csv_records(content).into_iter();
+
+        let columns = self.column_positions(&records.next().unwrap_or_default())?;
+
+        let mut parsed = ParsedActivities::default();
+
+        for (index, record) in records.enumerate() {
+            // The header is the first row
+            parsed.push(index + 2, self.parse_record(&record, columns));
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::error::TestResult;
+
+    #[test]
+    fn test_parse_column_mapping_passes() -> TestResult<()> {
+        let columns = CsvColumnMapping::from_str("begin=Start, end=Stop,category=,tags=Labels")?;
+
+        assert_eq!(columns.begin(), "Start");
+        assert_eq!(columns.end(), "Stop");
+        assert_eq!(columns.description(), "description");
+        assert_eq!(columns.category(), &None);
+        assert_eq!(columns.tags().as_deref(), Some("Labels"));
+
+        assert!(CsvColumnMapping::from_str("start=Start").is_err());
+        assert!(CsvColumnMapping::from_str("begin=").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_exported_csv_passes() -> TestResult<()> {
+        let content = "\
+id,kind,status,category,description,begin,end,duration,tags,parent-id
+01HQZ,activity,completed,dev::pace,\"Review, then merge\",2024-03-01 09:00,2024-03-01 10:30,5400,oss;rust,
+01HR0,intermission,completed,dev::pace,\"Review, then merge\",2024-03-01 09:30,2024-03-01 09:45,900,,01HQZ
+01HR1,activity,in-progress,,Writing docs,2024-03-01 10:30,,,,
+";
+
+        let (activities, skipped) = CsvParser::default().parse(content)?.into_parts();
+
+        assert_eq!(activities.len(), 1);
+        assert_eq!(activities[0].description(), "Review, then merge");
+        assert_eq!(activities[0].category().as_deref(), Some("dev::pace"));
+        assert_eq!(activities[0].duration()?.inner(), 5400);
+        assert_eq!(
+            activities[0].tags().as_ref().map(|tags| tags.len()),
+            Some(2)
+        );
+
+        assert_eq!(
+            skipped
+                .iter()
+                .map(|entry| (*entry.position(), entry.reason().clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    3,
+                    SkipReasonKind::UnsupportedKind("intermission".to_string())
+                ),
+                (4, SkipReasonKind::StillActive),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_mapped_csv_passes() -> TestResult<()> {
+        let content = "\
+Task,Project,Start,Stop
+Write docs,pace,2024-03-01T09:00:00+01:00,2024-03-01T09:30:00+01:00
+Review,pace,yesterday,2024-03-01T09:30:00+01:00
+";
+
+        let parser = CsvParser::new(
+            CsvColumnMapping::from_str("description=Task,category=Project,begin=Start,end=Stop")?,
+            "%d.%m.%Y %H:%M".to_string(),
+        );
+
+        let parsed = parser.parse(content)?;
+
+        assert_eq!(parsed.activities().len(), 1);
+        assert_eq!(parsed.activities()[0].1.duration()?.inner(), 1800);
+        assert!(matches!(
+            parsed.skipped()[0].reason(),
+            SkipReasonKind::Invalid(_)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_csv_without_required_column_fails() {
+        let result = CsvParser::default().parse("Task,Start,Stop\nWrite docs,1,2\n");
+
+        assert!(result.is_err());
+    }
+}
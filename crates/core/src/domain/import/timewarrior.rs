@@ -0,0 +1,281 @@
+//! Parser for Timewarrior data files and the output of `timew export`
+//!
+//! A line of a data file looks like this:
+//!
+//! ```text
+//! inc 20240301T080000Z - 20240301T093000Z # dev "code review" # "Review the export"
+//! ```
+//!
+//! The annotation becomes the description of the activity. If there is no
+//! annotation, the first tag is used as the description. All tags are kept
+//! as tags, Timewarrior has no notion of a category.
+
+use std::collections::BTreeSet;
+
+use chrono::{Local, NaiveDateTime};
+use pace_time::date_time::PaceDateTime;
+use serde_derive::Deserialize;
+
+use crate::{
+    domain::{
+        activity::Activity,
+        import::{completed_activity, ActivityParser, ParsedActivities, SkipReasonKind},
+    },
+    error::PaceResult,
+};
+
+/// The format of date-time values within Timewarrior data
+const TIMEWARRIOR_DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Parser for Timewarrior data
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimewarriorParser;
+
+/// An interval as it is contained in the output of `timew export`
+#[derive(Debug, Deserialize)]
+struct TimewarriorInterval {
+    start: String,
+    end: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    annotation: Option<String>,
+}
+
+impl ActivityParser for TimewarriorParser {
+    fn parse(&self, content: &str) -> PaceResult<ParsedActivities> {
+        let mut parsed = ParsedActivities::default();
+
+        // The output of `timew export` is a JSON array, data files are line based
+        if content.trim_start().starts_with('[') {
+            let intervals: Vec<TimewarriorInterval> = serde_json::from_str(content)?;
+
+            for (index, interval) in intervals.into_iter().enumerate() {
+                parsed.push(index + 1, interval_into_activity(interval));
+            }
+
+            return Ok(parsed);
+        }
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            parsed.push(
+                index + 1,
+                parse_data_line(line).and_then(interval_into_activity),
+            );
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Parse a line of a Timewarrior data file into an interval
+fn parse_data_line(line: &str) -> Result<TimewarriorInterval, SkipReasonKind> {
+    let tokens = tokenize(line);
+
+    let mut tokens = tokens
+        .iter()
+        .map(|(token, quoted)| (token.as_str(), *quoted));
+
+    if tokens.next() != Some(("inc", false)) {
+        return Err(SkipReasonKind::Invalid(
+            "a line needs to start with `inc`".to_string(),
+        ));
+    }
+
+    let Some((start, false)) = tokens.next() else {
+        return Err(SkipReasonKind::Invalid("the start is missing".to_string()));
+    };
+
+    let mut interval = TimewarriorInterval {
+        start: start.to_string(),
+        end: None,
+        tags: vec![],
+        annotation: None,
+    };
+
+    let mut next = tokens.next();
+
+    if next == Some(("-", false)) {
+        let Some((end, false)) = tokens.next() else {
+            return Err(SkipReasonKind::Invalid("the end is missing".to_string()));
+        };
+
+        interval.end = Some(end.to_string());
+
+        next = tokens.next();
+    }
+
+    match next {
+        None => return Ok(interval),
+        Some(("#", false)) => {}
+        Some((token, _)) => {
+            return Err(SkipReasonKind::Invalid(format!(
+                "unexpected `{token}`, expected `#`"
+            )))
+        }
+    }
+
+    // Everything up to the next unquoted `#` is a tag, the annotation follows after it
+    for (token, quoted) in tokens.by_ref() {
+        if (token, quoted) == ("#", false) {
+            interval.annotation = tokens
+                .map(|(token, _)| token)
+                .collect::<Vec<_>>()
+                .join(" ")
+                .into();
+
+            break;
+        }
+
+        interval.tags.push(token.to_string());
+    }
+
+    Ok(interval)
+}
+
+/// Split a line into whitespace separated tokens
+///
+/// Tokens can be quoted with double quotes to contain whitespace, quotes
+/// within them are escaped with a backslash. Every token is returned
+/// together with the information if it was quoted.
+fn tokenize(line: &str) -> Vec<(String, bool)> {
+    let mut tokens = vec![];
+    let mut chars = line.chars().peekable();
+
+    while let Some(&character) = chars.peek() {
+        if character.is_whitespace() {
+            _ = chars.next();
+            continue;
+        }
+
+        let mut token = String::new();
+
+        if character == '"' {
+            _ = chars.next();
+
+            while let Some(character) = chars.next() {
+                match character {
+                    '\\' => token.extend(chars.next()),
+                    '"' => break,
+                    _ => token.push(character),
+                }
+            }
+
+            tokens.push((token, true));
+        } else {
+            while let Some(&character) = chars.peek() {
+                if character.is_whitespace() {
+                    break;
+                }
+
+                token.push(character);
+                _ = chars.next();
+            }
+
+            tokens.push((token, false));
+        }
+    }
+
+    tokens
+}
+
+/// Parse a date-time in UTC as it is used by Timewarrior
+fn parse_date_time(date_time: &str) -> Result<PaceDateTime, SkipReasonKind> {
+    let date_time = NaiveDateTime::parse_from_str(date_time, TIMEWARRIOR_DATE_TIME_FORMAT)
+        .map_err(|err| SkipReasonKind::Invalid(format!("`{date_time}`: {err}")))?;
+
+    Ok(PaceDateTime::from(
+        date_time.and_utc().with_timezone(&Local).fixed_offset(),
+    ))
+}
+
+/// Map a Timewarrior interval onto an activity
+fn interval_into_activity(interval: TimewarriorInterval) -> Result<Activity, SkipReasonKind> {
+    let begin = parse_date_time(&interval.start)?;
+
+    let end = interval.end.as_deref().map(parse_date_time).transpose()?;
+
+    let description = interval
+        .annotation
+        .filter(|annotation| !annotation.trim().is_empty())
+        .or_else(|| interval.tags.first().cloned())
+        .unwrap_or_default();
+
+    completed_activity(
+        description,
+        None,
+        begin,
+        end,
+        interval.tags.into_iter().collect::<BTreeSet<_>>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::error::TestResult;
+
+    #[test]
+    fn test_parse_timewarrior_data_passes() -> TestResult<()> {
+        let content = r#"
+inc 20240301T080000Z - 20240301T093000Z # dev "code review" # "Review the export"
+inc 20240301T100000Z - 20240301T103000Z # meeting
+inc 20240301T110000Z - 20240301T113000Z
+inc 20240301T120000Z # dev
+exc monday <8:00:00
+"#;
+
+        let (activities, skipped) = TimewarriorParser.parse(content)?.into_parts();
+
+        assert_eq!(activities.len(), 2);
+        assert_eq!(activities[0].description(), "Review the export");
+        assert_eq!(activities[0].duration()?.inner(), 5400);
+        assert!(activities[0]
+            .tags()
+            .as_ref()
+            .is_some_and(|tags| tags.contains("code review") && tags.contains("dev")));
+        assert_eq!(activities[1].description(), "meeting");
+
+        assert_eq!(
+            skipped
+                .iter()
+                .map(|entry| (*entry.position(), entry.reason().clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    4,
+                    SkipReasonKind::Invalid("the description is empty".to_string())
+                ),
+                (5, SkipReasonKind::StillActive),
+                (
+                    6,
+                    SkipReasonKind::Invalid("a line needs to start with `inc`".to_string())
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_timewarrior_export_passes() -> TestResult<()> {
+        let content = r#"[
+{"id":2,"start":"20240301T080000Z","end":"20240301T093000Z","tags":["dev"],"annotation":"Review"},
+{"id":1,"start":"20240301T100000Z","tags":["dev"]}
+]"#;
+
+        let parsed = TimewarriorParser.parse(content)?;
+
+        assert_eq!(parsed.activities().len(), 1);
+        assert_eq!(parsed.activities()[0].1.description(), "Review");
+        assert_eq!(parsed.skipped().len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+//! Parser for the `frames` file of Watson
+//!
+//! The file is a JSON array of frames, every frame is an array of
+//! `[start, stop, project, id, tags, updated_at]` with Unix timestamps.
+//!
+//! The project becomes the category and the description of the activity,
+//! the tags are kept as tags.
+
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Local};
+use pace_time::date_time::PaceDateTime;
+use serde_json::Value;
+
+use crate::{
+    domain::{
+        activity::Activity,
+        import::{completed_activity, ActivityParser, ParsedActivities, SkipReasonKind},
+    },
+    error::PaceResult,
+};
+
+/// Parser for Watson frames
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WatsonParser;
+
+impl ActivityParser for WatsonParser {
+    fn parse(&self, content: &str) -> PaceResult<ParsedActivities> {
+        let frames: Vec<Value> = serde_json::from_str(content)?;
+
+        let mut parsed = ParsedActivities::default();
+
+        for (index, frame) in frames.iter().enumerate() {
+            parsed.push(index + 1, frame_into_activity(frame));
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Convert a Unix timestamp into a date-time in the local time zone
+fn parse_timestamp(timestamp: &Value) -> Result<PaceDateTime, SkipReasonKind> {
+    timestamp
+        .as_i64()
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+        .map(|date_time| PaceDateTime::from(date_time.with_timezone(&Local).fixed_offset()))
+        .ok_or_else(|| SkipReasonKind::Invalid(format!("`{timestamp}` is not a timestamp")))
+}
+
+/// Map a Watson frame onto an activity
+fn frame_into_activity(frame: &Value) -> Result<Activity, SkipReasonKind> {
+    let Some(frame) = frame.as_array().filter(|frame| frame.len() >= 3) else {
+        return Err(SkipReasonKind::Invalid(
+            "a frame needs to contain at least a start, a stop and a project".to_string(),
+        ));
+    };
+
+    let begin = parse_timestamp(&frame[0])?;
+
+    let end = parse_timestamp(&frame[1])?;
+
+    let project = frame[2]
+        .as_str()
+        .ok_or_else(|| SkipReasonKind::Invalid("the project is not a string".to_string()))?;
+
+    let tags = frame
+        .get(4)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(ToString::to_string)
+        .collect::<BTreeSet<_>>();
+
+    completed_activity(
+        project.to_string(),
+        Some(project.to_string()),
+        begin,
+        Some(end),
+        tags,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::error::TestResult;
+
+    #[test]
+    fn test_parse_watson_frames_passes() -> TestResult<()> {
+        let content = r#"[
+    [1709280000, 1709285400, "pace", "0f5e2c1d", ["rust", "export"], 1709285400],
+    [1709290000, 1709291800, "meetings", "8a1b3c4d", []],
+    [1709295000, 1709294000, "pace", "1b2c3d4e", [], 1709295000],
+    [1709296000, "later", "pace", "2c3d4e5f", [], 1709296000]
+]"#;
+
+        let (activities, skipped) = WatsonParser.parse(content)?.into_parts();
+
+        assert_eq!(activities.len(), 2);
+        assert_eq!(activities[0].description(), "pace");
+        assert_eq!(activities[0].category().as_deref(), Some("pace"));
+        assert_eq!(activities[0].duration()?.inner(), 5400);
+        assert_eq!(activities[0].begin().inner().timestamp(), 1_709_280_000);
+        assert_eq!(
+            activities[0].tags().as_ref().map(|tags| tags.len()),
+            Some(2)
+        );
+        assert_eq!(activities[1].tags(), &None);
+
+        assert_eq!(skipped.len(), 2);
+        assert_eq!(skipped[0].reason(), &SkipReasonKind::EndBeforeBegin);
+        assert_eq!(*skipped[1].position(), 4);
+
+        Ok(())
+    }
+}
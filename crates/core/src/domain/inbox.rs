@@ -1,9 +1,286 @@
-use std::collections::BinaryHeap;
+//! Inbox entity and business logic
+//!
+//! The inbox holds items that have been captured quickly, until they are
+//! promoted to a task or an activity. Items are ordered by their priority,
+//! and by their age within the same priority.
 
-use crate::domain::task::Task;
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use chrono::{NaiveDateTime, TimeDelta};
+use getset::Getters;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    domain::task::{Task, TaskGuid, TaskList},
+    error::{PaceErrorKind, PaceResult},
+};
+
+/// An item in the inbox
+#[derive(Debug, Clone, Getters)]
+#[getset(get = "pub")]
+pub struct InboxItem {
+    /// The id of the item, which it keeps when it is promoted to a task
+    id: TaskGuid,
+
+    /// The captured item
+    task: Task,
+}
+
+impl InboxItem {
+    #[must_use]
+    pub const fn new(id: TaskGuid, task: Task) -> Self {
+        Self { id, task }
+    }
+
+    /// Consume the item and return its id and the captured task
+    #[must_use]
+    pub fn into_parts(self) -> (TaskGuid, Task) {
+        (self.id, self.task)
+    }
+}
+
+impl Ord for InboxItem {
+    /// Items with a higher priority come first, then older items
+    fn cmp(&self, other: &Self) -> Ordering {
+        // `ItemPriorityKind` is ordered from high to low, so we reverse the comparison
+        other
+            .task
+            .priority()
+            .cmp(self.task.priority())
+            .then_with(|| other.task.created_at().cmp(self.task.created_at()))
+            .then_with(|| other.id.cmp(&self.id))
+    }
+}
+
+impl PartialOrd for InboxItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for InboxItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for InboxItem {}
 
 /// Inbox entity
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(from = "TaskList", into = "TaskList")]
 pub struct Inbox {
-    items: BinaryHeap<Task>,
+    items: BinaryHeap<InboxItem>,
+}
+
+impl From<TaskList> for Inbox {
+    fn from(task_list: TaskList) -> Self {
+        Self {
+            items: task_list
+                .tasks()
+                .iter()
+                .map(|(id, task)| InboxItem::new(*id, task.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl From<Inbox> for TaskList {
+    fn from(inbox: Inbox) -> Self {
+        let mut task_list = Self::default();
+
+        for item in inbox.items {
+            let (id, task) = item.into_parts();
+
+            _ = task_list.insert(id, task);
+        }
+
+        task_list
+    }
+}
+
+impl Inbox {
+    /// Add an item to the inbox
+    ///
+    /// # Arguments
+    ///
+    /// * `task` - The item to capture
+    /// * `max_size` - The maximum amount of items the inbox can hold, `0` for no limit
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inbox is full
+    ///
+    /// # Returns
+    ///
+    /// The id of the added item
+    pub fn add(&mut self, task: Task, max_size: usize) -> PaceResult<TaskGuid> {
+        if max_size > 0 && self.items.len() >= max_size {
+            return Err(PaceErrorKind::InboxFull(max_size).into());
+        }
+
+        let id = TaskGuid::default();
+
+        self.items.push(InboxItem::new(id, task));
+
+        Ok(id)
+    }
+
+    /// Get the items in the order they should be worked on
+    #[must_use]
+    pub fn sorted(&self) -> Vec<&InboxItem> {
+        let mut items = self.items.iter().collect::<Vec<_>>();
+
+        items.sort_by(|left, right| right.cmp(left));
+
+        items
+    }
+
+    /// Take an item out of the inbox
+    ///
+    /// # Arguments
+    ///
+    /// * `id_or_prefix` - The id of the item or the beginning of it, case insensitive
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no item or more than one item matches
+    ///
+    /// # Returns
+    ///
+    /// The removed item
+    pub fn take(&mut self, id_or_prefix: &str) -> PaceResult<InboxItem> {
+        let id = TaskList::from(self.clone()).find_id(id_or_prefix)?;
+
+        let (taken, items): (Vec<_>, Vec<_>) = self.items.drain().partition(|item| item.id == id);
+
+        self.items = items.into_iter().collect();
+
+        taken
+            .into_iter()
+            .next()
+            .ok_or_else(|| PaceErrorKind::TaskNotFound(id_or_prefix.to_string()).into())
+    }
+
+    /// Remove all items that have been captured before a point in time
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current date and time
+    /// * `days` - The age of items in days, after which they are removed, `0` to keep all items
+    ///
+    /// # Returns
+    ///
+    /// The removed items
+    pub fn remove_stale(&mut self, now: NaiveDateTime, days: u32) -> Vec<InboxItem> {
+        if days == 0 {
+            return vec![];
+        }
+
+        let Some(threshold) =
+            TimeDelta::try_days(i64::from(days)).and_then(|days| now.checked_sub_signed(days))
+        else {
+            return vec![];
+        };
+
+        let (stale, items): (Vec<_>, Vec<_>) = self
+            .items
+            .drain()
+            .partition(|item| *item.task.created_at() < threshold);
+
+        self.items = items.into_iter().collect();
+
+        stale
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{domain::priority::ItemPriorityKind, error::TestResult};
+
+    fn task(title: &str, priority: ItemPriorityKind, created_at: &str) -> TestResult<Task> {
+        Ok(Task::builder()
+            .title(title)
+            .priority(priority)
+            .created_at(created_at.parse::<NaiveDateTime>()?)
+            .build())
+    }
+
+    #[test]
+    fn test_inbox_priority_order_and_archival_passes() -> TestResult<()> {
+        let mut inbox = Inbox::default();
+
+        _ = inbox.add(
+            task("Old low", ItemPriorityKind::Low, "2024-01-01T09:00:00")?,
+            4,
+        )?;
+        _ = inbox.add(
+            task("High", ItemPriorityKind::High, "2024-03-01T09:00:00")?,
+            4,
+        )?;
+        _ = inbox.add(
+            task(
+                "New medium",
+                ItemPriorityKind::Medium,
+                "2024-03-02T09:00:00",
+            )?,
+            4,
+        )?;
+        let old_medium = inbox.add(
+            task(
+                "Old medium",
+                ItemPriorityKind::Medium,
+                "2024-02-20T09:00:00",
+            )?,
+            4,
+        )?;
+
+        assert!(
+            inbox
+                .add(
+                    task("Too much", ItemPriorityKind::High, "2024-03-02T09:00:00")?,
+                    4
+                )
+                .is_err(),
+            "The inbox should be full."
+        );
+
+        assert_eq!(
+            inbox
+                .sorted()
+                .iter()
+                .map(|item| item.task().title().as_str())
+                .collect::<Vec<_>>(),
+            vec!["High", "Old medium", "New medium", "Old low"]
+        );
+
+        let taken = inbox.take(&old_medium.to_string().to_lowercase())?;
+
+        assert_eq!(taken.id(), &old_medium);
+        assert_eq!(inbox.len(), 3);
+
+        let stale = inbox.remove_stale("2024-03-10T09:00:00".parse()?, 30);
+
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].task().title(), "Old low");
+        assert_eq!(inbox.len(), 2);
+
+        let toml_string = toml::to_string(&inbox)?;
+
+        assert_eq!(toml::from_str::<Inbox>(&toml_string)?.len(), 2);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,212 @@
+use getset::Getters;
+use pace_time::date_time::PaceDateTime;
+use serde_derive::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+use crate::domain::{
+    activity::{ActivityGuid, ActivityItem},
+    activity_log::ActivityLog,
+};
+
+/// The operation that changed the activity log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display, EnumString)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum JournalOperationKind {
+    /// The complete activity log, replacing everything before it
+    Snapshot,
+
+    /// An activity has been created
+    Create,
+
+    /// An activity has begun, ending all other activities
+    Begin,
+
+    /// An activity has been updated
+    Update,
+
+    /// An activity has been deleted
+    Delete,
+
+    /// One or more activities have ended
+    End,
+
+    /// An activity has been held for an intermission
+    Hold,
+
+    /// A held activity has been resumed
+    Resume,
+
+    /// An activity has been archived
+    Archive,
+
+    /// An activity has been unarchived
+    Unarchive,
+
+    /// Tags of one or more activities have been changed
+    Tag,
+
+    /// An earlier change has been undone
+    Undo,
+
+    /// An undone change has been made again
+    Redo,
+
+    /// Activities have been merged from another activity log
+    Merge,
+
+    /// Activities have been imported from another time tracker
+    Import,
+}
+
+/// An entry of the activity log journal
+///
+/// Each entry records the state of the activities an operation has changed,
+/// so replaying the entries in order rebuilds the activity log. Entries about
+/// different activities don't depend on each other, which keeps journals of
+/// several devices easy to merge.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters, PartialEq, Eq)]
+#[getset(get = "pub")]
+#[serde(rename_all = "kebab-case")]
+pub struct JournalEntry {
+    /// When the operation happened
+    at: PaceDateTime,
+
+    /// The operation that changed the activity log
+    operation: JournalOperationKind,
+
+    /// The activities created or changed by the operation, after the change
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    changed: Vec<ActivityItem>,
+
+    /// The ids of the activities removed by the operation
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    removed: Vec<ActivityGuid>,
+}
+
+impl JournalEntry {
+    /// Create a snapshot of the complete activity log
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_log` - The activity log to take the snapshot of
+    #[must_use]
+    pub fn snapshot(activity_log: &ActivityLog) -> Self {
+        Self {
+            at: PaceDateTime::now(),
+            operation: JournalOperationKind::Snapshot,
+            changed: activity_log
+                .iter()
+                .map(|(guid, activity)| ActivityItem::new(*guid, activity.clone()))
+                .collect(),
+            removed: vec![],
+        }
+    }
+
+    /// Record the changes an operation made to the activity log
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation that changed the activity log
+    /// * `before` - The activity log before the operation
+    /// * `after` - The activity log after the operation
+    ///
+    /// # Returns
+    ///
+    /// The journal entry, or `None` if the operation didn't change anything
+    #[must_use]
+    pub fn from_changes(
+        operation: JournalOperationKind,
+        before: &ActivityLog,
+        after: &ActivityLog,
+    ) -> Option<Self> {
+        let changed = after
+            .iter()
+            .filter(|(guid, activity)| before.get(guid) != Some(activity))
+            .map(|(guid, activity)| ActivityItem::new(*guid, activity.clone()))
+            .collect::<Vec<_>>();
+
+        let removed = before
+            .keys()
+            .filter(|guid| !after.contains_key(guid))
+            .copied()
+            .collect::<Vec<_>>();
+
+        if changed.is_empty() && removed.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            at: PaceDateTime::now(),
+            operation,
+            changed,
+            removed,
+        })
+    }
+
+    /// Apply the entry to an activity log
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_log` - The activity log to apply the entry to
+    pub fn apply(&self, activity_log: &mut ActivityLog) {
+        if self.operation == JournalOperationKind::Snapshot {
+            activity_log.clear();
+        }
+
+        for activity_item in &self.changed {
+            let _ = activity_log.insert(*activity_item.guid(), activity_item.activity().clone());
+        }
+
+        for guid in &self.removed {
+            let _ = activity_log.remove(guid);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::domain::activity::Activity;
+
+    use super::*;
+
+    #[test]
+    fn test_journal_entry_replays_changes_passes() {
+        let first = ActivityItem::from(Activity::builder().description("First").build());
+        let second = ActivityItem::from(Activity::builder().description("Second").build());
+
+        let before = ActivityLog::from_iter(vec![first.clone()]);
+
+        let mut updated = first.activity().clone();
+        updated.set_description("Updated".to_string());
+
+        let after = ActivityLog::from_iter(vec![
+            ActivityItem::new(*first.guid(), updated),
+            second.clone(),
+        ]);
+
+        let entry = JournalEntry::from_changes(JournalOperationKind::Update, &before, &after);
+
+        assert!(entry.is_some());
+
+        let mut replayed = before.clone();
+        entry.iter().for_each(|entry| entry.apply(&mut replayed));
+
+        assert_eq!(replayed, after);
+
+        assert!(JournalEntry::from_changes(JournalOperationKind::Update, &after, &after).is_none());
+
+        let removal = JournalEntry::from_changes(JournalOperationKind::Delete, &after, &before);
+
+        let mut replayed = after.clone();
+        removal.iter().for_each(|entry| entry.apply(&mut replayed));
+
+        assert_eq!(replayed, before);
+
+        let mut replayed = ActivityLog::from_iter(vec![second]);
+        JournalEntry::snapshot(&after).apply(&mut replayed);
+
+        assert_eq!(replayed, after);
+    }
+}
@@ -0,0 +1,341 @@
+//! Pomodoro sessions and their work and break phases
+//!
+//! A pomodoro session alternates between work sessions and breaks. Work
+//! sessions are tracked as [`ActivityKind::PomodoroWork`] and are numbered
+//! within their pomodoro session, the number is stored as [`PomodoroCycle::Work`].
+//! Breaks are tracked as [`ActivityKind::PomodoroIntermission`] and are linked
+//! to the work session they follow by its id. Every `sessions_before_long_break`
+//! work sessions, the break is a long one.
+
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+};
+
+use getset::Getters;
+use pace_time::{
+    date_time::PaceDateTime,
+    duration::{calculate_duration, PaceDuration},
+};
+
+use crate::{
+    config::PomodoroConfig,
+    domain::activity::{Activity, ActivityGuid, ActivityKind, ActivityKindOptions, PomodoroCycle},
+};
+
+/// The kind of a phase within a pomodoro session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, displaydoc::Display)]
+pub enum PomodoroPhaseKind {
+    /// work session
+    Work,
+
+    /// short break
+    ShortBreak,
+
+    /// long break
+    LongBreak,
+}
+
+impl PomodoroPhaseKind {
+    /// Returns `true` if the phase is a break.
+    #[must_use]
+    pub const fn is_break(&self) -> bool {
+        matches!(self, Self::ShortBreak | Self::LongBreak)
+    }
+}
+
+/// A phase within a pomodoro session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct PomodoroPhase {
+    /// The kind of the phase
+    kind: PomodoroPhaseKind,
+
+    /// The number of the work session within the pomodoro session, starting at `1`
+    ///
+    /// Breaks have the number of the work session they follow.
+    cycle: usize,
+
+    /// The planned duration of the phase
+    duration: PaceDuration,
+}
+
+impl Display for PomodoroPhase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.kind.is_break() {
+            write!(f, "{} after work session {}", self.kind, self.cycle)
+        } else {
+            write!(f, "{} {}", self.kind, self.cycle)
+        }
+    }
+}
+
+impl PomodoroPhase {
+    /// Create the phase for a work session
+    ///
+    /// # Arguments
+    ///
+    /// * `cycle` - The number of the work session within the pomodoro session
+    /// * `config` - The pomodoro configuration
+    #[must_use]
+    pub fn work(cycle: usize, config: &PomodoroConfig) -> Self {
+        Self {
+            kind: PomodoroPhaseKind::Work,
+            cycle,
+            duration: minutes(*config.work_duration_minutes()),
+        }
+    }
+
+    /// Create the phase for the break after a work session
+    ///
+    /// The break is a long one, if the work session completes a set of
+    /// `sessions_before_long_break` work sessions.
+    ///
+    /// # Arguments
+    ///
+    /// * `cycle` - The number of the work session the break follows
+    /// * `config` - The pomodoro configuration
+    #[must_use]
+    pub fn break_after(cycle: usize, config: &PomodoroConfig) -> Self {
+        let sessions_before_long_break = *config.sessions_before_long_break() as usize;
+
+        if sessions_before_long_break > 0 && cycle % sessions_before_long_break == 0 {
+            Self {
+                kind: PomodoroPhaseKind::LongBreak,
+                cycle,
+                duration: minutes(*config.long_break_duration_minutes()),
+            }
+        } else {
+            Self {
+                kind: PomodoroPhaseKind::ShortBreak,
+                cycle,
+                duration: minutes(*config.break_duration_minutes()),
+            }
+        }
+    }
+
+    /// Get the phase that follows this phase
+    ///
+    /// A work session is followed by a break, a break by the next work session.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The pomodoro configuration
+    #[must_use]
+    pub fn next(&self, config: &PomodoroConfig) -> Self {
+        if self.kind.is_break() {
+            Self::work(self.cycle + 1, config)
+        } else {
+            Self::break_after(self.cycle, config)
+        }
+    }
+
+    /// Get the phase an activity has been tracked for
+    ///
+    /// # Arguments
+    ///
+    /// * `activity` - The pomodoro activity
+    /// * `parent` - The work session a break is linked to, not needed for work sessions
+    /// * `config` - The pomodoro configuration
+    ///
+    /// # Returns
+    ///
+    /// The phase, or `None` if the activity is not a pomodoro activity or has no cycle information
+    #[must_use]
+    pub fn from_activity(
+        activity: &Activity,
+        parent: Option<&Activity>,
+        config: &PomodoroConfig,
+    ) -> Option<Self> {
+        match activity.kind() {
+            ActivityKind::PomodoroWork => {
+                work_cycle(activity).map(|cycle| Self::work(cycle, config))
+            }
+            ActivityKind::PomodoroIntermission => parent
+                .and_then(work_cycle)
+                .map(|cycle| Self::break_after(cycle, config)),
+            _ => None,
+        }
+    }
+
+    /// Create the activity to track this phase
+    ///
+    /// # Arguments
+    ///
+    /// * `description` - The description of the pomodoro session
+    /// * `category` - The category of the pomodoro session
+    /// * `tags` - The tags of the pomodoro session, only used for work sessions
+    /// * `begin` - The begin of the phase
+    /// * `parent_id` - The id of the work session a break follows, not needed for work sessions
+    #[must_use]
+    pub fn to_activity(
+        &self,
+        description: String,
+        category: Option<String>,
+        tags: Option<HashSet<String>>,
+        begin: PaceDateTime,
+        parent_id: Option<ActivityGuid>,
+    ) -> Activity {
+        if self.kind.is_break() {
+            Activity::builder()
+                .description(description)
+                .category(category)
+                .begin(begin)
+                .kind(ActivityKind::PomodoroIntermission)
+                .activity_kind_options(parent_id.map(ActivityKindOptions::with_parent_id))
+                .pomodoro_cycle_options(PomodoroCycle::Intermission)
+                .build()
+        } else {
+            Activity::builder()
+                .description(description)
+                .category(category)
+                .tags(tags)
+                .begin(begin)
+                .kind(ActivityKind::PomodoroWork)
+                .pomodoro_cycle_options(PomodoroCycle::Work(self.cycle))
+                .build()
+        }
+    }
+
+    /// Get the time that is left of the phase
+    ///
+    /// # Arguments
+    ///
+    /// * `begin` - The begin of the phase
+    /// * `now` - The current date and time
+    ///
+    /// # Returns
+    ///
+    /// The remaining time, which is zero once the phase is over
+    #[must_use]
+    pub fn remaining(&self, begin: &PaceDateTime, now: &PaceDateTime) -> PaceDuration {
+        self.duration - calculate_duration(begin, now).unwrap_or_default()
+    }
+
+    /// Get the time the phase has been running longer than planned
+    ///
+    /// # Arguments
+    ///
+    /// * `begin` - The begin of the phase
+    /// * `now` - The current date and time
+    ///
+    /// # Returns
+    ///
+    /// The overtime, which is zero as long as the phase is not over
+    #[must_use]
+    pub fn overtime(&self, begin: &PaceDateTime, now: &PaceDateTime) -> PaceDuration {
+        calculate_duration(begin, now).unwrap_or_default() - self.duration
+    }
+}
+
+/// Get the number of a pomodoro work session
+fn work_cycle(activity: &Activity) -> Option<usize> {
+    match activity.pomodoro_cycle_options() {
+        Some(PomodoroCycle::Work(cycle)) if activity.kind().is_pomodoro_work() => Some(*cycle),
+        _ => None,
+    }
+}
+
+/// Convert minutes from the configuration into a duration
+fn minutes(minutes: u32) -> PaceDuration {
+    PaceDuration::new(u64::from(minutes) * 60)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::error::TestResult;
+
+    #[test]
+    fn test_pomodoro_phases_count_towards_long_break_passes() {
+        let config = PomodoroConfig::default();
+
+        let mut phase = PomodoroPhase::work(1, &config);
+
+        let mut kinds = vec![phase.kind];
+
+        for _ in 0..9 {
+            phase = phase.next(&config);
+            kinds.push(phase.kind);
+        }
+
+        assert_eq!(
+            kinds,
+            vec![
+                PomodoroPhaseKind::Work,
+                PomodoroPhaseKind::ShortBreak,
+                PomodoroPhaseKind::Work,
+                PomodoroPhaseKind::ShortBreak,
+                PomodoroPhaseKind::Work,
+                PomodoroPhaseKind::ShortBreak,
+                PomodoroPhaseKind::Work,
+                PomodoroPhaseKind::LongBreak,
+                PomodoroPhaseKind::Work,
+                PomodoroPhaseKind::ShortBreak,
+            ]
+        );
+
+        assert_eq!(phase.cycle, 5);
+        assert_eq!(
+            PomodoroPhase::break_after(8, &config).duration.inner(),
+            15 * 60
+        );
+    }
+
+    #[test]
+    fn test_pomodoro_phase_from_activity_passes() -> TestResult<()> {
+        let config = PomodoroConfig::default();
+
+        let begin = PaceDateTime::now();
+
+        let work = PomodoroPhase::work(4, &config).to_activity(
+            "Write docs".to_string(),
+            None,
+            None,
+            begin,
+            None,
+        );
+
+        assert_eq!(work.kind(), &ActivityKind::PomodoroWork);
+        assert_eq!(
+            PomodoroPhase::from_activity(&work, None, &config),
+            Some(PomodoroPhase::work(4, &config))
+        );
+
+        let intermission = PomodoroPhase::break_after(4, &config).to_activity(
+            "Write docs".to_string(),
+            None,
+            None,
+            begin,
+            Some(ActivityGuid::default()),
+        );
+
+        assert_eq!(intermission.kind(), &ActivityKind::PomodoroIntermission);
+        assert!(intermission.parent_id().is_some());
+        assert_eq!(
+            PomodoroPhase::from_activity(&intermission, Some(&work), &config)
+                .map(|phase| phase.kind),
+            Some(PomodoroPhaseKind::LongBreak)
+        );
+        assert_eq!(
+            PomodoroPhase::from_activity(&intermission, None, &config),
+            None
+        );
+
+        let later = begin.add_duration(PaceDuration::new(30 * 60))?;
+
+        assert_eq!(
+            PomodoroPhase::work(1, &config).remaining(&begin, &later),
+            PaceDuration::zero()
+        );
+        assert_eq!(
+            PomodoroPhase::work(1, &config)
+                .overtime(&begin, &later)
+                .inner(),
+            5 * 60
+        );
+
+        Ok(())
+    }
+}
@@ -1,7 +1,13 @@
 use serde_derive::{Deserialize, Serialize};
+use strum_macros::Display;
 
-#[derive(Debug, Serialize, Default, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+/// The priority of a task
+#[derive(
+    Debug, Serialize, Default, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Display,
+)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
 pub enum ItemPriorityKind {
     High,
     #[default]
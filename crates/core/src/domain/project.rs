@@ -1,14 +1,20 @@
 use std::{
     collections::BTreeMap,
     fmt::{Display, Formatter},
-    path::PathBuf,
+    path::{Path, PathBuf},
+    str::FromStr,
 };
 
+use getset::Getters;
 use serde_derive::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use ulid::Ulid;
+use wildmatch::WildMatch;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+use crate::error::{PaceErrorKind, PaceResult};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Getters)]
+#[getset(get = "pub")]
 pub struct ProjectList {
     /// The tasks in the list
     #[serde(flatten)]
@@ -16,6 +22,170 @@ pub struct ProjectList {
     defaults: Option<DefaultOptions>,
 }
 
+impl ProjectList {
+    /// Get a project by its id
+    #[must_use]
+    pub fn get(&self, project_id: &ProjectGuid) -> Option<&Project> {
+        self.projects.get(project_id)
+    }
+
+    /// Find the id of a project by its name, its id or a unique prefix of its id
+    ///
+    /// # Arguments
+    ///
+    /// * `name_or_id` - The name of the project (case insensitive), its id or the beginning of it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no project or more than one project matches
+    ///
+    /// # Returns
+    ///
+    /// The id of the matching project
+    pub fn find_id(&self, name_or_id: &str) -> PaceResult<ProjectGuid> {
+        let name_or_id = name_or_id.trim();
+
+        let prefix = name_or_id.to_uppercase();
+
+        let mut matches = self
+            .projects
+            .iter()
+            .filter(|(project_id, project)| {
+                !name_or_id.is_empty()
+                    && (project.name.eq_ignore_ascii_case(name_or_id)
+                        || project_id.to_string().starts_with(&prefix))
+            })
+            .map(|(project_id, _)| project_id);
+
+        match (matches.next(), matches.next()) {
+            (Some(project_id), None) => Ok(*project_id),
+            (Some(_), Some(_)) => {
+                Err(PaceErrorKind::AmbiguousProject(name_or_id.to_string()).into())
+            }
+            (None, _) => Err(PaceErrorKind::ProjectNotFound(name_or_id.to_string()).into()),
+        }
+    }
+
+    /// Get the project followed by its parent projects, up to the root project
+    ///
+    /// # Arguments
+    ///
+    /// * `project_id` - The id of the project
+    ///
+    /// # Returns
+    ///
+    /// The ids of the project and its parents, empty if the project doesn't exist
+    #[must_use]
+    pub fn parent_chain(&self, project_id: ProjectGuid) -> Vec<ProjectGuid> {
+        let mut chain = vec![];
+
+        let mut next = Some(project_id);
+
+        while let Some(project_id) = next {
+            // Stop at unknown projects and cycles in the hierarchy
+            if chain.contains(&project_id) {
+                break;
+            }
+
+            let Some(project) = self.projects.get(&project_id) else {
+                break;
+            };
+
+            chain.push(project_id);
+
+            next = project.parent_id();
+        }
+
+        chain
+    }
+
+    /// Get the project and all of its subprojects, including nested ones
+    ///
+    /// # Arguments
+    ///
+    /// * `project_id` - The id of the project
+    ///
+    /// # Returns
+    ///
+    /// The ids of the project and its subprojects
+    #[must_use]
+    pub fn with_subprojects(&self, project_id: ProjectGuid) -> Vec<ProjectGuid> {
+        self.projects
+            .keys()
+            .filter(|candidate| self.parent_chain(**candidate).contains(&project_id))
+            .copied()
+            .collect()
+    }
+
+    /// Get the project a directory belongs to
+    ///
+    /// Subprojects are located in the directory of their tasks file, relative to
+    /// the directory of the projects file. The deepest subproject containing the
+    /// directory wins, otherwise the directory belongs to the root project.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The directory the projects file is located in
+    /// * `directory` - The directory to find the project for
+    ///
+    /// # Returns
+    ///
+    /// The id of the project, `None` if there are no projects
+    #[must_use]
+    pub fn project_for_directory(&self, root: &Path, directory: &Path) -> Option<ProjectGuid> {
+        let subproject = self
+            .projects
+            .iter()
+            .filter(|(_, project)| project.parent_id().is_some())
+            .filter_map(|(project_id, project)| {
+                let project_dir = root.join(project.tasks_file.parent()?);
+
+                (project_dir != root && directory.starts_with(&project_dir))
+                    .then(|| (project_dir.components().count(), *project_id))
+            })
+            .max_by_key(|(depth, _)| *depth)
+            .map(|(_, project_id)| project_id);
+
+        subproject.or_else(|| {
+            self.projects
+                .iter()
+                .find(|(_, project)| project.parent_id().is_none())
+                .map(|(project_id, _)| *project_id)
+        })
+    }
+
+    /// Get the category new activities get, if no category is given
+    ///
+    /// This is the first of the default categories.
+    #[must_use]
+    pub fn default_category(&self) -> Option<&str> {
+        self.defaults
+            .as_ref()?
+            .categories
+            .as_ref()?
+            .first()
+            .map(|category| category.name.as_str())
+    }
+
+    /// Get the category filters of a project and all of its subprojects
+    ///
+    /// # Arguments
+    ///
+    /// * `project_id` - The id of the project
+    ///
+    /// # Returns
+    ///
+    /// The filters the category of an activity can match
+    #[must_use]
+    pub fn category_filters(&self, project_id: ProjectGuid) -> Vec<String> {
+        self.with_subprojects(project_id)
+            .iter()
+            .filter_map(|project_id| self.projects.get(project_id))
+            .flat_map(Project::category_filters)
+            .collect()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DefaultOptions {
     categories: Option<Vec<Category>>,
@@ -28,7 +198,10 @@ pub struct Category {
     description: Option<String>,
 }
 
-#[derive(Debug, TypedBuilder, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Debug, TypedBuilder, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Getters,
+)]
+#[getset(get = "pub")]
 #[serde(rename_all = "kebab-case")]
 pub struct Project {
     name: String,
@@ -44,10 +217,34 @@ pub struct Project {
     subproject_options: Option<SubprojectOptions>,
 }
 
+impl Project {
+    /// Get the id of the parent project, if this is a subproject
+    #[must_use]
+    pub fn parent_id(&self) -> Option<ProjectGuid> {
+        self.subproject_options
+            .as_ref()
+            .and_then(|options| options.parent_id)
+    }
+
+    /// Get the filters of the project
+    ///
+    /// A filter consists of wildcard patterns separated by a comma, a category
+    /// matches the filter if it matches all of its patterns.
+    #[must_use]
+    pub fn category_filters(&self) -> Vec<String> {
+        self.filters
+            .iter()
+            .flatten()
+            .filter(|filter| !filter.trim().is_empty())
+            .cloned()
+            .collect()
+    }
+}
+
 #[derive(Debug, TypedBuilder, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "kebab-case")]
 pub struct SubprojectOptions {
-    parent_id: Option<Ulid>,
+    parent_id: Option<ProjectGuid>,
 }
 
 /// The unique identifier of an activity
@@ -66,6 +263,71 @@ impl Default for ProjectGuid {
     }
 }
 
+impl FromStr for ProjectGuid {
+    type Err = PaceErrorKind;
+
+    fn from_str(project_id: &str) -> Result<Self, Self::Err> {
+        Ulid::from_string(project_id)
+            .map(Self)
+            .map_err(|_| PaceErrorKind::ProjectNotFound(project_id.to_string()))
+    }
+}
+
+/// A filter for the project an activity belongs to
+///
+/// Activities that have been tracked for a project belong to it and to its
+/// parent projects. Activities without a project belong to a project, if their
+/// category matches one of the filters of the project or its subprojects.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, TypedBuilder, Getters)]
+#[getset(get = "pub")]
+pub struct ProjectFilter {
+    /// The id of the project
+    project_id: ProjectGuid,
+
+    /// The filters for the category of activities without a project
+    #[builder(default)]
+    category_filters: Vec<String>,
+}
+
+impl ProjectFilter {
+    /// Create a filter for a project and its subprojects
+    ///
+    /// # Arguments
+    ///
+    /// * `project_list` - The project list the project is defined in
+    /// * `project_id` - The id of the project
+    #[must_use]
+    pub fn new(project_list: &ProjectList, project_id: ProjectGuid) -> Self {
+        Self {
+            project_id,
+            category_filters: project_list.category_filters(project_id),
+        }
+    }
+
+    /// If an activity belongs to the project
+    ///
+    /// # Arguments
+    ///
+    /// * `projects` - The project the activity has been tracked for, followed by its parents
+    /// * `category` - The category of the activity
+    #[must_use]
+    pub fn matches(&self, projects: Option<&[ProjectGuid]>, category: &str) -> bool {
+        match projects {
+            Some(projects) if !projects.is_empty() => projects.contains(&self.project_id),
+            _ => {
+                let category = category.to_lowercase();
+
+                self.category_filters.iter().any(|filter| {
+                    filter
+                        .split(',')
+                        .map(str::trim)
+                        .all(|pattern| WildMatch::new(&pattern.to_lowercase()).matches(&category))
+                })
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -84,4 +346,57 @@ mod tests {
 
         Ok(())
     }
+
+    #[rstest]
+    fn test_project_hierarchy_passes(
+        #[files("../../config/projects.pace.toml")] config_path: PathBuf,
+    ) -> TestResult<()> {
+        let project_list = toml::from_str::<ProjectList>(&fs::read_to_string(config_path)?)?;
+
+        let root_id = project_list.find_id("pace project")?;
+        let subproject_id = project_list.find_id("01HPY7F03K6")?;
+
+        assert_eq!(root_id.to_string(), "01HPY7F03JBVKSWDNTM2RSBXSJ");
+        assert!(project_list.find_id("01HPY7F03K").is_err(), "Ambiguous.");
+        assert!(
+            project_list.find_id("Pace Subproject C").is_err(),
+            "Unknown."
+        );
+
+        assert_eq!(
+            project_list.parent_chain(subproject_id),
+            vec![subproject_id, root_id]
+        );
+        assert_eq!(project_list.with_subprojects(root_id).len(), 3);
+        assert_eq!(
+            project_list.with_subprojects(subproject_id),
+            vec![subproject_id]
+        );
+
+        let root = Path::new("/work/pace");
+
+        assert_eq!(
+            project_list.project_for_directory(root, &root.join("subproject-a/src")),
+            Some(subproject_id)
+        );
+        assert_eq!(
+            project_list.project_for_directory(root, &root.join("docs")),
+            Some(root_id)
+        );
+        assert_eq!(project_list.default_category(), Some("Uncategorized"));
+
+        let filter = ProjectFilter::new(&project_list, subproject_id);
+
+        assert!(filter.matches(Some(&[subproject_id, root_id]), "Frontend"));
+        assert!(!filter.matches(Some(&[root_id]), "dev::subproject-a"));
+        assert!(filter.matches(None, "Pace::Subproject-A"));
+        assert!(!filter.matches(None, "dev::pace"));
+
+        let filter = ProjectFilter::new(&project_list, root_id);
+
+        assert!(filter.matches(Some(&[subproject_id, root_id]), "Frontend"));
+        assert!(filter.matches(None, "dev::pace"));
+
+        Ok(())
+    }
 }
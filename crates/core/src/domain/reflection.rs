@@ -1,7 +1,7 @@
 use getset::{Getters, MutGetters, Setters};
-use pace_time::{date_time::PaceDateTime, duration::PaceDuration, time_range::TimeRangeOptions};
+use pace_time::{date::PaceDate, duration::PaceDuration, time_range::TimeRangeOptions};
 use serde_derive::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use strum_macros::EnumString;
 use tabled::{
     builder::Builder,
@@ -10,7 +10,17 @@ use tabled::{
 
 use typed_builder::TypedBuilder;
 
-use crate::domain::activity::{ActivityGroup, ActivityItem, ActivityKind};
+use crate::{
+    domain::{
+        activity::{ActivityGroup, ActivityItem, ActivityKind, ActivitySession},
+        goal::GoalProgress,
+        project::{ProjectGuid, ProjectList},
+        rounding::{RoundingOptions, RoundingScopeKind},
+        statistics::{ReflectionComparison, ReflectionStatistics},
+        task::{TaskGuid, TaskList},
+    },
+    util::escape_csv_field,
+};
 
 /// The kind of review format
 /// Default: `console`
@@ -28,6 +38,34 @@ pub enum ReflectionsFormatKind {
     Csv,
 }
 
+/// The dimension to group activities by in a reflection
+/// Default: `category`
+///
+/// Options: `category`, `tag`
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, EnumString, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum ReflectionGroupByKind {
+    /// Group activities by their category and subcategory
+    #[default]
+    Category,
+
+    /// Group activities by their tags, an activity with several tags is part of each group
+    Tag,
+}
+
+impl ReflectionGroupByKind {
+    /// The name of the column the groups are listed in
+    #[must_use]
+    pub const fn column_name(&self) -> &'static str {
+        match self {
+            Self::Category => "Category",
+            Self::Tag => "Tag",
+        }
+    }
+}
+
 /// Represents a category for summarizing activities.
 // We use a string to allow for user-defined categories for now,
 // but we may want to change this to an enum in the future.
@@ -35,6 +73,10 @@ pub type SummaryCategories = (String, String);
 
 pub type SummaryGroupByCategory = BTreeMap<SummaryCategories, SummaryActivityGroup>;
 
+pub type SummaryGroupByTask = BTreeMap<TaskGuid, SummaryTaskGroup>;
+
+pub type SummaryGroupByProject = BTreeMap<ProjectGuid, SummaryProjectGroup>;
+
 /// Represents a summary of activities and insights for a specified review period.
 #[derive(
     Debug, TypedBuilder, Serialize, Getters, Setters, MutGetters, Clone, Eq, PartialEq, Default,
@@ -50,13 +92,53 @@ pub struct ReflectionSummary {
     /// Total time spent on intermissions within the review period.
     total_break_duration: PaceDuration,
 
+    /// Total time spent on all activities, rounded with the rounding rule of the reflection.
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rounded_time_spent: Option<PaceDuration>,
+
+    /// The rounding rule the rounded durations have been computed with.
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rounding: Option<RoundingOptions>,
+
     /// Summary of activities grouped by a category or another relevant identifier.
+    #[serde(serialize_with = "serialize_summary_groups_by_category")]
     summary_groups_by_category: SummaryGroupByCategory,
-    // TODO: Highlights extracted from the review data, offering insights into user productivity.
-    // highlights: Highlights,
 
-    // TODO: Suggestions for the user based on the review, aimed at improving productivity or time management.
-    // suggestions: Vec<String>,
+    /// Time spent on the tasks activities have been tracked for.
+    #[builder(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    summary_groups_by_task: SummaryGroupByTask,
+
+    /// Time spent on projects, including the time spent on their subprojects.
+    #[builder(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    summary_groups_by_project: SummaryGroupByProject,
+
+    /// The dimension the summary groups are grouped by.
+    #[builder(default)]
+    group_by: ReflectionGroupByKind,
+
+    /// Statistics and highlights of the review period, offering insights into user productivity.
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    statistics: Option<ReflectionStatistics>,
+
+    /// Comparison to the previous equivalent review period.
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comparison: Option<ReflectionComparison>,
+
+    /// Recommendations for the user based on the review, aimed at improving productivity or time management.
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    recommendations: Vec<String>,
+
+    /// Progress towards the goals of the configuration within the review period.
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    goals: Vec<GoalProgress>,
 }
 
 impl ReflectionSummary {
@@ -65,79 +147,331 @@ impl ReflectionSummary {
         time_range: TimeRangeOptions,
         summary_groups_by_category: SummaryGroupByCategory,
     ) -> Self {
-        let total_time_spent = PaceDuration::from_seconds(
-            summary_groups_by_category
-                .values()
-                .map(|group| group.total_duration().as_secs())
-                .sum(),
-        );
+        let mut total_time_spent = PaceDuration::default();
+        let mut total_break_duration = PaceDuration::default();
 
-        let total_break_duration = PaceDuration::from_seconds(
-            summary_groups_by_category
-                .values()
-                .map(|group| group.total_break_duration().as_secs())
-                .sum(),
-        );
+        let mut summary_groups_by_task = SummaryGroupByTask::new();
+        let mut summary_groups_by_project = SummaryGroupByProject::new();
+
+        for session in unique_sessions(&summary_groups_by_category) {
+            total_time_spent += *session.adjusted_duration();
+            total_break_duration += *session.intermission_duration();
+
+            let activity = session.root_activity().activity();
+
+            if let Some(task_id) = activity.task_id() {
+                let task_group = summary_groups_by_task.entry(*task_id).or_default();
+
+                task_group.total_duration += *session.adjusted_duration();
+                task_group.session_count += 1;
+            }
+
+            // The time spent on a subproject rolls up into its parent projects
+            for project_id in activity.projects().iter().flatten() {
+                let project_group = summary_groups_by_project.entry(*project_id).or_default();
+
+                project_group.total_duration += *session.adjusted_duration();
+                project_group.session_count += 1;
+            }
+        }
 
         Self {
             time_range,
             total_time_spent,
             total_break_duration,
+            rounded_time_spent: None,
+            rounding: None,
             summary_groups_by_category,
+            summary_groups_by_task,
+            summary_groups_by_project,
+            group_by: ReflectionGroupByKind::default(),
+            statistics: None,
+            comparison: None,
+            recommendations: vec![],
+            goals: vec![],
+        }
+    }
+
+    /// The activity sessions within the review period
+    ///
+    /// Every session is only returned once, even if it is part of several groups.
+    pub fn activity_sessions(&self) -> impl Iterator<Item = &ActivitySession> {
+        unique_sessions(&self.summary_groups_by_category)
+    }
+
+    /// Round the time spent on the activity groups, to show it next to the tracked time
+    ///
+    /// Each activity group is rounded following the scope of the rounding rule, the
+    /// summary groups and the total are the sums of the rounded activity groups. When
+    /// rounding per session, the total is the sum of the rounded sessions instead,
+    /// so sessions that are part of several groups are only counted once.
+    ///
+    /// # Arguments
+    ///
+    /// * `rounding` - The rounding rule, nothing is rounded if it is disabled
+    pub fn apply_rounding(&mut self, rounding: RoundingOptions) {
+        if !rounding.is_enabled() {
+            return;
+        }
+
+        let mut rounded_time_spent = PaceDuration::default();
+
+        for summary_group in self.summary_groups_by_category.values_mut() {
+            let mut rounded_duration = PaceDuration::default();
+
+            for activity_group in summary_group.activity_groups_by_description.values_mut() {
+                let rounded = rounding.round_sessions(activity_group.activity_sessions());
+
+                _ = activity_group.set_rounded_duration(Some(rounded));
+
+                rounded_duration += rounded;
+            }
+
+            summary_group.rounded_duration = Some(rounded_duration);
+
+            rounded_time_spent += rounded_duration;
+        }
+
+        if *rounding.scope() == RoundingScopeKind::Session {
+            rounded_time_spent = rounding.round_sessions(self.activity_sessions());
+        }
+
+        self.rounded_time_spent = Some(rounded_time_spent);
+        self.rounding = Some(rounding);
+    }
+
+    /// Fill in the titles of the tasks activities have been tracked for
+    ///
+    /// Tasks that have been removed from the task list keep no title.
+    ///
+    /// # Arguments
+    ///
+    /// * `task_list` - The task list to look up the titles in
+    pub fn set_task_titles(&mut self, task_list: &TaskList) {
+        for (task_id, task_group) in &mut self.summary_groups_by_task {
+            task_group.title = task_list.get(task_id).map(|task| task.title().clone());
+        }
+    }
+
+    /// Fill in the names of the projects activities have been tracked for
+    ///
+    /// Projects that are not defined in the project list keep no name.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_list` - The project list to look up the names in
+    pub fn set_project_names(&mut self, project_list: &ProjectList) {
+        for (project_id, project_group) in &mut self.summary_groups_by_project {
+            project_group.name = project_list
+                .get(project_id)
+                .map(|project| project.name().clone());
+        }
+    }
+
+    /// Render the summary as CSV with a header row
+    ///
+    /// By default, there is one row per category, subcategory and description.
+    /// In detailed mode, there is one row per activity session instead.
+    /// Durations are given in seconds. If the durations have been rounded, the
+    /// rounded duration follows the duration. Sessions only have one if they are
+    /// rounded on their own.
+    ///
+    /// # Arguments
+    ///
+    /// * `detailed` - Emit one row per activity session
+    ///
+    /// # Returns
+    ///
+    /// The summary as CSV
+    #[must_use]
+    pub fn to_csv(&self, detailed: bool) -> String {
+        let group_column = self.group_by.column_name().to_lowercase();
+
+        let mut rows = vec![if detailed {
+            vec![
+                group_column.as_str(),
+                "subcategory",
+                "description",
+                "id",
+                "begin",
+                "end",
+                "duration",
+                "rounded-duration",
+                "break-duration",
+                "breaks",
+            ]
+        } else {
+            vec![
+                group_column.as_str(),
+                "subcategory",
+                "description",
+                "duration",
+                "rounded-duration",
+                "sessions",
+                "break-duration",
+                "breaks",
+            ]
+        }
+        .into_iter()
+        .filter(|column| self.rounding.is_some() || *column != "rounded-duration")
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()];
+
+        // The rounded duration column is only there if the durations have been rounded
+        let with_rounded = |mut row: Vec<String>, rounded: Option<PaceDuration>| {
+            if self.rounding.is_some() {
+                row.insert(
+                    4 + usize::from(detailed) * 3,
+                    rounded
+                        .map(|rounded| rounded.as_secs().to_string())
+                        .unwrap_or_default(),
+                );
+            }
+
+            row
+        };
+
+        for ((category, subcategory), summary_group) in &self.summary_groups_by_category {
+            for (description, activity_group) in summary_group.activity_groups_by_description() {
+                if !detailed {
+                    rows.push(with_rounded(
+                        vec![
+                            category.clone(),
+                            subcategory.clone(),
+                            description.clone(),
+                            activity_group.adjusted_duration().as_secs().to_string(),
+                            activity_group.activity_sessions().len().to_string(),
+                            activity_group.intermission_duration().as_secs().to_string(),
+                            activity_group.intermission_count().to_string(),
+                        ],
+                        *activity_group.rounded_duration(),
+                    ));
+
+                    continue;
+                }
+
+                for session in activity_group.activity_sessions() {
+                    let root_activity = session.root_activity().activity();
+
+                    let rounded = self
+                        .rounding
+                        .filter(|rounding| *rounding.scope() == RoundingScopeKind::Session)
+                        .map(|rounding| rounding.round(*session.adjusted_duration()));
+
+                    rows.push(with_rounded(
+                        vec![
+                            category.clone(),
+                            subcategory.clone(),
+                            description.clone(),
+                            session.root_activity().guid().to_string(),
+                            root_activity.begin().inner().to_rfc3339(),
+                            root_activity
+                                .activity_end_options()
+                                .as_ref()
+                                .map(|end_opts| end_opts.end().inner().to_rfc3339())
+                                .unwrap_or_default(),
+                            session.adjusted_duration().as_secs().to_string(),
+                            session.intermission_duration().as_secs().to_string(),
+                            session.intermissions().len().to_string(),
+                        ],
+                        rounded,
+                    ));
+                }
+            }
         }
+
+        rows.iter()
+            .map(|row| {
+                row.iter()
+                    .map(|field| escape_csv_field(field))
+                    .collect::<Vec<_>>()
+                    .join(",")
+                    + "\n"
+            })
+            .collect()
     }
 }
 
 // TODO!: Refine the display of the review summary
-impl std::fmt::Display for ReflectionSummary {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl ReflectionSummary {
+    /// The table with the time spent on each group, and its rounded time if it has been rounded
+    fn summary_table(&self) -> String {
         let mut builder = Builder::new();
 
-        builder.push_record(vec![
-            "Category",
-            "Description",
-            "Duration (Sessions)",
-            "Breaks (Amount)",
-        ]);
+        // The rounded durations are shown next to the tracked ones
+        let rounded = |duration: Option<PaceDuration>| {
+            self.rounding
+                .map(|_| duration.unwrap_or_default().to_string())
+        };
+
+        builder.push_record(
+            [
+                Some(self.group_by.column_name().to_string()),
+                Some("Description".to_string()),
+                Some("Duration (Sessions)".to_string()),
+                self.rounding.map(|_| "Rounded".to_string()),
+                Some("Breaks (Amount)".to_string()),
+            ]
+            .into_iter()
+            .flatten(),
+        );
 
         for ((category, subcategory), summary_group) in &self.summary_groups_by_category {
-            builder.push_record(vec![
-                category,
-                "",
-                &summary_group.total_duration().to_string(),
-                &summary_group.total_break_duration().to_string(),
-            ]);
+            builder.push_record(
+                [
+                    Some(category.clone()),
+                    Some(String::new()),
+                    Some(summary_group.total_duration().to_string()),
+                    rounded(summary_group.rounded_duration),
+                    Some(summary_group.total_break_duration().to_string()),
+                ]
+                .into_iter()
+                .flatten(),
+            );
 
             for (description, activity_group) in summary_group.activity_groups_by_description() {
-                builder.push_record(vec![
-                    subcategory,
-                    description,
-                    format!(
-                        "{} ({})",
-                        &activity_group.adjusted_duration().to_string(),
-                        &activity_group.activity_sessions().len()
-                    )
-                    .as_str(),
-                    format!(
-                        "{} ({})",
-                        &activity_group.intermission_duration().to_string(),
-                        &activity_group.intermission_count().to_string()
-                    )
-                    .as_str(),
-                ]);
+                builder.push_record(
+                    [
+                        Some(subcategory.clone()),
+                        Some(description.clone()),
+                        Some(format!(
+                            "{} ({})",
+                            &activity_group.adjusted_duration().to_string(),
+                            &activity_group.activity_sessions().len()
+                        )),
+                        rounded(*activity_group.rounded_duration()),
+                        Some(format!(
+                            "{} ({})",
+                            &activity_group.intermission_duration().to_string(),
+                            &activity_group.intermission_count().to_string()
+                        )),
+                    ]
+                    .into_iter()
+                    .flatten(),
+                );
             }
         }
 
-        builder.push_record(vec![
-            "Total",
-            "",
-            &self.total_time_spent.to_string(),
-            &self.total_break_duration.to_string(),
-        ]);
+        builder.push_record(
+            [
+                Some("Total".to_string()),
+                Some(String::new()),
+                Some(self.total_time_spent.to_string()),
+                rounded(self.rounded_time_spent),
+                Some(self.total_break_duration.to_string()),
+            ]
+            .into_iter()
+            .flatten(),
+        );
+
+        let rounding = self
+            .rounding
+            .map(|rounding| format!("\n\nDurations {rounding}"))
+            .unwrap_or_default();
 
         let table_config = Settings::default()
             .with(Panel::header(format!(
-                "Your activity insights for the period:\n\n{}",
+                "Your activity insights for the period:\n\n{}{rounding}",
                 self.time_range
             )))
             .with(Padding::new(1, 1, 0, 0))
@@ -145,13 +479,172 @@ impl std::fmt::Display for ReflectionSummary {
             .with(Modify::new(Columns::new(2..)).with(Alignment::right()))
             .with(Modify::new(Columns::new(0..=1)).with(Alignment::center()));
 
-        let table = builder.build().with(table_config).to_string();
-        write!(f, "{table}")?;
+        builder.build().with(table_config).to_string()
+    }
+}
+
+impl std::fmt::Display for ReflectionSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.summary_table())?;
+
+        if !self.summary_groups_by_project.is_empty() {
+            write_rollup_table(
+                f,
+                "Time spent on projects, including their subprojects",
+                "Project",
+                self.summary_groups_by_project
+                    .iter()
+                    .map(|(project_id, project_group)| {
+                        (
+                            project_group.name.as_deref(),
+                            project_id.to_string(),
+                            project_group.total_duration,
+                            project_group.session_count,
+                        )
+                    }),
+            )?;
+        }
+
+        if !self.summary_groups_by_task.is_empty() {
+            write_rollup_table(
+                f,
+                "Time spent on tasks",
+                "Task",
+                self.summary_groups_by_task
+                    .iter()
+                    .map(|(task_id, task_group)| {
+                        (
+                            task_group.title.as_deref(),
+                            task_id.to_string(),
+                            task_group.total_duration,
+                            task_group.session_count,
+                        )
+                    }),
+            )?;
+        }
+
+        if let Some(statistics) = &self.statistics {
+            write!(f, "{statistics}")?;
+        }
+
+        if let Some(comparison) = &self.comparison {
+            write!(f, "{comparison}")?;
+        }
+
+        if !self.recommendations.is_empty() {
+            write!(f, "\n\nRecommendations:")?;
+
+            for recommendation in &self.recommendations {
+                write!(f, "\n  - {recommendation}")?;
+            }
+        }
+
+        if !self.goals.is_empty() {
+            write!(f, "\n\nGoals:")?;
+
+            for goal in &self.goals {
+                write!(f, "\n  {goal}")?;
+            }
+        }
 
         Ok(())
     }
 }
 
+/// Serialize the summary groups with `category::subcategory` keys, as map keys need to be strings
+fn serialize_summary_groups_by_category<S: serde::Serializer>(
+    summary_groups_by_category: &SummaryGroupByCategory,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(summary_groups_by_category.iter().map(
+        |((category, subcategory), summary_group)| {
+            (format!("{category}::{subcategory}"), summary_group)
+        },
+    ))
+}
+
+/// The activity sessions of all summary groups, every session is only returned once
+///
+/// A session can be part of several groups, e.g. when grouping by tags.
+fn unique_sessions(
+    summary_groups_by_category: &SummaryGroupByCategory,
+) -> impl Iterator<Item = &ActivitySession> {
+    let mut seen_sessions = HashSet::new();
+
+    summary_groups_by_category
+        .values()
+        .flat_map(|group| group.activity_groups_by_description().values())
+        .flat_map(ActivityGroup::activity_sessions)
+        .filter(move |session| seen_sessions.insert(*session.root_activity().guid()))
+}
+
+/// Write a table with the time spent on tasks or projects below the summary
+///
+/// # Arguments
+///
+/// * `f` - The formatter to write to
+/// * `header` - The header of the table
+/// * `name_column` - The title of the column with the names
+/// * `rows` - The name, id, duration and amount of sessions of each row
+fn write_rollup_table<'a>(
+    f: &mut std::fmt::Formatter<'_>,
+    header: &str,
+    name_column: &str,
+    rows: impl Iterator<Item = (Option<&'a str>, String, PaceDuration, usize)>,
+) -> std::fmt::Result {
+    let mut builder = Builder::new();
+
+    builder.push_record(vec![name_column, "Id", "Duration (Sessions)"]);
+
+    for (name, id, duration, session_count) in rows {
+        builder.push_record(vec![
+            name.unwrap_or_default().to_string(),
+            id,
+            format!("{duration} ({session_count})"),
+        ]);
+    }
+
+    let table_config = Settings::default()
+        .with(Panel::header(header))
+        .with(Padding::new(1, 1, 0, 0))
+        .with(Style::modern_rounded())
+        .with(Modify::new(Columns::new(2..)).with(Alignment::right()));
+
+    let table = builder.build().with(table_config).to_string();
+
+    write!(f, "\n{table}")
+}
+
+/// The time spent on a task within the review period.
+#[derive(Debug, Serialize, Getters, Clone, Eq, PartialEq, Default)]
+#[getset(get = "pub")]
+pub struct SummaryTaskGroup {
+    /// The title of the task, if it is still in the task list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+
+    /// The total time spent on activities for the task.
+    total_duration: PaceDuration,
+
+    /// The amount of activity sessions for the task.
+    session_count: usize,
+}
+
+/// The time spent on a project within the review period.
+#[derive(Debug, Serialize, Getters, Clone, Eq, PartialEq, Default)]
+#[getset(get = "pub")]
+pub struct SummaryProjectGroup {
+    /// The name of the project, if it is defined in the project list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+
+    /// The total time spent on activities for the project and its subprojects.
+    total_duration: PaceDuration,
+
+    /// The amount of activity sessions for the project and its subprojects.
+    session_count: usize,
+}
+
 /// A group of activities for a summary category.
 #[derive(
     Debug, TypedBuilder, Serialize, Getters, Setters, MutGetters, Clone, Eq, PartialEq, Default,
@@ -169,6 +662,11 @@ pub struct SummaryActivityGroup {
 
     /// The groups of activities for a summary category
     activity_groups_by_description: BTreeMap<String, ActivityGroup>,
+
+    /// The total duration rounded with the rounding rule of the reflection, if any
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rounded_duration: Option<PaceDuration>,
 }
 
 impl SummaryActivityGroup {
@@ -182,6 +680,7 @@ impl SummaryActivityGroup {
                 activity_group.description().to_owned(),
                 activity_group,
             )]),
+            rounded_duration: None,
         }
     }
 
@@ -216,7 +715,7 @@ impl SummaryActivityGroup {
 #[allow(clippy::struct_field_names)]
 pub struct Highlights {
     /// The day with the highest productive hours.
-    pub most_productive_day: PaceDateTime,
+    pub most_productive_day: PaceDate,
 
     /// The kind of activity most frequently logged.
     pub most_frequent_activity_kind: ActivityKind,
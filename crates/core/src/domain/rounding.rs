@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+
+use getset::Getters;
+use pace_time::{date::PaceDate, duration::PaceDuration};
+use serde_derive::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+use typed_builder::TypedBuilder;
+
+use crate::domain::activity::ActivitySession;
+
+/// How durations are rounded
+/// Default: `up`
+///
+/// Options: `nearest`, `up`, `down`
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Copy, Default, EnumString, Display, PartialEq, Eq,
+)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum RoundingModeKind {
+    /// Round to the nearest multiple of the rounding interval
+    Nearest,
+
+    /// Round up to the next multiple of the rounding interval
+    #[default]
+    Up,
+
+    /// Round down to the previous multiple of the rounding interval
+    Down,
+}
+
+impl RoundingModeKind {
+    /// Round a duration to a multiple of the interval, durations are kept as they are for an empty interval
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - The duration to round
+    /// * `interval` - The interval the duration is rounded to a multiple of
+    #[must_use]
+    pub const fn round(self, duration: PaceDuration, interval: PaceDuration) -> PaceDuration {
+        let (duration, interval) = (duration.as_secs(), interval.as_secs());
+
+        if interval == 0 {
+            return PaceDuration::new(duration);
+        }
+
+        PaceDuration::new(match self {
+            Self::Nearest => (duration + interval / 2) / interval * interval,
+            Self::Up => duration.div_ceil(interval) * interval,
+            Self::Down => duration / interval * interval,
+        })
+    }
+}
+
+/// What durations are rounded on their own in a reflection
+/// Default: `session`
+///
+/// Options: `session`, `day`, `group`
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Copy, Default, EnumString, Display, PartialEq, Eq,
+)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum RoundingScopeKind {
+    /// Round the duration of each activity session
+    #[default]
+    Session,
+
+    /// Round the time spent on an activity per day
+    Day,
+
+    /// Round the time spent on an activity within the whole period
+    Group,
+}
+
+/// The rounding rule for the durations of a reflection
+///
+/// The rounded durations are shown next to the durations that have been tracked.
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Getters, TypedBuilder, Default,
+)]
+#[getset(get = "pub")]
+#[serde(default, rename_all = "kebab-case")]
+pub struct RoundingOptions {
+    /// The interval durations are rounded to (in minutes), `0` disables rounding
+    /// Default: `0`
+    minutes: u64,
+
+    /// How durations are rounded
+    /// Default: `up`
+    #[builder(default)]
+    mode: RoundingModeKind,
+
+    /// What durations are rounded on their own
+    /// Default: `session`
+    #[builder(default)]
+    scope: RoundingScopeKind,
+}
+
+impl RoundingOptions {
+    /// If durations are rounded at all
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.minutes > 0
+    }
+
+    /// Round a duration to a multiple of the interval
+    #[must_use]
+    pub const fn round(&self, duration: PaceDuration) -> PaceDuration {
+        self.mode
+            .round(duration, PaceDuration::new(self.minutes * 60))
+    }
+
+    /// The rounded time spent on activity sessions, following the scope
+    ///
+    /// # Arguments
+    ///
+    /// * `sessions` - The activity sessions of an activity
+    #[must_use]
+    pub fn round_sessions<'a>(
+        &self,
+        sessions: impl IntoIterator<Item = &'a ActivitySession>,
+    ) -> PaceDuration {
+        let mut rounded = PaceDuration::default();
+
+        match self.scope {
+            RoundingScopeKind::Session => {
+                for session in sessions {
+                    rounded += self.round(*session.adjusted_duration());
+                }
+            }
+            RoundingScopeKind::Day => {
+                let mut days = BTreeMap::<PaceDate, PaceDuration>::new();
+
+                for session in sessions {
+                    *days
+                        .entry(PaceDate::from(session.root_activity().activity().begin()))
+                        .or_default() += *session.adjusted_duration();
+                }
+
+                for duration in days.into_values() {
+                    rounded += self.round(duration);
+                }
+            }
+            RoundingScopeKind::Group => {
+                let mut duration = PaceDuration::default();
+
+                for session in sessions {
+                    duration += *session.adjusted_duration();
+                }
+
+                rounded = self.round(duration);
+            }
+        }
+
+        rounded
+    }
+}
+
+impl std::fmt::Display for RoundingOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = match self.mode {
+            RoundingModeKind::Nearest => "to the nearest",
+            RoundingModeKind::Up => "up to",
+            RoundingModeKind::Down => "down to",
+        };
+
+        write!(
+            f,
+            "rounded {mode} {} minutes per {}",
+            self.minutes, self.scope
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(RoundingModeKind::Nearest, 20, 15)]
+    #[case(RoundingModeKind::Nearest, 23, 30)]
+    #[case(RoundingModeKind::Up, 16, 30)]
+    #[case(RoundingModeKind::Up, 30, 30)]
+    #[case(RoundingModeKind::Down, 29, 15)]
+    fn test_rounding_mode_rounds_to_interval_passes(
+        #[case] mode: RoundingModeKind,
+        #[case] minutes: u64,
+        #[case] expected_minutes: u64,
+    ) {
+        assert_eq!(
+            mode.round(PaceDuration::new(minutes * 60), PaceDuration::new(15 * 60)),
+            PaceDuration::new(expected_minutes * 60)
+        );
+    }
+
+    #[test]
+    fn test_rounding_options_display_passes() {
+        let rounding = RoundingOptions::builder()
+            .minutes(6)
+            .scope(RoundingScopeKind::Day)
+            .build();
+
+        assert_eq!(rounding.to_string(), "rounded up to 6 minutes per day");
+    }
+
+    #[test]
+    fn test_rounding_mode_keeps_duration_without_interval_passes() {
+        assert_eq!(
+            RoundingModeKind::Up.round(PaceDuration::new(61), PaceDuration::default()),
+            PaceDuration::new(61)
+        );
+    }
+}
@@ -0,0 +1,530 @@
+use chrono::Datelike;
+use getset::Getters;
+use pace_time::{
+    date::PaceDate,
+    duration::{PaceDuration, PaceDurationRange, PaceDurationRangeOptions},
+    time_range::TimeRangeOptions,
+};
+use serde_derive::Serialize;
+use std::collections::BTreeMap;
+use tabled::{
+    builder::Builder,
+    settings::{object::Columns, Alignment, Modify, Padding, Panel, Settings, Style},
+};
+
+use crate::domain::{
+    activity::{ActivityKind, ActivitySession},
+    reflection::{Highlights, ReflectionSummary},
+};
+
+/// A share of breaks above this percentage is considered to be high
+const HIGH_BREAK_PERCENTAGE: u64 = 30;
+
+/// A share of breaks below this percentage is considered to be low
+const LOW_BREAK_PERCENTAGE: u64 = 5;
+
+/// Sessions longer than this on average are considered to be long, in seconds
+const LONG_SESSION_DURATION: u64 = 90 * 60;
+
+/// Sessions shorter than this on average are considered to be short, in seconds
+const SHORT_SESSION_DURATION: u64 = 15 * 60;
+
+/// A share of time spent in short sessions above this percentage is considered to be fragmented
+const FRAGMENTED_PERCENTAGE: u64 = 25;
+
+/// A change of the time spent of at least this percentage is considered to be significant
+const SIGNIFICANT_CHANGE_PERCENTAGE: i64 = 25;
+
+/// Statistics about the activity sessions within a review period
+#[derive(Debug, Serialize, Getters, Clone, Eq, PartialEq, Default)]
+#[getset(get = "pub")]
+pub struct ReflectionStatistics {
+    /// The amount of activity sessions within the review period
+    session_count: usize,
+
+    /// The average time spent per activity session, without breaks
+    average_session_duration: PaceDuration,
+
+    /// The share of breaks of the time spent on activities and breaks, in percent
+    break_percentage: u64,
+
+    /// Highlights of the review period, if there are any activity sessions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    highlights: Option<Highlights>,
+
+    /// The time spent per day, by the day the activity sessions began
+    daily_distribution: BTreeMap<PaceDate, PaceDuration>,
+
+    /// The time spent per ISO week, e.g. `2024-W11`, by the week the activity sessions began
+    weekly_distribution: BTreeMap<String, PaceDuration>,
+
+    /// The boundaries between short, medium and long activity sessions
+    duration_ranges: PaceDurationRangeOptions,
+
+    /// The time spent in short, medium and long activity sessions, showing how fragmented the work is
+    fragmentation: BTreeMap<PaceDurationRange, DurationRangeShare>,
+}
+
+/// The time spent in activity sessions of a duration range
+#[derive(Debug, Serialize, Getters, Clone, Copy, Eq, PartialEq, Default)]
+#[getset(get = "pub")]
+pub struct DurationRangeShare {
+    /// The amount of activity sessions within the duration range
+    session_count: usize,
+
+    /// The time spent in activity sessions within the duration range
+    duration: PaceDuration,
+
+    /// The share of the total time spent, in percent
+    percentage: u64,
+}
+
+impl ReflectionStatistics {
+    /// Calculate the statistics for the activity sessions of a reflection summary
+    ///
+    /// # Arguments
+    ///
+    /// * `summary` - The reflection summary to calculate the statistics for
+    /// * `duration_ranges` - The boundaries between short, medium and long activity sessions
+    ///
+    /// # Returns
+    ///
+    /// The statistics of the review period
+    #[must_use]
+    pub fn from_summary(
+        summary: &ReflectionSummary,
+        duration_ranges: PaceDurationRangeOptions,
+    ) -> Self {
+        let mut session_count = 0;
+        let mut fragmentation = BTreeMap::<PaceDurationRange, DurationRangeShare>::new();
+        let mut daily_distribution = BTreeMap::<PaceDate, PaceDuration>::new();
+        let mut weekly_distribution = BTreeMap::<String, PaceDuration>::new();
+        let mut kind_counts = BTreeMap::<ActivityKind, usize>::new();
+        let mut longest_session: Option<&ActivitySession> = None;
+
+        for session in summary.activity_sessions() {
+            session_count += 1;
+
+            let root_activity = session.root_activity().activity();
+            let begin = root_activity.begin();
+
+            *daily_distribution.entry(PaceDate::from(begin)).or_default() +=
+                *session.adjusted_duration();
+
+            let week = begin.inner().iso_week();
+
+            *weekly_distribution
+                .entry(format!("{}-W{:02}", week.year(), week.week()))
+                .or_default() += *session.adjusted_duration();
+
+            *kind_counts.entry(*root_activity.kind()).or_default() += 1;
+
+            let share = fragmentation
+                .entry(duration_ranges.range_of(*session.adjusted_duration()))
+                .or_default();
+
+            share.session_count += 1;
+            share.duration += *session.adjusted_duration();
+
+            if longest_session.map_or(true, |longest| {
+                session.adjusted_duration() > longest.adjusted_duration()
+            }) {
+                longest_session = Some(session);
+            }
+        }
+
+        let total_time_spent = summary.total_time_spent().as_secs();
+        let total_break_duration = summary.total_break_duration().as_secs();
+
+        let average_session_duration = u64::try_from(session_count)
+            .ok()
+            .and_then(|session_count| total_time_spent.checked_div(session_count))
+            .map(PaceDuration::new)
+            .unwrap_or_default();
+
+        let break_percentage = (total_break_duration * 100)
+            .checked_div(total_time_spent + total_break_duration)
+            .unwrap_or_default();
+
+        for share in fragmentation.values_mut() {
+            share.percentage = (share.duration.as_secs() * 100)
+                .checked_div(total_time_spent)
+                .unwrap_or_default();
+        }
+
+        let highlights = longest_session.map(|longest_session| {
+            // On a tie, the earlier day and the first kind are taken
+            let most_productive_day = daily_distribution
+                .iter()
+                .rev()
+                .max_by_key(|(_, duration)| **duration)
+                .map(|(day, _)| *day)
+                .unwrap_or_default();
+
+            let most_frequent_activity_kind = kind_counts
+                .iter()
+                .rev()
+                .max_by_key(|(_, count)| **count)
+                .map(|(kind, _)| *kind)
+                .unwrap_or_default();
+
+            Highlights::builder()
+                .most_productive_day(most_productive_day)
+                .most_frequent_activity_kind(most_frequent_activity_kind)
+                .most_time_spent_on(longest_session.root_activity().clone())
+                .build()
+        });
+
+        Self {
+            session_count,
+            average_session_duration,
+            break_percentage,
+            highlights,
+            daily_distribution,
+            weekly_distribution,
+            duration_ranges,
+            fragmentation,
+        }
+    }
+
+    /// Give recommendations based on the statistics of the review period
+    ///
+    /// # Arguments
+    ///
+    /// * `comparison` - The comparison to the previous review period, if any
+    ///
+    /// # Returns
+    ///
+    /// The recommendations for the user, empty if there is nothing to recommend
+    #[must_use]
+    pub fn recommendations(&self, comparison: Option<&ReflectionComparison>) -> Vec<String> {
+        let mut recommendations = vec![];
+
+        if self.session_count == 0 {
+            return recommendations;
+        }
+
+        if self.break_percentage < LOW_BREAK_PERCENTAGE {
+            recommendations.push(format!(
+                "Breaks make up only {}% of your time, consider taking regular breaks to stay focused.",
+                self.break_percentage
+            ));
+        } else if self.break_percentage > HIGH_BREAK_PERCENTAGE {
+            recommendations.push(format!(
+                "Breaks make up {}% of your time, consider fewer interruptions while working.",
+                self.break_percentage
+            ));
+        }
+
+        let average_session_duration = self.average_session_duration.as_secs();
+
+        if average_session_duration > LONG_SESSION_DURATION {
+            recommendations.push(format!(
+                "Your sessions last {} on average, consider splitting them up with short breaks.",
+                self.average_session_duration
+            ));
+        } else if average_session_duration < SHORT_SESSION_DURATION {
+            recommendations.push(format!(
+                "Your sessions last {} on average, consider grouping similar work into longer sessions.",
+                self.average_session_duration
+            ));
+        }
+
+        if let Some(short_share) = self
+            .fragmentation
+            .get(&PaceDurationRange::Short)
+            .filter(|short_share| short_share.percentage > FRAGMENTED_PERCENTAGE)
+        {
+            recommendations.push(format!(
+                "{}% of your time was spent in sessions shorter than {}, consider blocking time for focused work.",
+                short_share.percentage,
+                minutes(*self.duration_ranges.short_below_minutes())
+            ));
+        }
+
+        if let Some(change) =
+            comparison.and_then(ReflectionComparison::time_spent_change_percentage)
+        {
+            if change >= SIGNIFICANT_CHANGE_PERCENTAGE {
+                recommendations.push(format!(
+                    "You spent {change}% more time than in the previous period, make sure to keep a sustainable pace."
+                ));
+            } else if change <= -SIGNIFICANT_CHANGE_PERCENTAGE {
+                recommendations.push(format!(
+                    "You spent {}% less time than in the previous period.",
+                    change.unsigned_abs()
+                ));
+            }
+        }
+
+        recommendations
+    }
+}
+
+/// The time spent on a group within the review period and the previous period
+#[derive(Debug, Serialize, Getters, Clone, Copy, Eq, PartialEq, Default)]
+#[getset(get = "pub")]
+pub struct GroupComparison {
+    /// The time spent within the review period
+    current: PaceDuration,
+
+    /// The time spent within the previous period
+    previous: PaceDuration,
+}
+
+/// A comparison of a review period to the previous equivalent period
+#[derive(Debug, Serialize, Getters, Clone, Eq, PartialEq, Default)]
+#[getset(get = "pub")]
+pub struct ReflectionComparison {
+    /// The time range of the previous period
+    previous_time_range: TimeRangeOptions,
+
+    /// Total time spent on all activities within the previous period
+    previous_total_time_spent: PaceDuration,
+
+    /// Total time spent on intermissions within the previous period
+    previous_total_break_duration: PaceDuration,
+
+    /// The amount of activity sessions within the previous period
+    previous_session_count: usize,
+
+    /// The difference of the time spent to the previous period, in seconds
+    time_spent_difference: i64,
+
+    /// The time spent per group, e.g. `category::subcategory`, in both periods
+    groups: BTreeMap<String, GroupComparison>,
+}
+
+impl ReflectionComparison {
+    /// Compare a reflection summary to the one of the previous period
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - The reflection summary of the review period
+    /// * `previous_time_range` - The time range of the previous period
+    /// * `previous` - The reflection summary of the previous period, if there were any activities
+    ///
+    /// # Returns
+    ///
+    /// The comparison of both periods
+    #[must_use]
+    pub fn new(
+        current: &ReflectionSummary,
+        previous_time_range: TimeRangeOptions,
+        previous: Option<&ReflectionSummary>,
+    ) -> Self {
+        let mut groups = BTreeMap::<String, GroupComparison>::new();
+
+        for (key, summary_group) in current.summary_groups_by_category() {
+            groups.entry(group_name(key)).or_default().current += *summary_group.total_duration();
+        }
+
+        let (previous_total_time_spent, previous_total_break_duration, previous_session_count) =
+            previous.map_or_else(Default::default, |previous| {
+                for (key, summary_group) in previous.summary_groups_by_category() {
+                    groups.entry(group_name(key)).or_default().previous +=
+                        *summary_group.total_duration();
+                }
+
+                (
+                    *previous.total_time_spent(),
+                    *previous.total_break_duration(),
+                    previous.activity_sessions().count(),
+                )
+            });
+
+        let time_spent_difference =
+            seconds(*current.total_time_spent()).saturating_sub(seconds(previous_total_time_spent));
+
+        Self {
+            previous_time_range,
+            previous_total_time_spent,
+            previous_total_break_duration,
+            previous_session_count,
+            time_spent_difference,
+            groups,
+        }
+    }
+
+    /// The change of the time spent to the previous period, in percent
+    ///
+    /// # Returns
+    ///
+    /// The change in percent, or `None` if no time was spent within the previous period
+    #[must_use]
+    pub fn time_spent_change_percentage(&self) -> Option<i64> {
+        (self.time_spent_difference.saturating_mul(100))
+            .checked_div(seconds(self.previous_total_time_spent))
+    }
+}
+
+/// The name of a summary group, the subcategory is left out if there is none
+fn group_name((category, subcategory): &(String, String)) -> String {
+    if subcategory.is_empty() {
+        category.clone()
+    } else {
+        format!("{category}::{subcategory}")
+    }
+}
+
+/// A duration of minutes, e.g. for labelling the boundaries of duration ranges
+const fn minutes(minutes: u64) -> PaceDuration {
+    PaceDuration::new(minutes.saturating_mul(60))
+}
+
+/// The seconds of a duration as a signed number, for calculating differences
+fn seconds(duration: PaceDuration) -> i64 {
+    i64::try_from(duration.as_secs()).unwrap_or(i64::MAX)
+}
+
+impl std::fmt::Display for ReflectionStatistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut builder = Builder::new();
+
+        builder.push_record(vec!["Sessions".to_string(), self.session_count.to_string()]);
+        builder.push_record(vec![
+            "Average session".to_string(),
+            self.average_session_duration.to_string(),
+        ]);
+        builder.push_record(vec![
+            "Breaks".to_string(),
+            format!("{}%", self.break_percentage),
+        ]);
+
+        if let Some(highlights) = &self.highlights {
+            builder.push_record(vec![
+                "Most productive day".to_string(),
+                highlights.most_productive_day().to_string(),
+            ]);
+            builder.push_record(vec![
+                "Most frequent kind".to_string(),
+                highlights.most_frequent_activity_kind().to_string(),
+            ]);
+            builder.push_record(vec![
+                "Most time spent on".to_string(),
+                format!(
+                    "{} ({})",
+                    highlights.most_time_spent_on().activity().description(),
+                    highlights
+                        .most_time_spent_on()
+                        .activity()
+                        .duration_or_elapsed()
+                ),
+            ]);
+        }
+
+        write_table(f, "Statistics", builder)?;
+
+        let mut builder = Builder::new();
+
+        builder.push_record(vec!["Day", "Duration"]);
+
+        for (day, duration) in &self.daily_distribution {
+            builder.push_record(vec![day.to_string(), duration.to_string()]);
+        }
+
+        write_table(f, "Time spent per day", builder)?;
+
+        let mut builder = Builder::new();
+
+        builder.push_record(vec!["Week", "Duration"]);
+
+        for (week, duration) in &self.weekly_distribution {
+            builder.push_record(vec![week.clone(), duration.to_string()]);
+        }
+
+        write_table(f, "Time spent per week", builder)?;
+
+        let mut builder = Builder::new();
+
+        builder.push_record(vec!["Session length", "Sessions", "Duration", "Share"]);
+
+        let short_below = minutes(*self.duration_ranges.short_below_minutes());
+        let long_from = minutes(*self.duration_ranges.long_from_minutes());
+
+        for (range, label) in [
+            (PaceDurationRange::Short, format!("Short (< {short_below})")),
+            (
+                PaceDurationRange::Medium,
+                format!("Medium ({short_below} - {long_from})"),
+            ),
+            (PaceDurationRange::Long, format!("Long (>= {long_from})")),
+        ] {
+            let share = self.fragmentation.get(&range).copied().unwrap_or_default();
+
+            builder.push_record(vec![
+                label,
+                share.session_count.to_string(),
+                share.duration.to_string(),
+                format!("{}%", share.percentage),
+            ]);
+        }
+
+        write_table(f, "Time spent per session length", builder)
+    }
+}
+
+impl std::fmt::Display for ReflectionComparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut builder = Builder::new();
+
+        builder.push_record(vec!["Group", "Current", "Previous", "Change"]);
+
+        for (name, group) in &self.groups {
+            builder.push_record(vec![
+                name.clone(),
+                group.current.to_string(),
+                group.previous.to_string(),
+                format_change(seconds(group.current), seconds(group.previous)),
+            ]);
+        }
+
+        let previous_total = seconds(self.previous_total_time_spent);
+        let current_total = previous_total.saturating_add(self.time_spent_difference);
+
+        builder.push_record(vec![
+            "Total".to_string(),
+            PaceDuration::new(current_total.unsigned_abs()).to_string(),
+            self.previous_total_time_spent.to_string(),
+            format_change(current_total, previous_total),
+        ]);
+
+        write_table(
+            f,
+            &format!(
+                "Compared to the previous period:\n\n{}",
+                self.previous_time_range
+            ),
+            builder,
+        )
+    }
+}
+
+/// Format the change between two amounts of seconds in percent, e.g. `+25%`
+fn format_change(current: i64, previous: i64) -> String {
+    current
+        .saturating_sub(previous)
+        .saturating_mul(100)
+        .checked_div(previous)
+        .map_or_else(
+            || if current == 0 { "-" } else { "new" }.to_string(),
+            |change| format!("{change:+}%"),
+        )
+}
+
+/// Write a table with a header below the summary
+fn write_table(
+    f: &mut std::fmt::Formatter<'_>,
+    header: &str,
+    builder: Builder,
+) -> std::fmt::Result {
+    let table_config = Settings::default()
+        .with(Panel::header(header))
+        .with(Padding::new(1, 1, 0, 0))
+        .with(Style::modern_rounded())
+        .with(Modify::new(Columns::new(1..)).with(Alignment::right()));
+
+    let table = builder.build().with(table_config).to_string();
+
+    write!(f, "\n{table}")
+}
@@ -1,10 +1,18 @@
 use serde_derive::{Deserialize, Serialize};
+use strum_macros::Display;
 
-#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+/// The status of a task
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Display,
+)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
 pub enum TaskStatus {
     Completed,
     #[serde(rename = "wip")]
+    #[strum(serialize = "wip")]
+    #[cfg_attr(feature = "clap", value(name = "wip"))]
     WorkInProgress,
     Paused,
     Pending,
@@ -0,0 +1,404 @@
+use std::collections::BTreeMap;
+
+use getset::Getters;
+use merge::Merge;
+use pace_time::date_time::PaceDateTime;
+use strum_macros::Display;
+
+use crate::{
+    domain::{
+        activity::{Activity, ActivityGuid},
+        history::History,
+    },
+    error::PaceResult,
+};
+
+/// The activity log an activity of a merge has been taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum MergeSideKind {
+    /// The activity log of this device
+    Local,
+
+    /// The activity log that is merged into the local one
+    Other,
+}
+
+/// A conflict found while merging two activity logs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeConflict {
+    /// An activity has been changed differently in both activity logs
+    ConflictingChanges {
+        /// The id of the activity
+        activity_id: ActivityGuid,
+
+        /// The description of the activity
+        description: String,
+
+        /// The names of the fields that differ
+        fields: Vec<String>,
+
+        /// The activity log whose changes have been kept
+        winner: MergeSideKind,
+    },
+
+    /// An activity has been changed differently in both activity logs, but it's
+    /// unknown which changes have been made last, so the local ones have been kept
+    UnresolvedChanges {
+        /// The id of the activity
+        activity_id: ActivityGuid,
+
+        /// The description of the activity
+        description: String,
+
+        /// The names of the fields that differ
+        fields: Vec<String>,
+    },
+
+    /// Two activities have been active at the same time, so the earlier one has been ended
+    ConcurrentlyActive {
+        /// The id of the activity that has been ended
+        ended_id: ActivityGuid,
+
+        /// The description of the activity that has been ended
+        ended_description: String,
+
+        /// The id of the activity that is still active
+        active_id: ActivityGuid,
+
+        /// The description of the activity that is still active
+        active_description: String,
+
+        /// When the earlier activity has been ended, which is the begin of the later one
+        end: PaceDateTime,
+    },
+}
+
+impl std::fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ConflictingChanges {
+                description,
+                fields,
+                winner,
+                ..
+            } => write!(
+                f,
+                "\"{description}\" has been changed in both activity logs ({}), kept the changes of the {winner} activity log",
+                fields.join(", ")
+            ),
+            Self::UnresolvedChanges {
+                description,
+                fields,
+                ..
+            } => write!(
+                f,
+                "\"{description}\" has been changed in both activity logs ({}), but it's unknown which changes are newer, kept the local ones, adjust it if needed",
+                fields.join(", ")
+            ),
+            Self::ConcurrentlyActive {
+                ended_description,
+                active_description,
+                end,
+                ..
+            } => write!(
+                f,
+                "\"{ended_description}\" and \"{active_description}\" have been active at the same time, ended \"{ended_description}\" at {end}"
+            ),
+        }
+    }
+}
+
+/// The result of merging another activity log into the local one
+///
+/// Activity ids are ULIDs, so activities created on different devices never
+/// collide and the merged activity log is the union of both. An activity that
+/// has been changed in both activity logs is merged with the [`Merge`] impl of
+/// [`Activity`], so the changes of the activity log it has been changed in last
+/// win. An activity that has ended in either activity log stays ended, though.
+///
+/// Activities deleted in one of the activity logs are restored by the merge, as
+/// the activity logs don't keep track of deleted activities.
+#[derive(Debug, Clone, Getters, PartialEq, Eq)]
+#[getset(get = "pub")]
+pub struct ActivityLogMerge {
+    /// The merged activities
+    activities: BTreeMap<ActivityGuid, Activity>,
+
+    /// The ids of the activities that only exist in the other activity log
+    added: Vec<ActivityGuid>,
+
+    /// The conflicts found while merging, and how they have been resolved
+    conflicts: Vec<MergeConflict>,
+}
+
+impl ActivityLogMerge {
+    /// Merge another activity log into the local one
+    ///
+    /// # Arguments
+    ///
+    /// * `local` - The activities of the local activity log
+    /// * `other` - The activities of the other activity log
+    /// * `last_writer` - The activity log that has been written last, its changes win conflicts
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be compared or an activity cannot be ended
+    ///
+    /// # Returns
+    ///
+    /// The merged activities with the conflicts that have been resolved
+    pub fn new(
+        local: &BTreeMap<ActivityGuid, Activity>,
+        other: &BTreeMap<ActivityGuid, Activity>,
+        last_writer: MergeSideKind,
+    ) -> PaceResult<Self> {
+        Self::with_last_writers(local, other, |_| Some(last_writer))
+    }
+
+    /// Merge another activity log into the local one, the activity changed last on either side wins
+    ///
+    /// If both histories have recorded changes of an activity, the times of the
+    /// last changes decide. Otherwise the latest times of the activity itself
+    /// decide, when it has begun or ended. If those are the same as well, the
+    /// local activity is kept and the conflict is reported as unresolved.
+    ///
+    /// # Arguments
+    ///
+    /// * `local` - The activities of the local activity log
+    /// * `other` - The activities of the other activity log
+    /// * `local_history` - The history of the local activity log, if there is one
+    /// * `other_history` - The history of the other activity log, if there is one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be compared or an activity cannot be ended
+    ///
+    /// # Returns
+    ///
+    /// The merged activities with the conflicts that have been resolved
+    pub fn by_change_times(
+        local: &BTreeMap<ActivityGuid, Activity>,
+        other: &BTreeMap<ActivityGuid, Activity>,
+        local_history: Option<&History>,
+        other_history: Option<&History>,
+    ) -> PaceResult<Self> {
+        let last_change = |history: Option<&History>, activity_id: ActivityGuid| {
+            history.and_then(|history| {
+                history
+                    .changes_of(activity_id)
+                    .last()
+                    .map(|(entry, _)| *entry.at())
+            })
+        };
+
+        Self::with_last_writers(local, other, |activity_id| {
+            let (local_changed, other_changed) = match (
+                last_change(local_history, activity_id),
+                last_change(other_history, activity_id),
+            ) {
+                (Some(local_changed), Some(other_changed)) => (local_changed, other_changed),
+                _ => (
+                    latest_time(local.get(&activity_id)?),
+                    latest_time(other.get(&activity_id)?),
+                ),
+            };
+
+            match local_changed.cmp(&other_changed) {
+                std::cmp::Ordering::Greater => Some(MergeSideKind::Local),
+                std::cmp::Ordering::Less => Some(MergeSideKind::Other),
+                std::cmp::Ordering::Equal => None,
+            }
+        })
+    }
+
+    /// Merge another activity log into the local one, with the last writer decided per activity
+    ///
+    /// # Arguments
+    ///
+    /// * `local` - The activities of the local activity log
+    /// * `other` - The activities of the other activity log
+    /// * `last_writer` - The activity log an activity has been changed in last, its changes
+    ///   win conflicts, `None` if that's unknown and the local changes are kept
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be compared or an activity cannot be ended
+    ///
+    /// # Returns
+    ///
+    /// The merged activities with the conflicts that have been resolved
+    pub fn with_last_writers(
+        local: &BTreeMap<ActivityGuid, Activity>,
+        other: &BTreeMap<ActivityGuid, Activity>,
+        last_writer: impl Fn(ActivityGuid) -> Option<MergeSideKind>,
+    ) -> PaceResult<Self> {
+        let mut merge = Self {
+            activities: local.clone(),
+            added: vec![],
+            conflicts: vec![],
+        };
+
+        for (guid, other_activity) in other {
+            let Some(local_activity) = merge.activities.get_mut(guid) else {
+                merge.added.push(*guid);
+                let _ = merge.activities.insert(*guid, other_activity.clone());
+                continue;
+            };
+
+            let fields = local_activity.changed_fields(other_activity)?;
+
+            if fields.is_empty() {
+                continue;
+            }
+
+            let Some(winner) = last_writer(*guid) else {
+                merge.conflicts.push(MergeConflict::UnresolvedChanges {
+                    activity_id: *guid,
+                    description: local_activity.description().clone(),
+                    fields,
+                });
+                continue;
+            };
+
+            let (mut merged, latest) = match winner {
+                MergeSideKind::Local => (other_activity.clone(), local_activity.clone()),
+                MergeSideKind::Other => (local_activity.clone(), other_activity.clone()),
+            };
+
+            let earlier_end = merged.activity_end_options().clone();
+
+            merged.merge(latest);
+
+            // Ending an activity can't be undone by a device that didn't know about it yet
+            if let (None, Some(end_opts)) = (merged.activity_end_options(), earlier_end) {
+                merged.end_activity(end_opts);
+            }
+
+            *local_activity = merged;
+
+            merge.conflicts.push(MergeConflict::ConflictingChanges {
+                activity_id: *guid,
+                description: local_activity.description().clone(),
+                fields,
+                winner,
+            });
+        }
+
+        merge.end_concurrently_active()?;
+
+        Ok(merge)
+    }
+
+    /// End all active activities but the one that has begun last, when it has begun
+    fn end_concurrently_active(&mut self) -> PaceResult<()> {
+        let mut active = self
+            .activities
+            .iter()
+            .filter(|(_, activity)| {
+                activity.is_in_progress()
+                    && !activity.kind().is_intermission()
+                    && !activity.kind().is_pomodoro_intermission()
+            })
+            .map(|(guid, activity)| (*guid, *activity.begin()))
+            .collect::<Vec<_>>();
+
+        active.sort_by_key(|(_, begin)| *begin);
+
+        let Some((active_id, end)) = active.pop() else {
+            return Ok(());
+        };
+
+        let active_description = self
+            .activities
+            .get(&active_id)
+            .map(|activity| activity.description().clone())
+            .unwrap_or_default();
+
+        for (ended_id, begin) in active {
+            let Some(activity) = self.activities.get_mut(&ended_id) else {
+                continue;
+            };
+
+            activity.end_activity_with_duration_calc(begin, end)?;
+
+            self.conflicts.push(MergeConflict::ConcurrentlyActive {
+                ended_id,
+                ended_description: activity.description().clone(),
+                active_id,
+                active_description: active_description.clone(),
+                end,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// The latest time of an activity, when it has ended or begun
+fn latest_time(activity: &Activity) -> PaceDateTime {
+    let begin = *activity.begin();
+
+    activity
+        .activity_end_options()
+        .as_ref()
+        .map_or(begin, |end_opts| (*end_opts.end()).max(begin))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use chrono::{Duration, Local};
+
+    use crate::{domain::activity::ActivityItem, error::TestResult};
+
+    use super::*;
+
+    #[test]
+    fn test_activity_log_merge_resolves_conflicts_passes() -> TestResult<()> {
+        let now = Local::now().fixed_offset();
+
+        let shared = ActivityItem::from(
+            Activity::builder()
+                .description("Shared")
+                .begin(PaceDateTime::from(now - Duration::hours(1)))
+                .build(),
+        );
+
+        // Ended on this device
+        let mut ended = shared.activity().clone();
+        ended.end_activity_with_duration_calc(*ended.begin(), PaceDateTime::from(now))?;
+
+        // Retitled on the other device, which doesn't know about the end yet
+        let mut retitled = shared.activity().clone();
+        retitled.set_description("Retitled".to_string());
+
+        let added = ActivityItem::from(Activity::builder().description("Added").build());
+
+        let local = BTreeMap::from([(*shared.guid(), ended)]);
+        let other = BTreeMap::from([
+            (*shared.guid(), retitled),
+            (*added.guid(), added.activity().clone()),
+        ]);
+
+        let merge = ActivityLogMerge::new(&local, &other, MergeSideKind::Other)?;
+
+        let merged = merge
+            .activities()
+            .get(shared.guid())
+            .ok_or("shared activity missing")?;
+
+        assert_eq!(merged.description(), "Retitled");
+        assert!(merged.is_completed(), "Should have kept the end.");
+        assert_eq!(merge.added(), &vec![*added.guid()]);
+        assert!(matches!(
+            merge.conflicts().as_slice(),
+            [MergeConflict::ConflictingChanges {
+                winner: MergeSideKind::Other,
+                ..
+            }]
+        ));
+
+        Ok(())
+    }
+}
@@ -3,6 +3,8 @@ use serde_derive::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use ulid::Ulid;
 
+use crate::error::{PaceErrorKind, PaceResult};
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
 pub struct TagGuid(Ulid);
 
@@ -26,3 +28,27 @@ impl Tag {
         Self { guid, text }
     }
 }
+
+/// Trim the whitespace around a tag given by the user and check it
+///
+/// # Arguments
+///
+/// * `tag` - The tag to check
+///
+/// # Errors
+///
+/// Returns an error if the tag is empty or contains a comma, as tags are
+/// separated by commas on the command line
+///
+/// # Returns
+///
+/// The trimmed tag
+pub fn normalize_tag(tag: &str) -> PaceResult<String> {
+    let normalized = tag.trim();
+
+    if normalized.is_empty() || normalized.contains(',') {
+        return Err(PaceErrorKind::InvalidTag(tag.to_string()).into());
+    }
+
+    Ok(normalized.to_string())
+}
@@ -3,42 +3,227 @@
 use std::{
     collections::BTreeMap,
     fmt::{Display, Formatter},
+    str::FromStr,
 };
 
-use chrono::NaiveDateTime;
+use chrono::{Local, NaiveDateTime};
+use getset::{Getters, MutGetters, Setters};
 use serde_derive::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use ulid::Ulid;
 
-use crate::domain::{priority::ItemPriorityKind, status::TaskStatus};
+use crate::{
+    domain::{priority::ItemPriorityKind, status::TaskStatus},
+    error::{PaceErrorKind, PaceResult},
+};
 
-#[derive(Debug, TypedBuilder, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[derive(
+    Debug,
+    TypedBuilder,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Getters,
+    Setters,
+    MutGetters,
+)]
+#[getset(get = "pub", set = "pub", get_mut = "pub")]
 pub struct Task {
+    #[builder(default = Local::now().naive_local())]
     created_at: NaiveDateTime,
 
+    #[builder(default, setter(into))]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     description: String,
 
     #[builder(default, setter(strip_option))]
     #[serde(skip_serializing_if = "Option::is_none")]
     finished_at: Option<NaiveDateTime>,
 
+    #[builder(default)]
     priority: ItemPriorityKind,
 
+    #[builder(default)]
     status: TaskStatus,
 
+    #[builder(default)]
+    #[serde(default)]
     tags: Vec<String>,
 
+    #[builder(setter(into))]
     title: String,
     // TODO: It would be nice to have a way to track the number of pomodoro cycles for each task
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl Task {
+    /// If the task has been completed
+    #[must_use]
+    pub fn is_completed(&self) -> bool {
+        self.status == TaskStatus::Completed
+    }
+
+    /// Mark the task as completed at the current time
+    pub fn complete(&mut self) {
+        self.status = TaskStatus::Completed;
+        self.finished_at = Some(Local::now().naive_local());
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Getters)]
+#[getset(get = "pub")]
 pub struct TaskList {
     /// The tasks in the list
     #[serde(flatten)]
     tasks: BTreeMap<TaskGuid, Task>,
 }
 
+impl TaskList {
+    /// Add a task to the list
+    ///
+    /// # Arguments
+    ///
+    /// * `task` - The task to add
+    ///
+    /// # Returns
+    ///
+    /// The id of the added task
+    pub fn add(&mut self, task: Task) -> TaskGuid {
+        let task_id = TaskGuid::default();
+
+        _ = self.tasks.insert(task_id, task);
+
+        task_id
+    }
+
+    /// Insert a task with an existing id into the list
+    ///
+    /// # Arguments
+    ///
+    /// * `task_id` - The id of the task
+    /// * `task` - The task to insert
+    ///
+    /// # Returns
+    ///
+    /// The task that had the same id before, if any
+    pub fn insert(&mut self, task_id: TaskGuid, task: Task) -> Option<Task> {
+        self.tasks.insert(task_id, task)
+    }
+
+    /// Get a task by its id
+    #[must_use]
+    pub fn get(&self, task_id: &TaskGuid) -> Option<&Task> {
+        self.tasks.get(task_id)
+    }
+
+    /// Get a mutable reference to a task by its id
+    pub fn get_mut(&mut self, task_id: &TaskGuid) -> Option<&mut Task> {
+        self.tasks.get_mut(task_id)
+    }
+
+    /// Remove a task from the list
+    ///
+    /// # Returns
+    ///
+    /// The removed task, `None` if there is no task with the id
+    pub fn remove(&mut self, task_id: &TaskGuid) -> Option<Task> {
+        self.tasks.remove(task_id)
+    }
+
+    /// Find the id of a task by its id or a unique prefix of it
+    ///
+    /// # Arguments
+    ///
+    /// * `id_or_prefix` - The id of the task or the beginning of it, case insensitive
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no task or more than one task matches
+    ///
+    /// # Returns
+    ///
+    /// The id of the matching task
+    pub fn find_id(&self, id_or_prefix: &str) -> PaceResult<TaskGuid> {
+        let prefix = id_or_prefix.trim().to_uppercase();
+
+        let mut matches = self
+            .tasks
+            .keys()
+            .filter(|task_id| !prefix.is_empty() && task_id.to_string().starts_with(&prefix));
+
+        match (matches.next(), matches.next()) {
+            (Some(task_id), None) => Ok(*task_id),
+            (Some(_), Some(_)) => {
+                Err(PaceErrorKind::AmbiguousTaskId(id_or_prefix.to_string()).into())
+            }
+            (None, _) => Err(PaceErrorKind::TaskNotFound(id_or_prefix.to_string()).into()),
+        }
+    }
+
+    /// Iterate over the tasks matching a filter
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The filter the tasks need to match
+    pub fn filter<'a>(
+        &'a self,
+        filter: &'a TaskFilter,
+    ) -> impl Iterator<Item = (&'a TaskGuid, &'a Task)> + 'a {
+        self.tasks.iter().filter(|(_, task)| filter.matches(task))
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+}
+
+/// A filter for tasks
+#[derive(Debug, Clone, Default, PartialEq, Eq, TypedBuilder, Getters)]
+#[getset(get = "pub")]
+pub struct TaskFilter {
+    /// Only tasks with this status, completed tasks are excluded if not set
+    #[builder(default)]
+    status: Option<TaskStatus>,
+
+    /// Only tasks with this priority
+    #[builder(default)]
+    priority: Option<ItemPriorityKind>,
+
+    /// Only tasks that have all of these tags
+    #[builder(default)]
+    tags: Vec<String>,
+
+    /// Include completed tasks, if no status is set
+    #[builder(default)]
+    include_completed: bool,
+}
+
+impl TaskFilter {
+    /// If a task matches the filter
+    #[must_use]
+    pub fn matches(&self, task: &Task) -> bool {
+        let matches_status = self.status.map_or_else(
+            || self.include_completed || !task.is_completed(),
+            |status| task.status == status,
+        );
+
+        matches_status
+            && self
+                .priority
+                .map_or(true, |priority| task.priority == priority)
+            && self.tags.iter().all(|tag| task.tags.contains(tag))
+    }
+}
+
 /// The unique identifier of an activity
 #[derive(Debug, Clone, Serialize, Deserialize, Ord, PartialEq, PartialOrd, Eq, Copy, Hash)]
 pub struct TaskGuid(Ulid);
@@ -55,6 +240,16 @@ impl Default for TaskGuid {
     }
 }
 
+impl FromStr for TaskGuid {
+    type Err = PaceErrorKind;
+
+    fn from_str(task_id: &str) -> Result<Self, Self::Err> {
+        Ulid::from_string(task_id)
+            .map(Self)
+            .map_err(|_| PaceErrorKind::TaskNotFound(task_id.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -73,4 +268,63 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_find_task_id_by_prefix_passes() -> TestResult<()> {
+        let mut task_list = toml::from_str::<TaskList>(
+            r#"
+[01HPY7H596FT2R880SEKH7KN25]
+title = "Implement feature X"
+created_at = "2024-02-04T12:34:56"
+priority = "high"
+status = "pending"
+
+[01HPY7F03JQ6SJF5C97H7G7E0E]
+title = "Fix bug Y"
+created_at = "2024-02-06T12:34:56"
+priority = "medium"
+status = "completed"
+tags = ["bug"]
+"#,
+        )?;
+
+        let task_id = task_list.find_id("01hpy7h")?;
+
+        assert_eq!(task_id.to_string(), "01HPY7H596FT2R880SEKH7KN25");
+        assert!(task_list.find_id("01HPY7").is_err(), "Prefix is ambiguous.");
+        assert!(task_list.find_id("").is_err(), "Prefix is empty.");
+        assert!(task_list.find_id("01HQ").is_err(), "Task doesn't exist.");
+
+        assert_eq!(task_list.filter(&TaskFilter::default()).count(), 1);
+        assert_eq!(
+            task_list
+                .filter(
+                    &TaskFilter::builder()
+                        .tags(vec!["bug".to_string()])
+                        .include_completed(true)
+                        .build()
+                )
+                .count(),
+            1
+        );
+
+        task_list
+            .get_mut(&task_id)
+            .ok_or("Task should exist.")?
+            .complete();
+
+        assert!(task_list.filter(&TaskFilter::default()).next().is_none());
+
+        let new_task_id = task_list.add(Task::builder().title("Write docs").build());
+
+        assert_eq!(
+            task_list
+                .filter(&TaskFilter::builder().status(Some(TaskStatus::Todo)).build())
+                .map(|(task_id, _)| *task_id)
+                .collect::<Vec<_>>(),
+            vec![new_task_id]
+        );
+
+        Ok(())
+    }
 }
@@ -92,6 +92,15 @@ impl PaceError {
             PaceErrorKind::ActivityLog(ActivityLogErrorKind::ActivityAlreadyArchived(_))
         )
     }
+
+    /// Is this error about an activity that doesn't exist (anymore)?
+    #[must_use]
+    pub const fn is_activity_not_found(&self) -> bool {
+        matches!(
+            self.0,
+            PaceErrorKind::ActivityLog(ActivityLogErrorKind::ActivityNotFound(_))
+        )
+    }
 }
 
 /// [`PaceErrorKind`] describes the errors that can happen while executing a high-level command.
@@ -133,10 +142,16 @@ pub enum PaceErrorKind {
     #[error(transparent)]
     Json(#[from] serde_json::Error),
 
-    // /// SQLite error: {0}
-    // #[error(transparent)]
-    // #[cfg(feature = "sqlite")]
-    // SQLite(#[from] diesel::ConnectionError),
+    /// `SQLite` connection error: `{0}`
+    #[error(transparent)]
+    #[cfg(feature = "sqlite")]
+    SQLite(#[from] diesel::ConnectionError),
+
+    /// `SQLite` query error: `{0}`
+    #[error(transparent)]
+    #[cfg(feature = "sqlite")]
+    SQLiteQuery(#[from] diesel::result::Error),
+
     /// Chrono parse error: `{0}`
     #[error(transparent)]
     ChronoParse(#[from] chrono::ParseError),
@@ -160,9 +175,84 @@ pub enum PaceErrorKind {
     /// Database storage not implemented, yet!
     DatabaseStorageNotImplemented,
 
+    /// Database configuration not found, please add a `[database]` section to your configuration
+    DatabaseConfigNotFound,
+
+    /// Database schema version {0} is newer than version {1} supported by this version of pace, please upgrade pace
+    DatabaseSchemaTooNew(i32, i32),
+
+    /// The time format `{0}` is invalid, please use a format supported by `chrono`, e.g. `%Y-%m-%d %H:%M`
+    InvalidTimeFormat(String),
+
+    /// The CSV file has no column `{0}`, please map the columns of the file with `--columns`
+    CsvColumnNotFound(String),
+
+    /// Invalid CSV column mapping `{0}`, expected `field=column` with one of the fields begin, end, description, category, tags, or kind
+    InvalidCsvColumnMapping(String),
+
+    /// There is no pomodoro session running, please provide a description to start a new one
+    NoPomodoroSessionFound,
+
+    /// There is no task with the id `{0}`
+    TaskNotFound(String),
+
+    /// The task id `{0}` matches more than one task, please provide more characters
+    AmbiguousTaskId(String),
+
+    /// Please provide a description for the activity, or a task to track it for
+    NoActivityDescription,
+
+    /// There is no project with the name or id `{0}`
+    ProjectNotFound(String),
+
+    /// The project `{0}` matches more than one project, please provide its id
+    AmbiguousProject(String),
+
+    /// The inbox already holds the maximum of {0} items, please promote some of them first
+    InboxFull(usize),
+
+    /// There is no activity with the id `{0}`
+    ActivityIdNotFound(String),
+
+    /// The activity id `{0}` matches more than one activity, please provide more characters
+    AmbiguousActivityId(String),
+
+    /// There is no active activity to tag, please provide the id of an activity with `--activity`
+    NoActivityToTag,
+
+    /// The tag `{0}` is invalid, tags can't be empty or contain a comma
+    InvalidTag(String),
+
     /// There is no path available to store the activity log
     NoPathAvailable,
 
+    /// The event subscriber `{0}` failed: {1}
+    EventSubscriberFailed(String, String),
+
+    /// The event subscriber `{0}` didn't finish within {1} seconds and has been stopped
+    EventSubscriberTimedOut(String, u64),
+
+    /// The activity {0} has been changed in the meantime, please undo or redo its later changes first
+    HistoryConflict(ActivityGuid),
+
+    /// There is no history of the changes to the activities to undo or redo them
+    NoHistory,
+
+    /// There is no activity log at `{0}`
+    ActivityLogNotFound(String),
+
+    /// The git command `git {0}` failed: {1}
+    GitCommandFailed(String, String),
+
+    /// The activity log is not stored in git, please set `storage-kind = "git"` in the `[general]` section of your configuration
+    GitStorageNotConfigured,
+
+    /// The activity log on {0} has changes you don't have yet, pull them with `pace sync pull` before pushing
+    GitPullRequired(String),
+
+    /// There is no git remote configured, please set `remote` in the `[git]` section of your configuration or use `--remote`
+    GitRemoteNotConfigured,
+
     /// {0}
     #[error(transparent)]
     Template(#[from] TemplatingErrorKind),
@@ -226,6 +316,15 @@ pub enum ActivityLogErrorKind {
     /// Activity with id {0} already has been archived
     ActivityAlreadyArchived(ActivityGuid),
 
+    /// Activity with id {0} can't be archived, because it hasn't ended yet
+    ActivityNotArchivable(ActivityGuid),
+
+    /// Activity with id {0} has not been archived
+    ActivityNotArchived(ActivityGuid),
+
+    /// Activity with id {0} has no tag `{1}`
+    TagNotFound(ActivityGuid, String),
+
     /// Active activity with id {0} found, although we wanted a held activity
     ActiveActivityFound(ActivityGuid),
 
@@ -249,6 +348,9 @@ pub enum ActivityLogErrorKind {
 
     /// No end options found for activity
     NoEndOptionsFound,
+
+    /// Invalid entry in line {0} of the activity log journal: {1}
+    InvalidJournalEntry(usize, String),
 }
 
 /// [`TemplatingErrorKind`] describes the errors that can happen while dealing with templating.
@@ -259,7 +361,13 @@ pub enum TemplatingErrorKind {
     FailedToGenerateContextFromSerialize(tera::Error),
 
     /// Failed to render template: {0}
-    RenderingToTemplateFailed(tera::Error),
+    RenderingToTemplateFailed(String),
+
+    /// Failed to load the templates from {0}: {1}
+    LoadingTemplatesFailed(String, String),
+
+    /// Template `{0}` not found, available templates: {1}
+    TemplateNotFound(String, String),
 
     /// Failed to read template file: {0}
     FailedToReadTemplateFile(io::Error),
@@ -311,6 +419,8 @@ impl PaceErrorMarker for toml::ser::Error {}
 impl PaceErrorMarker for serde_json::Error {}
 #[cfg(feature = "sqlite")]
 impl PaceErrorMarker for diesel::ConnectionError {}
+#[cfg(feature = "sqlite")]
+impl PaceErrorMarker for diesel::result::Error {}
 impl PaceErrorMarker for chrono::ParseError {}
 impl PaceErrorMarker for chrono::OutOfRangeError {}
 impl PaceErrorMarker for ActivityLogErrorKind {}
@@ -15,6 +15,11 @@ pub mod constants {
     pub const PACE_CORE_VERSION: &str = env!("CARGO_PKG_VERSION");
     pub const PACE_CONFIG_FILENAME: &str = "pace.toml";
     pub const PACE_ACTIVITY_LOG_FILENAME: &str = "activities.pace.toml";
+    pub const PACE_TASKS_FILENAME: &str = "tasks.pace.toml";
+    pub const PACE_INBOX_FILENAME: &str = "inbox.pace.toml";
+    pub const PACE_HISTORY_FILENAME: &str = "history.pace.jsonl";
+    pub const PACE_ARCHIVE_DIRNAME: &str = "archive";
+    pub const PACE_PROJECTS_FILENAME: &str = "projects.pace.toml";
     pub const PACE_DOCS_URL: &str = "https://pace.cli.rs/docs";
     pub const PACE_DEV_DOCS_URL: &str = "https://pace.cli.rs/dev-docs";
     pub const PACE_CONFIG_DOCS_URL: &str =
@@ -30,44 +35,117 @@ pub mod prelude {
     pub use crate::{
         commands::{
             adjust::AdjustCommandOptions,
+            archive::ArchiveCommandOptions,
             begin::BeginCommandOptions,
             docs::DocsCommandOptions,
             end::EndCommandOptions,
+            export::ExportCommandOptions,
+            history::{LogCommandOptions, RedoCommandOptions, UndoCommandOptions},
             hold::{HoldCommandOptions, HoldOptions},
+            import::ImportCommandOptions,
+            inbox::{
+                AddInboxItemCommandOptions, ListInboxCommandOptions, PromoteInboxItemCommandOptions,
+            },
+            invoice::{InvoiceCommandOptions, DEFAULT_INVOICE_TEMPLATE_NAME},
+            migrate::MigrateCommandOptions,
             now::NowCommandOptions,
+            pomo::PomoCommandOptions,
             reflect::{ExpensiveFlags, ReflectCommandOptions},
             resume::{ResumeCommandOptions, ResumeOptions},
+            sync::{
+                GitRemoteOptions, MergeSyncCommandOptions, PullSyncCommandOptions,
+                PushSyncCommandOptions,
+            },
+            tag::{
+                AddTagCommandOptions, ListTagsCommandOptions, RemoveTagCommandOptions,
+                RenameTagCommandOptions,
+            },
+            tasks::{
+                AddTaskCommandOptions, DoneTaskCommandOptions, EditTaskCommandOptions,
+                ListTasksCommandOptions, RemoveTaskCommandOptions,
+            },
             DeleteOptions, EndOptions, KeywordOptions, UpdateOptions,
         },
         config::{
             find_root_config_file_path, find_root_project_file, get_activity_log_paths,
             get_config_paths, get_home_activity_log_path, get_home_config_path,
-            ActivityLogFormatKind, ActivityLogStorageKind, AutoArchivalConfig, DatabaseConfig,
-            ExportConfig, GeneralConfig, InboxConfig, PaceConfig, PomodoroConfig,
-            ReflectionsConfig,
+            ActivityLogFormatKind, ActivityLogStorageKind, AutoArchivalConfig, BillingConfig,
+            DatabaseConfig, DatabaseEngineKind, EventSubscriberConfig, EventsConfig, ExportConfig,
+            GeneralConfig, GitConfig, InboxConfig, PaceConfig, PomodoroConfig, ReflectionsConfig,
+            ServerConfig,
         },
         domain::{
             activity::{
                 Activity, ActivityEndOptions, ActivityGroup, ActivityGuid, ActivityItem,
-                ActivityKind, ActivityKindOptions, ActivitySession,
+                ActivityKind, ActivityKindOptions, ActivitySession, PomodoroCycle,
             },
             activity_log::ActivityLog,
+            billing::{Amount, Invoice, InvoiceLineItem},
             category::split_category_by_category_separator,
+            event::{PaceEvent, PaceEventKind},
+            export::{validate_time_format, ActivityExport, ExportFormatKind, ExportedActivity},
             filter::{ActivityFilterKind, FilterOptions, FilteredActivities},
+            goal::{Goal, GoalPeriodKind, GoalProgress},
+            history::{ActivityChange, History, HistoryEntry},
+            import::{
+                completed_activity,
+                csv::{CsvColumnMapping, CsvParser},
+                timewarrior::TimewarriorParser,
+                watson::WatsonParser,
+                ActivityParser, ActivityParserKind, ImportFormatKind, ParsedActivities,
+                SkipReasonKind, SkippedEntry,
+            },
+            inbox::{Inbox, InboxItem},
             intermission::IntermissionAction,
+            journal::{JournalEntry, JournalOperationKind},
+            pomodoro::{PomodoroPhase, PomodoroPhaseKind},
+            priority::ItemPriorityKind,
+            project::{Project, ProjectFilter, ProjectGuid, ProjectList},
             reflection::{
-                Highlights, ReflectionSummary, ReflectionsFormatKind, SummaryActivityGroup,
-                SummaryCategories, SummaryGroupByCategory,
+                Highlights, ReflectionGroupByKind, ReflectionSummary, ReflectionsFormatKind,
+                SummaryActivityGroup, SummaryCategories, SummaryGroupByCategory,
+                SummaryGroupByProject, SummaryGroupByTask, SummaryProjectGroup, SummaryTaskGroup,
+            },
+            rounding::{RoundingModeKind, RoundingOptions, RoundingScopeKind},
+            statistics::{
+                DurationRangeShare, GroupComparison, ReflectionComparison, ReflectionStatistics,
             },
-            status::ActivityStatusKind,
+            status::{ActivityStatusKind, TaskStatus},
+            sync::{ActivityLogMerge, MergeConflict, MergeSideKind},
+            tag::normalize_tag,
+            task::{Task, TaskFilter, TaskGuid, TaskList},
         },
         error::{PaceError, PaceErrorKind, PaceOptResult, PaceResult, TestResult, UserMessage},
-        service::{activity_store::ActivityStore, activity_tracker::ActivityTracker},
+        service::{
+            activity_archive::ActivityArchive,
+            activity_history::ActivityHistory,
+            activity_store::ActivityStore,
+            activity_tracker::ActivityTracker,
+            event_bus::{
+                EventBus, EventSubscriber, ShellCommandSubscriber,
+                DEFAULT_EVENT_SUBSCRIBER_TIMEOUT_SECS,
+            },
+            inbox_store::InboxStore,
+            task_store::TaskStore,
+        },
         storage::{
-            file::TomlActivityStorage, get_storage_from_config, in_memory::InMemoryActivityStorage,
-            ActivityQuerying, ActivityReadOps, ActivityStateManagement, ActivityStorage,
-            ActivityWriteOps, StorageKind, SyncStorage,
+            file::TomlActivityStorage,
+            get_storage_from_config, get_storage_from_path,
+            git::{GitActivityStorage, GitPull},
+            in_memory::InMemoryActivityStorage,
+            journal::{JournalActivityStorage, DEFAULT_JOURNAL_COMPACTION_THRESHOLD},
+            ActivityArchiving, ActivityQuerying, ActivityReadOps, ActivityStateManagement,
+            ActivityStorage, ActivityTagging, ActivityWriteOps, StorageKind, SyncStorage,
+        },
+        util::{escape_csv_field, overwrite_left_with_right, parse_csv_records, to_plain_text},
+    };
+
+    #[cfg(feature = "sqlite")]
+    pub use crate::storage::{
+        get_sqlite_storage_from_config,
+        sqlite::{
+            migrations::{Migration, LATEST_SCHEMA_VERSION},
+            SqliteActivityStorage,
         },
-        util::overwrite_left_with_right,
     };
 }
@@ -4,4 +4,34 @@
 ///
 pub mod activity_store;
 
+/// An activity history service
+///
+/// This module contains the logic for recording and persisting the changes to the activities.
+pub mod activity_history;
+
 pub mod activity_tracker;
+
+/// An event bus service
+///
+/// This module contains the bus the activity store publishes its events on and its subscribers.
+pub mod event_bus;
+
+/// A task store service
+///
+/// This module contains the logic for loading and persisting the task list.
+pub mod task_store;
+
+/// A project store service
+///
+/// This module contains the logic for discovering and loading the project hierarchy.
+pub mod project_store;
+
+/// An inbox store service
+///
+/// This module contains the logic for loading, archiving and persisting the inbox.
+pub mod inbox_store;
+
+/// An activity archive service
+///
+/// This module contains the logic for moving ended activities to the per-year archive.
+pub mod activity_archive;
@@ -0,0 +1,316 @@
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, read_to_string},
+    path::{Path, PathBuf},
+};
+
+use chrono::{Datelike, TimeDelta};
+use getset::Getters;
+use pace_time::{date_time::PaceDateTime, time_range::TimeRangeOptions};
+use tracing::debug;
+
+use crate::{
+    commands::DeleteOptions,
+    config::PaceConfig,
+    domain::{
+        activity::{Activity, ActivityGuid},
+        activity_log::ActivityLog,
+        filter::{ActivityFilterKind, FilteredActivities},
+        journal::JournalOperationKind,
+    },
+    error::PaceResult,
+    service::activity_store::ActivityStore,
+    storage::{
+        lock::ActivityLogFile, ActivityArchiving, ActivityQuerying, ActivityReadOps,
+        ActivityWriteOps, ChangedActivities, StorageKind,
+    },
+};
+
+/// The activity archive is responsible for moving ended activities out of the activity log
+///
+/// Archived activities are stored in one activity log per year in the archive
+/// directory, e.g. `2023.pace.toml`, so the activity log itself stays small.
+#[derive(Debug, Getters)]
+#[getset(get = "pub")]
+pub struct ActivityArchive {
+    /// The path to the archive directory
+    path: PathBuf,
+}
+
+impl ActivityArchive {
+    /// Create a new `ActivityArchive` for an archive directory
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the archive directory, it is created on the first archival
+    #[must_use]
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Create a new `ActivityArchive` for the archive directory in the configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    #[must_use]
+    pub fn from_config(config: &PaceConfig) -> Self {
+        Self::new(config.archive_path())
+    }
+
+    /// Get the path to the activity log of a year in the archive
+    #[must_use]
+    pub fn year_path(&self, year: i32) -> PathBuf {
+        self.path.join(format!("{year}.pace.toml"))
+    }
+
+    /// Load the archived activities of a year
+    ///
+    /// # Arguments
+    ///
+    /// * `year` - The year the activities began in
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activity log of the year exists but cannot be read or deserialized
+    ///
+    /// # Returns
+    ///
+    /// The archived activities, empty if nothing has been archived for the year
+    pub fn load_year(&self, year: i32) -> PaceResult<ActivityLog> {
+        let path = self.year_path(year);
+
+        if !path.exists() {
+            debug!("No archive found for {year}");
+
+            return Ok(ActivityLog::default());
+        }
+
+        let mut activity_log = ActivityLog::default();
+
+        ActivityLogFile::new(path).load(|path| {
+            activity_log = toml::from_str::<ActivityLog>(&read_to_string(path)?)?;
+            Ok(())
+        })?;
+
+        Ok(activity_log)
+    }
+
+    /// Load the archived activities of all years a time range touches
+    ///
+    /// # Arguments
+    ///
+    /// * `time_range_opts` - The time range to load the archived activities for
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an activity log in the archive cannot be read or deserialized
+    ///
+    /// # Returns
+    ///
+    /// The archived activities of the years, they still need to be filtered by the time range
+    pub fn load_time_range(&self, time_range_opts: &TimeRangeOptions) -> PaceResult<ActivityLog> {
+        let start_year = time_range_opts.start().inner().year();
+        let end_year = time_range_opts.end().inner().year();
+
+        let mut activity_log = ActivityLog::default();
+
+        for year in start_year..=end_year {
+            activity_log.extend(self.load_year(year)?.activities().clone());
+        }
+
+        Ok(activity_log)
+    }
+
+    /// Archive the activities that have ended a number of days ago and move them to the archive
+    ///
+    /// Intermissions are archived together with the activity they belong to. Activities
+    /// that have been archived before, but are still in the activity log, are moved as well.
+    /// The changes are recorded as one archive operation in the history of the activity
+    /// store and still need to be synced afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_store` - The activity store to archive the activities from
+    /// * `days` - The amount of days ago the activities need to have ended, `0` for all ended activities
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be archived or the archive cannot be written
+    ///
+    /// # Returns
+    ///
+    /// The amount of activities that have been moved to the archive
+    #[tracing::instrument(skip(self, activity_store))]
+    pub fn archive_activities(
+        &self,
+        activity_store: &ActivityStore,
+        days: u32,
+    ) -> PaceResult<usize> {
+        // The storage is changed directly, so the activities aren't recorded one by one
+        let changed = ChangedActivities::Filtered(vec![
+            ActivityFilterKind::Ended,
+            ActivityFilterKind::Archived,
+        ]);
+
+        activity_store.record_history(JournalOperationKind::Archive, &changed, || {
+            self.archive_ended_activities(activity_store.storage(), days)
+        })
+    }
+
+    /// Archive the activities that have ended a number of days ago in the storage and move them to the archive
+    ///
+    /// # Arguments
+    ///
+    /// * `storage` - The storage to archive the activities from
+    /// * `days` - The amount of days ago the activities need to have ended, `0` for all ended activities
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be archived or the archive cannot be written
+    ///
+    /// # Returns
+    ///
+    /// The amount of activities that have been moved to the archive
+    fn archive_ended_activities(&self, storage: &StorageKind, days: u32) -> PaceResult<usize> {
+        // Nothing can have ended before a point in time that is out of range
+        let Some(threshold) = TimeDelta::try_days(i64::from(days))
+            .and_then(|days| PaceDateTime::now().inner().checked_sub_signed(days))
+            .map(PaceDateTime::with_date_time_fixed_offset)
+        else {
+            return self.move_archived_activities(storage);
+        };
+
+        for activity_id in storage
+            .list_activities(ActivityFilterKind::Ended)?
+            .map(FilteredActivities::into_vec)
+            .unwrap_or_default()
+        {
+            let activity_item = storage.read_activity(activity_id)?;
+
+            let activity = activity_item.activity();
+
+            // Intermissions are archived together with their activity
+            if activity.parent_id().is_some() {
+                continue;
+            }
+
+            let has_ended_before_threshold = activity
+                .activity_end_options()
+                .as_ref()
+                .is_some_and(|end_opts| *end_opts.end() <= threshold);
+
+            if !has_ended_before_threshold {
+                continue;
+            }
+
+            let intermissions = storage
+                .list_intermissions_for_activity_id(activity_id)?
+                .unwrap_or_default();
+
+            if intermissions
+                .iter()
+                .any(|intermission| !intermission.activity().is_completed())
+            {
+                debug!("Activity {activity_id} has intermissions that haven't ended yet");
+
+                continue;
+            }
+
+            for intermission in intermissions {
+                _ = storage.archive_activity(*intermission.guid())?;
+            }
+
+            _ = storage.archive_activity(activity_id)?;
+        }
+
+        self.move_archived_activities(storage)
+    }
+
+    /// Move all archived activities from the activity store to the archive
+    ///
+    /// # Arguments
+    ///
+    /// * `storage` - The storage to move the archived activities from
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive cannot be written or the activities cannot be removed
+    ///
+    /// # Returns
+    ///
+    /// The amount of activities that have been moved to the archive
+    fn move_archived_activities(&self, storage: &StorageKind) -> PaceResult<usize> {
+        let archived = storage
+            .list_activities(ActivityFilterKind::Archived)?
+            .map(FilteredActivities::into_vec)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|activity_id| {
+                storage
+                    .read_activity(activity_id)
+                    .map(|activity_item| (activity_id, activity_item.activity().clone()))
+            })
+            .collect::<PaceResult<BTreeMap<ActivityGuid, Activity>>>()?;
+
+        if archived.is_empty() {
+            debug!("No archived activities to move");
+
+            return Ok(0);
+        }
+
+        // Intermissions go to the year their activity began in, so they stay together
+        let mut by_year: BTreeMap<i32, Vec<(ActivityGuid, Activity)>> = BTreeMap::new();
+
+        for (activity_id, activity) in &archived {
+            let year = activity
+                .parent_id()
+                .and_then(|parent_id| archived.get(&parent_id))
+                .unwrap_or(activity)
+                .begin()
+                .inner()
+                .year();
+
+            by_year
+                .entry(year)
+                .or_default()
+                .push((*activity_id, activity.clone()));
+        }
+
+        create_dir_all(&self.path)?;
+
+        // Write the archive first, so no activity gets lost if something goes wrong
+        for (year, activities) in by_year {
+            let file = ActivityLogFile::new(self.year_path(year));
+
+            let mut activity_log = ActivityLog::default();
+
+            // Holds the lock until the archive is written, so other pace processes don't overwrite it
+            file.lock_for_changes(|path| {
+                activity_log = toml::from_str::<ActivityLog>(&read_to_string(path)?)?;
+                Ok(())
+            })?;
+
+            activity_log.extend(activities);
+
+            file.write_atomically(toml::to_string(&activity_log)?.as_bytes())?;
+            file.finish_changes()?;
+
+            debug!("Archived activities for {year}");
+        }
+
+        // Remove intermissions before their activities, as some backends remove them
+        // together with their activity
+        let (intermissions, activities): (Vec<_>, Vec<_>) = archived
+            .iter()
+            .partition(|(_, activity)| activity.parent_id().is_some());
+
+        for (activity_id, _) in intermissions.into_iter().chain(activities) {
+            _ = storage.delete_activity(*activity_id, DeleteOptions::default())?;
+        }
+
+        Ok(archived.len())
+    }
+}
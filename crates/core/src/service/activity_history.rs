@@ -0,0 +1,228 @@
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, read_to_string, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use getset::Getters;
+use parking_lot::{Mutex, RwLock};
+use tracing::{debug, warn};
+
+use crate::{
+    config::PaceConfig,
+    domain::{
+        activity::{Activity, ActivityGuid},
+        history::{History, HistoryEntry},
+        journal::JournalOperationKind,
+    },
+    error::{PaceErrorKind, PaceResult},
+    storage::SyncStorage,
+};
+
+/// The activity history is responsible for recording and persisting the changes to the activity log
+///
+/// The history is stored as one JSON entry per line, so new entries are appended to it.
+#[derive(Debug, Getters)]
+#[getset(get = "pub")]
+pub struct ActivityHistory {
+    /// The path to the history file
+    path: PathBuf,
+
+    /// The entries that have been written to the history file
+    #[getset(skip)]
+    history: RwLock<History>,
+
+    /// The entries that haven't been written to the history file yet
+    #[getset(skip)]
+    pending: Mutex<Vec<HistoryEntry>>,
+
+    /// If the history file ends with an incomplete line, e.g. after a crash while writing it
+    #[getset(skip)]
+    incomplete: Mutex<bool>,
+}
+
+impl ActivityHistory {
+    /// Create a new `ActivityHistory` from a history file
+    ///
+    /// The history starts empty, if the file doesn't exist yet. Entries that
+    /// cannot be read, e.g. because they have been written only partially, are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the history file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read
+    ///
+    /// # Returns
+    ///
+    /// Returns a new `ActivityHistory`
+    #[tracing::instrument(skip(path))]
+    pub fn new(path: impl AsRef<Path>) -> PaceResult<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        let data = if path.exists() {
+            read_to_string(&path)?
+        } else {
+            debug!("History file {} doesn't exist yet", path.display());
+
+            String::new()
+        };
+
+        let entries = data
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .filter_map(
+                |(line_number, line)| match serde_json::from_str::<HistoryEntry>(line) {
+                    Ok(entry) => Some(entry),
+                    Err(err) => {
+                        warn!(
+                            "Skipping invalid entry {} of the history: {err}",
+                            line_number + 1
+                        );
+                        None
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            path,
+            history: RwLock::new(History::from(entries)),
+            pending: Mutex::new(vec![]),
+            incomplete: Mutex::new(!data.is_empty() && !data.ends_with('\n')),
+        })
+    }
+
+    /// Create a new `ActivityHistory` from the history file in the configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file exists but cannot be read
+    ///
+    /// # Returns
+    ///
+    /// Returns a new `ActivityHistory`
+    pub fn from_config(config: &PaceConfig) -> PaceResult<Self> {
+        Self::new(config.history_path())
+    }
+
+    /// Record the changes an operation made to the activities
+    ///
+    /// Several operations of the same command, e.g. ending the current activity
+    /// before beginning a new one, are recorded as one entry, so they are undone together.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation that changed the activities
+    /// * `before` - The activities before the operation
+    /// * `after` - The activities after the operation
+    pub fn record(
+        &self,
+        operation: JournalOperationKind,
+        before: &BTreeMap<ActivityGuid, Activity>,
+        after: &BTreeMap<ActivityGuid, Activity>,
+    ) {
+        let Some(entry) = HistoryEntry::from_changes(operation, before, after) else {
+            return;
+        };
+
+        let mut pending = self.pending.lock();
+
+        match pending.last_mut() {
+            Some(last)
+                if !matches!(
+                    last.operation(),
+                    JournalOperationKind::Undo | JournalOperationKind::Redo
+                ) =>
+            {
+                last.merge(entry);
+
+                if last.changes().is_empty() {
+                    let _ = pending.pop();
+                }
+            }
+            _ => pending.push(entry),
+        }
+    }
+
+    /// Add an entry to the history, e.g. for undoing or redoing changes
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The history entry
+    pub fn push(&self, entry: HistoryEntry) {
+        self.pending.lock().push(entry);
+    }
+
+    /// The complete history, including the entries that haven't been written yet
+    #[must_use]
+    pub fn history(&self) -> History {
+        let mut history = self.history.read().clone();
+
+        for entry in self.pending.lock().iter() {
+            history.push(entry.clone());
+        }
+
+        history
+    }
+}
+
+impl SyncStorage for ActivityHistory {
+    #[tracing::instrument(skip(self))]
+    fn sync(&self) -> PaceResult<()> {
+        let mut pending = self.pending.lock();
+
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let mut data = String::new();
+
+        let mut incomplete = self.incomplete.lock();
+
+        // Start on a new line, so an incomplete entry doesn't break the first new one
+        if *incomplete {
+            data.push('\n');
+        }
+
+        for entry in pending.iter() {
+            data.push_str(&serde_json::to_string(entry)?);
+            data.push('\n');
+        }
+
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.exists()) {
+            create_dir_all(parent)
+                .map_err(|_| PaceErrorKind::ParentDirNotFound(self.path.clone()))?;
+        }
+
+        let mut file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&self.path)?;
+
+        file.write_all(data.as_bytes())?;
+        file.sync_data()?;
+
+        *incomplete = false;
+        drop(incomplete);
+
+        let mut history = self.history.write();
+
+        for entry in pending.drain(..) {
+            history.push(entry);
+        }
+
+        drop(history);
+        drop(pending);
+
+        Ok(())
+    }
+}
@@ -4,30 +4,41 @@ use std::{
 };
 
 use getset::{Getters, MutGetters, Setters};
-use pace_time::{date::PaceDate, duration::PaceDurationRange, time_range::TimeRangeOptions};
+use itertools::Itertools;
+use pace_time::{
+    date::PaceDate,
+    duration::{PaceDurationRange, PaceDurationRangeOptions},
+    time_range::TimeRangeOptions,
+};
 use tracing::debug;
 use typed_builder::TypedBuilder;
 
-use wildmatch::WildMatch;
-
 use crate::{
     commands::{
         hold::HoldOptions, resume::ResumeOptions, DeleteOptions, EndOptions, KeywordOptions,
         UpdateOptions,
     },
+    config::{PaceConfig, PomodoroConfig},
     domain::{
         activity::{
             Activity, ActivityGroup, ActivityGuid, ActivityItem, ActivityKind, ActivitySession,
         },
         category,
+        event::PaceEvent,
         filter::{ActivityFilterKind, FilterOptions, FilteredActivities},
-        reflection::{SummaryActivityGroup, SummaryGroupByCategory},
+        history::HistoryEntry,
+        journal::JournalOperationKind,
+        pomodoro::PomodoroPhase,
+        reflection::{ReflectionGroupByKind, SummaryActivityGroup, SummaryGroupByCategory},
         status::ActivityStatusKind,
+        sync::ActivityLogMerge,
     },
-    error::{ActivityStoreErrorKind, PaceOptResult, PaceResult},
+    error::{ActivityStoreErrorKind, PaceErrorKind, PaceOptResult, PaceResult},
+    service::{activity_history::ActivityHistory, event_bus::EventBus},
     storage::{
-        ActivityQuerying, ActivityReadOps, ActivityStateManagement, ActivityStorage,
-        ActivityWriteOps, StorageKind, SyncStorage,
+        get_storage_from_config, ActivityArchiving, ActivityQuerying, ActivityReadOps,
+        ActivityStateManagement, ActivityStorage, ActivityTagging, ActivityWriteOps,
+        ChangedActivities, StorageKind, SyncStorage,
     },
 };
 
@@ -40,6 +51,14 @@ pub struct ActivityStore {
 
     /// The storage backend
     storage: Arc<StorageKind>,
+
+    /// The bus the changes of activity states are published on, once they are synced
+    #[builder(default)]
+    event_bus: Arc<EventBus>,
+
+    /// The history the changes to the activities are recorded in, to undo them
+    #[builder(default)]
+    history: Option<Arc<ActivityHistory>>,
 }
 
 #[derive(Debug, TypedBuilder, Getters, Setters, MutGetters, Clone, Eq, PartialEq, Default)]
@@ -75,6 +94,8 @@ impl ActivityStore {
         let mut store = Self {
             cache: ActivityStoreCache::default(),
             storage,
+            event_bus: Arc::default(),
+            history: None,
         };
 
         store.setup_storage()?;
@@ -84,6 +105,72 @@ impl ActivityStore {
         Ok(store)
     }
 
+    /// Create a new `ActivityStore` with the storage backend, event subscribers and history from the configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The pace configuration
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the storage backend cannot be used
+    ///
+    /// # Returns
+    ///
+    /// This method returns a new `ActivityStore`, that publishes its events
+    /// to the subscribers from the configuration and records its changes in the history
+    pub fn from_config(config: &PaceConfig) -> PaceResult<Self> {
+        let mut store = Self::with_storage(get_storage_from_config(config)?)?;
+
+        store.event_bus = Arc::new(EventBus::from_config(config));
+        store.history = Some(Arc::new(ActivityHistory::from_config(config)?));
+
+        Ok(store)
+    }
+
+    /// Queue an event for each of the given activity items, to be published on [`SyncStorage::sync`]
+    fn queue_all<'a>(
+        &self,
+        activity_items: impl IntoIterator<Item = &'a ActivityItem>,
+        event: fn(ActivityItem) -> PaceEvent,
+    ) {
+        for activity_item in activity_items {
+            self.event_bus.queue(event(activity_item.clone()));
+        }
+    }
+
+    /// Record the changes an operation makes to the activities in the history, if there is one
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The kind of the operation
+    /// * `changed` - The activities the operation may change
+    /// * `change` - Changes the activities
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be read or `change` fails
+    pub(crate) fn record_history<T>(
+        &self,
+        operation: JournalOperationKind,
+        changed: &ChangedActivities,
+        change: impl FnOnce() -> PaceResult<T>,
+    ) -> PaceResult<T> {
+        let Some(history) = &self.history else {
+            return change();
+        };
+
+        let before = changed.before(&*self.storage)?;
+
+        let result = change()?;
+
+        let after = changed.after(&*self.storage, &before)?;
+
+        history.record(operation, &before, &after);
+
+        Ok(result)
+    }
+
     /// Populate the in-memory cache with activities from the storage backend
     ///
     /// This method is called during the initialization of the activity store
@@ -104,6 +191,364 @@ impl ActivityStore {
         Ok(())
     }
 
+    /// Find the id of an activity by its id or a unique prefix of it
+    ///
+    /// # Arguments
+    ///
+    /// * `id_or_prefix` - The id of the activity or the beginning of it, case insensitive
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no activity or more than one activity matches
+    ///
+    /// # Returns
+    ///
+    /// The id of the matching activity
+    #[tracing::instrument(skip(self))]
+    pub fn find_activity_id(&self, id_or_prefix: &str) -> PaceResult<ActivityGuid> {
+        let activities = self.list_activities_by_id()?.unwrap_or_default();
+
+        ActivityGuid::find_by_prefix(activities.keys(), id_or_prefix)
+    }
+
+    /// Undo the most recent change to the activities, that hasn't been undone yet
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no history, one of the changed activities has
+    /// been changed in the meantime or the activities cannot be restored
+    ///
+    /// # Returns
+    ///
+    /// The history entry of the undone change, `None` if there is nothing to undo
+    #[tracing::instrument(skip(self))]
+    pub fn undo(&self) -> PaceOptResult<HistoryEntry> {
+        let history = self.history.as_ref().ok_or(PaceErrorKind::NoHistory)?;
+
+        let Some(entry) = history.history().next_undo().cloned() else {
+            debug!("Nothing to undo.");
+
+            return Ok(None);
+        };
+
+        self.apply_history_entry(history, entry.inverted(JournalOperationKind::Undo))?;
+
+        Ok(Some(entry))
+    }
+
+    /// Redo the most recently undone change to the activities
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no history, one of the changed activities has
+    /// been changed in the meantime or the activities cannot be restored
+    ///
+    /// # Returns
+    ///
+    /// The history entry of the redone change, `None` if there is nothing to redo
+    #[tracing::instrument(skip(self))]
+    pub fn redo(&self) -> PaceOptResult<HistoryEntry> {
+        let history = self.history.as_ref().ok_or(PaceErrorKind::NoHistory)?;
+
+        let Some(entry) = history.history().next_redo().cloned() else {
+            debug!("Nothing to redo.");
+
+            return Ok(None);
+        };
+
+        self.apply_history_entry(history, entry.repeated(JournalOperationKind::Redo))?;
+
+        Ok(Some(entry))
+    }
+
+    /// Bring the activities into their state after the changes of a history entry
+    ///
+    /// Nothing is changed, if any of the activities isn't in its state before the changes anymore.
+    fn apply_history_entry(
+        &self,
+        history: &ActivityHistory,
+        entry: HistoryEntry,
+    ) -> PaceResult<()> {
+        let current = self.storage.list_activities_by_id()?.unwrap_or_default();
+
+        for change in entry.changes() {
+            if !change.applies_to(current.get(change.guid()))? {
+                return Err(PaceErrorKind::HistoryConflict(*change.guid()).into());
+            }
+        }
+
+        for change in entry.changes() {
+            let guid = *change.guid();
+
+            match (current.get(&guid), change.after()) {
+                (Some(_), None) => {
+                    let _ = self
+                        .storage
+                        .delete_activity(guid, DeleteOptions::default())?;
+                }
+                (Some(_), Some(activity)) => {
+                    let _ = self.storage.update_activity(
+                        guid,
+                        activity.clone(),
+                        UpdateOptions::default(),
+                    )?;
+                }
+                (None, Some(activity)) => {
+                    let _ = self
+                        .storage
+                        .insert_activity(ActivityItem::new(guid, activity.clone()))?;
+                }
+                (None, None) => {}
+            }
+        }
+
+        history.push(entry);
+
+        Ok(())
+    }
+
+    /// Write the merged activities of another activity log into the storage
+    ///
+    /// The merge is recorded in the history as a single change, so it can be undone.
+    ///
+    /// # Arguments
+    ///
+    /// * `merge` - The merged activities
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be written
+    ///
+    /// # Returns
+    ///
+    /// The number of activities that have been added or changed
+    #[tracing::instrument(skip(self, merge))]
+    pub fn apply_merge(&self, merge: &ActivityLogMerge) -> PaceResult<usize> {
+        let changed = ChangedActivities::Ids(merge.activities().keys().copied().collect());
+
+        self.record_history(JournalOperationKind::Merge, &changed, || {
+            let current = self.storage.list_activities_by_id()?.unwrap_or_default();
+
+            let mut count = 0;
+
+            for (guid, activity) in merge.activities() {
+                match current.get(guid) {
+                    Some(existing) if existing == activity => continue,
+                    Some(_) => {
+                        let _ = self.storage.update_activity(
+                            *guid,
+                            activity.clone(),
+                            UpdateOptions::default(),
+                        )?;
+                    }
+                    None => {
+                        let _ = self
+                            .storage
+                            .insert_activity(ActivityItem::new(*guid, activity.clone()))?;
+                    }
+                }
+
+                count += 1;
+            }
+
+            Ok(count)
+        })
+    }
+
+    /// Create the activities imported from another time tracker
+    ///
+    /// The import is recorded in the history as a single change, so it can be undone at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `activities` - The imported activities
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be written
+    ///
+    /// # Returns
+    ///
+    /// The created activity items
+    #[tracing::instrument(skip(self, activities))]
+    pub fn import_activities(
+        &self,
+        activities: impl IntoIterator<Item = Activity>,
+    ) -> PaceResult<Vec<ActivityItem>> {
+        let activity_items = activities
+            .into_iter()
+            .map(ActivityItem::from)
+            .collect::<Vec<_>>();
+
+        let changed =
+            ChangedActivities::Ids(activity_items.iter().map(|item| *item.guid()).collect());
+
+        self.record_history(JournalOperationKind::Import, &changed, || {
+            activity_items
+                .into_iter()
+                .map(|activity_item| self.storage.insert_activity(activity_item))
+                .collect()
+        })
+    }
+
+    /// List the activity items within a time range that match the filter options
+    ///
+    /// In contrast to [`ActivityQuerying::list_activities_by_time_range`], all kinds of
+    /// activities (e.g. intermissions) are included, unless the filter options restrict
+    /// the activity kind. Active activities are not ended.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter_opts` - The options to filter the activities by
+    /// * `time_range_opts` - The time range the activities need to begin in
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities could not be read from the storage
+    ///
+    /// # Returns
+    ///
+    /// The matching activity items ordered by their begin, `None` if there are none
+    #[tracing::instrument(skip(self))]
+    pub fn list_activity_items_for_time_range(
+        &self,
+        filter_opts: &FilterOptions,
+        time_range_opts: TimeRangeOptions,
+    ) -> PaceOptResult<Vec<ActivityItem>> {
+        let Some(activity_items) = self
+            .storage
+            .list_activity_items_by_time_range(time_range_opts)?
+        else {
+            debug!("No activities found for time range: {:?}", time_range_opts);
+
+            return Ok(None);
+        };
+
+        let mut activity_items = activity_items
+            .into_iter()
+            .filter(|item| {
+                let activity = item.activity();
+
+                filter_opts.matches_activity_kind(*activity.kind())
+                    && filter_opts
+                        .matches_category(activity.category().as_deref().unwrap_or("Uncategorized"))
+                    && filter_opts.matches_project(activity)
+            })
+            .collect::<Vec<_>>();
+
+        if activity_items.is_empty() {
+            debug!("No activities found for time range: {:?}", time_range_opts);
+
+            return Ok(None);
+        }
+
+        activity_items.sort_by_key(|item| *item.activity().begin());
+
+        Ok(Some(activity_items))
+    }
+
+    /// Get the pomodoro phase an activity has been tracked for
+    ///
+    /// Breaks get their cycle from the work session they are linked to.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity` - The activity to get the pomodoro phase for
+    /// * `config` - The pomodoro configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the linked work session could not be read
+    ///
+    /// # Returns
+    ///
+    /// The pomodoro phase, `None` if the activity is not a pomodoro activity
+    #[tracing::instrument(skip(self))]
+    pub fn pomodoro_phase(
+        &self,
+        activity: &Activity,
+        config: &PomodoroConfig,
+    ) -> PaceOptResult<PomodoroPhase> {
+        if !activity.kind().is_pomodoro_work() && !activity.kind().is_pomodoro_intermission() {
+            return Ok(None);
+        }
+
+        let parent = activity
+            .parent_id()
+            .map(|parent_id| self.read_activity(parent_id))
+            .transpose()?;
+
+        Ok(PomodoroPhase::from_activity(
+            activity,
+            parent.as_ref().map(ActivityItem::activity),
+            config,
+        ))
+    }
+
+    /// Get the most recent running pomodoro phase
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The pomodoro configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities could not be read from the storage
+    ///
+    /// # Returns
+    ///
+    /// The activity item of the running phase, the activity item of the work session
+    /// the phase belongs to and the phase itself, `None` if no pomodoro session is running
+    #[tracing::instrument(skip(self))]
+    pub fn running_pomodoro_phase(
+        &self,
+        config: &PomodoroConfig,
+    ) -> PaceOptResult<(ActivityItem, ActivityItem, PomodoroPhase)> {
+        let Some(current) = self.list_current_activities(ActivityFilterKind::Active)? else {
+            debug!("No active activities found.");
+
+            return Ok(None);
+        };
+
+        // Activity ids are sortable by their creation time, so the last one is the most recent
+        for activity_id in current.into_iter().sorted().rev() {
+            let activity_item = self.read_activity(activity_id)?;
+
+            let Some(phase) = self.pomodoro_phase(activity_item.activity(), config)? else {
+                continue;
+            };
+
+            let work_session = match activity_item.activity().parent_id() {
+                Some(parent_id) if phase.kind().is_break() => self.read_activity(parent_id)?,
+                _ => activity_item.clone(),
+            };
+
+            return Ok(Some((activity_item, work_session, phase)));
+        }
+
+        debug!("No running pomodoro phase found.");
+
+        Ok(None)
+    }
+
+    /// Summarize the activities within a time range, grouped by category or by tag
+    ///
+    /// When grouping by tag, the groups have an empty subcategory and an activity
+    /// with several tags is part of each of their groups. Activities without tags
+    /// are grouped as `Untagged`.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter_opts` - The filter and grouping options
+    /// * `time_range_opts` - The time range to summarize
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities could not be read from the storage
+    ///
+    /// # Returns
+    ///
+    /// The summary groups, `None` if there are no activities in the time range
     #[tracing::instrument(skip(self))]
     pub fn summary_groups_by_category_for_time_range(
         &self,
@@ -124,10 +569,6 @@ impl ActivityStore {
             Vec<ActivitySession>,
         > = HashMap::new();
 
-        // Temporarily end all activities for duration calculation
-        let _ = self.end_all_active_intermissions(EndOptions::default())?;
-        let _ = self.end_all_activities(EndOptions::default())?;
-
         for activity_guid in activity_guids {
             let activity_item = self.read_activity(activity_guid)?;
 
@@ -138,17 +579,12 @@ impl ActivityStore {
                 .unwrap_or("Uncategorized")
                 .to_string();
 
-            // Skip if category does not match user input
-            if let Some(category) = filter_opts.category() {
-                let (filter_category, activity_category) = if *filter_opts.case_sensitive() {
-                    (category.clone(), activity_category.clone())
-                } else {
-                    (category.to_lowercase(), activity_category.to_lowercase())
-                };
-
-                if !WildMatch::new(&filter_category).matches(&activity_category) {
-                    continue;
-                }
+            // Skip if category, project or tags do not match user input
+            if !filter_opts.matches_category(&activity_category)
+                || !filter_opts.matches_project(activity_item.activity())
+                || !filter_opts.matches_tags(activity_item.activity())
+            {
+                continue;
             }
 
             let mut activity_session = ActivitySession::new(activity_item.clone());
@@ -159,19 +595,50 @@ impl ActivityStore {
                 activity_session.add_multiple_intermissions(intermissions);
             };
 
-            // Handle splitting subcategories
-            let (category, subcategory) =
-                category::split_category_by_category_separator(&activity_category, None);
-
-            // Deduplicate activities by category and description first
-            _ = activity_sessions_lookup_by_category
-                .entry((
-                    category,
-                    subcategory,
-                    activity_item.activity().description().to_owned(),
-                ))
-                .and_modify(|e| e.push(activity_session.clone()))
-                .or_insert_with(|| vec![activity_session.clone()]);
+            let groups = match filter_opts.group_by() {
+                // Handle splitting subcategories
+                ReflectionGroupByKind::Category => {
+                    vec![category::split_category_by_category_separator(
+                        &activity_category,
+                        None,
+                    )]
+                }
+                // An activity is part of the group of each of its (matching) tags
+                ReflectionGroupByKind::Tag => {
+                    let mut tags = activity_item
+                        .activity()
+                        .tags()
+                        .iter()
+                        .flatten()
+                        .filter(|tag| filter_opts.matches_tag(tag))
+                        .map(|tag| (tag.clone(), None))
+                        .collect::<Vec<_>>();
+
+                    if tags.is_empty() {
+                        tags.push(("Untagged".to_string(), None));
+                    }
+
+                    tags
+                }
+            };
+
+            // Deduplicate activities by group and description first
+            for (category, subcategory) in groups {
+                activity_sessions_lookup_by_category
+                    .entry((
+                        category,
+                        subcategory,
+                        activity_item.activity().description().to_owned(),
+                    ))
+                    .or_default()
+                    .push(activity_session.clone());
+            }
+        }
+
+        if activity_sessions_lookup_by_category.is_empty() {
+            debug!("No activities match the filter options: {:?}", filter_opts);
+
+            return Ok(None);
         }
 
         debug!(
@@ -218,7 +685,17 @@ impl ActivityStorage for ActivityStore {
 impl SyncStorage for ActivityStore {
     #[tracing::instrument(skip(self))]
     fn sync(&self) -> PaceResult<()> {
-        self.storage.sync()
+        self.storage.sync()?;
+
+        if let Some(history) = &self.history {
+            history.sync()?;
+        }
+
+        // Publish the events only now, the changes are persisted and the lock on
+        // the activity log is released, so subscribers can read or change it
+        self.event_bus.publish_queued();
+
+        Ok(())
     }
 }
 
@@ -237,7 +714,22 @@ impl ActivityReadOps for ActivityStore {
 impl ActivityWriteOps for ActivityStore {
     #[tracing::instrument(skip(self))]
     fn create_activity(&self, activity: Activity) -> PaceResult<ActivityItem> {
-        self.storage.create_activity(activity)
+        let activity_item = ActivityItem::from(activity);
+
+        let changed = ChangedActivities::Ids(vec![*activity_item.guid()]);
+
+        self.record_history(JournalOperationKind::Create, &changed, || {
+            self.storage.insert_activity(activity_item)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn insert_activity(&self, activity_item: ActivityItem) -> PaceResult<ActivityItem> {
+        let changed = ChangedActivities::Ids(vec![*activity_item.guid()]);
+
+        self.record_history(JournalOperationKind::Create, &changed, || {
+            self.storage.insert_activity(activity_item)
+        })
     }
 
     #[tracing::instrument(skip(self))]
@@ -247,8 +739,20 @@ impl ActivityWriteOps for ActivityStore {
         updated_activity: Activity,
         update_opts: UpdateOptions,
     ) -> PaceResult<ActivityItem> {
-        self.storage
-            .update_activity(activity_id, updated_activity, update_opts)
+        let activity_item = self.record_history(
+            JournalOperationKind::Update,
+            &ChangedActivities::Ids(vec![activity_id]),
+            || {
+                self.storage
+                    .update_activity(activity_id, updated_activity, update_opts)
+            },
+        )?;
+
+        self.event_bus.queue(PaceEvent::ActivityAdjusted(
+            self.storage.read_activity(activity_id)?,
+        ));
+
+        Ok(activity_item)
     }
 
     #[tracing::instrument(skip(self))]
@@ -257,14 +761,34 @@ impl ActivityWriteOps for ActivityStore {
         activity_id: ActivityGuid,
         delete_opts: DeleteOptions,
     ) -> PaceResult<ActivityItem> {
-        self.storage.delete_activity(activity_id, delete_opts)
+        self.record_history(
+            JournalOperationKind::Delete,
+            &ChangedActivities::Ids(vec![activity_id]),
+            || self.storage.delete_activity(activity_id, delete_opts),
+        )
     }
 }
 
 impl ActivityStateManagement for ActivityStore {
     #[tracing::instrument(skip(self))]
     fn begin_activity(&self, activity: Activity) -> PaceResult<ActivityItem> {
-        self.storage.begin_activity(activity)
+        let (ended_activities, activity_item) = self.record_history(
+            JournalOperationKind::Begin,
+            &ChangedActivities::unfinished(),
+            || {
+                // End the unfinished activities here, so their end is published as well
+                let ended_activities = self.storage.end_all_activities(EndOptions::default())?;
+
+                Ok((ended_activities, self.storage.begin_activity(activity)?))
+            },
+        )?;
+
+        self.queue_all(ended_activities.iter().flatten(), PaceEvent::ActivityEnded);
+
+        self.event_bus
+            .queue(PaceEvent::ActivityBegan(activity_item.clone()));
+
+        Ok(activity_item)
     }
 
     #[tracing::instrument(skip(self))]
@@ -273,17 +797,42 @@ impl ActivityStateManagement for ActivityStore {
         activity_id: ActivityGuid,
         end_opts: EndOptions,
     ) -> PaceResult<ActivityItem> {
-        self.storage.end_activity(activity_id, end_opts)
+        let activity_item = self.record_history(
+            JournalOperationKind::End,
+            &ChangedActivities::Ids(vec![activity_id]),
+            || self.storage.end_activity(activity_id, end_opts),
+        )?;
+
+        self.event_bus
+            .queue(PaceEvent::ActivityEnded(activity_item.clone()));
+
+        Ok(activity_item)
     }
 
     #[tracing::instrument(skip(self))]
     fn end_all_activities(&self, end_opts: EndOptions) -> PaceOptResult<Vec<ActivityItem>> {
-        self.storage.end_all_activities(end_opts)
+        let ended_activities = self.record_history(
+            JournalOperationKind::End,
+            &ChangedActivities::unfinished(),
+            || self.storage.end_all_activities(end_opts),
+        )?;
+
+        self.queue_all(ended_activities.iter().flatten(), PaceEvent::ActivityEnded);
+
+        Ok(ended_activities)
     }
 
     #[tracing::instrument(skip(self))]
     fn end_last_unfinished_activity(&self, end_opts: EndOptions) -> PaceOptResult<ActivityItem> {
-        self.storage.end_last_unfinished_activity(end_opts)
+        let ended_activity = self.record_history(
+            JournalOperationKind::End,
+            &ChangedActivities::unfinished(),
+            || self.storage.end_last_unfinished_activity(end_opts),
+        )?;
+
+        self.queue_all(&ended_activity, PaceEvent::ActivityEnded);
+
+        Ok(ended_activity)
     }
 
     #[tracing::instrument(skip(self))]
@@ -291,7 +840,15 @@ impl ActivityStateManagement for ActivityStore {
         &self,
         hold_opts: HoldOptions,
     ) -> PaceOptResult<ActivityItem> {
-        self.storage.hold_most_recent_active_activity(hold_opts)
+        let held_activity = self.record_history(
+            JournalOperationKind::Hold,
+            &ChangedActivities::unfinished(),
+            || self.storage.hold_most_recent_active_activity(hold_opts),
+        )?;
+
+        self.queue_all(&held_activity, PaceEvent::ActivityHeld);
+
+        Ok(held_activity)
     }
 
     #[tracing::instrument(skip(self))]
@@ -299,7 +856,11 @@ impl ActivityStateManagement for ActivityStore {
         &self,
         end_opts: EndOptions,
     ) -> PaceOptResult<Vec<ActivityGuid>> {
-        self.storage.end_all_active_intermissions(end_opts)
+        self.record_history(
+            JournalOperationKind::End,
+            &ChangedActivities::unfinished(),
+            || self.storage.end_all_active_intermissions(end_opts),
+        )
     }
 
     #[tracing::instrument(skip(self))]
@@ -308,7 +869,17 @@ impl ActivityStateManagement for ActivityStore {
         activity_id: ActivityGuid,
         resume_opts: ResumeOptions,
     ) -> PaceResult<ActivityItem> {
-        self.storage.resume_activity(activity_id, resume_opts)
+        let resumed_activity = self.record_history(
+            JournalOperationKind::Resume,
+            &ChangedActivities::unfinished(),
+            || self.storage.resume_activity(activity_id, resume_opts),
+        )?;
+
+        self.event_bus.queue(PaceEvent::ActivityResumed(
+            self.storage.read_activity(*resumed_activity.guid())?,
+        ));
+
+        Ok(resumed_activity)
     }
 
     #[tracing::instrument(skip(self))]
@@ -317,7 +888,16 @@ impl ActivityStateManagement for ActivityStore {
         activity_id: ActivityGuid,
         hold_opts: HoldOptions,
     ) -> PaceResult<ActivityItem> {
-        self.storage.hold_activity(activity_id, hold_opts)
+        let held_activity = self.record_history(
+            JournalOperationKind::Hold,
+            &ChangedActivities::unfinished(),
+            || self.storage.hold_activity(activity_id, hold_opts),
+        )?;
+
+        self.event_bus
+            .queue(PaceEvent::ActivityHeld(held_activity.clone()));
+
+        Ok(held_activity)
     }
 
     #[tracing::instrument(skip(self))]
@@ -325,7 +905,81 @@ impl ActivityStateManagement for ActivityStore {
         &self,
         resume_opts: ResumeOptions,
     ) -> PaceOptResult<ActivityItem> {
-        self.storage.resume_most_recent_activity(resume_opts)
+        let resumed_activity = self.record_history(
+            JournalOperationKind::Resume,
+            &ChangedActivities::unfinished(),
+            || self.storage.resume_most_recent_activity(resume_opts),
+        )?;
+
+        if let Some(resumed_activity) = &resumed_activity {
+            self.event_bus.queue(PaceEvent::ActivityResumed(
+                self.storage.read_activity(*resumed_activity.guid())?,
+            ));
+        }
+
+        Ok(resumed_activity)
+    }
+}
+
+impl ActivityArchiving for ActivityStore {
+    #[tracing::instrument(skip(self))]
+    fn archive_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        self.record_history(
+            JournalOperationKind::Archive,
+            &ChangedActivities::Ids(vec![activity_id]),
+            || self.storage.archive_activity(activity_id),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn unarchive_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        self.record_history(
+            JournalOperationKind::Unarchive,
+            &ChangedActivities::Ids(vec![activity_id]),
+            || self.storage.unarchive_activity(activity_id),
+        )
+    }
+}
+
+impl ActivityTagging for ActivityStore {
+    #[tracing::instrument(skip(self))]
+    fn add_tag_to_activity(
+        &self,
+        activity_id: ActivityGuid,
+        tag: &str,
+    ) -> PaceResult<ActivityItem> {
+        self.record_history(
+            JournalOperationKind::Tag,
+            &ChangedActivities::Ids(vec![activity_id]),
+            || self.storage.add_tag_to_activity(activity_id, tag),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn remove_tag_from_activity(
+        &self,
+        activity_id: ActivityGuid,
+        tag: &str,
+    ) -> PaceResult<ActivityItem> {
+        self.record_history(
+            JournalOperationKind::Tag,
+            &ChangedActivities::Ids(vec![activity_id]),
+            || self.storage.remove_tag_from_activity(activity_id, tag),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn rename_tag(&self, old_tag: &str, new_tag: &str) -> PaceResult<usize> {
+        let changed = ChangedActivities::tagged(&*self.storage, old_tag)?;
+
+        self.record_history(JournalOperationKind::Tag, &changed, || {
+            self.storage.rename_tag(old_tag, new_tag)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_tags(&self) -> PaceResult<BTreeMap<String, usize>> {
+        self.storage.list_tags()
     }
 }
 
@@ -338,8 +992,10 @@ impl ActivityQuerying for ActivityStore {
     #[tracing::instrument(skip(self))]
     fn group_activities_by_duration_range(
         &self,
+        duration_range_opts: PaceDurationRangeOptions,
     ) -> PaceOptResult<BTreeMap<PaceDurationRange, Vec<ActivityItem>>> {
-        self.storage.group_activities_by_duration_range()
+        self.storage
+            .group_activities_by_duration_range(duration_range_opts)
     }
 
     #[tracing::instrument(skip(self))]
@@ -377,6 +1033,15 @@ impl ActivityQuerying for ActivityStore {
         self.storage.list_activities_by_time_range(time_range_opts)
     }
 
+    #[tracing::instrument(skip(self))]
+    fn list_activity_items_by_time_range(
+        &self,
+        time_range_opts: TimeRangeOptions,
+    ) -> PaceOptResult<Vec<ActivityItem>> {
+        self.storage
+            .list_activity_items_by_time_range(time_range_opts)
+    }
+
     #[tracing::instrument(skip(self))]
     fn group_activities_by_status(
         &self,
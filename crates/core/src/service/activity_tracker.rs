@@ -1,14 +1,31 @@
 //! This module contains the domain logic for tracking activities and their intermissions.
 
-use pace_time::{time_frame::PaceTimeFrame, time_range::TimeRangeOptions};
+use chrono::{Days, NaiveDate, NaiveTime};
+use pace_time::{
+    date::PaceDate, date_time::PaceDateTime, duration::PaceDurationRangeOptions,
+    time_frame::PaceTimeFrame, time_range::TimeRangeOptions,
+};
 use tracing::debug;
 
 use crate::{
-    domain::{filter::FilterOptions, reflection::ReflectionSummary},
-    error::PaceOptResult,
+    commands::reflect::ExpensiveFlags,
+    domain::{
+        activity::ActivityGroup,
+        filter::FilterOptions,
+        goal::{Goal, GoalProgress},
+        reflection::ReflectionSummary,
+        statistics::{ReflectionComparison, ReflectionStatistics},
+    },
+    error::{PaceOptResult, PaceResult},
     service::activity_store::ActivityStore,
 };
 
+/// How many days before a time frame the activities are read at first, to count the streaks of goals
+const GOAL_STREAK_INITIAL_LOOKBACK_DAYS: u64 = 14;
+
+/// How many days before a time frame the activities are read at most, to count the streaks of goals
+const GOAL_STREAK_MAX_LOOKBACK_DAYS: u64 = 366 + 7;
+
 // This struct represents the overall structure for tracking activities and their intermissions.
 pub struct ActivityTracker {
     pub store: ActivityStore,
@@ -21,31 +38,163 @@ impl ActivityTracker {
         Self { store }
     }
 
-    // TODO!
-    // - [ ] implement the `detailed` flag
-    // - [ ] implement the `comparative` flag
-    // - [ ] implement the `recommendations` flag
-
     /// Generate a reflection for the specified time frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter_opts` - The filter options for the activities to reflect on
+    /// * `time_frame` - The time frame to reflect on
+    /// * `expensive_flags` - Whether to add statistics, a comparison to the previous
+    ///   time frame and recommendations to the reflection
+    /// * `duration_ranges` - The boundaries between short, medium and long activity sessions
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the time range cannot be calculated or the activities cannot be read
+    ///
+    /// # Returns
+    ///
+    /// The reflection, or `None` if there are no activities within the time frame
     #[tracing::instrument(skip(self))]
     pub fn generate_reflection(
         &self,
         filter_opts: FilterOptions,
         time_frame: PaceTimeFrame,
+        expensive_flags: &ExpensiveFlags,
+        duration_ranges: &PaceDurationRangeOptions,
     ) -> PaceOptResult<ReflectionSummary> {
-        let time_range_opts = TimeRangeOptions::try_from(time_frame)?;
+        let time_range_opts = TimeRangeOptions::try_from(time_frame.clone())?;
+
+        let group_by = *filter_opts.group_by();
 
         let Some(summary_groups) = self
             .store
-            .summary_groups_by_category_for_time_range(filter_opts, time_range_opts)?
+            .summary_groups_by_category_for_time_range(filter_opts.clone(), time_range_opts)?
         else {
             return Ok(None);
         };
 
-        let summary = ReflectionSummary::new(time_range_opts, summary_groups);
+        let mut summary = ReflectionSummary::new(time_range_opts, summary_groups);
+
+        _ = summary.set_group_by(group_by);
+
+        if *expensive_flags.comparative() {
+            let previous_time_range = time_frame.previous_time_range()?;
+
+            let previous_summary = self
+                .store
+                .summary_groups_by_category_for_time_range(filter_opts, previous_time_range)?
+                .map(|summary_groups| ReflectionSummary::new(previous_time_range, summary_groups));
+
+            _ = summary.set_comparison(Some(ReflectionComparison::new(
+                &summary,
+                previous_time_range,
+                previous_summary.as_ref(),
+            )));
+        }
+
+        if *expensive_flags.detailed() || *expensive_flags.recommendations() {
+            let statistics = ReflectionStatistics::from_summary(&summary, *duration_ranges);
+
+            if *expensive_flags.recommendations() {
+                let recommendations = statistics.recommendations(summary.comparison().as_ref());
+
+                _ = summary.set_recommendations(recommendations);
+            }
+
+            if *expensive_flags.detailed() {
+                _ = summary.set_statistics(Some(statistics));
+            }
+        }
 
         debug!("Generated reflection: {:#?}", summary);
 
         Ok(Some(summary))
     }
+
+    /// Evaluate the progress towards goals within a time frame
+    ///
+    /// The activities before the time frame are only read as far back as the
+    /// streaks reach, doubling the lookback until every streak has ended, but
+    /// at most up to a year before the time frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `goals` - The goals to evaluate
+    /// * `time_frame` - The time frame to evaluate the goals for
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the time range cannot be calculated or the activities cannot be read
+    ///
+    /// # Returns
+    ///
+    /// The progress towards each goal, in the order of the goals
+    #[tracing::instrument(skip(self))]
+    pub fn goal_progress(
+        &self,
+        goals: &[Goal],
+        time_frame: PaceTimeFrame,
+    ) -> PaceResult<Vec<GoalProgress>> {
+        if goals.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let time_range = TimeRangeOptions::try_from(time_frame)?;
+
+        let today = PaceDate::default();
+
+        let mut lookback_days = GOAL_STREAK_INITIAL_LOOKBACK_DAYS;
+
+        loop {
+            // Covers at least the week before, weekly goals are evaluated from the beginning of the week
+            let lookback_start = PaceDate::from(time_range.start())
+                .checked_sub_days(Days::new(lookback_days))
+                .unwrap_or(NaiveDate::MIN);
+
+            let lookback_range = TimeRangeOptions::builder()
+                .start(PaceDateTime::try_from(
+                    lookback_start.and_time(NaiveTime::MIN),
+                )?)
+                .end(*time_range.end())
+                .build();
+
+            let summary_groups = self
+                .store
+                .summary_groups_by_category_for_time_range(
+                    FilterOptions::default(),
+                    lookback_range,
+                )?
+                .unwrap_or_default();
+
+            // Grouped by category, every session is part of exactly one group
+            let sessions = summary_groups
+                .values()
+                .flat_map(|group| group.activity_groups_by_description().values())
+                .flat_map(ActivityGroup::activity_sessions)
+                .collect::<Vec<_>>();
+
+            let progress = goals
+                .iter()
+                .map(|goal| {
+                    goal.progress(
+                        &goal.time_spent_by_day(sessions.iter().copied()),
+                        &time_range,
+                        today,
+                        PaceDate::new(lookback_start),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            if lookback_days >= GOAL_STREAK_MAX_LOOKBACK_DAYS
+                || progress.iter().all(|progress| *progress.streak_ended())
+            {
+                return Ok(progress);
+            }
+
+            debug!("Goal streaks reach further back than {lookback_days} days, reading further");
+
+            lookback_days = (lookback_days * 2).min(GOAL_STREAK_MAX_LOOKBACK_DAYS);
+        }
+    }
 }
@@ -0,0 +1,483 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Debug, Formatter},
+    io::{ErrorKind, Write},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use parking_lot::{Mutex, RwLock};
+use tracing::{debug, warn};
+
+use crate::{
+    config::{EventSubscriberConfig, PaceConfig},
+    domain::{
+        activity::ActivityGuid,
+        event::{PaceEvent, PaceEventKind},
+    },
+    error::{PaceErrorKind, PaceResult},
+};
+
+/// A subscriber to the events published on the [`EventBus`]
+pub trait EventSubscriber: Send + Sync {
+    /// Handle an event
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The published event
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subscriber failed to handle the event
+    fn notify(&self, event: &PaceEvent) -> PaceResult<()>;
+}
+
+impl<F> EventSubscriber for F
+where
+    F: Fn(&PaceEvent) -> PaceResult<()> + Send + Sync,
+{
+    fn notify(&self, event: &PaceEvent) -> PaceResult<()> {
+        self(event)
+    }
+}
+
+/// A subscription to the events of the given kinds, all kinds if empty
+struct Subscription {
+    kinds: HashSet<PaceEventKind>,
+    subscriber: Box<dyn EventSubscriber>,
+}
+
+impl Subscription {
+    fn matches(&self, kind: PaceEventKind) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
+}
+
+/// An in-process bus the activity store publishes its events on
+///
+/// Subscribers either receive the events of all activities or only the
+/// events of a single activity, keyed by its [`ActivityGuid`]. A failing
+/// subscriber is logged and doesn't affect the other subscribers or the
+/// operation that published the event.
+///
+/// Events can be queued while the changes they describe aren't persisted yet
+/// and are published once [`EventBus::publish_queued`] is called, so
+/// subscribers don't see stale state or block on the lock of the activity log.
+#[derive(Default)]
+pub struct EventBus {
+    /// Subscriptions to the events of all activities
+    subscriptions: RwLock<Vec<Subscription>>,
+
+    /// Subscriptions to the events of a single activity
+    activity_subscriptions: RwLock<HashMap<ActivityGuid, Vec<Subscription>>>,
+
+    /// Events waiting to be published
+    queued: Mutex<Vec<PaceEvent>>,
+}
+
+impl Debug for EventBus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBus")
+            .field("subscriptions", &self.subscriptions.read().len())
+            .field(
+                "activity_subscriptions",
+                &self.activity_subscriptions.read().len(),
+            )
+            .field("queued", &self.queued.lock().len())
+            .finish()
+    }
+}
+
+impl EventBus {
+    /// Create an event bus with the subscribers from the configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The pace configuration
+    ///
+    /// # Returns
+    ///
+    /// The event bus, without subscribers if the configuration has no `[events]` section
+    #[must_use]
+    pub fn from_config(config: &PaceConfig) -> Self {
+        let event_bus = Self::default();
+
+        for subscriber_config in config
+            .events()
+            .as_ref()
+            .map(|events| events.subscribers().as_slice())
+            .unwrap_or_default()
+        {
+            event_bus.subscribe(
+                subscriber_config.events().clone(),
+                ShellCommandSubscriber::from(subscriber_config),
+            );
+        }
+
+        event_bus
+    }
+
+    /// Subscribe to the events of all activities
+    ///
+    /// # Arguments
+    ///
+    /// * `kinds` - The kinds of events to subscribe to, all kinds if empty
+    /// * `subscriber` - The subscriber to notify
+    pub fn subscribe(
+        &self,
+        kinds: impl IntoIterator<Item = PaceEventKind>,
+        subscriber: impl EventSubscriber + 'static,
+    ) {
+        self.subscriptions.write().push(Subscription {
+            kinds: kinds.into_iter().collect(),
+            subscriber: Box::new(subscriber),
+        });
+    }
+
+    /// Subscribe to the events of a single activity
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_id` - The id of the activity
+    /// * `kinds` - The kinds of events to subscribe to, all kinds if empty
+    /// * `subscriber` - The subscriber to notify
+    pub fn subscribe_to_activity(
+        &self,
+        activity_id: ActivityGuid,
+        kinds: impl IntoIterator<Item = PaceEventKind>,
+        subscriber: impl EventSubscriber + 'static,
+    ) {
+        self.activity_subscriptions
+            .write()
+            .entry(activity_id)
+            .or_default()
+            .push(Subscription {
+                kinds: kinds.into_iter().collect(),
+                subscriber: Box::new(subscriber),
+            });
+    }
+
+    /// Publish an event to all matching subscribers
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to publish
+    #[tracing::instrument(skip(self))]
+    pub fn publish(&self, event: &PaceEvent) {
+        let kind = event.kind();
+
+        let subscriptions = self.subscriptions.read();
+        let activity_subscriptions = self.activity_subscriptions.read();
+
+        for subscription in subscriptions
+            .iter()
+            .chain(
+                activity_subscriptions
+                    .get(&event.activity_id())
+                    .into_iter()
+                    .flatten(),
+            )
+            .filter(|subscription| subscription.matches(kind))
+        {
+            if let Err(err) = subscription.subscriber.notify(event) {
+                warn!("Failed to notify subscriber of {kind} event: {err}");
+            }
+        }
+    }
+
+    /// Queue an event to be published later with [`EventBus::publish_queued`]
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to queue
+    pub fn queue(&self, event: PaceEvent) {
+        self.queued.lock().push(event);
+    }
+
+    /// Publish all queued events in the order they were queued
+    pub fn publish_queued(&self) {
+        // Take the events out first, so subscribers can queue new events
+        let events = std::mem::take(&mut *self.queued.lock());
+
+        for event in &events {
+            self.publish(event);
+        }
+    }
+}
+
+/// The seconds a shell command subscriber may run, before it is stopped
+pub const DEFAULT_EVENT_SUBSCRIBER_TIMEOUT_SECS: u64 = 10;
+
+/// How often a running shell command is checked for having finished
+const EVENT_SUBSCRIBER_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A subscriber running a shell command for each event
+///
+/// The event is passed as JSON on stdin, its kind and the id of the activity
+/// are available in the `PACE_EVENT` and `PACE_ACTIVITY_ID` environment variables.
+/// A command that doesn't finish within its timeout is killed, so a hanging
+/// command can't block `pace`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellCommandSubscriber {
+    /// The command to run with the shell
+    command: String,
+
+    /// How long to wait for the command to finish
+    timeout: Duration,
+}
+
+impl ShellCommandSubscriber {
+    /// Create a new subscriber for the given shell command with the default timeout
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            timeout: Duration::from_secs(DEFAULT_EVENT_SUBSCRIBER_TIMEOUT_SECS),
+        }
+    }
+
+    /// Set how long to wait for the command to finish, before it is killed
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    #[cfg(windows)]
+    fn shell_command(&self) -> Command {
+        let mut command = Command::new("cmd");
+        let _ = command.arg("/C").arg(&self.command);
+        command
+    }
+
+    #[cfg(not(windows))]
+    fn shell_command(&self) -> Command {
+        let mut command = Command::new("sh");
+        let _ = command.arg("-c").arg(&self.command);
+        command
+    }
+}
+
+impl From<&EventSubscriberConfig> for ShellCommandSubscriber {
+    fn from(config: &EventSubscriberConfig) -> Self {
+        let timeout_secs = config
+            .timeout_secs()
+            .unwrap_or(DEFAULT_EVENT_SUBSCRIBER_TIMEOUT_SECS);
+
+        Self::new(config.command()).with_timeout(Duration::from_secs(timeout_secs))
+    }
+}
+
+impl EventSubscriber for ShellCommandSubscriber {
+    fn notify(&self, event: &PaceEvent) -> PaceResult<()> {
+        debug!("Running `{}` for {} event", self.command, event.kind());
+
+        let payload = serde_json::to_vec(event)?;
+
+        let mut child = self
+            .shell_command()
+            .env("PACE_EVENT", event.kind().to_string())
+            .env("PACE_ACTIVITY_ID", event.activity_id().to_string())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // The command doesn't need to read the event
+            if let Err(err) = stdin.write_all(&payload) {
+                if err.kind() != ErrorKind::BrokenPipe {
+                    return Err(err.into());
+                }
+            }
+        }
+
+        let deadline = Instant::now() + self.timeout;
+
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+
+            if Instant::now() >= deadline {
+                // The command may have finished in the meantime, so killing it can fail
+                if let Err(err) = child.kill() {
+                    debug!("Failed to kill `{}`: {err}", self.command);
+                }
+
+                _ = child.wait()?;
+
+                return Err(PaceErrorKind::EventSubscriberTimedOut(
+                    self.command.clone(),
+                    self.timeout.as_secs(),
+                )
+                .into());
+            }
+
+            thread::sleep(EVENT_SUBSCRIBER_POLL_INTERVAL);
+        };
+
+        if !status.success() {
+            return Err(PaceErrorKind::EventSubscriberFailed(
+                self.command.clone(),
+                status.to_string(),
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use crate::{
+        domain::activity::{Activity, ActivityItem},
+        error::{PaceError, TestResult},
+    };
+
+    use super::*;
+
+    fn activity_item(description: &str) -> ActivityItem {
+        ActivityItem::from(Activity::builder().description(description).build())
+    }
+
+    fn counting_subscriber(counter: &Arc<AtomicUsize>) -> impl EventSubscriber {
+        let counter = Arc::clone(counter);
+
+        move |_: &PaceEvent| -> PaceResult<()> {
+            let _ = counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_event_bus_publishes_to_matching_subscribers_passes() {
+        let event_bus = EventBus::default();
+
+        let all_events = Arc::new(AtomicUsize::new(0));
+        let began_events = Arc::new(AtomicUsize::new(0));
+        let activity_events = Arc::new(AtomicUsize::new(0));
+
+        let first = activity_item("First");
+        let second = activity_item("Second");
+
+        event_bus.subscribe(vec![], counting_subscriber(&all_events));
+        event_bus.subscribe(
+            vec![PaceEventKind::ActivityBegan],
+            counting_subscriber(&began_events),
+        );
+        event_bus.subscribe_to_activity(
+            *first.guid(),
+            vec![],
+            counting_subscriber(&activity_events),
+        );
+
+        event_bus.publish(&PaceEvent::ActivityBegan(first.clone()));
+        event_bus.publish(&PaceEvent::ActivityEnded(first));
+        event_bus.publish(&PaceEvent::ActivityBegan(second));
+
+        assert_eq!(all_events.load(Ordering::SeqCst), 3);
+        assert_eq!(began_events.load(Ordering::SeqCst), 2);
+        assert_eq!(activity_events.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_event_bus_publishes_queued_events_passes() {
+        let event_bus = EventBus::default();
+
+        let events = Arc::new(AtomicUsize::new(0));
+
+        event_bus.subscribe(vec![], counting_subscriber(&events));
+
+        event_bus.queue(PaceEvent::ActivityBegan(activity_item("First")));
+        event_bus.queue(PaceEvent::ActivityBegan(activity_item("Second")));
+
+        assert_eq!(
+            events.load(Ordering::SeqCst),
+            0,
+            "Queued events should not be published yet."
+        );
+
+        event_bus.publish_queued();
+        event_bus.publish_queued();
+
+        assert_eq!(
+            events.load(Ordering::SeqCst),
+            2,
+            "Queued events should be published exactly once."
+        );
+    }
+
+    #[test]
+    fn test_event_bus_failing_subscriber_does_not_stop_others_passes() {
+        let event_bus = EventBus::default();
+
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        event_bus.subscribe(vec![], |_: &PaceEvent| -> PaceResult<()> {
+            Err(
+                PaceErrorKind::EventSubscriberFailed("test".to_string(), "failed".to_string())
+                    .into(),
+            )
+        });
+        event_bus.subscribe(vec![], counting_subscriber(&counter));
+
+        event_bus.publish(&PaceEvent::ActivityHeld(activity_item("Held")));
+
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_shell_command_subscriber_receives_json_on_stdin_passes() -> TestResult<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let output_path = temp_dir.path().join("event.json");
+
+        let subscriber = ShellCommandSubscriber::new(format!(
+            "cat > '{}' && test \"$PACE_EVENT\" = activity-resumed",
+            output_path.display()
+        ));
+
+        let activity_item = activity_item("Resumed");
+
+        subscriber.notify(&PaceEvent::ActivityResumed(activity_item.clone()))?;
+
+        let payload: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(output_path)?)?;
+
+        assert_eq!(payload["event"], "activity-resumed");
+        assert_eq!(payload["guid"], activity_item.guid().to_string());
+
+        assert!(ShellCommandSubscriber::new("exit 1")
+            .notify(&PaceEvent::ActivityResumed(activity_item))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_shell_command_subscriber_kills_command_after_timeout_fails() {
+        let subscriber =
+            ShellCommandSubscriber::new("sleep 30").with_timeout(Duration::from_millis(100));
+
+        let started = Instant::now();
+
+        let result = subscriber.notify(&PaceEvent::ActivityBegan(activity_item("Hanging")));
+
+        assert!(matches!(
+            result.map_err(PaceError::into_inner),
+            Err(PaceErrorKind::EventSubscriberTimedOut(..))
+        ));
+        assert!(
+            started.elapsed() < Duration::from_secs(10),
+            "Should not wait for the command to finish."
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use std::{
+    fs::{create_dir_all, read_to_string},
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDateTime;
+use getset::{Getters, MutGetters};
+use tracing::debug;
+
+use crate::{
+    config::{InboxConfig, PaceConfig},
+    domain::{
+        inbox::{Inbox, InboxItem},
+        task::TaskList,
+    },
+    error::{PaceErrorKind, PaceResult},
+    storage::{lock::ActivityLogFile, SyncStorage},
+};
+
+/// The inbox store is responsible for loading and persisting the inbox
+///
+/// The inbox is stored in a TOML file in the same format as the tasks file.
+/// Items that are older than the configured amount of days are moved to an
+/// archive file next to it, e.g. `inbox.archive.pace.toml`.
+///
+/// Like the activity log, the inbox is locked while it is changed and written
+/// atomically, so concurrent pace processes don't lose or corrupt items.
+#[derive(Debug, Getters, MutGetters)]
+#[getset(get = "pub")]
+pub struct InboxStore {
+    /// The path to the inbox file
+    path: PathBuf,
+
+    /// The inbox file, locked while the inbox is changed
+    #[getset(skip)]
+    file: ActivityLogFile,
+
+    /// The inbox configuration
+    config: InboxConfig,
+
+    /// The inbox
+    #[getset(get_mut = "pub")]
+    inbox: Inbox,
+
+    /// The items that have been archived, but not written to the archive yet
+    archived: Vec<InboxItem>,
+}
+
+impl InboxStore {
+    /// Create a new `InboxStore` from an inbox file
+    ///
+    /// The inbox starts empty, if the file doesn't exist yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the inbox file
+    /// * `config` - The inbox configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or deserialized
+    ///
+    /// # Returns
+    ///
+    /// Returns a new `InboxStore`
+    #[tracing::instrument(skip(path))]
+    pub fn new(path: impl AsRef<Path>, config: InboxConfig) -> PaceResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = ActivityLogFile::new(&path);
+
+        let mut inbox = Inbox::default();
+
+        if path.exists() {
+            file.load(|path| {
+                inbox = load_inbox(path)?;
+                Ok(())
+            })?;
+        } else {
+            debug!("Inbox file {} doesn't exist yet", path.display());
+        }
+
+        Ok(Self {
+            path,
+            file,
+            config,
+            inbox,
+            archived: vec![],
+        })
+    }
+
+    /// Create a new `InboxStore` from the inbox file in the configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inbox file exists but cannot be read or deserialized
+    ///
+    /// # Returns
+    ///
+    /// Returns a new `InboxStore`
+    pub fn from_config(config: &PaceConfig) -> PaceResult<Self> {
+        Self::new(config.inbox_path(), config.inbox().unwrap_or_default())
+    }
+
+    /// Take the lock on the inbox file before changing the inbox
+    ///
+    /// The inbox is reloaded, if it has changed on disk in the meantime, so no
+    /// items of other pace processes get lost. The lock is held until the inbox
+    /// is written with [`SyncStorage::sync`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock cannot be taken or the inbox cannot be reloaded
+    pub fn lock_for_changes(&mut self) -> PaceResult<()> {
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.exists()) {
+            create_dir_all(parent)
+                .map_err(|_| PaceErrorKind::ParentDirNotFound(self.path.clone()))?;
+        }
+
+        let inbox = &mut self.inbox;
+
+        self.file.lock_for_changes(|path| {
+            *inbox = load_inbox(path)?;
+            Ok(())
+        })
+    }
+
+    /// Get the path to the archive file of the inbox
+    #[must_use]
+    pub fn archive_path(&self) -> PathBuf {
+        let stem = self
+            .path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or("inbox", |name| {
+                name.strip_suffix(".pace.toml").unwrap_or(name)
+            })
+            .to_string();
+
+        self.path
+            .with_file_name(format!("{stem}.archive.pace.toml"))
+    }
+
+    /// The maximum amount of items the inbox can hold, `0` for no limit
+    #[must_use]
+    pub fn max_size(&self) -> usize {
+        usize::try_from(*self.config.max_size()).unwrap_or(usize::MAX)
+    }
+
+    /// Move items that are older than the configured amount of days out of the inbox
+    ///
+    /// The items are written to the archive on the next sync, so the inbox needs
+    /// to be locked with [`InboxStore::lock_for_changes`] first.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current date and time
+    ///
+    /// # Returns
+    ///
+    /// The amount of archived items
+    pub fn archive_stale(&mut self, now: NaiveDateTime) -> usize {
+        let stale = self
+            .inbox
+            .remove_stale(now, *self.config.auto_archive_after_days());
+
+        debug!("Archiving {} stale inbox items", stale.len());
+
+        let count = stale.len();
+
+        self.archived.extend(stale);
+
+        count
+    }
+}
+
+impl SyncStorage for InboxStore {
+    #[tracing::instrument(skip(self))]
+    fn sync(&self) -> PaceResult<()> {
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.exists()) {
+            create_dir_all(parent)
+                .map_err(|_| PaceErrorKind::ParentDirNotFound(self.path.clone()))?;
+        }
+
+        // Write the archive first, so archived items are never lost
+        if !self.archived.is_empty() {
+            let archive_file = ActivityLogFile::new(self.archive_path());
+
+            let mut archive = TaskList::default();
+
+            // Holds the lock until the archive is written, so other pace processes don't overwrite it
+            archive_file.lock_for_changes(|path| {
+                archive = toml::from_str::<TaskList>(&read_to_string(path)?)?;
+                Ok(())
+            })?;
+
+            for item in &self.archived {
+                _ = archive.insert(*item.id(), item.task().clone());
+            }
+
+            archive_file.write_atomically(toml::to_string(&archive)?.as_bytes())?;
+            archive_file.finish_changes()?;
+        }
+
+        self.file
+            .write_atomically(toml::to_string(&self.inbox)?.as_bytes())?;
+        self.file.finish_changes()?;
+
+        Ok(())
+    }
+}
+
+/// Read the inbox from a file
+///
+/// # Arguments
+///
+/// * `path` - The path to the inbox file
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or deserialized
+fn load_inbox(path: &Path) -> PaceResult<Inbox> {
+    Ok(toml::from_str::<Inbox>(&read_to_string(path)?)?)
+}
@@ -0,0 +1,94 @@
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use getset::Getters;
+use tracing::debug;
+
+use crate::{
+    config::find_root_project_file,
+    constants::PACE_PROJECTS_FILENAME,
+    domain::project::{ProjectGuid, ProjectList},
+    error::{PaceOptResult, PaceResult},
+};
+
+/// The project store is responsible for loading the project hierarchy
+///
+/// The projects are defined in a `projects.pace.toml` file, which applies to
+/// the directory it is located in and all directories below.
+#[derive(Debug, Getters)]
+#[getset(get = "pub")]
+pub struct ProjectStore {
+    /// The path to the projects file
+    path: PathBuf,
+
+    /// The project list
+    project_list: ProjectList,
+}
+
+impl ProjectStore {
+    /// Create a new `ProjectStore` from a projects file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the projects file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or deserialized
+    ///
+    /// # Returns
+    ///
+    /// Returns a new `ProjectStore`
+    #[tracing::instrument(skip(path))]
+    pub fn new(path: impl AsRef<Path>) -> PaceResult<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        let project_list = toml::from_str::<ProjectList>(&read_to_string(&path)?)?;
+
+        Ok(Self { path, project_list })
+    }
+
+    /// Find the nearest projects file from a directory upwards and load it
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory to start searching from
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the projects file cannot be read or deserialized
+    ///
+    /// # Returns
+    ///
+    /// Returns a new `ProjectStore`, `None` if there is no projects file
+    pub fn discover(directory: impl AsRef<Path>) -> PaceOptResult<Self> {
+        let Some(path) = find_root_project_file(directory, PACE_PROJECTS_FILENAME) else {
+            debug!("No projects file found");
+
+            return Ok(None);
+        };
+
+        debug!("Found projects file: {}", path.display());
+
+        Self::new(path).map(Some)
+    }
+
+    /// Get the project a directory belongs to
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory to find the project for
+    ///
+    /// # Returns
+    ///
+    /// The id of the project, `None` if there are no projects
+    #[must_use]
+    pub fn project_for_directory(&self, directory: impl AsRef<Path>) -> Option<ProjectGuid> {
+        let root = self.path.parent().unwrap_or_else(|| Path::new(""));
+
+        self.project_list
+            .project_for_directory(root, directory.as_ref())
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    fs::{create_dir_all, read_to_string, write},
+    path::{Path, PathBuf},
+};
+
+use getset::{Getters, MutGetters};
+use tracing::debug;
+
+use crate::{
+    config::PaceConfig,
+    domain::task::TaskList,
+    error::{PaceErrorKind, PaceResult},
+    storage::SyncStorage,
+};
+
+/// The task store is responsible for loading and persisting the task list
+///
+/// The tasks are stored in a TOML file, keyed by their id.
+#[derive(Debug, Getters, MutGetters)]
+#[getset(get = "pub")]
+pub struct TaskStore {
+    /// The path to the tasks file
+    path: PathBuf,
+
+    /// The task list
+    #[getset(get_mut = "pub")]
+    task_list: TaskList,
+}
+
+impl TaskStore {
+    /// Create a new `TaskStore` from a tasks file
+    ///
+    /// The task list starts empty, if the file doesn't exist yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the tasks file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or deserialized
+    ///
+    /// # Returns
+    ///
+    /// Returns a new `TaskStore`
+    #[tracing::instrument(skip(path))]
+    pub fn new(path: impl AsRef<Path>) -> PaceResult<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        let task_list = if path.exists() {
+            toml::from_str::<TaskList>(&read_to_string(&path)?)?
+        } else {
+            debug!("Tasks file {} doesn't exist yet", path.display());
+
+            TaskList::default()
+        };
+
+        Ok(Self { path, task_list })
+    }
+
+    /// Create a new `TaskStore` from the tasks file in the configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tasks file exists but cannot be read or deserialized
+    ///
+    /// # Returns
+    ///
+    /// Returns a new `TaskStore`
+    pub fn from_config(config: &PaceConfig) -> PaceResult<Self> {
+        Self::new(config.tasks_path())
+    }
+}
+
+impl SyncStorage for TaskStore {
+    #[tracing::instrument(skip(self))]
+    fn sync(&self) -> PaceResult<()> {
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.exists()) {
+            create_dir_all(parent)
+                .map_err(|_| PaceErrorKind::ParentDirNotFound(self.path.clone()))?;
+        }
+
+        write(&self.path, toml::to_string(&self.task_list)?)?;
+
+        Ok(())
+    }
+}
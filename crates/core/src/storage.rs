@@ -1,8 +1,17 @@
-use std::{collections::BTreeMap, fmt::Display, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    path::Path,
+    sync::Arc,
+};
 
 use enum_dispatch::enum_dispatch;
 use itertools::Itertools;
-use pace_time::{date::PaceDate, duration::PaceDurationRange, time_range::TimeRangeOptions};
+use pace_time::{
+    date::PaceDate,
+    duration::{PaceDurationRange, PaceDurationRangeOptions},
+    time_range::TimeRangeOptions,
+};
 use tracing::debug;
 
 use crate::{
@@ -10,7 +19,7 @@ use crate::{
         hold::HoldOptions, resume::ResumeOptions, DeleteOptions, EndOptions, KeywordOptions,
         UpdateOptions,
     },
-    config::{ActivityLogStorageKind, PaceConfig},
+    config::{ActivityLogFormatKind, ActivityLogStorageKind, PaceConfig},
     domain::{
         activity::{Activity, ActivityGuid, ActivityItem, ActivityKind},
         filter::{ActivityFilterKind, FilteredActivities},
@@ -18,17 +27,35 @@ use crate::{
     },
     error::{PaceErrorKind, PaceOptResult, PaceResult},
     service::activity_store::ActivityStore,
-    storage::{file::TomlActivityStorage, in_memory::InMemoryActivityStorage},
+    storage::{
+        file::TomlActivityStorage,
+        git::GitActivityStorage,
+        in_memory::InMemoryActivityStorage,
+        journal::{JournalActivityStorage, DEFAULT_JOURNAL_COMPACTION_THRESHOLD},
+    },
 };
 
+#[cfg(feature = "sqlite")]
+use crate::{config::DatabaseEngineKind, storage::sqlite::SqliteActivityStorage};
+
 /// A type of storage that can be synced to a persistent medium - a file
 pub mod file;
 
+/// A git-backed storage backend for activities.
+pub mod git;
+
 /// An in-memory storage backend for activities.
 pub mod in_memory;
-// TODO: Implement conversion FromSQL and ToSQL
-// #[cfg(feature = "sqlite")]
-// pub mod sqlite;
+
+/// An append-only journal storage backend for activities.
+pub mod journal;
+
+/// Advisory locking and atomic writes for activity log files.
+pub mod lock;
+
+/// A `SQLite` storage backend for activities.
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 
 /// Get the storage backend from the configuration.
 ///
@@ -46,8 +73,25 @@ pub mod in_memory;
 pub fn get_storage_from_config(config: &PaceConfig) -> PaceResult<Arc<StorageKind>> {
     let storage: StorageKind = match config.general().activity_log_options().storage_kind() {
         ActivityLogStorageKind::File => {
-            TomlActivityStorage::new(config.general().activity_log_options().path())?.into()
+            let activity_log_options = config.general().activity_log_options();
+
+            match activity_log_options.format_kind() {
+                Some(ActivityLogFormatKind::Journal) => JournalActivityStorage::new(
+                    activity_log_options.path(),
+                    activity_log_options
+                        .journal_compaction_threshold()
+                        .unwrap_or(DEFAULT_JOURNAL_COMPACTION_THRESHOLD),
+                )?
+                .into(),
+                _ => TomlActivityStorage::new(activity_log_options.path())?.into(),
+            }
+        }
+        ActivityLogStorageKind::Git => {
+            GitActivityStorage::new(config.general().activity_log_options().path())?.into()
         }
+        #[cfg(feature = "sqlite")]
+        ActivityLogStorageKind::Database => get_sqlite_storage_from_config(config)?.into(),
+        #[cfg(not(feature = "sqlite"))]
         ActivityLogStorageKind::Database => {
             return Err(PaceErrorKind::DatabaseStorageNotImplemented.into())
         }
@@ -60,11 +104,76 @@ pub fn get_storage_from_config(config: &PaceConfig) -> PaceResult<Arc<StorageKin
     Ok(Arc::new(storage))
 }
 
+/// Get a file storage backend for the activity log at the given path.
+///
+/// Activity logs ending in `.jsonl` are read as journals, all others as TOML files.
+///
+/// # Arguments
+///
+/// * `path` - The path to the activity log.
+///
+/// # Errors
+///
+/// This function returns an error if the activity log doesn't exist or cannot be read.
+///
+/// # Returns
+///
+/// The storage backend for the activity log.
+pub fn get_storage_from_path(path: &Path) -> PaceResult<Arc<StorageKind>> {
+    if !path.is_file() {
+        return Err(PaceErrorKind::ActivityLogNotFound(path.display().to_string()).into());
+    }
+
+    let storage: StorageKind = match ActivityLogFormatKind::from_path(path) {
+        ActivityLogFormatKind::Journal => {
+            JournalActivityStorage::new(path, DEFAULT_JOURNAL_COMPACTION_THRESHOLD)?.into()
+        }
+        ActivityLogFormatKind::Toml => TomlActivityStorage::new(path)?.into(),
+    };
+
+    debug!("Using storage backend: {}", storage);
+
+    Ok(Arc::new(storage))
+}
+
+/// Get the `SQLite` storage backend from the database configuration.
+///
+/// # Arguments
+///
+/// * `config` - The application configuration.
+///
+/// # Errors
+///
+/// This function returns an error if there is no database configuration, the configured
+/// database engine is not supported or the connection to the database cannot be established.
+///
+/// # Returns
+///
+/// The `SQLite` storage backend.
+#[cfg(feature = "sqlite")]
+pub fn get_sqlite_storage_from_config(config: &PaceConfig) -> PaceResult<SqliteActivityStorage> {
+    let database_config = config
+        .database()
+        .as_ref()
+        .ok_or(PaceErrorKind::DatabaseConfigNotFound)?;
+
+    match database_config.engine() {
+        DatabaseEngineKind::Sqlite => {
+            SqliteActivityStorage::new(database_config.connection_string())
+        }
+        _ => Err(PaceErrorKind::DatabaseStorageNotImplemented.into()),
+    }
+}
+
 #[enum_dispatch]
 pub enum StorageKind {
     ActivityStore,
     InMemoryActivityStorage,
     TomlActivityStorage,
+    JournalActivityStorage,
+    GitActivityStorage,
+    #[cfg(feature = "sqlite")]
+    SqliteActivityStorage,
 }
 
 impl Display for StorageKind {
@@ -75,6 +184,30 @@ impl Display for StorageKind {
                 write!(f, "StorageKind: InMemoryActivityStorage")
             }
             Self::TomlActivityStorage(_) => write!(f, "StorageKind: TomlActivityStorage"),
+            Self::JournalActivityStorage(_) => write!(f, "StorageKind: JournalActivityStorage"),
+            Self::GitActivityStorage(_) => write!(f, "StorageKind: GitActivityStorage"),
+            #[cfg(feature = "sqlite")]
+            Self::SqliteActivityStorage(_) => write!(f, "StorageKind: SqliteActivityStorage"),
+        }
+    }
+}
+
+impl StorageKind {
+    /// The path to the file the activities are stored in
+    ///
+    /// # Returns
+    ///
+    /// The path of the activity log, `None` if the activities aren't stored in a file
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::ActivityStore(activity_store) => activity_store.storage().path(),
+            Self::TomlActivityStorage(storage) => Some(storage.path()),
+            Self::JournalActivityStorage(storage) => Some(storage.path()),
+            Self::GitActivityStorage(storage) => Some(storage.path()),
+            Self::InMemoryActivityStorage(_) => None,
+            #[cfg(feature = "sqlite")]
+            Self::SqliteActivityStorage(_) => None,
         }
     }
 }
@@ -103,10 +236,13 @@ pub trait SyncStorage {
 /// persistent storage that can be used to store activities.
 #[enum_dispatch(StorageKind)]
 pub trait ActivityStorage:
-    ActivityReadOps + ActivityWriteOps + ActivityStateManagement + SyncStorage + ActivityQuerying
-// TODO!: Implement other traits
-// + ActivityTagging
-// + ActivityArchiving
+    ActivityReadOps
+    + ActivityWriteOps
+    + ActivityStateManagement
+    + SyncStorage
+    + ActivityQuerying
+    + ActivityArchiving
+    + ActivityTagging // TODO!: Implement other traits
 // + ActivityStatistics
 {
     // This main trait combines all aspects of activity storage.
@@ -182,6 +318,23 @@ pub trait ActivityWriteOps: ActivityReadOps {
     /// If the activity was created successfully it should return the ID of the created activity.
     fn create_activity(&self, activity: Activity) -> PaceResult<ActivityItem>;
 
+    /// Insert an activity with the ID it already has into the storage backend.
+    ///
+    /// This is used to bring back activities that existed before, e.g. when undoing their deletion.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_item` - The activity to insert, together with its ID.
+    ///
+    /// # Errors
+    ///
+    /// This function should return an error if the ID is already in use or the activity cannot be inserted.
+    ///
+    /// # Returns
+    ///
+    /// If the activity was inserted successfully it should return the inserted activity.
+    fn insert_activity(&self, activity_item: ActivityItem) -> PaceResult<ActivityItem>;
+
     /// Update an existing activity in the storage backend.
     ///
     /// # Note
@@ -425,9 +578,16 @@ pub trait ActivityStateManagement: ActivityReadOps + ActivityWriteOps + Activity
 /// find all activities within a specific date range, or get a specific activity by its ID.
 #[enum_dispatch(StorageKind)]
 pub trait ActivityQuerying: ActivityReadOps {
-    /// Group activities by predefined duration ranges (e.g., short, medium, long).
+    /// Group activities by duration ranges (e.g., short, medium, long).
     ///
     /// This is useful for analyzing how time is spent on different activities.
+    /// Only activities that are tracked as work, i.e. activities and pomodoro work
+    /// sessions, are grouped. Activities that haven't ended yet are grouped by the
+    /// time that has elapsed since they began.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration_range_opts` - The boundaries between short, medium, and long durations
     ///
     /// # Errors
     ///
@@ -439,9 +599,9 @@ pub trait ActivityQuerying: ActivityReadOps {
     /// The key is the duration range, and the value is a list of activities that
     /// fall within that range.
     /// If no activities are found, it should return `Ok(None)`.
-    // TODO!: This method requires defining what constitutes short, medium, and long durations.
     fn group_activities_by_duration_range(
         &self,
+        duration_range_opts: PaceDurationRangeOptions,
     ) -> PaceOptResult<BTreeMap<PaceDurationRange, Vec<ActivityItem>>>;
 
     /// Group activities by their start date. This can help in analyzing how
@@ -532,6 +692,27 @@ pub trait ActivityQuerying: ActivityReadOps {
         time_range_opts: TimeRangeOptions,
     ) -> PaceOptResult<Vec<ActivityGuid>>;
 
+    /// List the activity items of all kinds that began within a time range from the storage backend.
+    ///
+    /// In contrast to [`ActivityQuerying::list_activities_by_time_range`], intermissions are included.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_range_opts` - The range options to filter the activities by.
+    ///
+    /// # Errors
+    ///
+    /// This function should return an error if the activities cannot be loaded.
+    ///
+    /// # Returns
+    ///
+    /// A collection of the activity items that began within the time range.
+    /// If no activities are found, it should return `Ok(None)`.
+    fn list_activity_items_by_time_range(
+        &self,
+        time_range_opts: TimeRangeOptions,
+    ) -> PaceOptResult<Vec<ActivityItem>>;
+
     /// Group activities by their status from the storage backend.
     ///
     /// # Errors
@@ -840,83 +1021,260 @@ pub trait ActivityQuerying: ActivityReadOps {
     }
 }
 
-// /// Tagging Activities
-// ///
-// /// Tagging activities is a way to categorize them. This is useful for grouping activities together that have something in common.
-// /// For example, you might want to tag all activities that are related to a specific project, or all activities that are related to a specific client.
-// /// Tags can be used to generate statistics or summaries of activities, or to filter activities by a specific tag.
-// pub trait ActivityTagging {
-//     /// Add a tag to an activity.
-//     ///
-//     /// # Arguments
-//     ///
-//     /// * `activity_id` - The ID of the activity to tag.
-//     /// * `tag` - The tag to add.
-//     ///
-//     /// # Errors
-//     ///
-//     /// This function should return an error if the tag cannot be added.
-//     ///
-//     /// # Returns
-//     ///
-//     /// If the tag was added successfully it should return `Ok(())`.
-//     fn add_tag_to_activity(&self, activity_id: ActivityGuid, tag: &str) -> PaceResult<()>;
+/// Tagging Activities
+///
+/// Tagging activities is a way to categorize them. This is useful for grouping activities together that have something in common.
+/// For example, you might want to tag all activities that are related to a specific project, or all activities that are related to a specific client.
+/// Tags can be used to generate statistics or summaries of activities, or to filter activities by a specific tag.
+#[enum_dispatch(StorageKind)]
+pub trait ActivityTagging: ActivityReadOps + ActivityWriteOps {
+    /// Add a tag to an activity.
+    ///
+    /// Adding a tag the activity already has is not an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_id` - The ID of the activity to tag.
+    /// * `tag` - The tag to add.
+    ///
+    /// # Errors
+    ///
+    /// This function should return an error if the tag cannot be added.
+    ///
+    /// # Returns
+    ///
+    /// If the tag was added successfully it should return the tagged activity.
+    fn add_tag_to_activity(&self, activity_id: ActivityGuid, tag: &str)
+        -> PaceResult<ActivityItem>;
 
-//     /// Remove a tag from an activity.
-//     ///
-//     /// # Arguments
-//     ///
-//     /// * `activity_id` - The ID of the activity to untag.
-//     /// * `tag` - The tag to remove.
-//     ///
-//     /// # Errors
-//     ///
-//     /// This function should return an error if the tag cannot be removed.
-//     ///
-//     /// # Returns
-//     ///
-//     /// If the tag was removed successfully it should return `Ok(())`.
-//     fn remove_tag_from_activity(&self, activity_id: ActivityGuid, tag: &str) -> PaceResult<()>;
-// }
+    /// Remove a tag from an activity.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_id` - The ID of the activity to untag.
+    /// * `tag` - The tag to remove.
+    ///
+    /// # Errors
+    ///
+    /// This function should return an error if the activity doesn't have the tag or it cannot be removed.
+    ///
+    /// # Returns
+    ///
+    /// If the tag was removed successfully it should return the untagged activity.
+    fn remove_tag_from_activity(
+        &self,
+        activity_id: ActivityGuid,
+        tag: &str,
+    ) -> PaceResult<ActivityItem>;
 
-// /// Archiving Activities
-// ///
-// /// Archiving activities is a way to remove them from the main list of activities, but still keep them around for reference.
-// /// This is useful for activities that are no longer relevant, but you still want to keep them around for historical purposes.
-// ///
-// /// For example, you might want to archive all activities from a previous year to keep the main list of activities clean and relevant.
-// /// Archiving is different from deleting an activity, as it doesn't remove the activity from the system, it just moves it to a different list.
-// pub trait ActivityArchiving {
-//     /// Archive an activity.
-//     ///
-//     /// # Arguments
-//     ///
-//     /// * `activity_id` - The ID of the activity to archive.
-//     ///
-//     /// # Errors
-//     ///
-//     /// This function should return an error if the activity cannot be archived.
-//     ///
-//     /// # Returns
-//     ///
-//     /// If the activity was archived successfully it should return `Ok(())`.
-//     fn archive_activity(&self, activity_id: ActivityGuid) -> PaceResult<()>;
+    /// Rename a tag on all activities.
+    ///
+    /// If an activity already has the new tag, both tags are merged into one.
+    ///
+    /// # Arguments
+    ///
+    /// * `old_tag` - The tag to rename.
+    /// * `new_tag` - The new name of the tag.
+    ///
+    /// # Errors
+    ///
+    /// This function should return an error if the tag cannot be renamed.
+    ///
+    /// # Returns
+    ///
+    /// The amount of activities the tag has been renamed on.
+    fn rename_tag(&self, old_tag: &str, new_tag: &str) -> PaceResult<usize>;
 
-//     /// Unarchive an activity.
-//     ///
-//     /// # Arguments
-//     ///
-//     /// * `activity_id` - The ID of the activity to unarchive.
-//     ///
-//     /// # Errors
-//     ///
-//     /// This function should return an error if the activity cannot be unarchived.
-//     ///
-//     /// # Returns
-//     ///
-//     /// If the activity was unarchived successfully it should return `Ok(())`.
-//     fn unarchive_activity(&self, activity_id: ActivityGuid) -> PaceResult<()>;
-// }
+    /// List all tags with the amount of activities they are used on.
+    ///
+    /// # Errors
+    ///
+    /// This function should return an error if the activities cannot be loaded.
+    ///
+    /// # Returns
+    ///
+    /// A map of the tags to the amount of activities tagged with them, sorted by tag.
+    fn list_tags(&self) -> PaceResult<BTreeMap<String, usize>>;
+}
+
+/// Archiving Activities
+///
+/// Archiving activities is a way to remove them from the main list of activities, but still keep them around for reference.
+/// This is useful for activities that are no longer relevant, but you still want to keep them around for historical purposes.
+///
+/// For example, you might want to archive all activities from a previous year to keep the main list of activities clean and relevant.
+/// Archiving is different from deleting an activity, as it doesn't remove the activity from the system, it just moves it to a different list.
+#[enum_dispatch(StorageKind)]
+pub trait ActivityArchiving: ActivityReadOps + ActivityWriteOps {
+    /// Archive an activity.
+    ///
+    /// Only activities that have ended can be archived.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_id` - The ID of the activity to archive.
+    ///
+    /// # Errors
+    ///
+    /// This function should return an error if the activity cannot be archived.
+    ///
+    /// # Returns
+    ///
+    /// If the activity was archived successfully it should return the archived activity.
+    fn archive_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem>;
+
+    /// Unarchive an activity.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_id` - The ID of the activity to unarchive.
+    ///
+    /// # Errors
+    ///
+    /// This function should return an error if the activity cannot be unarchived.
+    ///
+    /// # Returns
+    ///
+    /// If the activity was unarchived successfully it should return the unarchived activity.
+    fn unarchive_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem>;
+}
+
+/// The activities an operation may change
+///
+/// Only the states of these activities are read before and after the operation to
+/// record its changes, instead of listing the whole activity log twice.
+#[derive(Debug, Clone)]
+pub enum ChangedActivities {
+    /// The activities with these ids
+    Ids(Vec<ActivityGuid>),
+
+    /// The activities matching any of these filters, before or after the operation
+    Filtered(Vec<ActivityFilterKind>),
+}
+
+impl ChangedActivities {
+    /// The activities that haven't ended, which are the only ones that a change of the
+    /// activity state (e.g. begin, hold, resume or end) affects, besides newly created ones
+    pub fn unfinished() -> Self {
+        Self::Filtered(vec![
+            ActivityFilterKind::Active,
+            ActivityFilterKind::ActiveIntermission,
+            ActivityFilterKind::Held,
+        ])
+    }
+
+    /// The activities with a tag, e.g. to rename it
+    ///
+    /// There is no query for the activities with a tag, so they are looked up up front.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be listed
+    pub fn tagged(storage: &impl ActivityQuerying, tag: &str) -> PaceResult<Self> {
+        Ok(Self::Ids(
+            storage
+                .list_activities_by_id()?
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|(_, activity)| {
+                    activity
+                        .tags()
+                        .as_ref()
+                        .is_some_and(|tags| tags.contains(tag))
+                })
+                .map(|(activity_id, _)| activity_id)
+                .collect(),
+        ))
+    }
+
+    /// The ids of the activities that may be changed, as they are currently in the storage
+    fn ids(&self, storage: &impl ActivityReadOps) -> PaceResult<BTreeSet<ActivityGuid>> {
+        match self {
+            Self::Ids(ids) => Ok(ids.iter().copied().collect()),
+            Self::Filtered(filters) => {
+                let mut ids = BTreeSet::new();
+
+                for filter in filters {
+                    ids.extend(
+                        storage
+                            .list_activities(*filter)?
+                            .map(FilteredActivities::into_vec)
+                            .unwrap_or_default(),
+                    );
+                }
+
+                Ok(ids)
+            }
+        }
+    }
+
+    /// Read the states of the activities before the operation
+    ///
+    /// # Arguments
+    ///
+    /// * `storage` - The storage the operation changes
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be read
+    ///
+    /// # Returns
+    ///
+    /// The activities that exist before the operation by their id
+    pub fn before(
+        &self,
+        storage: &impl ActivityReadOps,
+    ) -> PaceResult<BTreeMap<ActivityGuid, Activity>> {
+        read_existing_activities(storage, self.ids(storage)?)
+    }
+
+    /// Read the states of the activities after the operation
+    ///
+    /// # Arguments
+    ///
+    /// * `storage` - The storage the operation has changed
+    /// * `before` - The states of the activities before the operation
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be read
+    ///
+    /// # Returns
+    ///
+    /// The activities that exist after the operation by their id, activities of `before`
+    /// that are missing have been deleted
+    pub fn after(
+        &self,
+        storage: &impl ActivityReadOps,
+        before: &BTreeMap<ActivityGuid, Activity>,
+    ) -> PaceResult<BTreeMap<ActivityGuid, Activity>> {
+        let mut ids = self.ids(storage)?;
+
+        ids.extend(before.keys().copied());
+
+        read_existing_activities(storage, ids)
+    }
+}
+
+/// Read the activities with the given ids, skipping the ones that don't exist
+fn read_existing_activities(
+    storage: &impl ActivityReadOps,
+    ids: impl IntoIterator<Item = ActivityGuid>,
+) -> PaceResult<BTreeMap<ActivityGuid, Activity>> {
+    let mut activities = BTreeMap::new();
+
+    for activity_id in ids {
+        match storage.read_activity(activity_id) {
+            Ok(activity_item) => {
+                _ = activities.insert(activity_id, activity_item.activity().clone());
+            }
+            Err(err) if err.is_activity_not_found() => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(activities)
+}
 
 // /// Generate Statistics for Activities
 // ///
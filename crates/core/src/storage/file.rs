@@ -1,11 +1,15 @@
 use std::{
     collections::BTreeMap,
-    fs::{create_dir_all, write, OpenOptions},
+    fs::{create_dir_all, read_to_string, OpenOptions},
     io::Write,
-    path::{Path, PathBuf},
+    path::Path,
 };
 
-use pace_time::{date::PaceDate, duration::PaceDurationRange, time_range::TimeRangeOptions};
+use pace_time::{
+    date::PaceDate,
+    duration::{PaceDurationRange, PaceDurationRangeOptions},
+    time_range::TimeRangeOptions,
+};
 
 use crate::{
     commands::{
@@ -20,20 +24,25 @@ use crate::{
     },
     error::{PaceErrorKind, PaceOptResult, PaceResult},
     storage::{
-        in_memory::InMemoryActivityStorage, ActivityQuerying, ActivityReadOps,
-        ActivityStateManagement, ActivityStorage, ActivityWriteOps, SyncStorage,
+        in_memory::InMemoryActivityStorage, lock::ActivityLogFile, ActivityArchiving,
+        ActivityQuerying, ActivityReadOps, ActivityStateManagement, ActivityStorage,
+        ActivityTagging, ActivityWriteOps, SyncStorage,
     },
 };
 
 /// In-memory backed TOML activity storage
 ///
 /// This storage is backed by an in-memory cache and a TOML file on disk for persistence.
+///
+/// Several pace processes can use the same activity log: changes take an advisory
+/// lock on the activity log, which is held until they are synced, and the cache
+/// is reloaded if the activity log has changed on disk since it was loaded.
 pub struct TomlActivityStorage {
     /// The in-memory cache
     cache: InMemoryActivityStorage,
 
-    /// The path to the TOML file
-    path: PathBuf,
+    /// The TOML file
+    file: ActivityLogFile,
 }
 
 impl SyncStorage for TomlActivityStorage {
@@ -57,16 +66,22 @@ impl TomlActivityStorage {
     ///
     /// Returns a new `TomlActivityStorage`
     pub fn new(path: impl AsRef<Path>) -> PaceResult<Self> {
-        let mut storage = Self {
+        let storage = Self {
             cache: InMemoryActivityStorage::new(),
-            path: path.as_ref().to_path_buf(),
+            file: ActivityLogFile::new(path),
         };
 
-        storage.load()?;
+        storage.file.load(|path| storage.load(path))?;
 
         Ok(storage)
     }
 
+    /// The path to the TOML file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
     /// Load the TOML file into the in-memory cache
     ///
     /// This will read the TOML file from disk and load it into the in-memory cache
@@ -79,16 +94,30 @@ impl TomlActivityStorage {
     ///
     /// Returns `Ok(())` if the data is loaded successfully
     #[tracing::instrument(skip(self))]
-    fn load(&mut self) -> PaceResult<()> {
-        let data = std::fs::read_to_string(&self.path)?;
-        self.cache = InMemoryActivityStorage::from(toml::from_str::<ActivityLog>(&data)?);
+    fn load(&self, path: &Path) -> PaceResult<()> {
+        let data = read_to_string(path)?;
+
+        self.cache
+            .set_activity_log(toml::from_str::<ActivityLog>(&data)?);
 
         Ok(())
     }
 
+    /// Reload the in-memory cache, if the TOML file has changed on disk
+    fn reload_if_stale(&self) -> PaceResult<()> {
+        self.file.reload_if_stale(|path| self.load(path))
+    }
+
+    /// Take the lock on the TOML file before changing the in-memory cache
+    fn lock_for_changes(&self) -> PaceResult<()> {
+        self.file.lock_for_changes(|path| self.load(path))
+    }
+
     /// Sync the in-memory cache to the TOML file
     ///
-    /// This will write the in-memory cache to the TOML file on disk
+    /// This will write the in-memory cache to a temporary file next to the TOML file,
+    /// flush it to disk and move it over the TOML file, so the activity log is never
+    /// left half written. Afterwards the lock on the TOML file is released.
     ///
     /// # Errors
     ///
@@ -99,27 +128,32 @@ impl TomlActivityStorage {
     /// Returns `Ok(())` if the cache is written successfully
     #[tracing::instrument(skip(self))]
     pub fn sync_to_file(&self) -> PaceResult<()> {
+        self.lock_for_changes()?;
+
         let data = toml::to_string(&self.cache.get_activity_log())?;
-        write(&self.path, data)?;
-        Ok(())
+
+        self.file.write_atomically(data.as_bytes())?;
+
+        self.file.finish_changes()
     }
 }
 
 impl ActivityStorage for TomlActivityStorage {
     #[tracing::instrument(skip(self))]
     fn setup_storage(&self) -> PaceResult<()> {
-        if !self.path.exists() {
+        let path = self.file.path();
+
+        if !path.exists() {
             create_dir_all(
-                self.path
-                    .parent()
-                    .ok_or(PaceErrorKind::ParentDirNotFound(self.path.clone()))?,
+                path.parent()
+                    .ok_or(PaceErrorKind::ParentDirNotFound(path.to_path_buf()))?,
             )?;
 
             let mut file = OpenOptions::new()
                 .write(true)
                 .create(true)
                 .truncate(true)
-                .open(&self.path)?;
+                .open(path)?;
 
             file.write_all(b"")?;
         }
@@ -130,11 +164,15 @@ impl ActivityStorage for TomlActivityStorage {
 impl ActivityReadOps for TomlActivityStorage {
     #[tracing::instrument(skip(self))]
     fn read_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        self.reload_if_stale()?;
+
         self.cache.read_activity(activity_id)
     }
 
     #[tracing::instrument(skip(self))]
     fn list_activities(&self, filter: ActivityFilterKind) -> PaceOptResult<FilteredActivities> {
+        self.reload_if_stale()?;
+
         self.cache.list_activities(filter)
     }
 }
@@ -142,11 +180,15 @@ impl ActivityReadOps for TomlActivityStorage {
 impl ActivityStateManagement for TomlActivityStorage {
     #[tracing::instrument(skip(self))]
     fn end_all_activities(&self, end_opts: EndOptions) -> PaceOptResult<Vec<ActivityItem>> {
+        self.lock_for_changes()?;
+
         self.cache.end_all_activities(end_opts)
     }
 
     #[tracing::instrument(skip(self))]
     fn end_last_unfinished_activity(&self, end_opts: EndOptions) -> PaceOptResult<ActivityItem> {
+        self.lock_for_changes()?;
+
         self.cache.end_last_unfinished_activity(end_opts)
     }
 
@@ -156,6 +198,8 @@ impl ActivityStateManagement for TomlActivityStorage {
         activity_id: ActivityGuid,
         end_opts: EndOptions,
     ) -> PaceResult<ActivityItem> {
+        self.lock_for_changes()?;
+
         self.cache.end_activity(activity_id, end_opts)
     }
 
@@ -164,6 +208,8 @@ impl ActivityStateManagement for TomlActivityStorage {
         &self,
         hold_opts: HoldOptions,
     ) -> PaceOptResult<ActivityItem> {
+        self.lock_for_changes()?;
+
         self.cache.hold_most_recent_active_activity(hold_opts)
     }
 
@@ -172,6 +218,8 @@ impl ActivityStateManagement for TomlActivityStorage {
         &self,
         end_opts: EndOptions,
     ) -> PaceOptResult<Vec<ActivityGuid>> {
+        self.lock_for_changes()?;
+
         self.cache.end_all_active_intermissions(end_opts)
     }
 
@@ -181,6 +229,8 @@ impl ActivityStateManagement for TomlActivityStorage {
         activity_id: ActivityGuid,
         resume_opts: ResumeOptions,
     ) -> PaceResult<ActivityItem> {
+        self.lock_for_changes()?;
+
         self.cache.resume_activity(activity_id, resume_opts)
     }
 
@@ -190,6 +240,8 @@ impl ActivityStateManagement for TomlActivityStorage {
         activity_id: ActivityGuid,
         hold_opts: HoldOptions,
     ) -> PaceResult<ActivityItem> {
+        self.lock_for_changes()?;
+
         self.cache.hold_activity(activity_id, hold_opts)
     }
 
@@ -198,6 +250,8 @@ impl ActivityStateManagement for TomlActivityStorage {
         &self,
         resume_opts: ResumeOptions,
     ) -> PaceOptResult<ActivityItem> {
+        self.lock_for_changes()?;
+
         self.cache.resume_most_recent_activity(resume_opts)
     }
 }
@@ -205,9 +259,18 @@ impl ActivityStateManagement for TomlActivityStorage {
 impl ActivityWriteOps for TomlActivityStorage {
     #[tracing::instrument(skip(self))]
     fn create_activity(&self, activity: Activity) -> PaceResult<ActivityItem> {
+        self.lock_for_changes()?;
+
         self.cache.create_activity(activity)
     }
 
+    #[tracing::instrument(skip(self))]
+    fn insert_activity(&self, activity_item: ActivityItem) -> PaceResult<ActivityItem> {
+        self.lock_for_changes()?;
+
+        self.cache.insert_activity(activity_item)
+    }
+
     #[tracing::instrument(skip(self))]
     fn update_activity(
         &self,
@@ -215,6 +278,8 @@ impl ActivityWriteOps for TomlActivityStorage {
         updated_activity: Activity,
         update_opts: UpdateOptions,
     ) -> PaceResult<ActivityItem> {
+        self.lock_for_changes()?;
+
         self.cache
             .update_activity(activity_id, updated_activity, update_opts)
     }
@@ -225,32 +290,98 @@ impl ActivityWriteOps for TomlActivityStorage {
         activity_id: ActivityGuid,
         delete_opts: DeleteOptions,
     ) -> PaceResult<ActivityItem> {
+        self.lock_for_changes()?;
+
         self.cache.delete_activity(activity_id, delete_opts)
     }
 }
 
+impl ActivityArchiving for TomlActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn archive_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        self.lock_for_changes()?;
+
+        self.cache.archive_activity(activity_id)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn unarchive_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        self.lock_for_changes()?;
+
+        self.cache.unarchive_activity(activity_id)
+    }
+}
+
+impl ActivityTagging for TomlActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn add_tag_to_activity(
+        &self,
+        activity_id: ActivityGuid,
+        tag: &str,
+    ) -> PaceResult<ActivityItem> {
+        self.lock_for_changes()?;
+
+        self.cache.add_tag_to_activity(activity_id, tag)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn remove_tag_from_activity(
+        &self,
+        activity_id: ActivityGuid,
+        tag: &str,
+    ) -> PaceResult<ActivityItem> {
+        self.lock_for_changes()?;
+
+        self.cache.remove_tag_from_activity(activity_id, tag)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn rename_tag(&self, old_tag: &str, new_tag: &str) -> PaceResult<usize> {
+        self.lock_for_changes()?;
+
+        self.cache.rename_tag(old_tag, new_tag)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_tags(&self) -> PaceResult<BTreeMap<String, usize>> {
+        self.reload_if_stale()?;
+
+        self.cache.list_tags()
+    }
+}
+
 impl ActivityQuerying for TomlActivityStorage {
     #[tracing::instrument(skip(self))]
     fn list_activities_by_id(&self) -> PaceOptResult<BTreeMap<ActivityGuid, Activity>> {
+        self.reload_if_stale()?;
+
         self.cache.list_activities_by_id()
     }
 
     #[tracing::instrument(skip(self))]
     fn most_recent_active_activity(&self) -> PaceOptResult<ActivityItem> {
+        self.reload_if_stale()?;
+
         self.cache.most_recent_active_activity()
     }
 
     #[tracing::instrument(skip(self))]
     fn group_activities_by_duration_range(
         &self,
+        duration_range_opts: PaceDurationRangeOptions,
     ) -> PaceOptResult<BTreeMap<PaceDurationRange, Vec<ActivityItem>>> {
-        self.cache.group_activities_by_duration_range()
+        self.reload_if_stale()?;
+
+        self.cache
+            .group_activities_by_duration_range(duration_range_opts)
     }
 
     #[tracing::instrument(skip(self))]
     fn group_activities_by_start_date(
         &self,
     ) -> PaceOptResult<BTreeMap<PaceDate, Vec<ActivityItem>>> {
+        self.reload_if_stale()?;
+
         self.cache.group_activities_by_start_date()
     }
 
@@ -258,6 +389,8 @@ impl ActivityQuerying for TomlActivityStorage {
     fn list_activities_with_intermissions(
         &self,
     ) -> PaceOptResult<BTreeMap<ActivityGuid, Vec<ActivityItem>>> {
+        self.reload_if_stale()?;
+
         self.cache.list_activities_with_intermissions()
     }
 
@@ -266,11 +399,15 @@ impl ActivityQuerying for TomlActivityStorage {
         &self,
         keyword_opts: KeywordOptions,
     ) -> PaceOptResult<BTreeMap<String, Vec<ActivityItem>>> {
+        self.reload_if_stale()?;
+
         self.cache.group_activities_by_keywords(keyword_opts)
     }
 
     #[tracing::instrument(skip(self))]
     fn group_activities_by_kind(&self) -> PaceOptResult<BTreeMap<ActivityKind, Vec<ActivityItem>>> {
+        self.reload_if_stale()?;
+
         self.cache.group_activities_by_kind()
     }
 
@@ -279,13 +416,28 @@ impl ActivityQuerying for TomlActivityStorage {
         &self,
         time_range_opts: TimeRangeOptions,
     ) -> PaceOptResult<Vec<ActivityGuid>> {
+        self.reload_if_stale()?;
+
         self.cache.list_activities_by_time_range(time_range_opts)
     }
 
+    #[tracing::instrument(skip(self))]
+    fn list_activity_items_by_time_range(
+        &self,
+        time_range_opts: TimeRangeOptions,
+    ) -> PaceOptResult<Vec<ActivityItem>> {
+        self.reload_if_stale()?;
+
+        self.cache
+            .list_activity_items_by_time_range(time_range_opts)
+    }
+
     #[tracing::instrument(skip(self))]
     fn group_activities_by_status(
         &self,
     ) -> PaceOptResult<BTreeMap<ActivityStatusKind, Vec<ActivityItem>>> {
+        self.reload_if_stale()?;
+
         self.cache.group_activities_by_status()
     }
 }
@@ -0,0 +1,844 @@
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, read_to_string, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use pace_time::{
+    date::PaceDate,
+    duration::{PaceDurationRange, PaceDurationRangeOptions},
+    time_range::TimeRangeOptions,
+};
+use parking_lot::Mutex;
+use tracing::debug;
+
+use crate::{
+    commands::{
+        hold::HoldOptions, resume::ResumeOptions, DeleteOptions, EndOptions, KeywordOptions,
+        UpdateOptions,
+    },
+    domain::{
+        activity::{Activity, ActivityGuid, ActivityItem, ActivityKind},
+        activity_log::ActivityLog,
+        filter::{ActivityFilterKind, FilteredActivities},
+        history::HistoryEntry,
+        journal::JournalOperationKind,
+        status::ActivityStatusKind,
+    },
+    error::{PaceErrorKind, PaceOptResult, PaceResult},
+    storage::{
+        file::TomlActivityStorage, ActivityArchiving, ActivityQuerying, ActivityReadOps,
+        ActivityStateManagement, ActivityStorage, ActivityTagging, ActivityWriteOps,
+        ChangedActivities, SyncStorage,
+    },
+};
+
+/// The outcome of pulling the activity log from a remote repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitPull {
+    /// The remote repository doesn't contain an activity log yet
+    NothingToPull,
+
+    /// The remote activity log has no changes missing in the local one
+    UpToDate,
+
+    /// Only the remote activity log has changed, so it replaced the local one
+    FastForwarded,
+
+    /// Both activity logs have changed, so they need to be merged
+    ///
+    /// The merge is committed with the next sync of the storage.
+    Diverged {
+        /// The activities of the remote activity log
+        activities: BTreeMap<ActivityGuid, Activity>,
+    },
+}
+
+/// Git-backed TOML activity storage
+///
+/// The activity log is stored in a [`TomlActivityStorage`] and committed to a git
+/// repository of its own, in a `.pace.git` directory next to it. A repository
+/// the activity log happens to be part of, e.g. of your dotfiles, isn't touched.
+/// Every sync commits the activity log, with a message describing the changes,
+/// e.g. `begin: <description>`.
+///
+/// The activity log can be pulled from and pushed to a remote repository. As
+/// activities are keyed by their ULID, diverged activity logs are merged activity
+/// by activity instead of line by line, see [`ActivityLogMerge`].
+///
+/// [`ActivityLogMerge`]: crate::domain::sync::ActivityLogMerge
+pub struct GitActivityStorage {
+    /// The TOML activity storage in the working tree
+    toml: TomlActivityStorage,
+
+    /// The git repository the activity log is committed to
+    repository: GitRepository,
+
+    /// The path of the activity log, relative to the root of the repository
+    relative_path: String,
+
+    /// The messages of the changes that haven't been committed yet
+    messages: Mutex<Vec<String>>,
+
+    /// The message of the merge that is committed with the next sync
+    merging: Mutex<Option<String>>,
+}
+
+impl GitActivityStorage {
+    /// Create a new `GitActivityStorage`
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the TOML file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TOML file cannot be read or the git repository cannot be opened or created
+    ///
+    /// # Returns
+    ///
+    /// Returns a new `GitActivityStorage`
+    pub fn new(path: impl AsRef<Path>) -> PaceResult<Self> {
+        let path = path.as_ref();
+
+        let toml = TomlActivityStorage::new(path)?;
+
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.canonicalize()?,
+            _ => std::env::current_dir()?,
+        };
+
+        let repository = GitRepository::open_or_init(&directory)?;
+
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| PaceErrorKind::ActivityLogNotFound(path.display().to_string()))?;
+
+        let relative_path = file_name.to_string_lossy().into_owned();
+
+        // The lock file of the activity log and the repository itself don't belong into the repository
+        repository.exclude(&format!("/{relative_path}.lock"))?;
+        repository.exclude(&format!("/{GIT_DIR_NAME}/"))?;
+
+        debug!(
+            "Storing the activity log {relative_path} in the git repository {}",
+            repository.git_dir.display()
+        );
+
+        Ok(Self {
+            toml,
+            repository,
+            relative_path,
+            messages: Mutex::new(vec![]),
+            merging: Mutex::new(None),
+        })
+    }
+
+    /// Record a message describing the changes an operation made to the activities
+    fn record<T>(
+        &self,
+        operation: JournalOperationKind,
+        changed: &ChangedActivities,
+        change: impl FnOnce(&TomlActivityStorage) -> PaceResult<T>,
+    ) -> PaceResult<T> {
+        let before = changed.before(&self.toml)?;
+
+        let result = change(&self.toml)?;
+
+        let after = changed.after(&self.toml, &before)?;
+
+        if let Some(entry) = HistoryEntry::from_changes(operation, &before, &after) {
+            let mut descriptions = entry.descriptions();
+            descriptions.dedup();
+
+            self.messages
+                .lock()
+                .push(format!("{operation}: {}", descriptions.join(", ")));
+        }
+
+        Ok(result)
+    }
+
+    /// Commit the activity log, if it has changed or a merge is in progress
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activity log cannot be committed
+    fn commit(&self) -> PaceResult<()> {
+        let _ = self.repository.git(&["add", "--", &self.relative_path])?;
+
+        let merging = self.merging.lock().take();
+
+        if merging.is_none()
+            && self.repository.succeeds(&[
+                "diff",
+                "--cached",
+                "--quiet",
+                "--",
+                &self.relative_path,
+            ])?
+        {
+            debug!("The activity log hasn't changed, nothing to commit");
+
+            return Ok(());
+        }
+
+        let messages = std::mem::take(&mut *self.messages.lock());
+
+        let subject = merging
+            .clone()
+            .or_else(|| messages.last().cloned())
+            .unwrap_or_else(|| "update activity log".to_string());
+
+        let mut message = subject.clone();
+
+        if messages.len() > 1 || (merging.is_some() && !messages.is_empty()) {
+            message.push_str("\n\n");
+
+            for line in &messages {
+                message.push_str("- ");
+                message.push_str(line);
+                message.push('\n');
+            }
+        }
+
+        // Git only commits a merge as a whole, otherwise only the activity log is committed
+        let _ = self.repository.commit(
+            &message,
+            merging.is_none().then_some(self.relative_path.as_str()),
+        )?;
+
+        debug!("Committed the activity log: {subject}");
+
+        Ok(())
+    }
+
+    /// The path to the activity log in the working tree
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.toml.path()
+    }
+
+    /// Pull the activity log from a remote repository
+    ///
+    /// If the activity logs have diverged, a merge is started that keeps the local
+    /// activity log as it is. The caller applies the merged activities and commits
+    /// the merge by syncing the storage, or aborts it with [`GitActivityStorage::abort_merge`].
+    ///
+    /// # Arguments
+    ///
+    /// * `remote` - The URL or path of the remote repository
+    /// * `branch` - The branch of the remote repository
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a git command fails or the remote activity log cannot be read
+    ///
+    /// # Returns
+    ///
+    /// How the local activity log relates to the remote one
+    #[tracing::instrument(skip(self))]
+    pub fn pull(&self, remote: &str, branch: &str) -> PaceResult<GitPull> {
+        // Pulling needs a commit to merge into
+        self.toml.sync()?;
+        self.commit()?;
+
+        if !self.repository.fetch(remote, branch)? {
+            return Ok(GitPull::NothingToPull);
+        }
+
+        if self
+            .repository
+            .succeeds(&["merge-base", "--is-ancestor", "FETCH_HEAD", "HEAD"])?
+        {
+            return Ok(GitPull::UpToDate);
+        }
+
+        if self
+            .repository
+            .succeeds(&["merge-base", "--is-ancestor", "HEAD", "FETCH_HEAD"])?
+        {
+            let _ = self.repository.git(&["merge", "--ff-only", "FETCH_HEAD"])?;
+
+            return Ok(GitPull::FastForwarded);
+        }
+
+        let remote_log = self
+            .repository
+            .git(&["show", &format!("FETCH_HEAD:{}", self.relative_path)])?;
+
+        let activities = toml::from_str::<ActivityLog>(&remote_log)?
+            .activities()
+            .clone();
+
+        // Record the merge without touching the working tree, the activities are merged by pace
+        let _ = self.repository.git(&[
+            "merge",
+            "--strategy=ours",
+            "--no-commit",
+            "--no-ff",
+            "--allow-unrelated-histories",
+            "FETCH_HEAD",
+        ])?;
+
+        *self.merging.lock() = Some(format!("merge: {branch} of {remote}"));
+
+        Ok(GitPull::Diverged { activities })
+    }
+
+    /// Abort the merge started by [`GitActivityStorage::pull`], if merging the activities failed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the merge cannot be aborted
+    pub fn abort_merge(&self) -> PaceResult<()> {
+        if self.merging.lock().take().is_some() {
+            debug!("Aborting the merge");
+
+            let _ = self.repository.git(&["merge", "--abort"])?;
+        }
+
+        Ok(())
+    }
+
+    /// Push the activity log to a remote repository
+    ///
+    /// # Arguments
+    ///
+    /// * `remote` - The URL or path of the remote repository
+    /// * `branch` - The branch of the remote repository
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activity log cannot be committed or pushed,
+    /// e.g. because the remote activity log has changes that need to be pulled first
+    #[tracing::instrument(skip(self))]
+    pub fn push(&self, remote: &str, branch: &str) -> PaceResult<()> {
+        self.toml.sync()?;
+        self.commit()?;
+
+        if self.repository.fetch(remote, branch)?
+            && !self
+                .repository
+                .succeeds(&["merge-base", "--is-ancestor", "FETCH_HEAD", "HEAD"])?
+        {
+            return Err(PaceErrorKind::GitPullRequired(format!("{branch} of {remote}")).into());
+        }
+
+        let _ =
+            self.repository
+                .git(&["push", "--", remote, &format!("HEAD:refs/heads/{branch}")])?;
+
+        Ok(())
+    }
+}
+
+impl SyncStorage for GitActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn sync(&self) -> PaceResult<()> {
+        self.toml.sync()?;
+
+        self.commit()
+    }
+}
+
+/// The name of the directory of the repository, next to the activity log
+const GIT_DIR_NAME: &str = ".pace.git";
+
+/// A git repository, used by running the `git` command line tool
+///
+/// The repository is a bare one with the directory of the activity log as its
+/// working tree, so git doesn't find it on its own and never adopts a repository
+/// the directory is part of.
+#[derive(Debug)]
+struct GitRepository {
+    /// The directory of the repository
+    git_dir: PathBuf,
+
+    /// The root of the working tree
+    work_dir: PathBuf,
+
+    /// If the user has configured an identity for git, which commits and merges need
+    has_identity: bool,
+}
+
+impl GitRepository {
+    /// Open the git repository of the activity logs in the given directory, or create it
+    fn open_or_init(directory: &Path) -> PaceResult<Self> {
+        let mut repository = Self {
+            git_dir: directory.join(GIT_DIR_NAME),
+            work_dir: directory.to_path_buf(),
+            has_identity: true,
+        };
+
+        if !repository.git_dir.exists() {
+            debug!(
+                "Creating a git repository in {}",
+                repository.git_dir.display()
+            );
+
+            let mut command = Command::new("git");
+            let _ = command
+                .args(["init", "--quiet", "--bare"])
+                .arg(&repository.git_dir);
+
+            let _ = run(command, &["init"])?;
+        }
+
+        repository.has_identity = repository.succeeds(&["config", "user.email"])?;
+
+        Ok(repository)
+    }
+
+    /// A git command in the repository
+    ///
+    /// Falls back to a pace identity, if the user hasn't configured one for git.
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new("git");
+
+        let _ = command
+            .arg("-C")
+            .arg(&self.work_dir)
+            .arg("--git-dir")
+            .arg(&self.git_dir)
+            .arg("--work-tree")
+            .arg(&self.work_dir);
+
+        if !self.has_identity {
+            let _ = command.args(["-c", "user.name=pace", "-c", "user.email=pace@localhost"]);
+        }
+
+        let _ = command.args(args);
+
+        command
+    }
+
+    /// Run a git command in the repository
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if git cannot be run or the command fails
+    ///
+    /// # Returns
+    ///
+    /// The output of the command
+    fn git(&self, args: &[&str]) -> PaceResult<String> {
+        run(self.command(args), args)
+    }
+
+    /// Run a git command that answers a question with its exit status
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if git cannot be run
+    fn succeeds(&self, args: &[&str]) -> PaceResult<bool> {
+        let output = self
+            .command(args)
+            .output()
+            .map_err(|err| PaceErrorKind::GitCommandFailed(args.join(" "), err.to_string()))?;
+
+        Ok(output.status.success())
+    }
+
+    /// Exclude files matching the pattern from the repository, without changing its `.gitignore`
+    fn exclude(&self, pattern: &str) -> PaceResult<()> {
+        let path = self.work_dir.join(
+            self.git(&["rev-parse", "--git-path", "info/exclude"])?
+                .trim(),
+        );
+
+        let excluded = read_to_string(&path).unwrap_or_default();
+
+        if excluded.lines().any(|line| line == pattern) {
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new().append(true).create(true).open(&path)?;
+
+        if !excluded.is_empty() && !excluded.ends_with('\n') {
+            writeln!(file)?;
+        }
+
+        writeln!(file, "{pattern}")?;
+
+        Ok(())
+    }
+
+    /// Fetch a branch of a remote repository into `FETCH_HEAD`
+    ///
+    /// # Returns
+    ///
+    /// `false` if the remote repository doesn't have the branch yet
+    fn fetch(&self, remote: &str, branch: &str) -> PaceResult<bool> {
+        let reference = format!("refs/heads/{branch}");
+
+        // The remote and branch come after `--`, so they are never taken for options
+        if !self
+            .git(&["ls-remote", "--heads", "--", remote, &reference])?
+            .lines()
+            .any(|line| line.split('\t').nth(1) == Some(reference.as_str()))
+        {
+            return Ok(false);
+        }
+
+        let _ = self.git(&["fetch", "--quiet", "--", remote, &reference])?;
+
+        Ok(true)
+    }
+
+    /// Commit the changes of a path, or all staged changes without one
+    ///
+    /// Other changes the user has staged are left as they are when committing a path.
+    fn commit(&self, message: &str, path: Option<&str>) -> PaceResult<String> {
+        let mut args = vec!["commit", "--quiet", "--allow-empty", "-m", message];
+
+        if let Some(path) = path {
+            args.extend(["--", path]);
+        }
+
+        self.git(&args)
+    }
+}
+
+/// Run a git command
+///
+/// # Arguments
+///
+/// * `command` - The git command to run
+/// * `args` - The arguments of the command, for logging and errors
+///
+/// # Errors
+///
+/// Returns an error if git cannot be run or the command fails
+///
+/// # Returns
+///
+/// The output of the command
+fn run(mut command: Command, args: &[&str]) -> PaceResult<String> {
+    debug!("Running git {}", args.join(" "));
+
+    let output = command
+        .output()
+        .map_err(|err| PaceErrorKind::GitCommandFailed(args.join(" "), err.to_string()))?;
+
+    if !output.status.success() {
+        return Err(PaceErrorKind::GitCommandFailed(
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+impl ActivityStorage for GitActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn setup_storage(&self) -> PaceResult<()> {
+        self.toml.setup_storage()
+    }
+}
+
+impl ActivityReadOps for GitActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn read_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        self.toml.read_activity(activity_id)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_activities(&self, filter: ActivityFilterKind) -> PaceOptResult<FilteredActivities> {
+        self.toml.list_activities(filter)
+    }
+}
+
+impl ActivityStateManagement for GitActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn begin_activity(&self, activity: Activity) -> PaceResult<ActivityItem> {
+        self.record(
+            JournalOperationKind::Begin,
+            &ChangedActivities::unfinished(),
+            |toml| toml.begin_activity(activity),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn end_all_activities(&self, end_opts: EndOptions) -> PaceOptResult<Vec<ActivityItem>> {
+        self.record(
+            JournalOperationKind::End,
+            &ChangedActivities::unfinished(),
+            |toml| toml.end_all_activities(end_opts),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn end_last_unfinished_activity(&self, end_opts: EndOptions) -> PaceOptResult<ActivityItem> {
+        self.record(
+            JournalOperationKind::End,
+            &ChangedActivities::unfinished(),
+            |toml| toml.end_last_unfinished_activity(end_opts),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn end_activity(
+        &self,
+        activity_id: ActivityGuid,
+        end_opts: EndOptions,
+    ) -> PaceResult<ActivityItem> {
+        self.record(
+            JournalOperationKind::End,
+            &ChangedActivities::Ids(vec![activity_id]),
+            |toml| toml.end_activity(activity_id, end_opts),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn hold_most_recent_active_activity(
+        &self,
+        hold_opts: HoldOptions,
+    ) -> PaceOptResult<ActivityItem> {
+        self.record(
+            JournalOperationKind::Hold,
+            &ChangedActivities::unfinished(),
+            |toml| toml.hold_most_recent_active_activity(hold_opts),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn end_all_active_intermissions(
+        &self,
+        end_opts: EndOptions,
+    ) -> PaceOptResult<Vec<ActivityGuid>> {
+        self.record(
+            JournalOperationKind::End,
+            &ChangedActivities::unfinished(),
+            |toml| toml.end_all_active_intermissions(end_opts),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn resume_activity(
+        &self,
+        activity_id: ActivityGuid,
+        resume_opts: ResumeOptions,
+    ) -> PaceResult<ActivityItem> {
+        self.record(
+            JournalOperationKind::Resume,
+            &ChangedActivities::unfinished(),
+            |toml| toml.resume_activity(activity_id, resume_opts),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn hold_activity(
+        &self,
+        activity_id: ActivityGuid,
+        hold_opts: HoldOptions,
+    ) -> PaceResult<ActivityItem> {
+        self.record(
+            JournalOperationKind::Hold,
+            &ChangedActivities::unfinished(),
+            |toml| toml.hold_activity(activity_id, hold_opts),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn resume_most_recent_activity(
+        &self,
+        resume_opts: ResumeOptions,
+    ) -> PaceOptResult<ActivityItem> {
+        self.record(
+            JournalOperationKind::Resume,
+            &ChangedActivities::unfinished(),
+            |toml| toml.resume_most_recent_activity(resume_opts),
+        )
+    }
+}
+
+impl ActivityWriteOps for GitActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn create_activity(&self, activity: Activity) -> PaceResult<ActivityItem> {
+        let activity_item = ActivityItem::from(activity);
+
+        let changed = ChangedActivities::Ids(vec![*activity_item.guid()]);
+
+        self.record(JournalOperationKind::Create, &changed, |toml| {
+            toml.insert_activity(activity_item)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn insert_activity(&self, activity_item: ActivityItem) -> PaceResult<ActivityItem> {
+        let changed = ChangedActivities::Ids(vec![*activity_item.guid()]);
+
+        self.record(JournalOperationKind::Create, &changed, |toml| {
+            toml.insert_activity(activity_item)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn update_activity(
+        &self,
+        activity_id: ActivityGuid,
+        updated_activity: Activity,
+        update_opts: UpdateOptions,
+    ) -> PaceResult<ActivityItem> {
+        self.record(
+            JournalOperationKind::Update,
+            &ChangedActivities::Ids(vec![activity_id]),
+            |toml| toml.update_activity(activity_id, updated_activity, update_opts),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn delete_activity(
+        &self,
+        activity_id: ActivityGuid,
+        delete_opts: DeleteOptions,
+    ) -> PaceResult<ActivityItem> {
+        self.record(
+            JournalOperationKind::Delete,
+            &ChangedActivities::Ids(vec![activity_id]),
+            |toml| toml.delete_activity(activity_id, delete_opts),
+        )
+    }
+}
+
+impl ActivityArchiving for GitActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn archive_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        self.record(
+            JournalOperationKind::Archive,
+            &ChangedActivities::Ids(vec![activity_id]),
+            |toml| toml.archive_activity(activity_id),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn unarchive_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        self.record(
+            JournalOperationKind::Unarchive,
+            &ChangedActivities::Ids(vec![activity_id]),
+            |toml| toml.unarchive_activity(activity_id),
+        )
+    }
+}
+
+impl ActivityTagging for GitActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn add_tag_to_activity(
+        &self,
+        activity_id: ActivityGuid,
+        tag: &str,
+    ) -> PaceResult<ActivityItem> {
+        self.record(
+            JournalOperationKind::Tag,
+            &ChangedActivities::Ids(vec![activity_id]),
+            |toml| toml.add_tag_to_activity(activity_id, tag),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn remove_tag_from_activity(
+        &self,
+        activity_id: ActivityGuid,
+        tag: &str,
+    ) -> PaceResult<ActivityItem> {
+        self.record(
+            JournalOperationKind::Tag,
+            &ChangedActivities::Ids(vec![activity_id]),
+            |toml| toml.remove_tag_from_activity(activity_id, tag),
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn rename_tag(&self, old_tag: &str, new_tag: &str) -> PaceResult<usize> {
+        let changed = ChangedActivities::tagged(&self.toml, old_tag)?;
+
+        self.record(JournalOperationKind::Tag, &changed, |toml| {
+            toml.rename_tag(old_tag, new_tag)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_tags(&self) -> PaceResult<BTreeMap<String, usize>> {
+        self.toml.list_tags()
+    }
+}
+
+impl ActivityQuerying for GitActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn list_activities_by_id(&self) -> PaceOptResult<BTreeMap<ActivityGuid, Activity>> {
+        self.toml.list_activities_by_id()
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn most_recent_active_activity(&self) -> PaceOptResult<ActivityItem> {
+        self.toml.most_recent_active_activity()
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_duration_range(
+        &self,
+        duration_range_opts: PaceDurationRangeOptions,
+    ) -> PaceOptResult<BTreeMap<PaceDurationRange, Vec<ActivityItem>>> {
+        self.toml
+            .group_activities_by_duration_range(duration_range_opts)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_start_date(
+        &self,
+    ) -> PaceOptResult<BTreeMap<PaceDate, Vec<ActivityItem>>> {
+        self.toml.group_activities_by_start_date()
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_activities_with_intermissions(
+        &self,
+    ) -> PaceOptResult<BTreeMap<ActivityGuid, Vec<ActivityItem>>> {
+        self.toml.list_activities_with_intermissions()
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_keywords(
+        &self,
+        keyword_opts: KeywordOptions,
+    ) -> PaceOptResult<BTreeMap<String, Vec<ActivityItem>>> {
+        self.toml.group_activities_by_keywords(keyword_opts)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_kind(&self) -> PaceOptResult<BTreeMap<ActivityKind, Vec<ActivityItem>>> {
+        self.toml.group_activities_by_kind()
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_activities_by_time_range(
+        &self,
+        time_range_opts: TimeRangeOptions,
+    ) -> PaceOptResult<Vec<ActivityGuid>> {
+        self.toml.list_activities_by_time_range(time_range_opts)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_activity_items_by_time_range(
+        &self,
+        time_range_opts: TimeRangeOptions,
+    ) -> PaceOptResult<Vec<ActivityItem>> {
+        self.toml.list_activity_items_by_time_range(time_range_opts)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_status(
+        &self,
+    ) -> PaceOptResult<BTreeMap<ActivityStatusKind, Vec<ActivityItem>>> {
+        self.toml.group_activities_by_status()
+    }
+}
@@ -2,7 +2,7 @@ use std::{collections::BTreeMap, sync::Arc};
 
 use pace_time::{
     date::PaceDate,
-    duration::{calculate_duration, PaceDurationRange},
+    duration::{calculate_duration, PaceDurationRange, PaceDurationRangeOptions},
     time_range::TimeRangeOptions,
 };
 use parking_lot::RwLock;
@@ -27,8 +27,8 @@ use crate::{
     },
     error::{ActivityLogErrorKind, PaceOptResult, PaceResult},
     storage::{
-        ActivityQuerying, ActivityReadOps, ActivityStateManagement, ActivityStorage,
-        ActivityWriteOps, SyncStorage,
+        ActivityArchiving, ActivityQuerying, ActivityReadOps, ActivityStateManagement,
+        ActivityStorage, ActivityTagging, ActivityWriteOps, SyncStorage,
     },
 };
 
@@ -80,6 +80,15 @@ impl InMemoryActivityStorage {
 
         activity_log.clone()
     }
+
+    /// Replace the `ActivityLog`, e.g. after it has been reloaded from disk
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_log` - The `ActivityLog` to use from now on
+    pub fn set_activity_log(&self, activity_log: ActivityLog) {
+        *self.log.write() = activity_log;
+    }
 }
 
 impl Default for InMemoryActivityStorage {
@@ -134,10 +143,12 @@ impl ActivityReadOps for InMemoryActivityStorage {
                 ActivityFilterKind::Ended => activity.is_completed(),
                 ActivityFilterKind::Archived => activity.is_archived(),
                 ActivityFilterKind::Held => activity.is_paused(),
-                ActivityFilterKind::Intermission => activity.kind().is_intermission(),
+                ActivityFilterKind::Intermission => {
+                    activity.kind().is_intermission() || activity.kind().is_pomodoro_intermission()
+                }
                 ActivityFilterKind::TimeRange(time_range_opts) => {
-                    // TODO: When adding Pomodoro support, we should also check for Pomodoro activities
-                    time_range_opts.is_in_range(*activity.begin()) && activity.kind().is_activity()
+                    time_range_opts.is_in_range(*activity.begin())
+                        && (activity.kind().is_activity() || activity.kind().is_pomodoro_work())
                 }
             })
             .map(|(activity_id, _)| activity_id)
@@ -175,9 +186,12 @@ impl ActivityReadOps for InMemoryActivityStorage {
 impl ActivityWriteOps for InMemoryActivityStorage {
     #[tracing::instrument(skip(self))]
     fn create_activity(&self, activity: Activity) -> PaceResult<ActivityItem> {
-        let activities = self.log.read();
+        self.insert_activity(ActivityItem::from(activity))
+    }
 
-        let activity_item = ActivityItem::from(activity);
+    #[tracing::instrument(skip(self))]
+    fn insert_activity(&self, activity_item: ActivityItem) -> PaceResult<ActivityItem> {
+        let activities = self.log.read();
 
         // Search for the activity in the list of activities to see if the ID is already in use.
         // We use a ULID as the ID for the activity, so it should be unique and not collide with
@@ -254,6 +268,137 @@ impl ActivityWriteOps for InMemoryActivityStorage {
     }
 }
 
+impl ActivityArchiving for InMemoryActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn archive_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        let mut activities = self.log.write();
+
+        let activity = activities
+            .get_mut(&activity_id)
+            .ok_or(ActivityLogErrorKind::ActivityNotFound(activity_id))?;
+
+        if activity.is_archived() {
+            debug!("Activity is already archived.");
+            return Err(ActivityLogErrorKind::ActivityAlreadyArchived(activity_id).into());
+        } else if !activity.is_completed() {
+            debug!("Activity has not ended yet.");
+            return Err(ActivityLogErrorKind::ActivityNotArchivable(activity_id).into());
+        }
+
+        activity.archive();
+
+        let archived_activity = activity.clone();
+
+        drop(activities);
+
+        Ok((activity_id, archived_activity).into())
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn unarchive_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        let mut activities = self.log.write();
+
+        let activity = activities
+            .get_mut(&activity_id)
+            .ok_or(ActivityLogErrorKind::ActivityNotFound(activity_id))?;
+
+        if !activity.is_archived() {
+            debug!("Activity is not archived.");
+            return Err(ActivityLogErrorKind::ActivityNotArchived(activity_id).into());
+        }
+
+        activity.unarchive();
+
+        let unarchived_activity = activity.clone();
+
+        drop(activities);
+
+        Ok((activity_id, unarchived_activity).into())
+    }
+}
+
+impl ActivityTagging for InMemoryActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn add_tag_to_activity(
+        &self,
+        activity_id: ActivityGuid,
+        tag: &str,
+    ) -> PaceResult<ActivityItem> {
+        let mut activities = self.log.write();
+
+        let activity = activities
+            .get_mut(&activity_id)
+            .ok_or(ActivityLogErrorKind::ActivityNotFound(activity_id))?;
+
+        if !activity.add_tag(tag) {
+            debug!("Activity already has the tag.");
+        }
+
+        let tagged_activity = activity.clone();
+
+        drop(activities);
+
+        Ok((activity_id, tagged_activity).into())
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn remove_tag_from_activity(
+        &self,
+        activity_id: ActivityGuid,
+        tag: &str,
+    ) -> PaceResult<ActivityItem> {
+        let mut activities = self.log.write();
+
+        let activity = activities
+            .get_mut(&activity_id)
+            .ok_or(ActivityLogErrorKind::ActivityNotFound(activity_id))?;
+
+        if !activity.remove_tag(tag) {
+            debug!("Activity doesn't have the tag.");
+            return Err(ActivityLogErrorKind::TagNotFound(activity_id, tag.to_string()).into());
+        }
+
+        let untagged_activity = activity.clone();
+
+        drop(activities);
+
+        Ok((activity_id, untagged_activity).into())
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn rename_tag(&self, old_tag: &str, new_tag: &str) -> PaceResult<usize> {
+        let mut activities = self.log.write();
+
+        let count = activities
+            .values_mut()
+            .filter_map(|activity| activity.rename_tag(old_tag, new_tag).then_some(()))
+            .count();
+
+        drop(activities);
+
+        Ok(count)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_tags(&self) -> PaceResult<BTreeMap<String, usize>> {
+        let activities = self.log.read();
+
+        let mut tags: BTreeMap<String, usize> = BTreeMap::new();
+
+        for tag in activities
+            .values()
+            .filter_map(|activity| activity.tags().as_ref())
+            .flatten()
+        {
+            *tags.entry(tag.clone()).or_default() += 1;
+        }
+
+        drop(activities);
+
+        Ok(tags)
+    }
+}
+
 impl ActivityStateManagement for InMemoryActivityStorage {
     #[tracing::instrument(skip(self))]
     fn end_activity(
@@ -587,8 +732,37 @@ impl ActivityQuerying for InMemoryActivityStorage {
     #[tracing::instrument(skip(self))]
     fn group_activities_by_duration_range(
         &self,
+        duration_range_opts: PaceDurationRangeOptions,
     ) -> PaceOptResult<BTreeMap<PaceDurationRange, Vec<ActivityItem>>> {
-        todo!("Implement grouping activities by duration range")
+        let activities = self.log.read();
+
+        let activities_by_duration_range = activities
+            .activities()
+            .iter()
+            .filter(|(_, activity)| {
+                activity.kind().is_activity() || activity.kind().is_pomodoro_work()
+            })
+            .fold(
+                BTreeMap::new(),
+                |mut acc: BTreeMap<PaceDurationRange, Vec<ActivityItem>>,
+                 (activity_id, activity)| {
+                    acc.entry(duration_range_opts.range_of(activity.duration_or_elapsed()))
+                        .or_default()
+                        .push(ActivityItem::from((*activity_id, activity.clone())));
+
+                    acc
+                },
+            );
+
+        drop(activities);
+
+        if activities_by_duration_range.is_empty() {
+            debug!("No activities found.");
+
+            return Ok(None);
+        }
+
+        Ok(Some(activities_by_duration_range))
     }
 
     #[tracing::instrument(skip(self))]
@@ -789,6 +963,34 @@ impl ActivityQuerying for InMemoryActivityStorage {
 
         Ok(Some(filtered_activities))
     }
+
+    #[tracing::instrument(skip(self))]
+    fn list_activity_items_by_time_range(
+        &self,
+        time_range_opts: TimeRangeOptions,
+    ) -> PaceOptResult<Vec<ActivityItem>> {
+        let activity_log = self.log.read();
+
+        let activity_items = activity_log
+            .iter()
+            .filter(|(_, activity)| time_range_opts.is_in_range(*activity.begin()))
+            .map(|(activity_id, activity)| ActivityItem::new(*activity_id, activity.clone()))
+            .collect::<Vec<_>>();
+
+        drop(activity_log);
+
+        if activity_items.is_empty() {
+            debug!(
+                "No activities found in time range between {} and {}.",
+                time_range_opts.start(),
+                time_range_opts.end()
+            );
+
+            return Ok(None);
+        }
+
+        Ok(Some(activity_items))
+    }
 }
 
 #[cfg(test)]
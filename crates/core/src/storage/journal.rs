@@ -0,0 +1,562 @@
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, read_to_string, OpenOptions},
+    path::Path,
+};
+
+use pace_time::{
+    date::PaceDate,
+    duration::{PaceDurationRange, PaceDurationRangeOptions},
+    time_range::TimeRangeOptions,
+};
+use parking_lot::Mutex;
+use tracing::{debug, warn};
+
+use crate::{
+    commands::{
+        hold::HoldOptions, resume::ResumeOptions, DeleteOptions, EndOptions, KeywordOptions,
+        UpdateOptions,
+    },
+    domain::{
+        activity::{Activity, ActivityGuid, ActivityItem, ActivityKind},
+        activity_log::ActivityLog,
+        filter::{ActivityFilterKind, FilteredActivities},
+        journal::{JournalEntry, JournalOperationKind},
+        status::ActivityStatusKind,
+    },
+    error::{ActivityLogErrorKind, PaceErrorKind, PaceOptResult, PaceResult},
+    storage::{
+        in_memory::InMemoryActivityStorage, lock::ActivityLogFile, ActivityArchiving,
+        ActivityQuerying, ActivityReadOps, ActivityStateManagement, ActivityStorage,
+        ActivityTagging, ActivityWriteOps, SyncStorage,
+    },
+};
+
+/// The default number of journal entries after which the journal is compacted
+pub const DEFAULT_JOURNAL_COMPACTION_THRESHOLD: usize = 500;
+
+/// In-memory backed journal activity storage
+///
+/// The activity log is stored as an append-only journal with one JSON encoded
+/// [`JournalEntry`] per line. On load the entries are replayed into an in-memory
+/// cache, and syncing only appends the entries of the changes made since, instead
+/// of rewriting the whole activity log. Once the journal has grown beyond the
+/// compaction threshold, it is replaced by a single snapshot entry.
+///
+/// The journal file is locked and reloaded the same way as for the [`TomlActivityStorage`].
+///
+/// [`TomlActivityStorage`]: crate::storage::file::TomlActivityStorage
+pub struct JournalActivityStorage {
+    /// The in-memory cache
+    cache: InMemoryActivityStorage,
+
+    /// The journal file
+    file: ActivityLogFile,
+
+    /// The number of entries after which the journal is compacted
+    compaction_threshold: usize,
+
+    /// The state of the journal file
+    journal: Mutex<JournalState>,
+}
+
+/// The state of the journal file, compared to the in-memory cache
+#[derive(Debug, Default)]
+struct JournalState {
+    /// The number of entries in the journal file
+    entries: usize,
+
+    /// The entries of the changes that haven't been synced yet
+    pending: Vec<JournalEntry>,
+
+    /// Whether the journal file needs to be rewritten, e.g. because its last entry is incomplete
+    needs_compaction: bool,
+}
+
+impl SyncStorage for JournalActivityStorage {
+    fn sync(&self) -> PaceResult<()> {
+        self.sync_to_file()
+    }
+}
+
+impl JournalActivityStorage {
+    /// Create a new `JournalActivityStorage`
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the journal file
+    /// * `compaction_threshold` - The number of entries after which the journal is compacted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal file cannot be read or contains invalid entries
+    ///
+    /// # Returns
+    ///
+    /// Returns a new `JournalActivityStorage`
+    pub fn new(path: impl AsRef<Path>, compaction_threshold: usize) -> PaceResult<Self> {
+        let storage = Self {
+            cache: InMemoryActivityStorage::new(),
+            file: ActivityLogFile::new(path),
+            compaction_threshold: compaction_threshold.max(1),
+            journal: Mutex::new(JournalState::default()),
+        };
+
+        storage.file.load(|path| storage.load(path))?;
+
+        Ok(storage)
+    }
+
+    /// The path to the journal file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Replay the journal file into the in-memory cache
+    ///
+    /// An incomplete last entry, e.g. from a crash while appending to the journal,
+    /// is skipped and the journal is rewritten on the next sync.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or an entry other than the last one is invalid
+    #[tracing::instrument(skip(self))]
+    fn load(&self, path: &Path) -> PaceResult<()> {
+        let data = read_to_string(path)?;
+
+        let lines = data
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .collect::<Vec<_>>();
+
+        let mut activity_log = ActivityLog::default();
+        let mut journal = JournalState::default();
+
+        for (index, (line_number, line)) in lines.iter().enumerate() {
+            match serde_json::from_str::<JournalEntry>(line) {
+                Ok(entry) => {
+                    entry.apply(&mut activity_log);
+                    journal.entries += 1;
+                }
+                Err(err) if index + 1 == lines.len() => {
+                    warn!("Skipping incomplete last entry of the activity log journal: {err}");
+                    journal.needs_compaction = true;
+                }
+                Err(err) => {
+                    return Err(ActivityLogErrorKind::InvalidJournalEntry(
+                        line_number + 1,
+                        err.to_string(),
+                    )
+                    .into())
+                }
+            }
+        }
+
+        // A journal not ending with a newline can't be appended to
+        if !data.is_empty() && !data.ends_with('\n') {
+            journal.needs_compaction = true;
+        }
+
+        debug!("Replayed {} journal entries", journal.entries);
+
+        self.cache.set_activity_log(activity_log);
+        *self.journal.lock() = journal;
+
+        Ok(())
+    }
+
+    /// Reload the in-memory cache, if the journal file has changed on disk
+    fn reload_if_stale(&self) -> PaceResult<()> {
+        self.file.reload_if_stale(|path| self.load(path))
+    }
+
+    /// Apply a change to the in-memory cache and record it in the journal
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation that changes the activity log
+    /// * `change` - Applies the change to the in-memory cache
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal file cannot be locked or the change fails
+    fn record<T>(
+        &self,
+        operation: JournalOperationKind,
+        change: impl FnOnce(&InMemoryActivityStorage) -> PaceResult<T>,
+    ) -> PaceResult<T> {
+        self.file.lock_for_changes(|path| self.load(path))?;
+
+        let before = self.cache.get_activity_log();
+
+        let result = change(&self.cache)?;
+
+        if let Some(entry) =
+            JournalEntry::from_changes(operation, &before, &self.cache.get_activity_log())
+        {
+            self.journal.lock().pending.push(entry);
+        }
+
+        Ok(result)
+    }
+
+    /// Sync the in-memory cache to the journal file
+    ///
+    /// This appends the pending entries to the journal file, or compacts the journal
+    /// to a single snapshot entry, if it has grown beyond the compaction threshold.
+    /// Afterwards the lock on the journal file is released.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entries cannot be written to the file
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if the entries are written successfully
+    #[tracing::instrument(skip(self))]
+    pub fn sync_to_file(&self) -> PaceResult<()> {
+        self.file.lock_for_changes(|path| self.load(path))?;
+
+        let mut journal = self.journal.lock();
+
+        if journal.needs_compaction
+            || journal.entries + journal.pending.len() > self.compaction_threshold
+        {
+            debug!("Compacting activity log journal");
+
+            let snapshot = JournalEntry::snapshot(&self.cache.get_activity_log());
+
+            self.file
+                .write_atomically(format!("{}\n", serde_json::to_string(&snapshot)?).as_bytes())?;
+
+            journal.entries = 1;
+            journal.needs_compaction = false;
+        } else if !journal.pending.is_empty() {
+            let mut data = String::new();
+
+            for entry in &journal.pending {
+                data.push_str(&serde_json::to_string(entry)?);
+                data.push('\n');
+            }
+
+            self.file.append(data.as_bytes())?;
+
+            journal.entries += journal.pending.len();
+        }
+
+        journal.pending.clear();
+        drop(journal);
+
+        self.file.finish_changes()
+    }
+}
+
+impl ActivityStorage for JournalActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn setup_storage(&self) -> PaceResult<()> {
+        let path = self.file.path();
+
+        if !path.exists() {
+            create_dir_all(
+                path.parent()
+                    .ok_or(PaceErrorKind::ParentDirNotFound(path.to_path_buf()))?,
+            )?;
+
+            let _ = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(path)?;
+        }
+        Ok(())
+    }
+}
+
+impl ActivityReadOps for JournalActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn read_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        self.reload_if_stale()?;
+
+        self.cache.read_activity(activity_id)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_activities(&self, filter: ActivityFilterKind) -> PaceOptResult<FilteredActivities> {
+        self.reload_if_stale()?;
+
+        self.cache.list_activities(filter)
+    }
+}
+
+impl ActivityStateManagement for JournalActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn begin_activity(&self, activity: Activity) -> PaceResult<ActivityItem> {
+        self.record(JournalOperationKind::Begin, |cache| {
+            cache.begin_activity(activity)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn end_all_activities(&self, end_opts: EndOptions) -> PaceOptResult<Vec<ActivityItem>> {
+        self.record(JournalOperationKind::End, |cache| {
+            cache.end_all_activities(end_opts)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn end_last_unfinished_activity(&self, end_opts: EndOptions) -> PaceOptResult<ActivityItem> {
+        self.record(JournalOperationKind::End, |cache| {
+            cache.end_last_unfinished_activity(end_opts)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn end_activity(
+        &self,
+        activity_id: ActivityGuid,
+        end_opts: EndOptions,
+    ) -> PaceResult<ActivityItem> {
+        self.record(JournalOperationKind::End, |cache| {
+            cache.end_activity(activity_id, end_opts)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn hold_most_recent_active_activity(
+        &self,
+        hold_opts: HoldOptions,
+    ) -> PaceOptResult<ActivityItem> {
+        self.record(JournalOperationKind::Hold, |cache| {
+            cache.hold_most_recent_active_activity(hold_opts)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn end_all_active_intermissions(
+        &self,
+        end_opts: EndOptions,
+    ) -> PaceOptResult<Vec<ActivityGuid>> {
+        self.record(JournalOperationKind::End, |cache| {
+            cache.end_all_active_intermissions(end_opts)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn resume_activity(
+        &self,
+        activity_id: ActivityGuid,
+        resume_opts: ResumeOptions,
+    ) -> PaceResult<ActivityItem> {
+        self.record(JournalOperationKind::Resume, |cache| {
+            cache.resume_activity(activity_id, resume_opts)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn hold_activity(
+        &self,
+        activity_id: ActivityGuid,
+        hold_opts: HoldOptions,
+    ) -> PaceResult<ActivityItem> {
+        self.record(JournalOperationKind::Hold, |cache| {
+            cache.hold_activity(activity_id, hold_opts)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn resume_most_recent_activity(
+        &self,
+        resume_opts: ResumeOptions,
+    ) -> PaceOptResult<ActivityItem> {
+        self.record(JournalOperationKind::Resume, |cache| {
+            cache.resume_most_recent_activity(resume_opts)
+        })
+    }
+}
+
+impl ActivityWriteOps for JournalActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn create_activity(&self, activity: Activity) -> PaceResult<ActivityItem> {
+        self.record(JournalOperationKind::Create, |cache| {
+            cache.create_activity(activity)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn insert_activity(&self, activity_item: ActivityItem) -> PaceResult<ActivityItem> {
+        self.record(JournalOperationKind::Create, |cache| {
+            cache.insert_activity(activity_item)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn update_activity(
+        &self,
+        activity_id: ActivityGuid,
+        updated_activity: Activity,
+        update_opts: UpdateOptions,
+    ) -> PaceResult<ActivityItem> {
+        self.record(JournalOperationKind::Update, |cache| {
+            cache.update_activity(activity_id, updated_activity, update_opts)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn delete_activity(
+        &self,
+        activity_id: ActivityGuid,
+        delete_opts: DeleteOptions,
+    ) -> PaceResult<ActivityItem> {
+        self.record(JournalOperationKind::Delete, |cache| {
+            cache.delete_activity(activity_id, delete_opts)
+        })
+    }
+}
+
+impl ActivityArchiving for JournalActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn archive_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        self.record(JournalOperationKind::Archive, |cache| {
+            cache.archive_activity(activity_id)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn unarchive_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        self.record(JournalOperationKind::Unarchive, |cache| {
+            cache.unarchive_activity(activity_id)
+        })
+    }
+}
+
+impl ActivityTagging for JournalActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn add_tag_to_activity(
+        &self,
+        activity_id: ActivityGuid,
+        tag: &str,
+    ) -> PaceResult<ActivityItem> {
+        self.record(JournalOperationKind::Tag, |cache| {
+            cache.add_tag_to_activity(activity_id, tag)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn remove_tag_from_activity(
+        &self,
+        activity_id: ActivityGuid,
+        tag: &str,
+    ) -> PaceResult<ActivityItem> {
+        self.record(JournalOperationKind::Tag, |cache| {
+            cache.remove_tag_from_activity(activity_id, tag)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn rename_tag(&self, old_tag: &str, new_tag: &str) -> PaceResult<usize> {
+        self.record(JournalOperationKind::Tag, |cache| {
+            cache.rename_tag(old_tag, new_tag)
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_tags(&self) -> PaceResult<BTreeMap<String, usize>> {
+        self.reload_if_stale()?;
+
+        self.cache.list_tags()
+    }
+}
+
+impl ActivityQuerying for JournalActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn list_activities_by_id(&self) -> PaceOptResult<BTreeMap<ActivityGuid, Activity>> {
+        self.reload_if_stale()?;
+
+        self.cache.list_activities_by_id()
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn most_recent_active_activity(&self) -> PaceOptResult<ActivityItem> {
+        self.reload_if_stale()?;
+
+        self.cache.most_recent_active_activity()
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_duration_range(
+        &self,
+        duration_range_opts: PaceDurationRangeOptions,
+    ) -> PaceOptResult<BTreeMap<PaceDurationRange, Vec<ActivityItem>>> {
+        self.reload_if_stale()?;
+
+        self.cache
+            .group_activities_by_duration_range(duration_range_opts)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_start_date(
+        &self,
+    ) -> PaceOptResult<BTreeMap<PaceDate, Vec<ActivityItem>>> {
+        self.reload_if_stale()?;
+
+        self.cache.group_activities_by_start_date()
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_activities_with_intermissions(
+        &self,
+    ) -> PaceOptResult<BTreeMap<ActivityGuid, Vec<ActivityItem>>> {
+        self.reload_if_stale()?;
+
+        self.cache.list_activities_with_intermissions()
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_keywords(
+        &self,
+        keyword_opts: KeywordOptions,
+    ) -> PaceOptResult<BTreeMap<String, Vec<ActivityItem>>> {
+        self.reload_if_stale()?;
+
+        self.cache.group_activities_by_keywords(keyword_opts)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_kind(&self) -> PaceOptResult<BTreeMap<ActivityKind, Vec<ActivityItem>>> {
+        self.reload_if_stale()?;
+
+        self.cache.group_activities_by_kind()
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_activities_by_time_range(
+        &self,
+        time_range_opts: TimeRangeOptions,
+    ) -> PaceOptResult<Vec<ActivityGuid>> {
+        self.reload_if_stale()?;
+
+        self.cache.list_activities_by_time_range(time_range_opts)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_activity_items_by_time_range(
+        &self,
+        time_range_opts: TimeRangeOptions,
+    ) -> PaceOptResult<Vec<ActivityItem>> {
+        self.reload_if_stale()?;
+
+        self.cache
+            .list_activity_items_by_time_range(time_range_opts)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_status(
+        &self,
+    ) -> PaceOptResult<BTreeMap<ActivityStatusKind, Vec<ActivityItem>>> {
+        self.reload_if_stale()?;
+
+        self.cache.group_activities_by_status()
+    }
+}
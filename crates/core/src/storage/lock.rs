@@ -0,0 +1,278 @@
+use std::{
+    ffi::OsString,
+    fs::{metadata, File, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use fs4::FileExt;
+use parking_lot::Mutex;
+use tempfile::NamedTempFile;
+use tracing::{debug, warn};
+
+use crate::error::{PaceErrorKind, PaceResult};
+
+/// An advisory lock on an activity log
+///
+/// The lock is taken on a `.lock` file next to the activity log, because the
+/// activity log itself is replaced on every write. It is released on drop.
+#[derive(Debug)]
+struct ActivityLogLock {
+    file: File,
+}
+
+impl ActivityLogLock {
+    /// Open the lock file of the activity log at the given path
+    fn open(path: &Path) -> PaceResult<File> {
+        let mut lock_path = OsString::from(path.as_os_str());
+        lock_path.push(".lock");
+
+        Ok(OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(lock_path)?)
+    }
+
+    /// Wait for a shared lock, e.g. for reading the activity log
+    fn shared(path: &Path) -> PaceResult<Self> {
+        let file = Self::open(path)?;
+        FileExt::lock_shared(&file)?;
+
+        Ok(Self { file })
+    }
+
+    /// Wait for an exclusive lock, e.g. for changing the activity log
+    fn exclusive(path: &Path) -> PaceResult<Self> {
+        let file = Self::open(path)?;
+        FileExt::lock_exclusive(&file)?;
+
+        Ok(Self { file })
+    }
+}
+
+impl Drop for ActivityLogLock {
+    fn drop(&mut self) {
+        if let Err(err) = FileExt::unlock(&self.file) {
+            warn!("Failed to unlock the activity log: {err}");
+        }
+    }
+}
+
+/// The metadata of an activity log, to find out if it has changed on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ActivityLogFingerprint {
+    len: u64,
+    modified: Option<SystemTime>,
+    #[cfg(unix)]
+    inode: u64,
+}
+
+impl ActivityLogFingerprint {
+    /// The fingerprint of the activity log at the given path, `None` if it doesn't exist
+    fn of(path: &Path) -> PaceResult<Option<Self>> {
+        let metadata = match metadata(path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(Some(Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+            #[cfg(unix)]
+            inode: std::os::unix::fs::MetadataExt::ino(&metadata),
+        }))
+    }
+}
+
+/// An activity log file shared with other pace processes
+///
+/// Changes take an advisory lock on the activity log, which is held until they
+/// are written with [`ActivityLogFile::finish_changes`], and the in-memory state
+/// is reloaded if the activity log has changed on disk since it was loaded.
+#[derive(Debug)]
+pub struct ActivityLogFile {
+    /// The path to the activity log
+    path: PathBuf,
+
+    /// The fingerprint of the activity log, when it was loaded or written the last time
+    fingerprint: Mutex<Option<ActivityLogFingerprint>>,
+
+    /// The exclusive lock on the activity log, held while there are changes that aren't written
+    lock: Mutex<Option<ActivityLogLock>>,
+}
+
+impl ActivityLogFile {
+    /// Create a new `ActivityLogFile` for the activity log at the given path
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            fingerprint: Mutex::new(None),
+            lock: Mutex::new(None),
+        }
+    }
+
+    /// The path to the activity log
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Load the activity log while holding a shared lock on it
+    ///
+    /// # Arguments
+    ///
+    /// * `load` - Reads the activity log into the in-memory state
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock cannot be taken or `load` fails
+    pub fn load(&self, load: impl FnOnce(&Path) -> PaceResult<()>) -> PaceResult<()> {
+        let _lock = ActivityLogLock::shared(&self.path)?;
+
+        self.load_locked(load)
+    }
+
+    /// Load the activity log, the caller needs to hold a lock on it
+    fn load_locked(&self, load: impl FnOnce(&Path) -> PaceResult<()>) -> PaceResult<()> {
+        let fingerprint = ActivityLogFingerprint::of(&self.path)?;
+
+        load(&self.path)?;
+
+        *self.fingerprint.lock() = fingerprint;
+
+        Ok(())
+    }
+
+    /// Check if the activity log has changed on disk since it was loaded or written
+    fn is_stale(&self) -> PaceResult<bool> {
+        Ok(ActivityLogFingerprint::of(&self.path)? != *self.fingerprint.lock())
+    }
+
+    /// Reload the activity log, if it has changed on disk
+    ///
+    /// While there are changes that aren't written yet, the in-memory state is
+    /// the newest state and is kept as it is.
+    ///
+    /// # Arguments
+    ///
+    /// * `load` - Reads the activity log into the in-memory state
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock cannot be taken or `load` fails
+    pub fn reload_if_stale(&self, load: impl FnOnce(&Path) -> PaceResult<()>) -> PaceResult<()> {
+        if self.lock.lock().is_some() || !self.is_stale()? {
+            return Ok(());
+        }
+
+        let _lock = ActivityLogLock::shared(&self.path)?;
+
+        if self.is_stale()? {
+            debug!("Activity log has changed on disk, reloading it");
+            self.load_locked(load)?;
+        }
+
+        Ok(())
+    }
+
+    /// Take the exclusive lock on the activity log before changing the in-memory state
+    ///
+    /// The activity log is reloaded, if it has changed on disk in the meantime,
+    /// so no changes of other processes get lost. The lock is held until the
+    /// changes are written.
+    ///
+    /// # Arguments
+    ///
+    /// * `load` - Reads the activity log into the in-memory state
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock cannot be taken or `load` fails
+    pub fn lock_for_changes(&self, load: impl FnOnce(&Path) -> PaceResult<()>) -> PaceResult<()> {
+        let mut lock = self.lock.lock();
+
+        if lock.is_some() {
+            return Ok(());
+        }
+
+        let exclusive_lock = ActivityLogLock::exclusive(&self.path)?;
+
+        if self.is_stale()? {
+            debug!("Activity log has changed on disk, reloading it before changing it");
+            self.load_locked(load)?;
+        }
+
+        *lock = Some(exclusive_lock);
+        drop(lock);
+
+        Ok(())
+    }
+
+    /// Replace the activity log with the given data
+    ///
+    /// The data is written to a temporary file next to the activity log, flushed
+    /// to disk and moved over the activity log, so it is never left half written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data cannot be written
+    pub fn write_atomically(&self, data: &[u8]) -> PaceResult<()> {
+        let parent = match self.path.parent() {
+            Some(parent) if parent.as_os_str().is_empty() => Path::new("."),
+            Some(parent) => parent,
+            None => return Err(PaceErrorKind::ParentDirNotFound(self.path.clone()).into()),
+        };
+
+        let mut temp_file = NamedTempFile::new_in(parent)?;
+
+        if let Ok(metadata) = metadata(&self.path) {
+            temp_file
+                .as_file()
+                .set_permissions(metadata.permissions())?;
+        }
+
+        temp_file.write_all(data)?;
+        temp_file.as_file().sync_all()?;
+
+        let _ = temp_file.persist(&self.path).map_err(|err| err.error)?;
+
+        // Make sure the rename itself is on disk
+        #[cfg(unix)]
+        File::open(parent)?.sync_all()?;
+
+        Ok(())
+    }
+
+    /// Append the given data to the activity log and flush it to disk
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data cannot be written
+    pub fn append(&self, data: &[u8]) -> PaceResult<()> {
+        let mut file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&self.path)?;
+
+        file.write_all(data)?;
+        file.sync_data()?;
+
+        Ok(())
+    }
+
+    /// Release the lock on the activity log, after the changes have been written
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the metadata of the activity log cannot be read
+    pub fn finish_changes(&self) -> PaceResult<()> {
+        *self.fingerprint.lock() = ActivityLogFingerprint::of(&self.path)?;
+
+        *self.lock.lock() = None;
+
+        Ok(())
+    }
+}
@@ -1,11 +1,1684 @@
-use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::create_dir_all,
+    path::Path,
+};
+
+use diesel::{connection::SimpleConnection, prelude::*, SqliteConnection};
+use merge::Merge;
+use pace_time::{
+    date::PaceDate,
+    duration::{calculate_duration, PaceDuration, PaceDurationRange, PaceDurationRangeOptions},
+    time_range::TimeRangeOptions,
+};
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use tracing::debug;
 
 use crate::{
-    domain::activity::{Activity, ActivityId, ActivityLog},
-    error::PaceResult,
-    storage::ActivityStorage,
+    commands::{
+        hold::HoldOptions, resume::ResumeOptions, DeleteOptions, EndOptions, KeywordOptions,
+        UpdateOptions,
+    },
+    domain::{
+        activity::{
+            Activity, ActivityEndOptions, ActivityGuid, ActivityItem, ActivityKind,
+            ActivityKindOptions,
+        },
+        filter::{ActivityFilterKind, FilteredActivities},
+        status::ActivityStatusKind,
+        task::TaskGuid,
+    },
+    error::{ActivityLogErrorKind, PaceError, PaceOptResult, PaceResult},
+    storage::{
+        ActivityArchiving, ActivityQuerying, ActivityReadOps, ActivityStateManagement,
+        ActivityStorage, ActivityTagging, ActivityWriteOps, SyncStorage,
+    },
+    util::to_plain_text,
 };
 
-struct SqliteActivityStorage {
-    conn: Connection,
+use self::{
+    migrations::{run_migrations, schema_version, Migration},
+    schema::{activities, activity_tags},
+};
+
+/// Versioned schema migrations for the `SQLite` storage backend
+pub mod migrations;
+
+/// The database schema for the `SQLite` storage backend
+// The `table!` macro generates fully qualified paths
+#[allow(unused_qualifications)]
+mod schema {
+    diesel::table! {
+        activities (guid) {
+            guid -> Text,
+            category -> Nullable<Text>,
+            description -> Text,
+            begin_time -> Text,
+            end_time -> Nullable<Text>,
+            duration -> Nullable<BigInt>,
+            kind -> Text,
+            status -> Text,
+            parent_id -> Nullable<Text>,
+            pomodoro_cycle -> Nullable<Text>,
+            task_id -> Nullable<Text>,
+            projects -> Nullable<Text>,
+        }
+    }
+
+    diesel::table! {
+        activity_tags (activity_guid, tag) {
+            activity_guid -> Text,
+            tag -> Text,
+        }
+    }
+
+    diesel::joinable!(activity_tags -> activities (activity_guid));
+
+    diesel::allow_tables_to_appear_in_same_query!(activities, activity_tags);
+}
+
+sql_function! {
+    /// The Julian day number of a date and time, so times with different offsets can be compared
+    fn julianday(time: diesel::sql_types::Text) -> diesel::sql_types::Double;
+}
+
+/// A query for rows of the `activities` table, the conditions can be added at runtime
+type ActivitiesQuery<'a> = activities::BoxedQuery<'a, diesel::sqlite::Sqlite>;
+
+/// A row in the `activities` table
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, AsChangeset)]
+#[diesel(table_name = activities, primary_key(guid), treat_none_as_null = true)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+struct ActivityRow {
+    guid: String,
+    category: Option<String>,
+    description: String,
+    begin_time: String,
+    end_time: Option<String>,
+    duration: Option<i64>,
+    kind: String,
+    status: String,
+    parent_id: Option<String>,
+    pomodoro_cycle: Option<String>,
+    task_id: Option<String>,
+    projects: Option<String>,
+}
+
+/// A row in the `activity_tags` table
+#[derive(Debug, Insertable)]
+#[diesel(table_name = activity_tags)]
+struct ActivityTagRow<'a> {
+    activity_guid: &'a str,
+    tag: &'a str,
+}
+
+/// Decode a value from the textual representation it has in the activity log
+///
+/// # Errors
+///
+/// Returns an error if the text cannot be deserialized into the value
+fn from_sql_text<T: DeserializeOwned>(text: &str) -> PaceResult<T> {
+    Ok(serde_json::from_value(serde_json::Value::String(
+        text.to_owned(),
+    ))?)
+}
+
+impl ActivityRow {
+    /// Create a row from an [`ActivityItem`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field of the activity cannot be encoded
+    // Durations are stored in seconds, so we won't get anywhere near the bounds of an `i64`
+    #[allow(clippy::cast_possible_wrap)]
+    fn try_from_item(item: &ActivityItem) -> PaceResult<Self> {
+        let activity = item.activity();
+
+        let (end_time, duration) = match activity.activity_end_options() {
+            Some(end_opts) => (
+                Some(to_plain_text(end_opts.end())?),
+                Some(end_opts.duration().inner() as i64),
+            ),
+            None => (None, None),
+        };
+
+        Ok(Self {
+            guid: item.guid().to_string(),
+            category: activity.category().clone(),
+            description: activity.description().clone(),
+            begin_time: to_plain_text(activity.begin())?,
+            end_time,
+            duration,
+            kind: to_plain_text(activity.kind())?,
+            status: to_plain_text(activity.status())?,
+            parent_id: activity.parent_id().map(|parent_id| parent_id.to_string()),
+            pomodoro_cycle: activity
+                .pomodoro_cycle_options()
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?,
+            task_id: activity.task_id().map(|task_id| task_id.to_string()),
+            projects: activity
+                .projects()
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?,
+        })
+    }
+
+    /// Convert the row into an [`ActivityItem`]
+    ///
+    /// # Arguments
+    ///
+    /// * `tags` - The tags of the activity
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a column cannot be decoded
+    #[allow(clippy::cast_sign_loss)]
+    fn try_into_item(self, tags: Option<HashSet<String>>) -> PaceResult<ActivityItem> {
+        let activity_end_options = match (self.end_time, self.duration) {
+            (Some(end_time), Some(duration)) => Some(ActivityEndOptions::new(
+                from_sql_text(&end_time)?,
+                PaceDuration::new(duration as u64),
+            )),
+            _ => None,
+        };
+
+        let activity_kind_options = self
+            .parent_id
+            .map(|parent_id| -> PaceResult<ActivityKindOptions> {
+                Ok(ActivityKindOptions::with_parent_id(from_sql_text(
+                    &parent_id,
+                )?))
+            })
+            .transpose()?;
+
+        let activity = Activity::builder()
+            .category(self.category)
+            .description(self.description)
+            .begin(from_sql_text::<pace_time::date_time::PaceDateTime>(
+                &self.begin_time,
+            )?)
+            .activity_end_options(activity_end_options)
+            .kind(from_sql_text(&self.kind)?)
+            .activity_kind_options(activity_kind_options)
+            .tags(tags)
+            .pomodoro_cycle_options(
+                self.pomodoro_cycle
+                    .as_deref()
+                    .map(serde_json::from_str)
+                    .transpose()?,
+            )
+            .task_id(
+                self.task_id
+                    .as_deref()
+                    .map(from_sql_text::<TaskGuid>)
+                    .transpose()?,
+            )
+            .projects(
+                self.projects
+                    .as_deref()
+                    .map(serde_json::from_str)
+                    .transpose()?,
+            )
+            .status(from_sql_text(&self.status)?)
+            .build();
+
+        Ok((from_sql_text(&self.guid)?, activity).into())
+    }
+}
+
+/// `SQLite` backed activity storage
+///
+/// Activities (including intermissions, which reference their parent activity
+/// via `parent_id`) are stored in the `activities` table, their tags in the
+/// `activity_tags` table.
+pub struct SqliteActivityStorage {
+    /// The connection to the `SQLite` database
+    connection: Mutex<SqliteConnection>,
+}
+
+impl SqliteActivityStorage {
+    /// Create a new `SqliteActivityStorage`
+    ///
+    /// # Arguments
+    ///
+    /// * `connection_string` - The path to the database file or `:memory:` for an in-memory database
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection to the database cannot be established.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new `SqliteActivityStorage`
+    pub fn new(connection_string: &str) -> PaceResult<Self> {
+        if connection_string != ":memory:" {
+            if let Some(parent) = Path::new(connection_string).parent() {
+                if !parent.as_os_str().is_empty() && !parent.exists() {
+                    create_dir_all(parent)?;
+                }
+            }
+        }
+
+        let mut connection = SqliteConnection::establish(connection_string)?;
+
+        // Foreign keys are disabled by default in SQLite, but we rely on them
+        // to remove intermissions and tags together with their activity
+        connection.batch_execute("PRAGMA foreign_keys = ON;")?;
+
+        debug!("Connected to SQLite database: {}", connection_string);
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Get the schema version of the database
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the schema version cannot be read
+    ///
+    /// # Returns
+    ///
+    /// The schema version of the database, `0` if the database has not been set up yet
+    pub fn schema_version(&self) -> PaceResult<i32> {
+        schema_version(&mut self.connection.lock())
+    }
+
+    /// Apply all pending schema migrations to the database
+    ///
+    /// # Arguments
+    ///
+    /// * `dry_run` - Only return the pending migrations without applying them
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database has a newer schema version than this
+    /// version of pace supports or a migration fails
+    ///
+    /// # Returns
+    ///
+    /// The migrations that have been applied (or would have been applied in a dry run)
+    pub fn migrate(&self, dry_run: bool) -> PaceResult<Vec<Migration>> {
+        run_migrations(&mut self.connection.lock(), dry_run)
+    }
+
+    /// Load activity items for the given rows including their tags
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tags cannot be loaded or the rows cannot be decoded
+    fn rows_into_items(
+        connection: &mut SqliteConnection,
+        rows: Vec<ActivityRow>,
+    ) -> PaceResult<Vec<ActivityItem>> {
+        let guids = rows.iter().map(|row| row.guid.as_str()).collect::<Vec<_>>();
+
+        let mut tags_by_guid: HashMap<String, HashSet<String>> = HashMap::new();
+
+        for (activity_guid, tag) in activity_tags::table
+            .filter(activity_tags::activity_guid.eq_any(&guids))
+            .select((activity_tags::activity_guid, activity_tags::tag))
+            .load::<(String, String)>(connection)?
+        {
+            _ = tags_by_guid.entry(activity_guid).or_default().insert(tag);
+        }
+
+        rows.into_iter()
+            .map(|row| {
+                let tags = tags_by_guid.remove(&row.guid);
+                row.try_into_item(tags)
+            })
+            .collect()
+    }
+
+    /// Load all activities from the database, ordered by their id
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be loaded
+    fn load_all_items(&self) -> PaceResult<Vec<ActivityItem>> {
+        self.load_items(activities::table.into_boxed())
+    }
+
+    /// Load the activities matching a query from the database, ordered by their id
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The query with the conditions the activities have to match
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activities cannot be loaded
+    fn load_items(&self, query: ActivitiesQuery<'_>) -> PaceResult<Vec<ActivityItem>> {
+        let mut connection = self.connection.lock();
+
+        let rows = query
+            .select(ActivityRow::as_select())
+            .order(activities::guid.asc())
+            .load(&mut *connection)?;
+
+        let activity_items = Self::rows_into_items(&mut connection, rows);
+
+        drop(connection);
+
+        activity_items
+    }
+
+    /// Write the complete data of an activity item to the database
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_item` - The activity item to write
+    /// * `is_new` - If the activity item needs to be inserted instead of updated
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activity item cannot be written
+    fn write_item(&self, activity_item: &ActivityItem, is_new: bool) -> PaceResult<()> {
+        let row = ActivityRow::try_from_item(activity_item)?;
+
+        let mut connection = self.connection.lock();
+
+        connection.transaction::<_, PaceError, _>(|connection| {
+            if is_new {
+                _ = diesel::insert_into(activities::table)
+                    .values(&row)
+                    .execute(connection)?;
+            } else {
+                _ = diesel::update(activities::table.find(&row.guid))
+                    .set(&row)
+                    .execute(connection)?;
+
+                _ = diesel::delete(
+                    activity_tags::table.filter(activity_tags::activity_guid.eq(&row.guid)),
+                )
+                .execute(connection)?;
+            }
+
+            if let Some(tags) = activity_item.activity().tags() {
+                let tag_rows = tags
+                    .iter()
+                    .map(|tag| ActivityTagRow {
+                        activity_guid: &row.guid,
+                        tag,
+                    })
+                    .collect::<Vec<_>>();
+
+                _ = diesel::insert_into(activity_tags::table)
+                    .values(&tag_rows)
+                    .execute(connection)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl ActivityStorage for SqliteActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn setup_storage(&self) -> PaceResult<()> {
+        debug!("Setting up SQLite storage");
+
+        let applied_migrations = self.migrate(false)?;
+
+        debug!("Applied migrations: {:?}", applied_migrations);
+
+        Ok(())
+    }
+}
+
+impl SyncStorage for SqliteActivityStorage {
+    fn sync(&self) -> PaceResult<()> {
+        // Every write is committed to the database immediately
+        debug!("Syncing SQLite storage");
+
+        Ok(())
+    }
+}
+
+impl ActivityReadOps for SqliteActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn read_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        let mut connection = self.connection.lock();
+
+        let row = activities::table
+            .find(activity_id.to_string())
+            .select(ActivityRow::as_select())
+            .first(&mut *connection)
+            .optional()?
+            .ok_or(ActivityLogErrorKind::ActivityNotFound(activity_id))?;
+
+        let activity = Self::rows_into_items(&mut connection, vec![row])?
+            .pop()
+            .ok_or(ActivityLogErrorKind::ActivityNotFound(activity_id))?;
+
+        drop(connection);
+
+        debug!("Activity with id {:?} found: {:?}", activity_id, activity);
+
+        Ok(activity)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_activities(&self, filter: ActivityFilterKind) -> PaceOptResult<FilteredActivities> {
+        let in_progress = to_plain_text(&ActivityStatusKind::InProgress)?;
+
+        let mut query = activities::table.into_boxed();
+
+        query = match filter {
+            ActivityFilterKind::Everything => query,
+            ActivityFilterKind::OnlyActivities => {
+                query.filter(activities::kind.eq(to_plain_text(&ActivityKind::Activity)?))
+            }
+            ActivityFilterKind::TimeRange(time_range_opts) => query
+                .filter(activities::kind.eq_any([
+                    to_plain_text(&ActivityKind::Activity)?,
+                    to_plain_text(&ActivityKind::PomodoroWork)?,
+                ]))
+                .filter(julianday(activities::begin_time).between(
+                    julianday(to_plain_text(time_range_opts.start())?),
+                    julianday(to_plain_text(time_range_opts.end())?),
+                )),
+            ActivityFilterKind::Active => query
+                .filter(activities::end_time.is_null())
+                .filter(activities::status.eq(in_progress)),
+            ActivityFilterKind::ActiveIntermission => query
+                .filter(activities::end_time.is_null())
+                .filter(activities::kind.eq_any([
+                    to_plain_text(&ActivityKind::Intermission)?,
+                    to_plain_text(&ActivityKind::PomodoroIntermission)?,
+                ]))
+                .filter(activities::status.eq(in_progress)),
+            ActivityFilterKind::Ended => query
+                .filter(activities::end_time.is_not_null())
+                .filter(activities::status.eq(to_plain_text(&ActivityStatusKind::Completed)?)),
+            ActivityFilterKind::Archived => {
+                query.filter(activities::status.eq(to_plain_text(&ActivityStatusKind::Archived)?))
+            }
+            ActivityFilterKind::Held => {
+                query.filter(activities::status.eq(to_plain_text(&ActivityStatusKind::Paused)?))
+            }
+            ActivityFilterKind::Intermission => query.filter(activities::kind.eq_any([
+                to_plain_text(&ActivityKind::Intermission)?,
+                to_plain_text(&ActivityKind::PomodoroIntermission)?,
+            ])),
+        };
+
+        let mut connection = self.connection.lock();
+
+        let guids = query
+            .select(activities::guid)
+            .order(activities::guid.asc())
+            .load::<String>(&mut *connection)?;
+
+        drop(connection);
+
+        let filtered = guids
+            .iter()
+            .map(|guid| from_sql_text(guid))
+            .collect::<PaceResult<Vec<ActivityGuid>>>()?;
+
+        debug!("Filtered activities: {:?}", filtered);
+
+        if filtered.is_empty() {
+            return Ok(None);
+        }
+
+        match filter {
+            ActivityFilterKind::Everything => Ok(Some(FilteredActivities::Everything(filtered))),
+            ActivityFilterKind::OnlyActivities => {
+                Ok(Some(FilteredActivities::OnlyActivities(filtered)))
+            }
+            ActivityFilterKind::Active => Ok(Some(FilteredActivities::Active(filtered))),
+            ActivityFilterKind::ActiveIntermission => {
+                Ok(Some(FilteredActivities::ActiveIntermission(filtered)))
+            }
+            ActivityFilterKind::Archived => Ok(Some(FilteredActivities::Archived(filtered))),
+            ActivityFilterKind::Ended => Ok(Some(FilteredActivities::Ended(filtered))),
+            ActivityFilterKind::Held => Ok(Some(FilteredActivities::Held(filtered))),
+            ActivityFilterKind::Intermission => {
+                Ok(Some(FilteredActivities::Intermission(filtered)))
+            }
+            ActivityFilterKind::TimeRange(_) => Ok(Some(FilteredActivities::TimeRange(filtered))),
+        }
+    }
+}
+
+impl ActivityWriteOps for SqliteActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn create_activity(&self, activity: Activity) -> PaceResult<ActivityItem> {
+        self.insert_activity(ActivityItem::from(activity))
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn insert_activity(&self, activity_item: ActivityItem) -> PaceResult<ActivityItem> {
+        // We use a ULID as the ID for the activity, so it should be unique and not collide with
+        // other activities. But still, let's check if the ID is already in use.
+        let mut connection = self.connection.lock();
+
+        let id_in_use = activities::table
+            .find(activity_item.guid().to_string())
+            .select(activities::guid)
+            .first::<String>(&mut *connection)
+            .optional()?
+            .is_some();
+
+        drop(connection);
+
+        if id_in_use {
+            debug!("Activity ID already in use: {:?}", activity_item.guid());
+            return Err(ActivityLogErrorKind::ActivityIdAlreadyInUse(*activity_item.guid()).into());
+        }
+
+        self.write_item(&activity_item, true)?;
+
+        Ok(activity_item)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn update_activity(
+        &self,
+        activity_id: ActivityGuid,
+        updated_activity: Activity,
+        update_opts: UpdateOptions,
+    ) -> PaceResult<ActivityItem> {
+        let original_activity = self.read_activity(activity_id)?;
+
+        debug!("Original activity: {:?}", original_activity);
+
+        let mut activity = original_activity.activity().clone();
+
+        activity.merge(updated_activity);
+
+        debug!("Updating activity: {:?}", activity);
+
+        self.write_item(&(activity_id, activity).into(), false)?;
+
+        Ok(original_activity)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn delete_activity(
+        &self,
+        activity_id: ActivityGuid,
+        delete_opts: DeleteOptions,
+    ) -> PaceResult<ActivityItem> {
+        let activity = self.read_activity(activity_id)?;
+
+        let mut connection = self.connection.lock();
+
+        _ = diesel::delete(activities::table.find(activity_id.to_string()))
+            .execute(&mut *connection)?;
+
+        drop(connection);
+
+        Ok(activity)
+    }
+}
+
+impl ActivityArchiving for SqliteActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn archive_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        let mut activity_item = self.read_activity(activity_id)?;
+
+        if activity_item.activity().is_archived() {
+            debug!("Activity is already archived.");
+            return Err(ActivityLogErrorKind::ActivityAlreadyArchived(activity_id).into());
+        } else if !activity_item.activity().is_completed() {
+            debug!("Activity has not ended yet.");
+            return Err(ActivityLogErrorKind::ActivityNotArchivable(activity_id).into());
+        }
+
+        activity_item.activity_mut().archive();
+
+        self.write_item(&activity_item, false)?;
+
+        Ok(activity_item)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn unarchive_activity(&self, activity_id: ActivityGuid) -> PaceResult<ActivityItem> {
+        let mut activity_item = self.read_activity(activity_id)?;
+
+        if !activity_item.activity().is_archived() {
+            debug!("Activity is not archived.");
+            return Err(ActivityLogErrorKind::ActivityNotArchived(activity_id).into());
+        }
+
+        activity_item.activity_mut().unarchive();
+
+        self.write_item(&activity_item, false)?;
+
+        Ok(activity_item)
+    }
+}
+
+impl ActivityTagging for SqliteActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn add_tag_to_activity(
+        &self,
+        activity_id: ActivityGuid,
+        tag: &str,
+    ) -> PaceResult<ActivityItem> {
+        let mut activity_item = self.read_activity(activity_id)?;
+
+        if !activity_item.activity_mut().add_tag(tag) {
+            debug!("Activity already has the tag.");
+            return Ok(activity_item);
+        }
+
+        self.write_item(&activity_item, false)?;
+
+        Ok(activity_item)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn remove_tag_from_activity(
+        &self,
+        activity_id: ActivityGuid,
+        tag: &str,
+    ) -> PaceResult<ActivityItem> {
+        let mut activity_item = self.read_activity(activity_id)?;
+
+        if !activity_item.activity_mut().remove_tag(tag) {
+            debug!("Activity doesn't have the tag.");
+            return Err(ActivityLogErrorKind::TagNotFound(activity_id, tag.to_string()).into());
+        }
+
+        self.write_item(&activity_item, false)?;
+
+        Ok(activity_item)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn rename_tag(&self, old_tag: &str, new_tag: &str) -> PaceResult<usize> {
+        let mut connection = self.connection.lock();
+
+        connection.transaction::<_, PaceError, _>(|connection| {
+            let activity_guids = activity_tags::table
+                .filter(activity_tags::tag.eq(old_tag))
+                .select(activity_tags::activity_guid)
+                .load::<String>(connection)?;
+
+            if activity_guids.is_empty() || old_tag == new_tag {
+                return Ok(activity_guids.len());
+            }
+
+            let tag_rows = activity_guids
+                .iter()
+                .map(|activity_guid| ActivityTagRow {
+                    activity_guid,
+                    tag: new_tag,
+                })
+                .collect::<Vec<_>>();
+
+            // Activities that already have the new tag keep it only once
+            _ = diesel::insert_or_ignore_into(activity_tags::table)
+                .values(&tag_rows)
+                .execute(connection)?;
+
+            _ = diesel::delete(activity_tags::table.filter(activity_tags::tag.eq(old_tag)))
+                .execute(connection)?;
+
+            Ok(activity_guids.len())
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_tags(&self) -> PaceResult<BTreeMap<String, usize>> {
+        let mut connection = self.connection.lock();
+
+        let tags = activity_tags::table
+            .group_by(activity_tags::tag)
+            .select((activity_tags::tag, diesel::dsl::count_star()))
+            .load::<(String, i64)>(&mut *connection)?;
+
+        drop(connection);
+
+        Ok(tags
+            .into_iter()
+            .map(|(tag, count)| (tag, usize::try_from(count).unwrap_or_default()))
+            .collect())
+    }
+}
+
+impl ActivityStateManagement for SqliteActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn end_activity(
+        &self,
+        activity_id: ActivityGuid,
+        end_opts: EndOptions,
+    ) -> PaceResult<ActivityItem> {
+        let mut activity_item = self.read_activity(activity_id)?;
+
+        let end_opts = ActivityEndOptions::new(
+            *end_opts.end_time(),
+            calculate_duration(activity_item.activity().begin(), end_opts.end_time())?,
+        );
+
+        debug!("End options: {:?}", end_opts);
+
+        activity_item.activity_mut().end_activity(end_opts);
+
+        self.write_item(&activity_item, false)?;
+
+        Ok(activity_item)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn end_last_unfinished_activity(&self, end_opts: EndOptions) -> PaceOptResult<ActivityItem> {
+        let Some(most_recent) = self.most_recent_active_activity()? else {
+            debug!("No active activity found.");
+            return Ok(None);
+        };
+
+        debug!("Most recent activity: {:?}", most_recent);
+
+        let activity = self.end_activity(*most_recent.guid(), end_opts)?;
+
+        Ok(Some(activity))
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn end_all_activities(&self, end_opts: EndOptions) -> PaceOptResult<Vec<ActivityItem>> {
+        // Activities that are in progress or held can be ended
+        let endable_activities = self
+            .load_items(
+                activities::table
+                    .into_boxed()
+                    .filter(activities::status.eq_any([
+                        to_plain_text(&ActivityStatusKind::InProgress)?,
+                        to_plain_text(&ActivityStatusKind::Paused)?,
+                    ])),
+            )?
+            .into_iter()
+            .map(|activity_item| *activity_item.guid())
+            .collect::<Vec<ActivityGuid>>();
+
+        debug!("Endable activities: {:?}", endable_activities);
+
+        // There are no active activities
+        if endable_activities.is_empty() {
+            debug!("No active activities found.");
+            return Ok(None);
+        }
+
+        let ended_activities = endable_activities
+            .iter()
+            .map(|activity_id| self.end_activity(*activity_id, end_opts.clone()))
+            .collect::<PaceResult<Vec<ActivityItem>>>()?;
+
+        debug!("Ended activities: {:?}", ended_activities);
+
+        Ok(Some(ended_activities))
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn hold_most_recent_active_activity(
+        &self,
+        hold_opts: HoldOptions,
+    ) -> PaceOptResult<ActivityItem> {
+        let Some(active_activity) = self.most_recent_active_activity()? else {
+            debug!("No active activity found.");
+
+            // There are no active activities
+            return Ok(None);
+        };
+
+        Some(self.hold_activity(*active_activity.guid(), hold_opts)).transpose()
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn end_all_active_intermissions(
+        &self,
+        end_opts: EndOptions,
+    ) -> PaceOptResult<Vec<ActivityGuid>> {
+        let Some(active_intermissions) = self.list_active_intermissions()? else {
+            debug!("No active intermissions found.");
+
+            // There are no active intermissions
+            return Ok(None);
+        };
+
+        let ended_intermissions = active_intermissions
+            .iter()
+            .map(|activity_id| -> PaceResult<ActivityGuid> {
+                let _ = self.end_activity(*activity_id, end_opts.clone())?;
+                Ok(*activity_id)
+            })
+            .collect::<PaceResult<Vec<ActivityGuid>>>()?;
+
+        debug!("Ended intermissions: {:?}", ended_intermissions);
+
+        Ok(Some(ended_intermissions))
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn resume_activity(
+        &self,
+        activity_id: ActivityGuid,
+        resume_opts: ResumeOptions,
+    ) -> PaceResult<ActivityItem> {
+        let resumable_activity = self.read_activity(activity_id)?;
+
+        debug!("Resumable activity: {:?}", resumable_activity);
+
+        if resumable_activity.activity().is_in_progress() {
+            debug!("Activity is already active.");
+            return Err(ActivityLogErrorKind::ActiveActivityFound(activity_id).into());
+        } else if resumable_activity.activity().is_completed() {
+            debug!("Activity has ended.");
+            return Err(ActivityLogErrorKind::ActivityAlreadyEnded(activity_id).into());
+        } else if resumable_activity.activity().is_archived() {
+            debug!("Activity is archived.");
+            return Err(ActivityLogErrorKind::ActivityAlreadyArchived(activity_id).into());
+        } else if !resumable_activity.activity().is_paused() {
+            debug!("Activity is not held.");
+            return Err(ActivityLogErrorKind::NoHeldActivityFound(activity_id).into());
+        }
+
+        // You can't have multiple intermissions at once, only one at a time.
+        let ended_intermission_ids = self.end_all_active_intermissions(resume_opts.into())?;
+
+        debug!("Ended intermission ids: {:?}", ended_intermission_ids);
+
+        let mut editable_activity = resumable_activity.clone();
+
+        let updated_activity = editable_activity
+            .activity_mut()
+            .set_status(ActivityStatusKind::InProgress)
+            .clone();
+
+        debug!("Updated activity: {:?}", updated_activity);
+
+        let _ = self.update_activity(
+            *resumable_activity.guid(),
+            updated_activity,
+            UpdateOptions::default(),
+        )?;
+
+        Ok(resumable_activity)
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn hold_activity(
+        &self,
+        activity_id: ActivityGuid,
+        hold_opts: HoldOptions,
+    ) -> PaceResult<ActivityItem> {
+        let active_activity = self.read_activity(activity_id)?;
+
+        debug!("Active activity: {:?}", active_activity);
+
+        if !active_activity.activity().is_in_progress() {
+            debug!("Activity is not active.");
+            return Err(ActivityLogErrorKind::NoActiveActivityFound(activity_id).into());
+        } else if active_activity.activity().is_completed() {
+            debug!("Activity has ended.");
+            return Err(ActivityLogErrorKind::ActivityAlreadyEnded(activity_id).into());
+        } else if active_activity.activity().is_archived() {
+            debug!("Activity is archived.");
+            return Err(ActivityLogErrorKind::ActivityAlreadyArchived(activity_id).into());
+        }
+
+        // Don't create another intermission, if there is already one and we only want to extend it
+        if let Some(intermissions) =
+            self.list_active_intermissions_for_activity_id(*active_activity.guid())?
+        {
+            debug!("Active intermissions: {:?}", intermissions);
+
+            if !intermissions.is_empty() && hold_opts.action().is_extend() {
+                debug!("Active intermission(s) found and action is extend.");
+
+                return Ok(active_activity);
+            }
+        }
+
+        // You can't have multiple intermissions at once, only one at a time.
+        let active_intermission_ids =
+            self.end_all_active_intermissions(hold_opts.clone().into())?;
+
+        debug!(
+            "Ended active intermission ids: {:?}",
+            active_intermission_ids
+        );
+
+        let activity_kind_opts = ActivityKindOptions::with_parent_id(*active_activity.guid());
+
+        let description = hold_opts
+            .reason()
+            .clone()
+            .unwrap_or_else(|| active_activity.activity().description().clone());
+
+        let intermission = Activity::builder()
+            .begin(*hold_opts.begin_time())
+            .kind(ActivityKind::Intermission)
+            .status(ActivityStatusKind::InProgress)
+            .description(description)
+            .category(active_activity.activity().category().clone())
+            .activity_kind_options(Some(activity_kind_opts))
+            .build();
+
+        let created_intermission_item = self.begin_activity(intermission)?;
+
+        debug!("Created intermission: {:?}", created_intermission_item);
+
+        let mut editable_activity = active_activity.clone();
+
+        let updated_activity = editable_activity
+            .activity_mut()
+            .set_status(ActivityStatusKind::Paused)
+            .clone();
+
+        debug!("Updated activity: {:?}", updated_activity);
+
+        let _ = self.update_activity(
+            *active_activity.guid(),
+            updated_activity.clone(),
+            UpdateOptions::default(),
+        )?;
+
+        Ok((*active_activity.guid(), updated_activity).into())
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn resume_most_recent_activity(
+        &self,
+        resume_opts: ResumeOptions,
+    ) -> PaceOptResult<ActivityItem> {
+        let Some(active_activity) = self.most_recent_held_activity()? else {
+            debug!("No held activity found.");
+
+            return Ok(None);
+        };
+
+        Some(self.resume_activity(*active_activity.guid(), resume_opts)).transpose()
+    }
+}
+
+impl ActivityQuerying for SqliteActivityStorage {
+    #[tracing::instrument(skip(self))]
+    fn list_activities_by_id(&self) -> PaceOptResult<BTreeMap<ActivityGuid, Activity>> {
+        let activities_by_id = self
+            .load_all_items()?
+            .into_iter()
+            .map(ActivityItem::into_parts)
+            .collect::<BTreeMap<_, _>>();
+
+        debug!("Activities by id: {:?}", activities_by_id.keys());
+
+        if activities_by_id.is_empty() {
+            debug!("No activities found.");
+
+            return Ok(None);
+        }
+
+        Ok(Some(activities_by_id))
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_duration_range(
+        &self,
+        duration_range_opts: PaceDurationRangeOptions,
+    ) -> PaceOptResult<BTreeMap<PaceDurationRange, Vec<ActivityItem>>> {
+        let activities_by_duration_range = self
+            .load_items(
+                activities::table
+                    .into_boxed()
+                    .filter(activities::kind.eq_any([
+                        to_plain_text(&ActivityKind::Activity)?,
+                        to_plain_text(&ActivityKind::PomodoroWork)?,
+                    ])),
+            )?
+            .into_iter()
+            .fold(
+                BTreeMap::new(),
+                |mut acc: BTreeMap<PaceDurationRange, Vec<ActivityItem>>, activity_item| {
+                    acc.entry(
+                        duration_range_opts
+                            .range_of(activity_item.activity().duration_or_elapsed()),
+                    )
+                    .or_default()
+                    .push(activity_item);
+
+                    acc
+                },
+            );
+
+        if activities_by_duration_range.is_empty() {
+            debug!("No activities found.");
+
+            return Ok(None);
+        }
+
+        Ok(Some(activities_by_duration_range))
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_start_date(
+        &self,
+    ) -> PaceOptResult<BTreeMap<PaceDate, Vec<ActivityItem>>> {
+        Ok(Some(self.load_all_items()?.into_iter().fold(
+            BTreeMap::new(),
+            |mut acc: BTreeMap<PaceDate, Vec<ActivityItem>>, activity_item| {
+                let begin_date = activity_item.activity().begin().date_naive();
+
+                debug!("Begin date: {:?}", begin_date);
+
+                acc.entry(begin_date).or_default().push(activity_item);
+
+                acc
+            },
+        )))
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_activities_with_intermissions(
+        &self,
+    ) -> PaceOptResult<BTreeMap<ActivityGuid, Vec<ActivityItem>>> {
+        let Some(intermissions) = self
+            .list_activities(ActivityFilterKind::Intermission)?
+            .map(FilteredActivities::into_vec)
+        else {
+            debug!("No intermissions found.");
+
+            return Ok(None);
+        };
+
+        debug!("Intermissions: {:?}", intermissions);
+
+        Some(intermissions.into_iter().try_fold(
+            BTreeMap::new(),
+            |mut acc: BTreeMap<ActivityGuid, Vec<ActivityItem>>, intermission_id| {
+                let intermission = self.read_activity(intermission_id)?;
+
+                debug!("Intermission: {:?}", intermission);
+
+                let parent_id = intermission
+                    .activity()
+                    .parent_id()
+                    .ok_or(ActivityLogErrorKind::ParentIdNotSet(intermission_id))?;
+
+                debug!("Parent id: {:?}", parent_id);
+
+                let parent_activity = self.read_activity(parent_id)?;
+
+                debug!("Parent activity: {:?}", parent_activity);
+
+                acc.entry(parent_id).or_default().push(parent_activity);
+
+                Ok(acc)
+            },
+        ))
+        .transpose()
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_keywords(
+        &self,
+        keyword_opts: KeywordOptions,
+    ) -> PaceOptResult<BTreeMap<String, Vec<ActivityItem>>> {
+        let mut query = activities::table.into_boxed();
+
+        // Only load the activities of matching categories, `LIKE` ignores the case of ASCII letters
+        if let Some(category) = keyword_opts.category() {
+            let pattern = category
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+
+            query = query.filter(
+                activities::category
+                    .like(format!("%{pattern}%"))
+                    .escape('\\'),
+            );
+        }
+
+        Some(self.load_items(query)?.into_iter().try_fold(
+            BTreeMap::new(),
+            |mut acc: BTreeMap<String, Vec<ActivityItem>>, activity_item| {
+                let activity = activity_item.activity();
+
+                // Group by category
+                if let Some(category) = keyword_opts.category() {
+                    let category = category.to_lowercase();
+
+                    debug!("Category: {:?}", category);
+
+                    if activity
+                        .category()
+                        .as_ref()
+                        .ok_or(ActivityLogErrorKind::CategoryNotSet(*activity_item.guid()))?
+                        .to_lowercase()
+                        .contains(category.as_str())
+                    {
+                        acc.entry(category).or_default().push(activity_item);
+                    }
+                } else {
+                    debug!("No category specified. Using 'Uncategorized' as the category.");
+
+                    acc.entry(
+                        activity
+                            .category()
+                            .clone()
+                            .unwrap_or_else(|| "Uncategorized".to_string()),
+                    )
+                    .or_default()
+                    .push(activity_item);
+                }
+
+                Ok(acc)
+            },
+        ))
+        .transpose()
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_kind(&self) -> PaceOptResult<BTreeMap<ActivityKind, Vec<ActivityItem>>> {
+        Ok(Some(self.load_all_items()?.into_iter().fold(
+            BTreeMap::new(),
+            |mut acc: BTreeMap<ActivityKind, Vec<ActivityItem>>, activity_item| {
+                acc.entry(*activity_item.activity().kind())
+                    .or_default()
+                    .push(activity_item);
+
+                acc
+            },
+        )))
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn group_activities_by_status(
+        &self,
+    ) -> PaceOptResult<BTreeMap<ActivityStatusKind, Vec<ActivityItem>>> {
+        Ok(Some(self.load_all_items()?.into_iter().fold(
+            BTreeMap::new(),
+            |mut acc: BTreeMap<ActivityStatusKind, Vec<ActivityItem>>, activity_item| {
+                acc.entry(*activity_item.activity().status())
+                    .or_default()
+                    .push(activity_item);
+
+                acc
+            },
+        )))
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_activities_by_time_range(
+        &self,
+        time_range_opts: TimeRangeOptions,
+    ) -> PaceOptResult<Vec<ActivityGuid>> {
+        let Some(filtered_activities) = self
+            .list_activities(ActivityFilterKind::TimeRange(time_range_opts))?
+            .map(FilteredActivities::into_vec)
+        else {
+            debug!(
+                "No activities found in time range between {} and {}.",
+                time_range_opts.start(),
+                time_range_opts.end()
+            );
+
+            return Ok(None);
+        };
+
+        Ok(Some(filtered_activities))
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn list_activity_items_by_time_range(
+        &self,
+        time_range_opts: TimeRangeOptions,
+    ) -> PaceOptResult<Vec<ActivityItem>> {
+        let activity_items = self.load_items(activities::table.into_boxed().filter(
+            julianday(activities::begin_time).between(
+                julianday(to_plain_text(time_range_opts.start())?),
+                julianday(to_plain_text(time_range_opts.end())?),
+            ),
+        ))?;
+
+        if activity_items.is_empty() {
+            debug!(
+                "No activities found in time range between {} and {}.",
+                time_range_opts.start(),
+                time_range_opts.end()
+            );
+
+            return Ok(None);
+        }
+
+        Ok(Some(activity_items))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::collections::HashSet;
+
+    use pace_time::date_time::PaceDateTime;
+
+    use super::*;
+    use crate::error::TestResult;
+
+    fn storage() -> TestResult<SqliteActivityStorage> {
+        let storage = SqliteActivityStorage::new(":memory:")?;
+        storage.setup_storage()?;
+        Ok(storage)
+    }
+
+    #[test]
+    fn test_sqlite_create_read_activity_roundtrip_passes() -> TestResult<()> {
+        let storage = storage()?;
+
+        let activity = Activity::builder()
+            .description("Test activity")
+            .category("Test::Category".to_string())
+            .begin(PaceDateTime::now())
+            .tags(Some(HashSet::from(["a".to_string(), "b".to_string()])))
+            .build();
+
+        let item = storage.create_activity(activity.clone())?;
+
+        let read_item = storage.read_activity(*item.guid())?;
+
+        assert_eq!(read_item, item);
+        assert_eq!(read_item.activity(), &activity);
+
+        let result = storage.create_activity(activity);
+
+        assert!(result.is_ok(), "A new id is generated for each activity");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite_update_and_delete_activity_passes() -> TestResult<()> {
+        let storage = storage()?;
+
+        let item = storage.create_activity(
+            Activity::builder()
+                .description("Original")
+                .tags(Some(HashSet::from(["old".to_string()])))
+                .build(),
+        )?;
+
+        let updated = Activity::builder()
+            .description("Updated")
+            .tags(Some(HashSet::from(["new".to_string()])))
+            .build();
+
+        let original = storage.update_activity(*item.guid(), updated, UpdateOptions::default())?;
+
+        assert_eq!(original, item);
+
+        let read_item = storage.read_activity(*item.guid())?;
+
+        assert_eq!(read_item.activity().description(), "Updated");
+        assert_eq!(
+            read_item.activity().tags(),
+            &Some(HashSet::from(["new".to_string()]))
+        );
+
+        let deleted = storage.delete_activity(*item.guid(), DeleteOptions::default())?;
+
+        assert_eq!(deleted, read_item);
+        assert!(storage.read_activity(*item.guid()).is_err());
+        assert!(storage.list_activities_by_id()?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite_begin_hold_resume_end_activity_passes() -> TestResult<()> {
+        let storage = storage()?;
+
+        let begin_time = PaceDateTime::now();
+
+        let item = storage.begin_activity(
+            Activity::builder()
+                .description("Test activity")
+                .begin(begin_time)
+                .build(),
+        )?;
+
+        assert!(storage.is_activity_active(*item.guid())?);
+
+        let held = storage.hold_most_recent_active_activity(HoldOptions::default())?;
+
+        assert!(held.is_some_and(|held| held.activity().is_paused()));
+
+        let intermissions = storage
+            .list_active_intermissions()?
+            .ok_or("No active intermission found")?;
+
+        assert_eq!(intermissions.len(), 1);
+
+        let intermission = storage.read_activity(intermissions[0])?;
+
+        assert_eq!(intermission.activity().parent_id(), Some(*item.guid()));
+
+        let _ = storage.resume_most_recent_activity(ResumeOptions::default())?;
+
+        assert!(storage.list_active_intermissions()?.is_none());
+        assert!(storage.is_activity_active(*item.guid())?);
+
+        let ended = storage
+            .end_last_unfinished_activity(EndOptions::default())?
+            .ok_or("No activity ended")?;
+
+        assert!(ended.activity().is_completed());
+        assert_eq!(
+            storage.read_activity(*item.guid())?.activity(),
+            ended.activity()
+        );
+
+        let Some(FilteredActivities::Ended(ended_ids)) =
+            storage.list_activities(ActivityFilterKind::Ended)?
+        else {
+            return Err("No ended activities found".into());
+        };
+
+        // The ended intermission is listed as well
+        assert_eq!(ended_ids.len(), 2);
+        assert!(ended_ids.contains(item.guid()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite_archive_activity_passes() -> TestResult<()> {
+        let storage = storage()?;
+
+        let item = storage.begin_activity(Activity::builder().description("Test").build())?;
+
+        assert!(
+            storage.archive_activity(*item.guid()).is_err(),
+            "Active activities can't be archived."
+        );
+
+        let _ = storage.end_activity(*item.guid(), EndOptions::default())?;
+
+        let _ = storage.archive_activity(*item.guid())?;
+
+        let Some(FilteredActivities::Archived(archived_ids)) =
+            storage.list_activities(ActivityFilterKind::Archived)?
+        else {
+            return Err("No archived activities found".into());
+        };
+
+        assert_eq!(archived_ids, vec![*item.guid()]);
+
+        let unarchived = storage.unarchive_activity(*item.guid())?;
+
+        assert!(!unarchived.activity().is_archived());
+        assert!(storage
+            .list_activities(ActivityFilterKind::Archived)?
+            .is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite_tagging_passes() -> TestResult<()> {
+        let storage = storage()?;
+
+        let first = storage.create_activity(
+            Activity::builder()
+                .description("First")
+                .tags(Some(HashSet::from(["old".to_string(), "new".to_string()])))
+                .build(),
+        )?;
+
+        let second = storage.create_activity(Activity::builder().description("Second").build())?;
+
+        let _ = storage.add_tag_to_activity(*second.guid(), "old")?;
+
+        assert_eq!(
+            storage.list_tags()?,
+            BTreeMap::from([("new".to_string(), 1), ("old".to_string(), 2)])
+        );
+
+        assert_eq!(storage.rename_tag("old", "new")?, 2);
+
+        assert_eq!(
+            storage.list_tags()?,
+            BTreeMap::from([("new".to_string(), 2)])
+        );
+
+        let _ = storage.remove_tag_from_activity(*first.guid(), "new")?;
+
+        assert_eq!(
+            storage.read_activity(*first.guid())?.activity().tags(),
+            &None
+        );
+
+        assert!(
+            storage
+                .remove_tag_from_activity(*first.guid(), "new")
+                .is_err(),
+            "The activity doesn't have the tag anymore."
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite_group_activities_by_duration_range_passes() -> TestResult<()> {
+        let storage = storage()?;
+
+        let begin = PaceDateTime::now();
+
+        for (description, kind, minutes) in [
+            ("Quick fix", ActivityKind::Activity, 10),
+            ("Review", ActivityKind::Activity, 30),
+            ("Deep work", ActivityKind::PomodoroWork, 120),
+            ("Break", ActivityKind::Intermission, 5),
+        ] {
+            let _ = storage.create_activity(
+                Activity::builder()
+                    .description(description)
+                    .kind(kind)
+                    .begin(begin)
+                    .activity_end_options(Some(ActivityEndOptions::new(
+                        begin,
+                        PaceDuration::new(minutes * 60),
+                    )))
+                    .build(),
+            )?;
+        }
+
+        let activities_by_duration_range = storage
+            .group_activities_by_duration_range(PaceDurationRangeOptions::default())?
+            .ok_or("Should have activities.")?
+            .into_iter()
+            .map(|(range, activity_items)| {
+                (
+                    range,
+                    activity_items
+                        .iter()
+                        .map(|activity_item| activity_item.activity().description().clone())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        assert_eq!(
+            activities_by_duration_range,
+            BTreeMap::from([
+                (PaceDurationRange::Short, vec!["Quick fix".to_string()]),
+                (PaceDurationRange::Medium, vec!["Review".to_string()]),
+                (PaceDurationRange::Long, vec!["Deep work".to_string()]),
+            ]),
+            "Intermissions should not be grouped."
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite_list_activities_by_time_range_passes() -> TestResult<()> {
+        let storage = storage()?;
+
+        let mut guids = BTreeMap::new();
+
+        // The same day in different offsets, compared by their point in time
+        for (description, begin) in [
+            ("Before", "2024-03-04T23:30:00+00:00"),
+            ("Early", "2024-03-05T00:30:00+01:00"),
+            ("Within", "2024-03-05T09:00:00+01:00"),
+            ("End", "2024-03-05T23:59:59+00:00"),
+            ("After", "2024-03-06T01:00:00+00:00"),
+        ] {
+            let item = storage.create_activity(
+                Activity::builder()
+                    .description(description)
+                    .begin(begin.parse::<PaceDateTime>()?)
+                    .build(),
+            )?;
+
+            _ = guids.insert(*item.guid(), description);
+        }
+
+        let time_range_opts = TimeRangeOptions::builder()
+            .start("2024-03-05T00:00:00+00:00".parse::<PaceDateTime>()?)
+            .end("2024-03-05T23:59:59+00:00".parse::<PaceDateTime>()?)
+            .build();
+
+        let mut descriptions = storage
+            .list_activities_by_time_range(time_range_opts)?
+            .ok_or("Should have activities.")?
+            .iter()
+            .filter_map(|guid| guids.get(guid).copied())
+            .collect::<Vec<_>>();
+        descriptions.sort_unstable();
+
+        assert_eq!(descriptions, vec!["End", "Within"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite_list_activity_items_by_time_range_includes_intermissions_passes(
+    ) -> TestResult<()> {
+        let storage = storage()?;
+
+        for (description, kind, begin) in [
+            (
+                "Before",
+                ActivityKind::Activity,
+                "2024-03-04T23:30:00+00:00",
+            ),
+            (
+                "Within",
+                ActivityKind::Activity,
+                "2024-03-05T09:00:00+01:00",
+            ),
+            (
+                "Break",
+                ActivityKind::Intermission,
+                "2024-03-05T10:00:00+01:00",
+            ),
+            ("After", ActivityKind::Activity, "2024-03-06T01:00:00+00:00"),
+        ] {
+            _ = storage.create_activity(
+                Activity::builder()
+                    .description(description)
+                    .kind(kind)
+                    .begin(begin.parse::<PaceDateTime>()?)
+                    .build(),
+            )?;
+        }
+
+        let time_range_opts = TimeRangeOptions::builder()
+            .start("2024-03-05T00:00:00+00:00".parse::<PaceDateTime>()?)
+            .end("2024-03-05T23:59:59+00:00".parse::<PaceDateTime>()?)
+            .build();
+
+        let mut descriptions = storage
+            .list_activity_items_by_time_range(time_range_opts)?
+            .ok_or("Should have activities.")?
+            .iter()
+            .map(|item| item.activity().description().clone())
+            .collect::<Vec<_>>();
+        descriptions.sort_unstable();
+
+        assert_eq!(descriptions, vec!["Break", "Within"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite_group_activities_by_keywords_passes() -> TestResult<()> {
+        let storage = storage()?;
+
+        for category in [
+            "Development::Pace",
+            "development::rustic",
+            "100%_done",
+            "1000 done",
+        ] {
+            let _ = storage.create_activity(
+                Activity::builder()
+                    .description("Work")
+                    .category(category.to_string())
+                    .begin(PaceDateTime::now())
+                    .build(),
+            )?;
+        }
+
+        let _ = storage.create_activity(
+            Activity::builder()
+                .description("Uncategorized")
+                .begin(PaceDateTime::now())
+                .build(),
+        )?;
+
+        let activities_by_keyword = storage
+            .group_activities_by_keywords(
+                KeywordOptions::builder().category("DEVELOPMENT").build(),
+            )?
+            .ok_or("Should have activities.")?;
+
+        assert_eq!(
+            activities_by_keyword.get("development").map(Vec::len),
+            Some(2),
+            "Should match the category ignoring its case."
+        );
+
+        let activities_by_keyword = storage
+            .group_activities_by_keywords(KeywordOptions::builder().category("0%_").build())?
+            .ok_or("Should have activities.")?;
+
+        assert_eq!(
+            activities_by_keyword.get("0%_").map(Vec::len),
+            Some(1),
+            "Should match `%` and `_` literally."
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite_delete_activity_removes_intermissions_passes() -> TestResult<()> {
+        let storage = storage()?;
+
+        let item = storage.begin_activity(Activity::builder().description("Test").build())?;
+
+        let _ = storage.hold_activity(*item.guid(), HoldOptions::default())?;
+
+        assert!(storage.list_activities_with_intermissions()?.is_some());
+
+        let _ = storage.delete_activity(*item.guid(), DeleteOptions::default())?;
+
+        assert!(storage
+            .list_activities(ActivityFilterKind::Everything)?
+            .is_none());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,250 @@
+//! Versioned schema migrations for the `SQLite` storage backend
+//!
+//! Migrations are embedded into the binary and applied in order of their version.
+//! The versions that have been applied are recorded in the `pace_schema_migrations` table.
+
+use diesel::{connection::SimpleConnection, prelude::*, sql_types::Bool, SqliteConnection};
+use getset::Getters;
+use pace_time::date_time::PaceDateTime;
+use tracing::debug;
+
+use crate::error::{PaceError, PaceErrorKind, PaceResult};
+
+// The `table!` macro generates fully qualified paths
+#[allow(unused_qualifications)]
+mod schema {
+    diesel::table! {
+        pace_schema_migrations (version) {
+            version -> Integer,
+            name -> Text,
+            applied_at -> Text,
+        }
+    }
+}
+
+use self::schema::pace_schema_migrations;
+
+/// A schema migration embedded into the binary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct Migration {
+    /// The schema version the database has after applying the migration
+    version: i32,
+
+    /// A short name describing the migration
+    name: &'static str,
+
+    /// The SQL statements of the migration
+    sql: &'static str,
+}
+
+/// All migrations, ordered by their version
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create_activities_and_tags",
+        sql: include_str!("migrations/0001_create_activities_and_tags.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "add_task_id_to_activities",
+        sql: include_str!("migrations/0002_add_task_id_to_activities.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "add_projects_to_activities",
+        sql: include_str!("migrations/0003_add_projects_to_activities.sql"),
+    },
+];
+
+/// The latest schema version this version of pace supports
+pub const LATEST_SCHEMA_VERSION: i32 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+/// SQL statement to create the table that records the applied migrations
+const CREATE_SCHEMA_MIGRATIONS_SQL: &str = r"
+CREATE TABLE IF NOT EXISTS pace_schema_migrations (
+    version INTEGER PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    applied_at TEXT NOT NULL
+);
+";
+
+/// Get the schema version of the database
+///
+/// # Arguments
+///
+/// * `connection` - The connection to the database
+///
+/// # Errors
+///
+/// Returns an error if the schema version cannot be read
+///
+/// # Returns
+///
+/// The schema version of the database, `0` if no migration has been applied yet
+pub fn schema_version(connection: &mut SqliteConnection) -> PaceResult<i32> {
+    // We don't want to create the table here, so a dry run doesn't change the database
+    let has_migrations_table = diesel::select(diesel::dsl::sql::<Bool>(
+        "EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'pace_schema_migrations')",
+    ))
+    .get_result::<bool>(connection)?;
+
+    if !has_migrations_table {
+        return Ok(0);
+    }
+
+    let version = pace_schema_migrations::table
+        .select(diesel::dsl::max(pace_schema_migrations::version))
+        .first::<Option<i32>>(connection)?
+        .unwrap_or_default();
+
+    Ok(version)
+}
+
+/// Get the migrations that have not been applied to the database, yet
+///
+/// # Arguments
+///
+/// * `connection` - The connection to the database
+///
+/// # Errors
+///
+/// Returns an error if the schema version cannot be read or the database
+/// has a newer schema version than this version of pace supports
+///
+/// # Returns
+///
+/// The pending migrations ordered by their version
+pub fn pending_migrations(connection: &mut SqliteConnection) -> PaceResult<Vec<Migration>> {
+    let database_version = schema_version(connection)?;
+
+    debug!(
+        "Database schema version: {}, latest supported version: {}",
+        database_version, LATEST_SCHEMA_VERSION
+    );
+
+    if database_version > LATEST_SCHEMA_VERSION {
+        return Err(
+            PaceErrorKind::DatabaseSchemaTooNew(database_version, LATEST_SCHEMA_VERSION).into(),
+        );
+    }
+
+    Ok(MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > database_version)
+        .copied()
+        .collect())
+}
+
+/// Apply all pending migrations to the database
+///
+/// Every migration is applied in its own transaction together with recording
+/// its version, so a failing migration leaves the database at the previous version.
+///
+/// # Arguments
+///
+/// * `connection` - The connection to the database
+/// * `dry_run` - Only return the pending migrations without applying them
+///
+/// # Errors
+///
+/// Returns an error if the pending migrations cannot be determined or a migration fails
+///
+/// # Returns
+///
+/// The migrations that have been applied (or would have been applied in a dry run)
+pub fn run_migrations(
+    connection: &mut SqliteConnection,
+    dry_run: bool,
+) -> PaceResult<Vec<Migration>> {
+    let pending = pending_migrations(connection)?;
+
+    if dry_run {
+        debug!("Dry run, not applying migrations: {:?}", pending);
+
+        return Ok(pending);
+    }
+
+    connection.batch_execute(CREATE_SCHEMA_MIGRATIONS_SQL)?;
+
+    for migration in &pending {
+        debug!(
+            "Applying migration {}: {}",
+            migration.version, migration.name
+        );
+
+        connection.transaction::<_, PaceError, _>(|connection| {
+            connection.batch_execute(migration.sql)?;
+
+            _ = diesel::insert_into(pace_schema_migrations::table)
+                .values((
+                    pace_schema_migrations::version.eq(migration.version),
+                    pace_schema_migrations::name.eq(migration.name),
+                    pace_schema_migrations::applied_at.eq(PaceDateTime::now().to_string()),
+                ))
+                .execute(connection)?;
+
+            Ok(())
+        })?;
+    }
+
+    Ok(pending)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::error::TestResult;
+
+    #[test]
+    fn test_migrations_are_ordered_passes() {
+        assert!(MIGRATIONS
+            .windows(2)
+            .all(|window| window[0].version < window[1].version));
+    }
+
+    #[test]
+    fn test_run_migrations_passes() -> TestResult<()> {
+        let mut connection = SqliteConnection::establish(":memory:")?;
+
+        assert_eq!(schema_version(&mut connection)?, 0);
+
+        let pending = run_migrations(&mut connection, true)?;
+
+        assert_eq!(pending.len(), MIGRATIONS.len());
+        assert_eq!(schema_version(&mut connection)?, 0);
+
+        let applied = run_migrations(&mut connection, false)?;
+
+        assert_eq!(applied, pending);
+        assert_eq!(schema_version(&mut connection)?, LATEST_SCHEMA_VERSION);
+        assert!(run_migrations(&mut connection, false)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_refuse_newer_database_schema_fails() -> TestResult<()> {
+        let mut connection = SqliteConnection::establish(":memory:")?;
+
+        _ = run_migrations(&mut connection, false)?;
+
+        _ = diesel::insert_into(pace_schema_migrations::table)
+            .values((
+                pace_schema_migrations::version.eq(LATEST_SCHEMA_VERSION + 1),
+                pace_schema_migrations::name.eq("from_the_future"),
+                pace_schema_migrations::applied_at.eq(PaceDateTime::now().to_string()),
+            ))
+            .execute(&mut connection)?;
+
+        let result = pending_migrations(&mut connection);
+
+        assert!(matches!(
+            result.map_err(PaceError::into_inner),
+            Err(PaceErrorKind::DatabaseSchemaTooNew(..))
+        ));
+
+        Ok(())
+    }
+}
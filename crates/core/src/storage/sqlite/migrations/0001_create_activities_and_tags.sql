@@ -0,0 +1,22 @@
+-- Activities, including intermissions, which reference their parent activity
+-- via `parent_id`, so they are removed together with the activity they belong to.
+CREATE TABLE IF NOT EXISTS activities (
+    guid TEXT PRIMARY KEY NOT NULL,
+    category TEXT,
+    description TEXT NOT NULL,
+    begin_time TEXT NOT NULL,
+    end_time TEXT,
+    duration BIGINT,
+    kind TEXT NOT NULL,
+    status TEXT NOT NULL,
+    parent_id TEXT REFERENCES activities (guid) ON DELETE CASCADE,
+    pomodoro_cycle TEXT
+);
+
+CREATE INDEX IF NOT EXISTS activities_parent_id_idx ON activities (parent_id);
+
+CREATE TABLE IF NOT EXISTS activity_tags (
+    activity_guid TEXT NOT NULL REFERENCES activities (guid) ON DELETE CASCADE,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (activity_guid, tag)
+);
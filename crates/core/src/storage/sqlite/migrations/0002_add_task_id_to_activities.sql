@@ -0,0 +1,2 @@
+-- Activities can be tracked for a task from the tasks file
+ALTER TABLE activities ADD COLUMN task_id TEXT;
@@ -0,0 +1,3 @@
+-- Activities can be tracked for a project from a projects file, the column
+-- holds a JSON array with the project followed by its parent projects
+ALTER TABLE activities ADD COLUMN projects TEXT;
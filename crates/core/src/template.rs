@@ -1,23 +1,267 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
 
-use once_cell::sync::Lazy;
-use pace_time::duration::PaceDuration;
-use tera::{from_value, to_value, Context, Error, Tera, Value};
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, NaiveDate,
+};
+use chrono_tz::Tz;
+use pace_time::{
+    date::PaceDate,
+    date_time::PaceDateTime,
+    duration::{PaceDuration, PaceDurationRangeOptions},
+};
+use serde_derive::Serialize;
+use tera::{from_value, to_value, Context, Error, Filter, Tera, Value};
+use tracing::debug;
 
-use crate::domain::reflection::{ReflectionSummary, SummaryActivityGroup};
+use crate::{
+    config::{PaceConfig, ReflectionsConfig},
+    domain::{
+        activity::{ActivityItem, ActivitySession},
+        billing::Invoice,
+        reflection::{
+            ReflectionSummary, SummaryActivityGroup, SummaryProjectGroup, SummaryTaskGroup,
+        },
+        rounding::{RoundingModeKind, RoundingOptions, RoundingScopeKind},
+        statistics::ReflectionStatistics,
+    },
+    error::TemplatingErrorKind,
+};
 
-pub static TEMPLATES: Lazy<Tera> = Lazy::new(|| {
-    let mut tera = match Tera::new("templates/reflections/**") {
-        Ok(t) => t,
-        Err(e) => {
-            println!("Parsing error(s): {e}");
-            ::std::process::exit(1);
-        }
-    };
-    tera.autoescape_on(vec![".html", ".sql"]);
-    tera.register_filter("human_duration", human_duration);
-    tera
-});
+/// The reflection templates bundled with pace, by their name
+const BUNDLED_TEMPLATES: [(&str, &str); 4] = [
+    ("basic.html", include_str!("template/basic.html")),
+    ("basic.md", include_str!("template/basic.md")),
+    ("invoice.md", include_str!("template/invoice.md")),
+    ("timesheet.md", include_str!("template/timesheet.md")),
+];
+
+/// The format of the `format_date` filter, if none is given
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// The template used for reflections, if none has been selected
+pub const DEFAULT_TEMPLATE_NAME: &str = "basic.html";
+
+/// The name of the directory with the user templates, within the reflections directory
+pub const USER_TEMPLATES_DIRECTORY: &str = "templates";
+
+/// The templates reflections can be rendered with
+///
+/// The templates bundled with pace are always available. The templates in the
+/// user template directory are layered on top of them, so a user template with
+/// the name of a bundled one, e.g. `basic.html`, replaces it.
+#[derive(Debug)]
+pub struct PaceTemplates {
+    tera: Tera,
+}
+
+impl PaceTemplates {
+    /// Load the bundled templates and the templates of the user template directory
+    ///
+    /// # Arguments
+    ///
+    /// * `user_directory` - The directory with the user templates, it may not exist
+    /// * `time_zone` - The time zone the `format_date` filter converts to, timestamps keep their offset if `None`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a template cannot be parsed
+    ///
+    /// # Returns
+    ///
+    /// The loaded templates
+    pub fn new(
+        user_directory: Option<&Path>,
+        time_zone: Option<Tz>,
+    ) -> Result<Self, TemplatingErrorKind> {
+        let mut bundled = Tera::default();
+
+        bundled
+            .add_raw_templates(BUNDLED_TEMPLATES)
+            .map_err(|err| {
+                TemplatingErrorKind::LoadingTemplatesFailed("pace".to_string(), error_chain(&err))
+            })?;
+
+        let mut tera = match user_directory.filter(|directory| directory.is_dir()) {
+            Some(directory) => {
+                debug!("Loading user templates from {}", directory.display());
+
+                let mut user =
+                    Tera::new(&format!("{}/**/*", directory.display())).map_err(|err| {
+                        TemplatingErrorKind::LoadingTemplatesFailed(
+                            directory.display().to_string(),
+                            error_chain(&err),
+                        )
+                    })?;
+
+                // Keeps the user templates named like bundled ones
+                user.extend(&bundled).map_err(|err| {
+                    TemplatingErrorKind::LoadingTemplatesFailed(
+                        directory.display().to_string(),
+                        error_chain(&err),
+                    )
+                })?;
+
+                user
+            }
+            None => bundled,
+        };
+
+        tera.autoescape_on(vec![".html", ".sql"]);
+        tera.register_filter("human_duration", human_duration);
+        tera.register_filter("decimal_hours", decimal_hours);
+        tera.register_filter("round_duration", round_duration);
+        tera.register_filter("format_amount", format_amount);
+        tera.register_filter("format_date", format_date(time_zone));
+
+        Ok(Self { tera })
+    }
+
+    /// Load the bundled templates and the templates of the user template directory in the configuration
+    ///
+    /// The user templates are read from the `templates` directory within the reflections
+    /// directory, dates are formatted in the default time zone of the configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the pace application
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a template cannot be parsed
+    ///
+    /// # Returns
+    ///
+    /// The loaded templates
+    pub fn from_config(config: &PaceConfig) -> Result<Self, TemplatingErrorKind> {
+        let user_directory = config
+            .reflections()
+            .as_ref()
+            .map(ReflectionsConfig::directory)
+            .filter(|directory| !directory.as_os_str().is_empty())
+            .map(|directory| directory.join(USER_TEMPLATES_DIRECTORY));
+
+        Self::new(
+            user_directory.as_deref(),
+            *config.general().default_time_zone(),
+        )
+    }
+
+    /// The names of all available templates, sorted
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        let mut names = self.tera.get_template_names().collect::<Vec<_>>();
+
+        names.sort_unstable();
+
+        names
+    }
+
+    /// Render a template by its name
+    ///
+    /// The extension of the name can be left out, as long as only one template has that name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the template, e.g. `basic.md` or `weekly`
+    /// * `context` - The context to render the template with
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no template with that name or it cannot be rendered
+    ///
+    /// # Returns
+    ///
+    /// The rendered template
+    pub fn render(&self, name: &str, context: &Context) -> Result<String, TemplatingErrorKind> {
+        let names = self.names();
+
+        let template_name = if names.contains(&name) {
+            name
+        } else {
+            match names
+                .iter()
+                .filter(|template_name| {
+                    Path::new(template_name).with_extension("") == Path::new(name)
+                })
+                .collect::<Vec<_>>()
+                .as_slice()
+            {
+                [template_name] => template_name,
+                _ => {
+                    return Err(TemplatingErrorKind::TemplateNotFound(
+                        name.to_string(),
+                        names.join(", "),
+                    ))
+                }
+            }
+        };
+
+        self.tera
+            .render(template_name, context)
+            .map_err(|err| TemplatingErrorKind::RenderingToTemplateFailed(error_chain(&err)))
+    }
+
+    /// Render a template file given by the user
+    ///
+    /// The template can extend or include the other templates, e.g. `{% extends "basic.html" %}`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the template file
+    /// * `context` - The context to render the template with
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the template file cannot be read, parsed or rendered
+    ///
+    /// # Returns
+    ///
+    /// The rendered template
+    pub fn render_file(
+        &self,
+        path: &Path,
+        context: &Context,
+    ) -> Result<String, TemplatingErrorKind> {
+        let template =
+            std::fs::read_to_string(path).map_err(TemplatingErrorKind::FailedToReadTemplateFile)?;
+
+        let name = path
+            .file_name()
+            .unwrap_or(path.as_os_str())
+            .to_string_lossy()
+            .to_string();
+
+        let mut tera = self.tera.clone();
+
+        tera.add_raw_template(&name, &template).map_err(|err| {
+            TemplatingErrorKind::LoadingTemplatesFailed(
+                path.display().to_string(),
+                error_chain(&err),
+            )
+        })?;
+
+        tera.render(&name, context)
+            .map_err(|err| TemplatingErrorKind::RenderingToTemplateFailed(error_chain(&err)))
+    }
+}
+
+/// The message of a Tera error with its causes, which hold the details, e.g. the line of a syntax error
+fn error_chain(err: &Error) -> String {
+    let mut messages = vec![err.to_string().trim().to_string()];
+
+    let mut source = std::error::Error::source(err);
+
+    while let Some(err) = source {
+        messages.push(err.to_string().trim().to_string());
+        source = err.source();
+    }
+
+    messages.join(": ")
+}
 
 /// Returns the human duration of the argument.
 pub fn human_duration(value: &Value, _: &HashMap<String, Value>) -> Result<Value, Error> {
@@ -30,6 +274,292 @@ pub fn human_duration(value: &Value, _: &HashMap<String, Value>) -> Result<Value
     to_value(duration.human_readable()).map_err(Error::json)
 }
 
+/// Returns the duration of the argument in hours, e.g. `1.75` for 1h 45m.
+///
+/// The `precision` argument is the number of decimal places, `2` by default.
+pub fn decimal_hours(value: &Value, args: &HashMap<String, Value>) -> Result<Value, Error> {
+    let duration = duration_value("decimal_hours", value)?;
+
+    let precision = filter_argument::<i32>("decimal_hours", args, "precision")?.unwrap_or(2);
+
+    let factor = 10_f64.powi(precision);
+
+    #[allow(clippy::cast_precision_loss)]
+    let hours = duration.as_secs() as f64 / 3600.0;
+
+    to_value((hours * factor).round() / factor).map_err(Error::json)
+}
+
+/// Returns the duration of the argument rounded to a multiple of the `minutes` argument, `15` by default.
+///
+/// The `mode` argument is one of `nearest` (default), `up` or `down`.
+pub fn round_duration(value: &Value, args: &HashMap<String, Value>) -> Result<Value, Error> {
+    let duration = duration_value("round_duration", value)?;
+
+    let minutes = filter_argument::<u64>("round_duration", args, "minutes")?.unwrap_or(15);
+
+    if minutes == 0 {
+        return Err(Error::msg(
+            "Filter `round_duration` needs a `minutes` argument greater than 0",
+        ));
+    }
+
+    let mode = filter_argument::<RoundingModeKind>("round_duration", args, "mode")?
+        .unwrap_or(RoundingModeKind::Nearest);
+
+    to_value(mode.round(duration, PaceDuration::new(minutes * 60))).map_err(Error::json)
+}
+
+/// Returns the amount of money of the argument with the `precision` argument as decimal places, `2` by default.
+pub fn format_amount(value: &Value, args: &HashMap<String, Value>) -> Result<Value, Error> {
+    let amount = from_value::<f64>(value.clone()).map_err(|_| {
+        Error::msg(format!(
+            "Filter `format_amount` received an invalid argument: `{value:?}`, expected a number"
+        ))
+    })?;
+
+    let precision = filter_argument::<usize>("format_amount", args, "precision")?.unwrap_or(2);
+
+    to_value(format!("{amount:.precision$}")).map_err(Error::json)
+}
+
+/// Returns a filter formatting a timestamp or date with the `format` argument, `%Y-%m-%d %H:%M` by default.
+///
+/// Timestamps are converted to the `timezone` argument, e.g. `Europe/Berlin`, which
+/// defaults to the given time zone. Without a time zone they keep their offset.
+fn format_date(time_zone: Option<Tz>) -> impl Filter {
+    move |value: &Value, args: &HashMap<String, Value>| -> Result<Value, Error> {
+        let format = filter_argument::<String>("format_date", args, "format")?
+            .unwrap_or_else(|| DEFAULT_DATE_FORMAT.to_string());
+
+        if StrftimeItems::new(&format).any(|item| matches!(item, Item::Error)) {
+            return Err(Error::msg(format!(
+                "Filter `format_date` received an invalid format: `{format}`"
+            )));
+        }
+
+        let time_zone = match filter_argument::<String>("format_date", args, "timezone")? {
+            Some(time_zone) => Some(time_zone.parse::<Tz>().map_err(|_| {
+                Error::msg(format!(
+                    "Filter `format_date` received an invalid time zone: `{time_zone}`"
+                ))
+            })?),
+            None => time_zone,
+        };
+
+        let text = value.as_str().unwrap_or_default();
+
+        let formatted = if let Ok(date_time) = DateTime::parse_from_rfc3339(text) {
+            time_zone
+                .map_or_else(
+                    || date_time.format(&format),
+                    |time_zone| date_time.with_timezone(&time_zone).format(&format),
+                )
+                .to_string()
+        } else if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+            date.format(&format).to_string()
+        } else {
+            return Err(Error::msg(format!(
+                "Filter `format_date` received an invalid argument: `{value:?}`"
+            )));
+        };
+
+        to_value(formatted).map_err(Error::json)
+    }
+}
+
+/// The duration a filter has been applied to
+fn duration_value(filter: &str, value: &Value) -> Result<PaceDuration, Error> {
+    from_value::<PaceDuration>(value.clone()).map_err(|_| {
+        Error::msg(format!(
+            "Filter `{filter}` received an invalid argument: `{value:?}`, expected a duration in seconds"
+        ))
+    })
+}
+
+/// An optional argument of a filter
+fn filter_argument<T: serde::de::DeserializeOwned>(
+    filter: &str,
+    args: &HashMap<String, Value>,
+    name: &str,
+) -> Result<Option<T>, Error> {
+    args.get(name)
+        .map(|value| {
+            from_value::<T>(value.clone()).map_err(|_| {
+                Error::msg(format!(
+                    "Filter `{filter}` received an invalid `{name}` argument: `{value:?}`"
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// The sessions by the day they began on and the time spent on each tag
+///
+/// The days have a rounded duration if the sessions are rounded on their own or per day.
+fn days_and_tags<'a>(
+    sessions: &[SessionContext<'a>],
+    rounding: Option<RoundingOptions>,
+) -> (Vec<DayContext<'a>>, Vec<TagContext<'a>>) {
+    let mut days = BTreeMap::<PaceDate, DayContext<'_>>::new();
+    let mut tags = BTreeMap::<&str, TagContext<'_>>::new();
+
+    // The time spent on each activity per day, to round it when rounding per day
+    let mut activities_by_day = BTreeMap::<_, PaceDuration>::new();
+
+    for session in sessions {
+        let date = PaceDate::from(session.begin);
+
+        let day = days.entry(date).or_insert_with(|| DayContext {
+            date,
+            duration: PaceDuration::default(),
+            rounded_duration: None,
+            break_duration: PaceDuration::default(),
+            sessions: vec![],
+        });
+
+        day.duration += session.duration;
+        day.break_duration += session.break_duration;
+        day.sessions.push(session.clone());
+
+        if let Some(rounded_duration) = session.rounded_duration {
+            *day.rounded_duration
+                .get_or_insert_with(PaceDuration::default) += rounded_duration;
+        }
+
+        *activities_by_day
+            .entry((date, session.category, session.description))
+            .or_default() += session.duration;
+
+        for name in &session.tags {
+            let tag = tags.entry(name).or_insert_with(|| TagContext {
+                name,
+                duration: PaceDuration::default(),
+                session_count: 0,
+            });
+
+            tag.duration += session.duration;
+            tag.session_count += 1;
+        }
+    }
+
+    if let Some(rounding) = rounding.filter(|rounding| *rounding.scope() == RoundingScopeKind::Day)
+    {
+        for ((date, ..), duration) in activities_by_day {
+            if let Some(day) = days.get_mut(&date) {
+                *day.rounded_duration
+                    .get_or_insert_with(PaceDuration::default) += rounding.round(duration);
+            }
+        }
+    }
+
+    (days.into_values().collect(), tags.into_values().collect())
+}
+
+/// The totals of the review period, durations are in seconds
+#[derive(Debug, Serialize)]
+struct TotalsContext {
+    duration: PaceDuration,
+    rounded_duration: Option<PaceDuration>,
+    break_duration: PaceDuration,
+    session_count: usize,
+}
+
+/// An activity session, durations are in seconds
+#[derive(Debug, Clone, Serialize)]
+struct SessionContext<'a> {
+    id: String,
+    description: &'a str,
+    category: Option<&'a str>,
+    tags: Vec<&'a str>,
+    task_id: Option<String>,
+    begin: PaceDateTime,
+    end: Option<PaceDateTime>,
+    duration: PaceDuration,
+    rounded_duration: Option<PaceDuration>,
+    break_duration: PaceDuration,
+    intermissions: Vec<IntermissionContext>,
+}
+
+impl<'a> From<&'a ActivitySession> for SessionContext<'a> {
+    fn from(session: &'a ActivitySession) -> Self {
+        let activity = session.root_activity().activity();
+
+        let mut tags = activity
+            .tags()
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        tags.sort_unstable();
+
+        Self {
+            id: session.root_activity().guid().to_string(),
+            description: session.description(),
+            category: activity.category().as_deref(),
+            tags,
+            task_id: activity.task_id().map(|task_id| task_id.to_string()),
+            begin: *activity.begin(),
+            end: activity
+                .activity_end_options()
+                .as_ref()
+                .map(|end_opts| *end_opts.end()),
+            duration: *session.adjusted_duration(),
+            rounded_duration: None,
+            break_duration: *session.intermission_duration(),
+            intermissions: session
+                .intermissions()
+                .iter()
+                .map(IntermissionContext::from)
+                .collect(),
+        }
+    }
+}
+
+/// An intermission of an activity session, the duration is in seconds
+#[derive(Debug, Clone, Serialize)]
+struct IntermissionContext {
+    id: String,
+    begin: PaceDateTime,
+    end: Option<PaceDateTime>,
+    duration: PaceDuration,
+}
+
+impl From<&ActivityItem> for IntermissionContext {
+    fn from(intermission: &ActivityItem) -> Self {
+        let activity = intermission.activity();
+
+        Self {
+            id: intermission.guid().to_string(),
+            begin: *activity.begin(),
+            end: activity
+                .activity_end_options()
+                .as_ref()
+                .map(|end_opts| *end_opts.end()),
+            duration: activity.duration_or_elapsed(),
+        }
+    }
+}
+
+/// The activity sessions that began on a day, durations are in seconds
+#[derive(Debug, Serialize)]
+struct DayContext<'a> {
+    date: PaceDate,
+    duration: PaceDuration,
+    rounded_duration: Option<PaceDuration>,
+    break_duration: PaceDuration,
+    sessions: Vec<SessionContext<'a>>,
+}
+
+/// The time spent on the activity sessions with a tag, the duration is in seconds
+#[derive(Debug, Serialize)]
+struct TagContext<'a> {
+    name: &'a str,
+    duration: PaceDuration,
+    session_count: usize,
+}
+
 #[derive(Debug)]
 pub struct PaceReflectionTemplate {
     context: Context,
@@ -39,6 +569,31 @@ impl PaceReflectionTemplate {
     pub fn into_context(self) -> Context {
         self.context
     }
+
+    /// Add an invoice for the reflection, available as `invoice` to the templates
+    ///
+    /// # Arguments
+    ///
+    /// * `invoice` - The invoice computed from the reflection
+    #[must_use]
+    pub fn with_invoice(mut self, invoice: &Invoice) -> Self {
+        self.context.insert("invoice", invoice);
+
+        self
+    }
+
+    /// Set the time zone the reflection is shown in, available as `time_zone` to the templates
+    ///
+    /// # Arguments
+    ///
+    /// * `time_zone` - The configured time zone, if any
+    #[must_use]
+    pub fn with_time_zone(mut self, time_zone: Option<Tz>) -> Self {
+        self.context
+            .insert("time_zone", time_zone.map(Tz::name).unwrap_or_default());
+
+        self
+    }
 }
 
 impl From<ReflectionSummary> for PaceReflectionTemplate {
@@ -71,6 +626,97 @@ impl From<ReflectionSummary> for PaceReflectionTemplate {
 
         context.insert("summary_groups_by_category", &summary_groups_by_category);
 
+        let summary_groups_by_task = value
+            .summary_groups_by_task()
+            .iter()
+            .map(|(task_id, task_group)| (task_id.to_string(), task_group))
+            .collect::<HashMap<String, &SummaryTaskGroup>>();
+
+        context.insert("summary_groups_by_task", &summary_groups_by_task);
+
+        let summary_groups_by_project = value
+            .summary_groups_by_project()
+            .iter()
+            .map(|(project_id, project_group)| (project_id.to_string(), project_group))
+            .collect::<HashMap<String, &SummaryProjectGroup>>();
+
+        context.insert("summary_groups_by_project", &summary_groups_by_project);
+
+        if let Some(statistics) = value.statistics() {
+            context.insert("statistics", statistics);
+        }
+
+        if let Some(comparison) = value.comparison() {
+            context.insert("comparison", comparison);
+        }
+
+        context.insert("recommendations", value.recommendations());
+        context.insert("goals", value.goals());
+
+        let rounding = *value.rounding();
+
+        // Sessions only have a rounded duration of their own, if they are rounded on their own
+        let session_rounding =
+            rounding.filter(|rounding| *rounding.scope() == RoundingScopeKind::Session);
+
+        let mut sessions = value
+            .activity_sessions()
+            .map(|session| {
+                let mut context = SessionContext::from(session);
+
+                context.rounded_duration =
+                    session_rounding.map(|rounding| rounding.round(*session.adjusted_duration()));
+
+                context
+            })
+            .collect::<Vec<_>>();
+
+        sessions.sort_by_key(|session| session.begin);
+
+        context.insert(
+            "totals",
+            &TotalsContext {
+                duration: *value.total_time_spent(),
+                rounded_duration: *value.rounded_time_spent(),
+                break_duration: *value.total_break_duration(),
+                session_count: sessions.len(),
+            },
+        );
+
+        let (days, tags) = days_and_tags(&sessions, rounding);
+
+        context.insert("days", &days);
+        context.insert("tags", &tags);
+        context.insert("sessions", &sessions);
+
+        // The highlights are cheap to compute, so they don't need the `--detailed` flag
+        let highlights = value.statistics().as_ref().map_or_else(
+            || {
+                ReflectionStatistics::from_summary(&value, PaceDurationRangeOptions::default())
+                    .highlights()
+                    .clone()
+            },
+            |statistics| statistics.highlights().clone(),
+        );
+
+        if let Some(highlights) = highlights {
+            context.insert("highlights", &highlights);
+        }
+
+        context.insert(
+            "rounded_time_spent",
+            &value
+                .rounded_time_spent()
+                .map(|rounded_time_spent| rounded_time_spent.human_readable()),
+        );
+        context.insert("rounding", &rounding);
+        context.insert(
+            "rounding_description",
+            &rounding.map(|rounding| rounding.to_string()),
+        );
+
+        context.insert("time_zone", "");
+
         Self { context }
     }
 }
@@ -78,6 +724,15 @@ impl From<ReflectionSummary> for PaceReflectionTemplate {
 #[cfg(test)]
 mod tests {
 
+    use std::sync::Arc;
+
+    use pace_time::time_range::TimeRangeOptions;
+
+    use crate::{
+        domain::filter::FilterOptions, error::TestResult, service::activity_store::ActivityStore,
+        storage::file::TomlActivityStorage,
+    };
+
     use super::*;
 
     #[test]
@@ -90,4 +745,171 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_template_filter_decimal_hours_passes() -> Result<(), Error> {
+        let args = HashMap::from([("precision".to_string(), to_value(1)?)]);
+
+        assert_eq!(
+            decimal_hours(&to_value(6300)?, &HashMap::default())?,
+            to_value(1.75)?
+        );
+        assert_eq!(decimal_hours(&to_value(6300)?, &args)?, to_value(1.8)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_filter_round_duration_passes() -> Result<(), Error> {
+        let round = |mode: &str| {
+            round_duration(
+                &to_value(20 * 60)?,
+                &HashMap::from([("mode".to_string(), to_value(mode)?)]),
+            )
+        };
+
+        assert_eq!(round("nearest")?, to_value(15 * 60)?);
+        assert_eq!(round("up")?, to_value(30 * 60)?);
+        assert_eq!(round("down")?, to_value(15 * 60)?);
+        assert!(round("sideways").is_err());
+        assert!(round_duration(
+            &to_value(60)?,
+            &HashMap::from([("minutes".to_string(), to_value(0)?)])
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_filter_format_date_passes() -> Result<(), Error> {
+        let filter = format_date(Some(Tz::Europe__Berlin));
+
+        let value = to_value("2024-02-27T10:48:39+00:00")?;
+
+        assert_eq!(
+            filter.filter(&value, &HashMap::default())?,
+            to_value("2024-02-27 11:48")?
+        );
+        assert_eq!(
+            filter.filter(
+                &value,
+                &HashMap::from([
+                    ("format".to_string(), to_value("%H:%M %Z")?),
+                    ("timezone".to_string(), to_value("UTC")?)
+                ])
+            )?,
+            to_value("10:48 UTC")?
+        );
+        assert_eq!(
+            filter.filter(
+                &to_value("2024-02-27")?,
+                &HashMap::from([("format".to_string(), to_value("%d.%m.%Y")?)])
+            )?,
+            to_value("27.02.2024")?
+        );
+        assert!(filter
+            .filter(&to_value("yesterday")?, &HashMap::default())
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_context_renders_timesheet_passes() -> TestResult<()> {
+        let storage = TomlActivityStorage::new(
+            Path::new("../../tests/fixtures/activity_tracker/activities.pace.toml")
+                .canonicalize()?,
+        )?;
+
+        let activity_store = ActivityStore::with_storage(Arc::new(storage.into()))?;
+
+        let time_range_opts =
+            TimeRangeOptions::specific_date("2024-02-27".parse::<NaiveDate>()?.into())?;
+
+        let summary_groups = activity_store
+            .summary_groups_by_category_for_time_range(FilterOptions::default(), time_range_opts)?
+            .ok_or("Should have activities.")?;
+
+        let context =
+            PaceReflectionTemplate::from(ReflectionSummary::new(time_range_opts, summary_groups))
+                .with_time_zone(Some(Tz::Europe__Berlin))
+                .into_context();
+
+        let sessions = context.get("sessions").ok_or("Should have sessions.")?;
+
+        assert_eq!(sessions.as_array().map(Vec::len), Some(2));
+        assert_eq!(sessions[1]["begin"], "2024-02-27T11:48:56+01:00");
+        assert_eq!(sessions[1]["duration"], 34);
+        assert_eq!(sessions[1]["intermissions"][0]["duration"], 10);
+        assert_eq!(context.get("time_zone"), Some(&to_value("Europe/Berlin")?));
+        assert!(context.get("highlights").is_some());
+
+        let timesheet =
+            PaceTemplates::new(None, Some(Tz::Europe__Berlin))?.render("timesheet.md", &context)?;
+
+        assert!(
+            timesheet.contains("## Tuesday, 2024-02-27"),
+            "Should have a section per day:\n{timesheet}"
+        );
+        assert!(
+            timesheet.contains("| 11:48 | 11:49 | More Testing | development::rustic |  | 0.01 |"),
+            "Should have a row per session:\n{timesheet}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_user_templates_override_bundled_passes() -> TestResult<()> {
+        let temp_dir = tempfile::tempdir()?;
+
+        std::fs::write(
+            temp_dir.path().join("basic.md"),
+            "Mine: {{ total_time_spent }}",
+        )?;
+        std::fs::write(
+            temp_dir.path().join("weekly.md"),
+            "{{ total_time_spent | upper }}",
+        )?;
+
+        let templates = PaceTemplates::new(Some(temp_dir.path()), None)?;
+
+        assert_eq!(
+            templates.names(),
+            vec![
+                "basic.html",
+                "basic.md",
+                "invoice.md",
+                "timesheet.md",
+                "weekly.md"
+            ]
+        );
+
+        let mut context = Context::new();
+        context.insert("total_time_spent", "1h");
+
+        assert_eq!(templates.render("basic.md", &context)?, "Mine: 1h");
+        assert_eq!(templates.render("weekly", &context)?, "1H");
+        assert!(matches!(
+            templates.render("basic", &context),
+            Err(TemplatingErrorKind::TemplateNotFound(..))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_invalid_user_template_fails() -> TestResult<()> {
+        let temp_dir = tempfile::tempdir()?;
+
+        std::fs::write(temp_dir.path().join("broken.md"), "{% if %}")?;
+
+        assert!(matches!(
+            PaceTemplates::new(Some(temp_dir.path()), None),
+            Err(TemplatingErrorKind::LoadingTemplatesFailed(..))
+        ));
+
+        Ok(())
+    }
 }
@@ -1,3 +1,7 @@
+use serde::Serialize;
+
+use crate::error::PaceResult;
+
 /// Overwrite any value with another.
 ///
 /// This can be used to overwrite an activity with another activity.
@@ -10,6 +14,110 @@ pub fn overwrite_left_with_right<T>(left: &mut T, right: T) {
     *left = right;
 }
 
+/// Encode a value into the same textual representation it has in the activity log
+///
+/// Unit variants of enums are encoded as their plain name, e.g. `intermission`
+/// instead of `"intermission"`.
+///
+/// # Arguments
+///
+/// * `value` - The value to encode
+///
+/// # Errors
+///
+/// Returns an error if the value cannot be serialized
+///
+/// # Returns
+///
+/// The textual representation of the value
+pub fn to_plain_text<T: Serialize>(value: &T) -> PaceResult<String> {
+    match serde_json::to_value(value)? {
+        serde_json::Value::String(text) => Ok(text),
+        other => Ok(other.to_string()),
+    }
+}
+
+/// Escape a field for a CSV file as described in RFC 4180
+///
+/// Fields containing a separator, a quote or a line break are enclosed
+/// in double quotes, quotes within the field are doubled.
+///
+/// # Arguments
+///
+/// * `field` - The field to escape
+///
+/// # Returns
+///
+/// The escaped field
+#[must_use]
+pub fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Parse the records of a CSV file as described in RFC 4180
+///
+/// Fields can be enclosed in double quotes to contain separators, quotes
+/// and line breaks. Empty lines are skipped.
+///
+/// # Arguments
+///
+/// * `content` - The content of the CSV file
+///
+/// # Returns
+///
+/// The records of the CSV file, including the header row
+#[must_use]
+pub fn parse_csv_records(content: &str) -> Vec<Vec<String>> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+
+    let mut chars = content.chars().peekable();
+
+    while let Some(character) = chars.next() {
+        if in_quotes {
+            match character {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    _ = chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(character),
+            }
+
+            continue;
+        }
+
+        match character {
+            '"' => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+
+                if record.iter().any(|field| !field.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            _ => field.push(character),
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    records
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -33,6 +141,40 @@ mod tests {
         assert_eq!(left, "right");
     }
 
+    #[test]
+    fn test_escape_csv_field_passes() {
+        assert_eq!(escape_csv_field("plain"), "plain");
+        assert_eq!(escape_csv_field("a, b"), "\"a, b\"");
+        assert_eq!(escape_csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_parse_csv_records_passes() {
+        let records =
+            parse_csv_records("a,b,c\r\n\n\"x, y\",\"say \"\"hi\"\"\",\"two\nlines\"\n1,,3");
+
+        assert_eq!(
+            records,
+            vec![
+                vec!["a", "b", "c"],
+                vec!["x, y", "say \"hi\"", "two\nlines"],
+                vec!["1", "", "3"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_plain_text_passes() -> crate::error::TestResult<()> {
+        assert_eq!(
+            to_plain_text(&crate::domain::activity::ActivityKind::PomodoroWork)?,
+            "pomodoro-work"
+        );
+        assert_eq!(to_plain_text(&42)?, "42");
+
+        Ok(())
+    }
+
     #[test]
     fn test_overwrite_activity_passes() {
         let mut left = Activity::default();
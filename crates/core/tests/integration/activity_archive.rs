@@ -0,0 +1,106 @@
+//! Test the `ActivityArchive` with an `InMemoryStorage` backend.
+
+use std::sync::Arc;
+
+use chrono::{Datelike, Local};
+use pace_core::prelude::{
+    ActivityArchive, ActivityFilterKind, ActivityHistory, ActivityReadOps, FilteredActivities,
+    JournalOperationKind, SyncStorage, TestResult,
+};
+use pace_time::{date_time::PaceDateTime, time_range::TimeRangeOptions};
+
+use crate::util::{activity_store, TestData};
+
+use rstest::rstest;
+use similar_asserts::assert_eq;
+
+#[rstest]
+fn test_activity_archive_moves_ended_activities_passes(
+    activity_store: TestResult<TestData>,
+) -> TestResult<()> {
+    let TestData { activities, store } = activity_store?;
+
+    let archive_dir = tempfile::tempdir()?;
+
+    let archive = ActivityArchive::new(archive_dir.path());
+
+    assert_eq!(
+        archive.archive_activities(&store, 30)?,
+        1,
+        "Only the activity that has been archived before is moved."
+    );
+
+    // The completed activity is moved now, the others haven't ended yet
+    assert_eq!(archive.archive_activities(&store, 0)?, 1);
+
+    let remaining = store
+        .list_activities(ActivityFilterKind::Everything)?
+        .map(FilteredActivities::into_vec)
+        .unwrap_or_default();
+
+    assert_eq!(remaining.len(), activities.len() - 2);
+    assert!(store.read_activity(*activities[1].guid()).is_err());
+    assert!(store.read_activity(*activities[2].guid()).is_err());
+
+    let year = Local::now().year();
+
+    assert!(archive.year_path(year).exists());
+
+    let archived = archive.load_time_range(
+        &TimeRangeOptions::builder()
+            .start(PaceDateTime::now())
+            .end(PaceDateTime::now())
+            .build(),
+    )?;
+
+    assert_eq!(archived.len(), 2);
+    assert!(archived
+        .values()
+        .all(|activity| activity.is_archived() && activity.activity_end_options().is_some()));
+
+    Ok(())
+}
+
+#[rstest]
+fn test_activity_archive_records_history_passes(
+    activity_store: TestResult<TestData>,
+) -> TestResult<()> {
+    let TestData {
+        activities,
+        mut store,
+    } = activity_store?;
+
+    let temp_dir = tempfile::tempdir()?;
+
+    let _ = store.set_history(Some(Arc::new(ActivityHistory::new(
+        temp_dir.path().join("history.pace.jsonl"),
+    )?)));
+
+    let archive = ActivityArchive::new(temp_dir.path().join("archive"));
+
+    assert_eq!(archive.archive_activities(&store, 0)?, 2);
+    store.sync()?;
+
+    let history = store.history().as_deref().ok_or("no history")?.history();
+
+    for activity in &activities[1..=2] {
+        assert_eq!(
+            history
+                .changes_of(*activity.guid())
+                .iter()
+                .map(|(entry, _)| *entry.operation())
+                .collect::<Vec<_>>(),
+            vec![JournalOperationKind::Archive],
+            "Archiving should be recorded as one operation."
+        );
+    }
+
+    let undone = store.undo()?.ok_or("nothing undone")?;
+    store.sync()?;
+
+    assert_eq!(*undone.operation(), JournalOperationKind::Archive);
+    assert!(store.read_activity(*activities[1].guid()).is_ok());
+    assert!(store.read_activity(*activities[2].guid()).is_ok());
+
+    Ok(())
+}
@@ -0,0 +1,215 @@
+//! Test undoing and redoing changes with the `ActivityHistory` of an `ActivityStore`.
+
+use std::{path::Path, sync::Arc};
+
+use pace_core::prelude::{
+    Activity, ActivityHistory, ActivityItem, ActivityQuerying, ActivityReadOps,
+    ActivityStateManagement, ActivityStore, ActivityWriteOps, HoldOptions, InMemoryActivityStorage,
+    JournalOperationKind, PaceError, PaceErrorKind, StorageKind, SyncStorage, TestResult,
+    UpdateOptions,
+};
+
+use rstest::rstest;
+use similar_asserts::assert_eq;
+
+fn activity_store_with_history(
+    storage: &Arc<StorageKind>,
+    history_path: &Path,
+) -> TestResult<ActivityStore> {
+    let mut store = ActivityStore::with_storage(storage.clone())?;
+
+    let _ = store.set_history(Some(Arc::new(ActivityHistory::new(history_path)?)));
+
+    Ok(store)
+}
+
+fn begin_activity(store: &ActivityStore, description: &str) -> TestResult<ActivityItem> {
+    let activity_item = store.begin_activity(
+        Activity::builder()
+            .description(description.to_string())
+            .build(),
+    )?;
+
+    store.sync()?;
+
+    Ok(activity_item)
+}
+
+#[rstest]
+fn test_activity_history_undo_and_redo_begin_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let history_path = temp_dir.path().join("history.pace.jsonl");
+
+    let storage = Arc::new(StorageKind::from(InMemoryActivityStorage::new()));
+    let store = activity_store_with_history(&storage, &history_path)?;
+
+    let first = begin_activity(&store, "First")?;
+    let second = begin_activity(&store, "Second")?;
+
+    assert!(store
+        .read_activity(*first.guid())?
+        .activity()
+        .is_completed());
+
+    // Undoing the begin of the second activity resumes the first one
+    let undone = store.undo()?.ok_or("nothing undone")?;
+    store.sync()?;
+
+    assert_eq!(*undone.operation(), JournalOperationKind::Begin);
+    assert!(store.read_activity(*second.guid()).is_err());
+    assert!(store
+        .read_activity(*first.guid())?
+        .activity()
+        .is_in_progress());
+
+    // The history is read from the file by the next command
+    let store = activity_store_with_history(&storage, &history_path)?;
+
+    let redone = store.redo()?.ok_or("nothing redone")?;
+    store.sync()?;
+
+    assert_eq!(redone.at(), undone.at(), "Should redo the undone change.");
+    assert_eq!(
+        store
+            .read_activity(*second.guid())?
+            .activity()
+            .description(),
+        "Second"
+    );
+    assert!(store
+        .read_activity(*first.guid())?
+        .activity()
+        .is_completed());
+
+    assert!(store.redo()?.is_none(), "Should have nothing left to redo.");
+
+    let history = store.history().as_deref().ok_or("no history")?.history();
+
+    assert_eq!(
+        history
+            .changes_of(*first.guid())
+            .iter()
+            .map(|(entry, _)| *entry.operation())
+            .collect::<Vec<_>>(),
+        vec![
+            JournalOperationKind::Begin,
+            JournalOperationKind::Begin,
+            JournalOperationKind::Undo,
+            JournalOperationKind::Redo
+        ]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_activity_history_undo_changed_activity_fails() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let history_path = temp_dir.path().join("history.pace.jsonl");
+
+    let storage = Arc::new(StorageKind::from(InMemoryActivityStorage::new()));
+    let store = activity_store_with_history(&storage, &history_path)?;
+
+    let activity_item = begin_activity(&store, "Original")?;
+
+    // A change that isn't recorded in the history
+    let mut adjusted = activity_item.activity().clone();
+    adjusted.set_description("Adjusted".to_string());
+
+    let _ = ActivityStore::with_storage(storage.clone())?.update_activity(
+        *activity_item.guid(),
+        adjusted,
+        UpdateOptions::default(),
+    )?;
+
+    let result = store.undo();
+
+    assert!(
+        matches!(
+            result.map_err(PaceError::into_inner),
+            Err(PaceErrorKind::HistoryConflict(guid)) if guid == *activity_item.guid()
+        ),
+        "Should not undo the begin of a changed activity."
+    );
+
+    assert_eq!(
+        store
+            .read_activity(*activity_item.guid())?
+            .activity()
+            .description(),
+        "Adjusted"
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_activity_history_undo_import_at_once_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let history_path = temp_dir.path().join("history.pace.jsonl");
+
+    let storage = Arc::new(StorageKind::from(InMemoryActivityStorage::new()));
+    let store = activity_store_with_history(&storage, &history_path)?;
+
+    let imported = store.import_activities(["First", "Second", "Third"].map(|description| {
+        Activity::builder()
+            .description(description.to_string())
+            .build()
+    }))?;
+    store.sync()?;
+
+    assert_eq!(imported.len(), 3);
+    assert_eq!(
+        store
+            .history()
+            .as_ref()
+            .map(|history| history.history().entries().len()),
+        Some(1),
+        "Should record the import as a single change."
+    );
+
+    let undone = store.undo()?.ok_or("nothing undone")?;
+    store.sync()?;
+
+    assert_eq!(*undone.operation(), JournalOperationKind::Import);
+    assert_eq!(store.list_activities_by_id()?.unwrap_or_default().len(), 0);
+
+    Ok(())
+}
+
+#[rstest]
+fn test_activity_history_undo_hold_removes_intermission_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let history_path = temp_dir.path().join("history.pace.jsonl");
+
+    let storage = Arc::new(StorageKind::from(InMemoryActivityStorage::new()));
+    let store = activity_store_with_history(&storage, &history_path)?;
+
+    let ended = begin_activity(&store, "Ended")?;
+    let held = begin_activity(&store, "Held")?;
+
+    _ = store
+        .hold_most_recent_active_activity(HoldOptions::default())?
+        .ok_or("nothing held")?;
+    store.sync()?;
+
+    let intermissions = store.list_active_intermissions()?.unwrap_or_default();
+
+    assert_eq!(intermissions.len(), 1);
+
+    let undone = store.undo()?.ok_or("nothing undone")?;
+    store.sync()?;
+
+    assert_eq!(*undone.operation(), JournalOperationKind::Hold);
+    assert!(
+        undone.change_of(*ended.guid()).is_none(),
+        "Should only record the activities the hold has changed."
+    );
+    assert!(store.read_activity(intermissions[0]).is_err());
+    assert!(store
+        .read_activity(*held.guid())?
+        .activity()
+        .is_in_progress());
+
+    Ok(())
+}
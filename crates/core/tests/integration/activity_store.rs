@@ -1,12 +1,23 @@
 //! Test the `ActivityStore` implementation with a `InMemoryStorage` backend.
 
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
 
 use pace_core::prelude::{
-    Activity, ActivityFilterKind, ActivityGuid, ActivityReadOps, ActivityStateManagement,
-    ActivityStatusKind, ActivityStore, ActivityWriteOps, DeleteOptions, EndOptions, HoldOptions,
-    InMemoryActivityStorage, ResumeOptions, TestResult, UpdateOptions,
+    Activity, ActivityArchiving, ActivityEndOptions, ActivityFilterKind, ActivityGuid,
+    ActivityKind, ActivityQuerying, ActivityReadOps, ActivityStateManagement, ActivityStatusKind,
+    ActivityStore, ActivityTagging, ActivityWriteOps, DeleteOptions, EndOptions, FilterOptions,
+    HoldOptions, InMemoryActivityStorage, PaceEvent, PaceEventKind, PaceResult, PomodoroConfig,
+    PomodoroPhase, PomodoroPhaseKind, ResumeOptions, SyncStorage, TestResult, UpdateOptions,
+};
+use pace_time::{
+    date_time::PaceDateTime,
+    duration::{PaceDuration, PaceDurationRange, PaceDurationRangeOptions},
+    time_range::TimeRangeOptions,
 };
+use parking_lot::Mutex;
 
 use crate::util::{
     activity_store, activity_store_empty, activity_store_no_intermissions, TestData,
@@ -694,3 +705,313 @@ fn test_begin_activity_with_held_activity() -> TestResult<()> {
 
     Ok(())
 }
+
+#[rstest]
+fn test_activity_store_pomodoro_session_passes(
+    activity_store_empty: TestResult<TestData>,
+) -> TestResult<()> {
+    let TestData {
+        activities: _,
+        store,
+    } = activity_store_empty?;
+
+    let config = PomodoroConfig::default();
+
+    let begin = "2024-03-01T09:00:00+01:00".parse::<PaceDateTime>()?;
+
+    let work_session = store.begin_activity(PomodoroPhase::work(4, &config).to_activity(
+        "Write docs".to_string(),
+        Some("dev::pace".to_string()),
+        None,
+        begin,
+        None,
+    ))?;
+
+    let (_, running_work_session, phase) = store
+        .running_pomodoro_phase(&config)?
+        .ok_or("Should have a running pomodoro phase.")?;
+
+    assert_eq!(running_work_session.guid(), work_session.guid());
+    assert_eq!(phase, PomodoroPhase::work(4, &config));
+
+    let break_begin = begin.add_duration(*phase.duration())?;
+
+    _ = store.end_activity(
+        *work_session.guid(),
+        EndOptions::builder().end_time(break_begin).build(),
+    )?;
+
+    let intermission = store.begin_activity(phase.next(&config).to_activity(
+        "Write docs".to_string(),
+        Some("dev::pace".to_string()),
+        None,
+        break_begin,
+        Some(*work_session.guid()),
+    ))?;
+
+    let (running, running_work_session, phase) = store
+        .running_pomodoro_phase(&config)?
+        .ok_or("Should have a running pomodoro phase.")?;
+
+    assert_eq!(running.guid(), intermission.guid());
+    assert_eq!(running_work_session.guid(), work_session.guid());
+    assert_eq!(
+        phase.kind(),
+        &PomodoroPhaseKind::LongBreak,
+        "The fourth work session should be followed by a long break."
+    );
+
+    _ = store.end_activity(
+        *intermission.guid(),
+        EndOptions::builder()
+            .end_time(break_begin.add_duration(*phase.duration())?)
+            .build(),
+    )?;
+
+    assert!(
+        store.running_pomodoro_phase(&config)?.is_none(),
+        "Should have no running pomodoro phase."
+    );
+
+    // The break follows the work session, so it doesn't reduce its duration
+    let summary_groups_by_category = store
+        .summary_groups_by_category_for_time_range(
+            FilterOptions::default(),
+            TimeRangeOptions::specific_date(begin.date_naive())?,
+        )?
+        .ok_or("Should have a summary.")?;
+
+    let group = summary_groups_by_category
+        .get(&("dev".to_string(), "pace".to_string()))
+        .ok_or("Should have a category.")?;
+
+    assert_eq!(group.total_duration().inner(), 25 * 60);
+    assert_eq!(group.total_break_duration().inner(), 15 * 60);
+    assert_eq!(*group.total_break_count(), 1);
+
+    Ok(())
+}
+
+#[rstest]
+fn test_activity_store_archive_and_unarchive_activity_passes(
+    activity_store: TestResult<TestData>,
+) -> TestResult<()> {
+    let TestData { activities, store } = activity_store?;
+
+    let created_id = *activities[0].guid();
+    let completed_id = *activities[2].guid();
+
+    assert!(
+        store.archive_activity(created_id).is_err(),
+        "Activities that haven't ended can't be archived."
+    );
+
+    let archived = store.archive_activity(completed_id)?;
+
+    assert!(archived.activity().is_archived());
+    assert!(store.read_activity(completed_id)?.activity().is_archived());
+
+    assert!(
+        store.archive_activity(completed_id).is_err(),
+        "Activity is already archived."
+    );
+
+    let unarchived = store.unarchive_activity(completed_id)?;
+
+    assert_eq!(
+        unarchived.activity().status(),
+        &ActivityStatusKind::Unarchived
+    );
+
+    assert!(
+        store.unarchive_activity(completed_id).is_err(),
+        "Activity is not archived anymore."
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_activity_store_tagging_passes(
+    activity_store_empty: TestResult<TestData>,
+) -> TestResult<()> {
+    let TestData {
+        activities: _,
+        store,
+    } = activity_store_empty?;
+
+    let first_id = *store
+        .create_activity(
+            Activity::builder()
+                .description("Write docs")
+                .tags(HashSet::from(["docs".to_string()]))
+                .build(),
+        )?
+        .guid();
+
+    let second_id = *store
+        .create_activity(Activity::builder().description("Review docs").build())?
+        .guid();
+
+    let tagged = store.add_tag_to_activity(second_id, "review")?;
+
+    assert!(tagged.activity().has_tag("review"));
+
+    _ = store.add_tag_to_activity(second_id, "docs")?;
+    _ = store.add_tag_to_activity(first_id, "docs")?;
+
+    assert_eq!(
+        store.list_tags()?,
+        BTreeMap::from([("docs".to_string(), 2), ("review".to_string(), 1)]),
+        "Adding a tag twice should keep it once."
+    );
+
+    // The second activity has both tags, so they are merged
+    assert_eq!(store.rename_tag("review", "docs")?, 1);
+    assert_eq!(store.rename_tag("missing", "docs")?, 0);
+
+    assert_eq!(
+        store.list_tags()?,
+        BTreeMap::from([("docs".to_string(), 2)])
+    );
+
+    let untagged = store.remove_tag_from_activity(first_id, "docs")?;
+
+    assert_eq!(untagged.activity().tags(), &None);
+
+    assert!(
+        store.remove_tag_from_activity(first_id, "docs").is_err(),
+        "Activity doesn't have the tag anymore."
+    );
+
+    assert_eq!(
+        store.find_activity_id(&second_id.to_string().to_lowercase())?,
+        second_id
+    );
+
+    assert!(
+        store.find_activity_id("").is_err(),
+        "An empty prefix shouldn't match any activity."
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_activity_store_group_activities_by_duration_range_passes(
+    activity_store_empty: TestResult<TestData>,
+) -> TestResult<()> {
+    let TestData {
+        activities: _,
+        store: storage,
+    } = activity_store_empty?;
+
+    let begin = PaceDateTime::now();
+
+    for (description, kind, minutes) in [
+        ("Quick fix", ActivityKind::Activity, 10),
+        ("Review", ActivityKind::Activity, 30),
+        ("Deep work", ActivityKind::PomodoroWork, 120),
+        ("Break", ActivityKind::Intermission, 5),
+    ] {
+        let _ = storage.create_activity(
+            Activity::builder()
+                .description(description)
+                .kind(kind)
+                .begin(begin)
+                .activity_end_options(Some(ActivityEndOptions::new(
+                    begin,
+                    PaceDuration::new(minutes * 60),
+                )))
+                .build(),
+        )?;
+    }
+
+    let activities_by_duration_range = storage
+        .group_activities_by_duration_range(PaceDurationRangeOptions::default())?
+        .ok_or("Should have activities.")?
+        .into_iter()
+        .map(|(range, activity_items)| {
+            (
+                range,
+                activity_items
+                    .iter()
+                    .map(|activity_item| activity_item.activity().description().clone())
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    assert_eq!(
+        activities_by_duration_range,
+        BTreeMap::from([
+            (PaceDurationRange::Short, vec!["Quick fix".to_string()]),
+            (PaceDurationRange::Medium, vec!["Review".to_string()]),
+            (PaceDurationRange::Long, vec!["Deep work".to_string()]),
+        ]),
+        "Intermissions should not be grouped."
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_activity_store_publishes_events_passes() -> TestResult<()> {
+    let store = ActivityStore::with_storage(Arc::new(InMemoryActivityStorage::new().into()))?;
+
+    let events = Arc::new(Mutex::new(vec![]));
+
+    let recorded_events = Arc::clone(&events);
+
+    store
+        .event_bus()
+        .subscribe(vec![], move |event: &PaceEvent| -> PaceResult<()> {
+            recorded_events
+                .lock()
+                .push((event.kind(), event.activity_id()));
+            Ok(())
+        });
+
+    let first = store.begin_activity(
+        Activity::builder()
+            .description("First activity".to_string())
+            .build(),
+    )?;
+
+    let _ = store.hold_most_recent_active_activity(HoldOptions::default())?;
+    let _ = store.resume_most_recent_activity(ResumeOptions::default())?;
+
+    let mut adjusted = first.activity().clone();
+    _ = adjusted.set_description("Adjusted activity".to_string());
+    let _ = store.update_activity(*first.guid(), adjusted, UpdateOptions::default())?;
+
+    let second = store.begin_activity(
+        Activity::builder()
+            .description("Second activity".to_string())
+            .build(),
+    )?;
+
+    let _ = store.end_all_activities(EndOptions::default())?;
+
+    assert!(
+        events.lock().is_empty(),
+        "Events should only be published once the changes are synced."
+    );
+
+    store.sync()?;
+
+    assert_eq!(
+        *events.lock(),
+        vec![
+            (PaceEventKind::ActivityBegan, *first.guid()),
+            (PaceEventKind::ActivityHeld, *first.guid()),
+            (PaceEventKind::ActivityResumed, *first.guid()),
+            (PaceEventKind::ActivityAdjusted, *first.guid()),
+            (PaceEventKind::ActivityEnded, *first.guid()),
+            (PaceEventKind::ActivityBegan, *second.guid()),
+            (PaceEventKind::ActivityEnded, *second.guid()),
+        ]
+    );
+
+    Ok(())
+}
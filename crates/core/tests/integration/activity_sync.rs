@@ -0,0 +1,551 @@
+//! Test merging the activity logs of several devices into an `ActivityStore`.
+
+use std::{
+    collections::BTreeMap,
+    fs::{read_to_string, write},
+    path::Path,
+    process::Command,
+    sync::Arc,
+};
+
+use chrono::{Duration, Local};
+use clap::Parser;
+use pace_core::prelude::{
+    get_storage_from_path, Activity, ActivityHistory, ActivityItem, ActivityLogMerge,
+    ActivityQuerying, ActivityReadOps, ActivityStateManagement, ActivityStore, ActivityWriteOps,
+    GitActivityStorage, GitPull, InMemoryActivityStorage, JournalOperationKind, MergeConflict,
+    MergeSideKind, MergeSyncCommandOptions, PaceConfig, PaceError, PaceErrorKind, PaceResult,
+    StorageKind, SyncStorage, TestResult, TomlActivityStorage, UpdateOptions,
+};
+use pace_time::date_time::PaceDateTime;
+
+use rstest::rstest;
+use similar_asserts::assert_eq;
+
+#[rstest]
+fn test_activity_sync_merge_ends_concurrently_active_activity_passes() -> TestResult<()> {
+    let now = Local::now().fixed_offset();
+
+    let laptop = ActivityStore::with_storage(Arc::new(InMemoryActivityStorage::new().into()))?;
+    let laptop_activity = laptop.begin_activity(
+        Activity::builder()
+            .description("On the laptop")
+            .begin(PaceDateTime::from(now - Duration::hours(2)))
+            .build(),
+    )?;
+
+    let desktop = ActivityStore::with_storage(Arc::new(InMemoryActivityStorage::new().into()))?;
+    let desktop_activity = desktop.begin_activity(
+        Activity::builder()
+            .description("On the desktop")
+            .begin(PaceDateTime::from(now - Duration::hours(1)))
+            .build(),
+    )?;
+
+    let merge = ActivityLogMerge::new(
+        &laptop.list_activities_by_id()?.unwrap_or_default(),
+        &desktop.list_activities_by_id()?.unwrap_or_default(),
+        MergeSideKind::Other,
+    )?;
+
+    assert_eq!(merge.added(), &vec![*desktop_activity.guid()]);
+    assert!(matches!(
+        merge.conflicts().as_slice(),
+        [MergeConflict::ConcurrentlyActive { ended_id, active_id, .. }]
+            if ended_id == laptop_activity.guid() && active_id == desktop_activity.guid()
+    ));
+
+    assert_eq!(laptop.apply_merge(&merge)?, 2);
+
+    let ended = laptop.read_activity(*laptop_activity.guid())?;
+
+    assert!(ended.activity().is_completed());
+    assert_eq!(
+        ended
+            .activity()
+            .activity_end_options()
+            .as_ref()
+            .map(|end_opts| *end_opts.end()),
+        Some(*desktop_activity.activity().begin()),
+        "Should have ended when the other activity began."
+    );
+    assert!(laptop
+        .read_activity(*desktop_activity.guid())?
+        .activity()
+        .is_in_progress());
+
+    assert_eq!(
+        laptop.apply_merge(&merge)?,
+        0,
+        "Merging again should not change anything."
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_activity_sync_merge_decides_last_writer_per_activity_passes() -> TestResult<()> {
+    let now = Local::now().fixed_offset();
+    let temp_dir = tempfile::tempdir()?;
+
+    let local_path = temp_dir.path().join("activities.pace.toml");
+    write(&local_path, "")?;
+
+    let mut config = PaceConfig::default();
+    config.set_activity_log_path(&local_path);
+
+    let store = ActivityStore::from_config(&config)?;
+
+    let [edited, retitled, ended] = ["Edited", "Retitled", "Ended"].map(|description| {
+        store.create_activity(
+            Activity::builder()
+                .description(description)
+                .begin(PaceDateTime::from(now - Duration::hours(2)))
+                .build(),
+        )
+    });
+    let (edited, retitled, ended) = (edited?, retitled?, ended?);
+    store.sync()?;
+
+    let local = store.list_activities_by_id()?.unwrap_or_default();
+
+    // The other device has changed all activities, but only recorded the first change in its history
+    let mut other = local.clone();
+
+    for (activity_item, description) in [
+        (&edited, "Edited on the other device"),
+        (&retitled, "Retitled on the other device"),
+    ] {
+        let activity = other
+            .get_mut(activity_item.guid())
+            .ok_or("Should have the activity.")?;
+        _ = activity.set_description(description.to_string());
+    }
+
+    other
+        .get_mut(ended.guid())
+        .ok_or("Should have the activity.")?
+        .end_activity_with_duration_calc(
+            PaceDateTime::from(now - Duration::hours(2)),
+            PaceDateTime::from(now - Duration::hours(1)),
+        )?;
+
+    let other_history_path = temp_dir.path().join("other-history.pace.jsonl");
+    let other_history = ActivityHistory::new(&other_history_path)?;
+
+    other_history.record(
+        JournalOperationKind::Update,
+        &BTreeMap::from([(*edited.guid(), edited.activity().clone())]),
+        &BTreeMap::from([(
+            *edited.guid(),
+            other
+                .get(edited.guid())
+                .ok_or("Should have the activity.")?
+                .clone(),
+        )]),
+    );
+    other_history.sync()?;
+
+    // Which has been an hour ago, before the local change
+    let mut entry =
+        serde_json::from_str::<serde_json::Value>(&read_to_string(&other_history_path)?)?;
+    entry["at"] = serde_json::to_value(PaceDateTime::from(now - Duration::hours(1)))?;
+    write(&other_history_path, format!("{entry}\n"))?;
+
+    let mut activity = edited.activity().clone();
+    _ = activity.set_description("Edited on this device".to_string());
+    _ = store.update_activity(*edited.guid(), activity, UpdateOptions::default())?;
+    store.sync()?;
+
+    // The other activity log is written after all local changes
+    let other_path = temp_dir.path().join("other.pace.toml");
+    write(&other_path, "")?;
+
+    let other_storage = TomlActivityStorage::new(&other_path)?;
+
+    for (guid, activity) in other {
+        _ = other_storage.insert_activity(ActivityItem::from((guid, activity)))?;
+    }
+
+    other_storage.sync()?;
+
+    let message = MergeSyncCommandOptions::try_parse_from([
+        "merge",
+        other_path.to_str().ok_or("Should be a valid path.")?,
+        "--other-history",
+        other_history_path
+            .to_str()
+            .ok_or("Should be a valid path.")?,
+    ])?
+    .handle_merge(&config)?;
+
+    let merged = TomlActivityStorage::new(&local_path)?;
+
+    let description = |activity_item: &ActivityItem| -> PaceResult<String> {
+        Ok(merged
+            .read_activity(*activity_item.guid())?
+            .activity()
+            .description()
+            .clone())
+    };
+
+    assert_eq!(
+        description(&edited)?,
+        "Edited on this device",
+        "Should keep the local changes the histories tell are newer."
+    );
+    assert_eq!(
+        description(&retitled)?,
+        "Retitled",
+        "Should keep the local activity, if it's unknown which changes are newer."
+    );
+    assert!(
+        merged
+            .read_activity(*ended.guid())?
+            .activity()
+            .is_completed(),
+        "Should keep the changes the activities tell are newer."
+    );
+    assert!(message
+        .to_string()
+        .contains("\"Retitled\" has been changed in both activity logs (description), but it's unknown which changes are newer"));
+
+    Ok(())
+}
+
+#[rstest]
+fn test_activity_sync_reads_other_activity_log_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = temp_dir
+        .path()
+        .join("activities (conflicted copy).pace.toml");
+
+    assert!(
+        get_storage_from_path(&path).is_err(),
+        "Should not read a missing activity log."
+    );
+
+    std::fs::write(&path, "")?;
+
+    let storage = TomlActivityStorage::new(&path)?;
+    let activity_item =
+        storage.begin_activity(Activity::builder().description("Conflicting copy").build())?;
+    storage.sync()?;
+
+    let other = get_storage_from_path(&path)?;
+
+    assert!(matches!(*other, StorageKind::TomlActivityStorage(_)));
+    assert_eq!(
+        other
+            .read_activity(*activity_item.guid())?
+            .activity()
+            .description(),
+        "Conflicting copy"
+    );
+
+    Ok(())
+}
+
+fn git(dir: &Path, args: &[&str]) -> TestResult<String> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+
+    assert!(output.status.success(), "git {} failed", args.join(" "));
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Run git in the repository of the activity log in the directory
+fn pace_git(dir: &Path, args: &[&str]) -> TestResult<String> {
+    let git_dir = dir.join(".pace.git").to_string_lossy().to_string();
+    let work_tree = dir.to_string_lossy().to_string();
+
+    git(
+        dir,
+        &[&["--git-dir", &git_dir, "--work-tree", &work_tree], args].concat(),
+    )
+}
+
+fn git_activity_store(dir: &Path) -> TestResult<ActivityStore> {
+    std::fs::create_dir_all(dir)?;
+
+    let path = dir.join("activities.pace.toml");
+
+    if !path.exists() {
+        std::fs::write(&path, "")?;
+    }
+
+    Ok(ActivityStore::with_storage(Arc::new(
+        GitActivityStorage::new(path)?.into(),
+    ))?)
+}
+
+fn begin_activity(store: &ActivityStore, description: &str) -> TestResult<()> {
+    let _ = store.begin_activity(
+        Activity::builder()
+            .description(description.to_string())
+            .build(),
+    )?;
+
+    store.sync()?;
+
+    Ok(())
+}
+
+/// Pull like `pace sync pull` does, returning if the activity logs have diverged
+fn pull(store: &ActivityStore, remote: &str) -> TestResult<GitPull> {
+    let StorageKind::GitActivityStorage(git) = store.storage().as_ref() else {
+        return Err("not stored in git".into());
+    };
+
+    let pulled = git.pull(remote, "main")?;
+
+    if let GitPull::Diverged { activities } = &pulled {
+        let local = store.list_activities_by_id()?.unwrap_or_default();
+
+        let _ = store.apply_merge(&ActivityLogMerge::by_change_times(
+            &local, activities, None, None,
+        )?)?;
+
+        store.sync()?;
+    }
+
+    Ok(pulled)
+}
+
+fn push(store: &ActivityStore, remote: &str) -> PaceResult<()> {
+    let StorageKind::GitActivityStorage(git) = store.storage().as_ref() else {
+        return Err(PaceErrorKind::GitStorageNotConfigured.into());
+    };
+
+    git.push(remote, "main")
+}
+
+fn descriptions(store: &ActivityStore) -> TestResult<Vec<String>> {
+    let mut descriptions = store
+        .list_activities_by_id()?
+        .unwrap_or_default()
+        .values()
+        .map(|activity| activity.description().clone())
+        .collect::<Vec<_>>();
+
+    descriptions.sort();
+
+    Ok(descriptions)
+}
+
+#[rstest]
+fn test_activity_sync_git_pull_and_push_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let remote_dir = temp_dir.path().join("remote.git");
+    let remote = remote_dir.to_string_lossy().to_string();
+
+    std::fs::create_dir_all(&remote_dir)?;
+    let _ = git(&remote_dir, &["init", "--quiet", "--bare"])?;
+
+    let laptop_dir = temp_dir.path().join("laptop");
+    let laptop = git_activity_store(&laptop_dir)?;
+
+    assert_eq!(pull(&laptop, &remote)?, GitPull::NothingToPull);
+
+    begin_activity(&laptop, "First")?;
+
+    assert_eq!(
+        pace_git(&laptop_dir, &["log", "-1", "--format=%s"])?,
+        "begin: First"
+    );
+
+    push(&laptop, &remote)?;
+
+    // Another device merges the activity log into its own one
+    let desktop_dir = temp_dir.path().join("desktop");
+    let desktop = git_activity_store(&desktop_dir)?;
+
+    begin_activity(&desktop, "Second")?;
+
+    assert!(matches!(pull(&desktop, &remote)?, GitPull::Diverged { .. }));
+    assert_eq!(descriptions(&desktop)?, vec!["First", "Second"]);
+
+    push(&desktop, &remote)?;
+
+    assert_eq!(pull(&laptop, &remote)?, GitPull::FastForwarded);
+    assert_eq!(descriptions(&laptop)?, vec!["First", "Second"]);
+    assert_eq!(pull(&laptop, &remote)?, GitPull::UpToDate);
+
+    // Both devices change the activity log before syncing
+    begin_activity(&desktop, "Third on the desktop")?;
+    push(&desktop, &remote)?;
+
+    begin_activity(&laptop, "Third on the laptop")?;
+
+    assert!(
+        matches!(
+            push(&laptop, &remote).map_err(PaceError::into_inner),
+            Err(PaceErrorKind::GitPullRequired(_))
+        ),
+        "Should not push before pulling the remote changes."
+    );
+    assert!(matches!(pull(&laptop, &remote)?, GitPull::Diverged { .. }));
+    assert_eq!(
+        descriptions(&laptop)?,
+        vec![
+            "First",
+            "Second",
+            "Third on the desktop",
+            "Third on the laptop"
+        ]
+    );
+    assert!(pace_git(&laptop_dir, &["log", "-1", "--format=%s"])?.starts_with("merge: "));
+    assert_eq!(
+        pace_git(&laptop_dir, &["status", "--porcelain"])?,
+        "",
+        "Should have committed the merge."
+    );
+
+    push(&laptop, &remote)?;
+
+    assert_eq!(pull(&desktop, &remote)?, GitPull::FastForwarded);
+    assert_eq!(descriptions(&desktop)?, descriptions(&laptop)?);
+
+    Ok(())
+}
+
+#[rstest]
+fn test_activity_sync_git_commits_only_the_activity_log_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let dir = temp_dir.path().join("laptop");
+    let store = git_activity_store(&dir)?;
+
+    // Something the user has staged in the repository on their own
+    std::fs::write(dir.join("notes.md"), "Not part of the activity log")?;
+    let _ = pace_git(&dir, &["add", "notes.md"])?;
+
+    begin_activity(&store, "First")?;
+
+    assert_eq!(
+        pace_git(&dir, &["show", "--name-only", "--format=", "HEAD"])?,
+        "activities.pace.toml"
+    );
+    assert_eq!(
+        pace_git(&dir, &["diff", "--cached", "--name-only"])?,
+        "notes.md",
+        "Should leave the changes staged by the user as they are."
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_activity_sync_git_uses_own_repository_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let home_dir = temp_dir.path().join("home");
+
+    // The activity log is part of another repository, e.g. of the dotfiles
+    std::fs::create_dir_all(&home_dir)?;
+    let _ = git(&home_dir, &["init", "--quiet"])?;
+
+    let dir = home_dir.join(".config").join("pace");
+    let store = git_activity_store(&dir)?;
+
+    begin_activity(&store, "First")?;
+
+    assert_eq!(
+        pace_git(&dir, &["log", "-1", "--format=%s"])?,
+        "begin: First"
+    );
+    assert_eq!(
+        git(&home_dir, &["rev-list", "--all", "--count"])?,
+        "0",
+        "Should not commit to the enclosing repository."
+    );
+    assert_eq!(
+        git(&home_dir, &["status", "--porcelain"])?,
+        "?? .config/",
+        "Should leave the enclosing repository as it is."
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_activity_sync_git_pulls_exactly_the_branch_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let remote_dir = temp_dir.path().join("remote.git");
+    let remote = remote_dir.to_string_lossy().to_string();
+
+    std::fs::create_dir_all(&remote_dir)?;
+    let _ = git(&remote_dir, &["init", "--quiet", "--bare"])?;
+
+    let laptop = git_activity_store(&temp_dir.path().join("laptop"))?;
+    begin_activity(&laptop, "First")?;
+
+    let StorageKind::GitActivityStorage(git_storage) = laptop.storage().as_ref() else {
+        return Err("not stored in git".into());
+    };
+
+    git_storage.push(&remote, "feature/main")?;
+
+    let desktop = git_activity_store(&temp_dir.path().join("desktop"))?;
+
+    assert_eq!(
+        pull(&desktop, &remote)?,
+        GitPull::NothingToPull,
+        "Should not pull a branch only ending with the name of the branch."
+    );
+
+    let StorageKind::GitActivityStorage(git_storage) = desktop.storage().as_ref() else {
+        return Err("not stored in git".into());
+    };
+
+    assert_eq!(
+        git_storage.pull(&remote, "--upload-pack=false")?,
+        GitPull::NothingToPull,
+        "Should take the branch for a branch, not for an option."
+    );
+    assert!(git_storage.pull("--upload-pack=false", "main").is_err());
+
+    Ok(())
+}
+
+#[rstest]
+fn test_activity_sync_git_aborts_merge_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let remote_dir = temp_dir.path().join("remote.git");
+    let remote = remote_dir.to_string_lossy().to_string();
+
+    std::fs::create_dir_all(&remote_dir)?;
+    let _ = git(&remote_dir, &["init", "--quiet", "--bare"])?;
+
+    let laptop = git_activity_store(&temp_dir.path().join("laptop"))?;
+    begin_activity(&laptop, "First")?;
+    push(&laptop, &remote)?;
+
+    let desktop_dir = temp_dir.path().join("desktop");
+    let desktop = git_activity_store(&desktop_dir)?;
+    begin_activity(&desktop, "Second")?;
+
+    let StorageKind::GitActivityStorage(git_storage) = desktop.storage().as_ref() else {
+        return Err("not stored in git".into());
+    };
+
+    assert!(matches!(
+        git_storage.pull(&remote, "main")?,
+        GitPull::Diverged { .. }
+    ));
+
+    let merge_head = desktop_dir.join(".pace.git").join("MERGE_HEAD");
+
+    assert!(merge_head.exists());
+
+    git_storage.abort_merge()?;
+
+    assert!(
+        !merge_head.exists(),
+        "Should not leave a merge in progress."
+    );
+    assert_eq!(
+        pace_git(&desktop_dir, &["log", "-1", "--format=%s"])?,
+        "begin: Second"
+    );
+
+    Ok(())
+}
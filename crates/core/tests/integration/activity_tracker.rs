@@ -1,12 +1,23 @@
 //! Test the `ActivityStore` implementation with a `InMemoryStorage` backend.
 
 use chrono::NaiveDate;
-use pace_core::prelude::{ActivityStore, ActivityTracker, FilterOptions, TestResult};
-use pace_time::{duration::PaceDuration, time_range::TimeRangeOptions};
+use pace_core::prelude::{
+    Activity, ActivityKind, ActivityStateManagement, ActivityStore, ActivityTracker, Amount,
+    BillingConfig, EndOptions, ExpensiveFlags, FilterOptions, Goal, GoalPeriodKind, Invoice,
+    ProjectList, ReflectionGroupByKind, ReflectionSummary, RoundingModeKind, RoundingOptions,
+    RoundingScopeKind, Task, TaskList, TestResult,
+};
+use pace_time::{
+    date::PaceDate,
+    date_time::PaceDateTime,
+    duration::{PaceDuration, PaceDurationRange, PaceDurationRangeOptions},
+    time_frame::PaceTimeFrame,
+    time_range::TimeRangeOptions,
+};
 use rstest::rstest;
 use similar_asserts::assert_eq;
 
-use crate::util::setup_activity_store_for_activity_tracker;
+use crate::util::{activity_store_empty, setup_activity_store_for_activity_tracker, TestData};
 
 #[rstest]
 fn test_activity_tracker(
@@ -69,3 +80,626 @@ fn test_activity_tracker(
 
     Ok(())
 }
+
+#[rstest]
+fn test_reflection_summary_to_csv(
+    setup_activity_store_for_activity_tracker: TestResult<ActivityStore>,
+) -> TestResult<()> {
+    let activity_tracker =
+        ActivityTracker::with_activity_store(setup_activity_store_for_activity_tracker?);
+
+    let time_range_opts =
+        TimeRangeOptions::specific_date("2024-02-27".parse::<NaiveDate>()?.into())?;
+
+    let summary_groups_by_category = activity_tracker
+        .store
+        .summary_groups_by_category_for_time_range(FilterOptions::default(), time_range_opts)?
+        .ok_or("Should have dates.")?;
+
+    let summary = ReflectionSummary::new(time_range_opts, summary_groups_by_category);
+
+    assert_eq!(
+        summary.to_csv(false),
+        "category,subcategory,description,duration,sessions,break-duration,breaks\n\
+         development,rustic,More Testing,51,2,10,1\n",
+        "Should have one row per description."
+    );
+
+    let detailed = summary.to_csv(true);
+
+    let rows = detailed.lines().collect::<Vec<_>>();
+
+    assert_eq!(
+        rows.len(),
+        3,
+        "Should have a header and one row per session."
+    );
+
+    assert_eq!(
+        rows[0],
+        "category,subcategory,description,id,begin,end,duration,break-duration,breaks"
+    );
+
+    assert!(
+        rows.contains(
+            &"development,rustic,More Testing,01HQN2X30PCBZXMV6EQ74D4GWZ,2024-02-27T11:48:56+01:00,2024-02-27T11:49:40+01:00,34,10,1"
+        ),
+        "Should contain the session with the intermission."
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_reflection_summary_by_task_passes(
+    activity_store_empty: TestResult<TestData>,
+) -> TestResult<()> {
+    let TestData {
+        activities: _,
+        store,
+    } = activity_store_empty?;
+
+    let mut task_list = TaskList::default();
+
+    let task_id = task_list.add(Task::builder().title("Write docs").build());
+
+    let begin = "2024-03-01T09:00:00+01:00".parse::<PaceDateTime>()?;
+
+    for (description, offset) in [("Write docs", 0), ("Review docs", 3600), ("Lunch", 7200)] {
+        let begin = begin.add_duration(PaceDuration::new(offset))?;
+
+        let activity_item = store.begin_activity(
+            Activity::builder()
+                .description(description)
+                .begin(begin)
+                .task_id((description != "Lunch").then_some(task_id))
+                .build(),
+        )?;
+
+        _ = store.end_activity(
+            *activity_item.guid(),
+            EndOptions::builder()
+                .end_time(begin.add_duration(PaceDuration::new(1800))?)
+                .build(),
+        )?;
+    }
+
+    let time_range_opts = TimeRangeOptions::specific_date(begin.date_naive())?;
+
+    let summary_groups_by_category = store
+        .summary_groups_by_category_for_time_range(FilterOptions::default(), time_range_opts)?
+        .ok_or("Should have a summary.")?;
+
+    let mut summary = ReflectionSummary::new(time_range_opts, summary_groups_by_category);
+
+    summary.set_task_titles(&task_list);
+
+    let task_group = summary
+        .summary_groups_by_task()
+        .get(&task_id)
+        .ok_or("Should have a summary for the task.")?;
+
+    assert_eq!(summary.summary_groups_by_task().len(), 1);
+    assert_eq!(task_group.title().as_deref(), Some("Write docs"));
+    assert_eq!(task_group.total_duration().inner(), 3600);
+    assert_eq!(*task_group.session_count(), 2);
+    assert_eq!(summary.total_time_spent().inner(), 3 * 1800);
+
+    Ok(())
+}
+
+#[rstest]
+fn test_reflection_summary_by_tag_passes(
+    activity_store_empty: TestResult<TestData>,
+) -> TestResult<()> {
+    let TestData {
+        activities: _,
+        store,
+    } = activity_store_empty?;
+
+    let begin = "2024-03-01T09:00:00+01:00".parse::<PaceDateTime>()?;
+
+    for (description, tags, offset) in [
+        ("Write docs", vec!["docs", "client-a"], 0),
+        ("Review docs", vec!["client-a"], 3600),
+        ("Lunch", vec![], 7200),
+    ] {
+        let begin = begin.add_duration(PaceDuration::new(offset))?;
+
+        let activity_item = store.begin_activity(
+            Activity::builder()
+                .description(description)
+                .begin(begin)
+                .tags((!tags.is_empty()).then(|| tags.into_iter().map(String::from).collect()))
+                .build(),
+        )?;
+
+        _ = store.end_activity(
+            *activity_item.guid(),
+            EndOptions::builder()
+                .end_time(begin.add_duration(PaceDuration::new(1800))?)
+                .build(),
+        )?;
+    }
+
+    let time_range_opts = TimeRangeOptions::specific_date(begin.date_naive())?;
+
+    let filter_opts = FilterOptions::builder()
+        .category(None)
+        .case_sensitive(false)
+        .group_by(ReflectionGroupByKind::Tag)
+        .build();
+
+    let summary_groups = store
+        .summary_groups_by_category_for_time_range(filter_opts, time_range_opts)?
+        .ok_or("Should have a summary.")?;
+
+    assert_eq!(
+        summary_groups.keys().cloned().collect::<Vec<_>>(),
+        vec![
+            ("Untagged".to_string(), String::new()),
+            ("client-a".to_string(), String::new()),
+            ("docs".to_string(), String::new()),
+        ]
+    );
+
+    let client_group = summary_groups
+        .get(&("client-a".to_string(), String::new()))
+        .ok_or("Should have a group for the tag.")?;
+
+    assert_eq!(client_group.total_duration().inner(), 2 * 1800);
+
+    let summary = ReflectionSummary::new(time_range_opts, summary_groups);
+
+    assert_eq!(
+        summary.total_time_spent().inner(),
+        3 * 1800,
+        "Activities with several tags should only be counted once."
+    );
+
+    let filter_opts = FilterOptions::builder()
+        .category(None)
+        .case_sensitive(false)
+        .tags(Some(vec!["CLIENT-*".to_string()]))
+        .group_by(ReflectionGroupByKind::Tag)
+        .build();
+
+    let summary_groups = store
+        .summary_groups_by_category_for_time_range(filter_opts, time_range_opts)?
+        .ok_or("Should have a summary.")?;
+
+    assert_eq!(
+        summary_groups.len(),
+        1,
+        "Should only have the filtered tag."
+    );
+
+    assert_eq!(
+        summary_groups
+            .get(&("client-a".to_string(), String::new()))
+            .ok_or("Should have a group for the tag.")?
+            .len(),
+        2
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_reflection_statistics_and_comparison_passes(
+    activity_store_empty: TestResult<TestData>,
+) -> TestResult<()> {
+    let TestData {
+        activities: _,
+        store,
+    } = activity_store_empty?;
+
+    for (description, kind, begin, duration) in [
+        (
+            "Write docs",
+            ActivityKind::Activity,
+            "2024-03-04T09:00:00+01:00",
+            3600,
+        ),
+        (
+            "Review docs",
+            ActivityKind::Activity,
+            "2024-03-04T11:00:00+01:00",
+            1800,
+        ),
+        (
+            "Fix bug",
+            ActivityKind::PomodoroWork,
+            "2024-03-05T09:00:00+01:00",
+            1200,
+        ),
+        // The week before
+        (
+            "Write docs",
+            ActivityKind::Activity,
+            "2024-02-26T09:00:00+01:00",
+            1800,
+        ),
+    ] {
+        let begin = begin.parse::<PaceDateTime>()?;
+
+        let activity_item = store.begin_activity(
+            Activity::builder()
+                .description(description)
+                .kind(kind)
+                .begin(begin)
+                .build(),
+        )?;
+
+        _ = store.end_activity(
+            *activity_item.guid(),
+            EndOptions::builder()
+                .end_time(begin.add_duration(PaceDuration::new(duration))?)
+                .build(),
+        )?;
+    }
+
+    let time_frame = PaceTimeFrame::DateRange(
+        TimeRangeOptions::builder()
+            .start("2024-03-04T00:00:00+01:00".parse::<PaceDateTime>()?)
+            .end("2024-03-10T23:59:59+01:00".parse::<PaceDateTime>()?)
+            .build(),
+    );
+
+    let activity_tracker = ActivityTracker::with_activity_store(store);
+
+    let summary = activity_tracker
+        .generate_reflection(
+            FilterOptions::default(),
+            time_frame,
+            &ExpensiveFlags::builder()
+                .detailed(true)
+                .comparative(true)
+                .recommendations(true)
+                .build(),
+            &PaceDurationRangeOptions::default(),
+        )?
+        .ok_or("Should have a summary.")?;
+
+    let statistics = summary
+        .statistics()
+        .as_ref()
+        .ok_or("Should have statistics.")?;
+
+    assert_eq!(*statistics.session_count(), 3);
+    assert_eq!(statistics.average_session_duration().inner(), 2200);
+    assert_eq!(*statistics.break_percentage(), 0);
+
+    assert_eq!(
+        statistics
+            .daily_distribution()
+            .iter()
+            .map(|(day, duration)| (*day, duration.inner()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("2024-03-04".parse::<PaceDate>()?, 5400),
+            ("2024-03-05".parse::<PaceDate>()?, 1200)
+        ]
+    );
+
+    assert_eq!(
+        statistics
+            .weekly_distribution()
+            .get("2024-W10")
+            .map(PaceDuration::inner),
+        Some(6600)
+    );
+
+    let highlights = statistics
+        .highlights()
+        .as_ref()
+        .ok_or("Should have highlights.")?;
+
+    assert_eq!(
+        *highlights.most_productive_day(),
+        "2024-03-04".parse::<PaceDate>()?
+    );
+    assert_eq!(
+        *highlights.most_frequent_activity_kind(),
+        ActivityKind::Activity
+    );
+    assert_eq!(
+        highlights.most_time_spent_on().activity().description(),
+        "Write docs"
+    );
+
+    let fragmentation = statistics.fragmentation();
+
+    assert_eq!(
+        fragmentation
+            .get(&PaceDurationRange::Medium)
+            .map(|share| (*share.session_count(), *share.percentage())),
+        Some((2, 45)),
+        "The 20 and 30 minute sessions are medium."
+    );
+    assert_eq!(
+        fragmentation
+            .get(&PaceDurationRange::Long)
+            .map(|share| (*share.session_count(), *share.percentage())),
+        Some((1, 54))
+    );
+    assert!(!fragmentation.contains_key(&PaceDurationRange::Short));
+
+    let comparison = summary
+        .comparison()
+        .as_ref()
+        .ok_or("Should have a comparison.")?;
+
+    assert_eq!(
+        comparison.previous_time_range().start(),
+        &"2024-02-26T00:00:00+01:00".parse::<PaceDateTime>()?
+    );
+    assert_eq!(comparison.previous_total_time_spent().inner(), 1800);
+    assert_eq!(*comparison.previous_session_count(), 1);
+    assert_eq!(*comparison.time_spent_difference(), 4800);
+    assert_eq!(comparison.time_spent_change_percentage(), Some(266));
+
+    assert_eq!(
+        summary.recommendations().len(),
+        2,
+        "Should recommend taking breaks and keeping a sustainable pace."
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[case::category(r#"category-rates = { development = 60 }"#, Some(6000), 1500)]
+#[case::subcategory(
+    r#"category-rates = { development = 60, "development::rustic" = 90 }"#,
+    Some(9000),
+    2250
+)]
+#[case::default_rate("default-rate = 30", Some(3000), 750)]
+#[case::unknown_project(
+    "default-rate = 30\nproject-rates = { unknown = 100 }",
+    Some(3000),
+    750
+)]
+#[case::unbilled("", None, 0)]
+fn test_reflection_summary_to_invoice(
+    setup_activity_store_for_activity_tracker: TestResult<ActivityStore>,
+    #[case] rates: &str,
+    #[case] expected_rate: Option<i64>,
+    #[case] expected_amount: i64,
+) -> TestResult<()> {
+    let activity_store = setup_activity_store_for_activity_tracker?;
+
+    let time_range_opts =
+        TimeRangeOptions::specific_date("2024-02-27".parse::<NaiveDate>()?.into())?;
+
+    let summary_groups_by_category = activity_store
+        .summary_groups_by_category_for_time_range(FilterOptions::default(), time_range_opts)?
+        .ok_or("Should have dates.")?;
+
+    let summary = ReflectionSummary::new(time_range_opts, summary_groups_by_category);
+
+    let billing = pace_core::toml::from_str::<BillingConfig>(&format!(
+        "currency = \"USD\"\nrounding-minutes = 15\n{rates}"
+    ))?;
+
+    let invoice = Invoice::new(
+        &summary,
+        &billing,
+        Some(&ProjectList::default()),
+        Some("development".to_string()),
+        None,
+    );
+
+    assert_eq!(invoice.currency(), "USD");
+    assert_eq!(
+        invoice.line_items().first().map(|item| *item.rate()),
+        expected_rate.map(Amount::from_minor_units)
+    );
+    assert_eq!(*invoice.amount(), Amount::from_minor_units(expected_amount));
+
+    if expected_rate.is_some() {
+        let item = invoice
+            .line_items()
+            .first()
+            .ok_or("Should have a line item.")?;
+
+        assert_eq!(item.category(), "development::rustic");
+        assert_eq!(*item.session_count(), 2);
+        assert_eq!(item.duration(), &PaceDuration::from_seconds(51));
+        assert_eq!(
+            item.billable_duration(),
+            &PaceDuration::from_seconds(15 * 60),
+            "Should round up to 15 minutes."
+        );
+        assert!(invoice.unbilled_duration().is_zero());
+    } else {
+        assert_eq!(
+            invoice.unbilled_duration(),
+            &PaceDuration::from_seconds(51),
+            "Should not bill activities without a rate."
+        );
+    }
+
+    Ok(())
+}
+
+#[rstest]
+fn test_invoice_uses_category_separator_passes(
+    activity_store_empty: TestResult<TestData>,
+) -> TestResult<()> {
+    let TestData {
+        activities: _,
+        store,
+    } = activity_store_empty?;
+
+    let begin = "2024-03-01T09:00:00+01:00".parse::<PaceDateTime>()?;
+
+    let activity_item = store.begin_activity(
+        Activity::builder()
+            .description("Landing page")
+            .category("acme/website".to_string())
+            .begin(begin)
+            .build(),
+    )?;
+
+    _ = store.end_activity(
+        *activity_item.guid(),
+        EndOptions::builder()
+            .end_time(begin.add_duration(PaceDuration::new(3600))?)
+            .build(),
+    )?;
+
+    let time_range_opts = TimeRangeOptions::specific_date(begin.date_naive())?;
+
+    let summary = ReflectionSummary::new(
+        time_range_opts,
+        store
+            .summary_groups_by_category_for_time_range(FilterOptions::default(), time_range_opts)?
+            .ok_or("Should have a summary.")?,
+    );
+
+    let billing = pace_core::toml::from_str::<BillingConfig>(
+        "currency = \"USD\"\ncategory-rates = { acme = 80 }",
+    )?;
+
+    let invoice = Invoice::new(&summary, &billing, None, None, Some("/"));
+
+    assert_eq!(
+        invoice.line_items().first().map(|item| *item.rate()),
+        Some(Amount::from_minor_units(8000)),
+        "Should fall back to the rate of the category before the separator."
+    );
+    assert_eq!(*invoice.amount(), Amount::from_minor_units(8000));
+
+    Ok(())
+}
+
+#[rstest]
+#[case::session(RoundingScopeKind::Session, RoundingModeKind::Up, 720)]
+#[case::day(RoundingScopeKind::Day, RoundingModeKind::Up, 360)]
+#[case::group(RoundingScopeKind::Group, RoundingModeKind::Up, 360)]
+#[case::down(RoundingScopeKind::Group, RoundingModeKind::Down, 0)]
+fn test_reflection_summary_rounding(
+    setup_activity_store_for_activity_tracker: TestResult<ActivityStore>,
+    #[case] scope: RoundingScopeKind,
+    #[case] mode: RoundingModeKind,
+    #[case] expected_seconds: u64,
+) -> TestResult<()> {
+    let activity_store = setup_activity_store_for_activity_tracker?;
+
+    let time_range_opts =
+        TimeRangeOptions::specific_date("2024-02-27".parse::<NaiveDate>()?.into())?;
+
+    let summary_groups_by_category = activity_store
+        .summary_groups_by_category_for_time_range(FilterOptions::default(), time_range_opts)?
+        .ok_or("Should have dates.")?;
+
+    let mut summary = ReflectionSummary::new(time_range_opts, summary_groups_by_category);
+
+    summary.apply_rounding(
+        RoundingOptions::builder()
+            .minutes(6)
+            .mode(mode)
+            .scope(scope)
+            .build(),
+    );
+
+    assert_eq!(
+        summary.total_time_spent(),
+        &PaceDuration::from_seconds(51),
+        "Should keep the tracked time."
+    );
+    assert_eq!(
+        summary.rounded_time_spent(),
+        &Some(PaceDuration::from_seconds(expected_seconds))
+    );
+    assert_eq!(
+        summary.to_csv(false),
+        format!(
+            "category,subcategory,description,duration,rounded-duration,sessions,break-duration,breaks\n\
+             development,rustic,More Testing,51,{expected_seconds},2,10,1\n"
+        ),
+        "Should show the rounded duration next to the tracked one."
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_reflection_summary_without_rounding(
+    setup_activity_store_for_activity_tracker: TestResult<ActivityStore>,
+) -> TestResult<()> {
+    let activity_store = setup_activity_store_for_activity_tracker?;
+
+    let time_range_opts =
+        TimeRangeOptions::specific_date("2024-02-27".parse::<NaiveDate>()?.into())?;
+
+    let summary_groups_by_category = activity_store
+        .summary_groups_by_category_for_time_range(FilterOptions::default(), time_range_opts)?
+        .ok_or("Should have dates.")?;
+
+    let mut summary = ReflectionSummary::new(time_range_opts, summary_groups_by_category);
+
+    summary.apply_rounding(RoundingOptions::default());
+
+    assert!(summary.rounded_time_spent().is_none());
+    assert!(!summary.to_string().contains("Rounded"));
+
+    Ok(())
+}
+
+#[rstest]
+#[case::daily("development::*", 1, GoalPeriodKind::Day, "2024-02-26", 202, true, 1)]
+#[case::daily_short("development::*", 1, GoalPeriodKind::Day, "2024-02-27", 51, false, 0)]
+#[case::weekly("development::*", 4, GoalPeriodKind::Week, "2024-02-27", 253, true, 1)]
+#[case::weekly_until_date("development::*", 4, GoalPeriodKind::Week, "2024-02-26", 202, false, 0)]
+#[case::case_insensitive(
+    "Development::Pace",
+    3,
+    GoalPeriodKind::Week,
+    "2024-02-27",
+    202,
+    true,
+    1
+)]
+#[case::other_category("learning::*", 1, GoalPeriodKind::Day, "2024-02-27", 0, false, 0)]
+fn test_activity_tracker_goal_progress(
+    setup_activity_store_for_activity_tracker: TestResult<ActivityStore>,
+    #[case] category: &str,
+    #[case] minutes: u64,
+    #[case] period: GoalPeriodKind,
+    #[case] date: &str,
+    #[case] expected_seconds: u64,
+    #[case] expected_reached: bool,
+    #[case] expected_streak: usize,
+) -> TestResult<()> {
+    let activity_tracker =
+        ActivityTracker::with_activity_store(setup_activity_store_for_activity_tracker?);
+
+    let goal = Goal::builder()
+        .category(category)
+        .minutes(minutes)
+        .period(period)
+        .build();
+
+    let progress = activity_tracker.goal_progress(
+        &[goal],
+        PaceTimeFrame::SpecificDate(date.parse::<PaceDate>()?),
+    )?;
+
+    let progress = progress.first().ok_or("Should have a goal progress.")?;
+
+    assert_eq!(
+        progress.time_spent(),
+        &PaceDuration::from_seconds(expected_seconds)
+    );
+    assert_eq!(progress.is_reached(), expected_reached);
+    assert_eq!(progress.streak(), &expected_streak);
+    assert!(
+        !progress.in_progress(),
+        "Should be over, as the activities are in the past."
+    );
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+//! Test changing the inbox from several `InboxStore`s at once.
+
+use chrono::NaiveDateTime;
+use pace_core::prelude::{
+    InboxConfig, InboxStore, ItemPriorityKind, SyncStorage, Task, TaskList, TestResult,
+};
+
+use rstest::rstest;
+use similar_asserts::assert_eq;
+
+fn task(title: &str, created_at: &str) -> TestResult<Task> {
+    Ok(Task::builder()
+        .title(title)
+        .priority(ItemPriorityKind::Medium)
+        .created_at(created_at.parse::<NaiveDateTime>()?)
+        .build())
+}
+
+#[rstest]
+fn test_inbox_store_keeps_items_of_concurrent_stores_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = temp_dir.path().join("inbox.pace.toml");
+
+    let mut first = InboxStore::new(&path, InboxConfig::default())?;
+    let mut second = InboxStore::new(&path, InboxConfig::default())?;
+
+    first.lock_for_changes()?;
+    _ = first
+        .inbox_mut()
+        .add(task("First", "2024-03-01T09:00:00")?, 0)?;
+    first.sync()?;
+
+    // The second store has been loaded before the first one wrote its item
+    second.lock_for_changes()?;
+    _ = second
+        .inbox_mut()
+        .add(task("Second", "2024-03-01T10:00:00")?, 0)?;
+    second.sync()?;
+
+    let inbox = InboxStore::new(&path, InboxConfig::default())?;
+
+    let mut titles = inbox
+        .inbox()
+        .sorted()
+        .into_iter()
+        .map(|item| item.task().title().clone())
+        .collect::<Vec<_>>();
+    titles.sort();
+
+    assert_eq!(titles, vec!["First", "Second"]);
+
+    Ok(())
+}
+
+#[rstest]
+fn test_inbox_store_archives_stale_items_on_sync_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = temp_dir.path().join("inbox.pace.toml");
+
+    let mut inbox_store = InboxStore::new(&path, InboxConfig::default())?;
+
+    inbox_store.lock_for_changes()?;
+    _ = inbox_store
+        .inbox_mut()
+        .add(task("Stale", "2024-01-01T09:00:00")?, 0)?;
+    _ = inbox_store
+        .inbox_mut()
+        .add(task("Fresh", "2024-03-01T09:00:00")?, 0)?;
+
+    assert_eq!(
+        inbox_store.archive_stale("2024-03-02T09:00:00".parse::<NaiveDateTime>()?),
+        1
+    );
+
+    inbox_store.sync()?;
+
+    let archive = pace_core::toml::from_str::<TaskList>(&std::fs::read_to_string(
+        inbox_store.archive_path(),
+    )?)?;
+
+    assert_eq!(archive.len(), 1);
+    assert_eq!(
+        InboxStore::new(&path, InboxConfig::default())?
+            .inbox()
+            .len(),
+        1
+    );
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+//! Test the `JournalActivityStorage` replaying and compacting its journal.
+
+use std::{
+    collections::BTreeMap,
+    fs::{read_to_string, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use pace_core::prelude::{
+    Activity, ActivityGuid, ActivityItem, ActivityQuerying, ActivityReadOps,
+    ActivityStateManagement, EndOptions, JournalActivityStorage, JournalEntry,
+    JournalOperationKind, PaceResult, SyncStorage, TestResult,
+};
+
+use rstest::rstest;
+use similar_asserts::assert_eq;
+
+fn begin_activity(storage: &JournalActivityStorage, description: &str) -> PaceResult<ActivityItem> {
+    storage.begin_activity(
+        Activity::builder()
+            .description(description.to_string())
+            .build(),
+    )
+}
+
+fn empty_journal(dir: &Path) -> TestResult<PathBuf> {
+    let path = dir.join("activities.pace.jsonl");
+    std::fs::write(&path, "")?;
+    Ok(path)
+}
+
+fn descriptions(storage: &JournalActivityStorage) -> TestResult<BTreeMap<ActivityGuid, String>> {
+    Ok(storage
+        .list_activities_by_id()?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(guid, activity)| (guid, activity.description().clone()))
+        .collect())
+}
+
+fn journal_entries(path: &Path) -> TestResult<Vec<JournalEntry>> {
+    Ok(read_to_string(path)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+#[rstest]
+fn test_journal_storage_appends_and_replays_changes_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = empty_journal(temp_dir.path())?;
+
+    let storage = JournalActivityStorage::new(&path, 100)?;
+
+    let first = begin_activity(&storage, "First")?;
+    storage.sync()?;
+
+    let second = begin_activity(&storage, "Second")?;
+    let _ = storage.end_activity(*second.guid(), EndOptions::default())?;
+    storage.sync()?;
+
+    let operations = journal_entries(&path)?
+        .iter()
+        .map(|entry| *entry.operation())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        operations,
+        vec![
+            JournalOperationKind::Begin,
+            JournalOperationKind::Begin,
+            JournalOperationKind::End
+        ]
+    );
+
+    let replayed = JournalActivityStorage::new(&path, 100)?;
+
+    assert_eq!(descriptions(&replayed)?, descriptions(&storage)?);
+    assert!(replayed
+        .read_activity(*first.guid())?
+        .activity()
+        .is_completed());
+    assert!(replayed
+        .read_activity(*second.guid())?
+        .activity()
+        .is_completed());
+
+    Ok(())
+}
+
+#[rstest]
+fn test_journal_storage_compacts_to_snapshot_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = empty_journal(temp_dir.path())?;
+
+    let storage = JournalActivityStorage::new(&path, 2)?;
+
+    for description in ["First", "Second", "Third"] {
+        let _ = begin_activity(&storage, description)?;
+        storage.sync()?;
+    }
+
+    let entries = journal_entries(&path)?;
+
+    assert_eq!(entries.len(), 1, "Should have compacted the journal.");
+    assert_eq!(*entries[0].operation(), JournalOperationKind::Snapshot);
+
+    assert_eq!(
+        descriptions(&JournalActivityStorage::new(&path, 2)?)?,
+        descriptions(&storage)?
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_journal_storage_skips_incomplete_last_entry_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = empty_journal(temp_dir.path())?;
+
+    let storage = JournalActivityStorage::new(&path, 100)?;
+    let activity = begin_activity(&storage, "Complete")?;
+    storage.sync()?;
+
+    // A crash while appending leaves half an entry behind
+    OpenOptions::new()
+        .append(true)
+        .open(&path)?
+        .write_all(br#"{"at":"2024-01-01T10:00:00+00:00","operation":"cre"#)?;
+
+    let storage = JournalActivityStorage::new(&path, 100)?;
+
+    assert_eq!(
+        storage
+            .read_activity(*activity.guid())?
+            .activity()
+            .description(),
+        activity.activity().description()
+    );
+
+    let _ = begin_activity(&storage, "After the crash")?;
+    storage.sync()?;
+
+    let entries = journal_entries(&path)?;
+
+    assert_eq!(entries.len(), 1, "Should have rewritten the journal.");
+    assert_eq!(entries[0].changed().len(), 2);
+
+    Ok(())
+}
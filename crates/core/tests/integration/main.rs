@@ -1,4 +1,10 @@
+mod activity_archive;
+mod activity_history;
 mod activity_store;
+mod activity_sync;
 mod activity_tracker;
 mod find_configs;
+mod inbox_store;
+mod journal_storage;
+mod toml_storage;
 mod util;
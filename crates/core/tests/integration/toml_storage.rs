@@ -0,0 +1,194 @@
+//! Test the `TomlActivityStorage` with several storages sharing one activity log.
+
+use std::{
+    fs::{read_dir, File},
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread,
+};
+
+use fs4::FileExt;
+use pace_core::prelude::{
+    Activity, ActivityItem, ActivityReadOps, ActivityStateManagement, ActivityStatusKind,
+    ActivityStore, ActivityWriteOps, HoldOptions, PaceEvent, PaceResult, SyncStorage, TestResult,
+    TomlActivityStorage,
+};
+use parking_lot::Mutex;
+
+use rstest::rstest;
+use similar_asserts::assert_eq;
+
+fn create_activity(storage: &TomlActivityStorage, description: &str) -> PaceResult<ActivityItem> {
+    storage.create_activity(
+        Activity::builder()
+            .description(description.to_string())
+            .build(),
+    )
+}
+
+fn empty_activity_log(dir: &Path) -> TestResult<PathBuf> {
+    let path = dir.join("activities.pace.toml");
+    std::fs::write(&path, "")?;
+    Ok(path)
+}
+
+#[rstest]
+fn test_toml_storage_reloads_stale_cache_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = empty_activity_log(temp_dir.path())?;
+
+    let first = TomlActivityStorage::new(&path)?;
+    let second = TomlActivityStorage::new(&path)?;
+
+    let activity = create_activity(&second, "Written by the second storage")?;
+    second.sync()?;
+
+    assert_eq!(
+        first
+            .read_activity(*activity.guid())?
+            .activity()
+            .description(),
+        activity.activity().description(),
+        "Should reload the activity log changed on disk."
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_toml_storage_concurrent_writes_keep_all_activities_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = empty_activity_log(temp_dir.path())?;
+
+    let first = TomlActivityStorage::new(&path)?;
+    let second = TomlActivityStorage::new(&path)?;
+
+    // Both storages loaded the empty activity log, the second one has to wait
+    // for the changes of the first one and build on top of them
+    let (first_activity, second_activity) = thread::scope(|scope| -> TestResult<_> {
+        let first_activity = create_activity(&first, "First")?;
+
+        let handle = scope.spawn(|| -> PaceResult<ActivityItem> {
+            let activity = create_activity(&second, "Second")?;
+            second.sync()?;
+            Ok(activity)
+        });
+
+        first.sync()?;
+
+        let second_activity = handle.join().map_err(|_| "writer thread panicked")??;
+
+        Ok((first_activity, second_activity))
+    })?;
+
+    let reloaded = TomlActivityStorage::new(&path)?;
+
+    assert_eq!(
+        reloaded
+            .read_activity(*first_activity.guid())?
+            .activity()
+            .description(),
+        first_activity.activity().description()
+    );
+    assert_eq!(
+        reloaded
+            .read_activity(*second_activity.guid())?
+            .activity()
+            .description(),
+        second_activity.activity().description()
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_toml_storage_sync_replaces_activity_log_atomically_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = empty_activity_log(temp_dir.path())?;
+
+    let storage = TomlActivityStorage::new(&path)?;
+    let activity = create_activity(&storage, "Synced")?;
+    storage.sync()?;
+
+    let mut file_names = read_dir(temp_dir.path())?
+        .map(|entry| Ok(entry?.file_name().to_string_lossy().to_string()))
+        .collect::<TestResult<Vec<_>>>()?;
+    file_names.sort();
+
+    assert_eq!(
+        file_names,
+        vec!["activities.pace.toml", "activities.pace.toml.lock"],
+        "Should leave no temporary files behind."
+    );
+
+    let reloaded = TomlActivityStorage::new(&path)?;
+
+    assert_eq!(
+        reloaded
+            .read_activity(*activity.guid())?
+            .activity()
+            .description(),
+        activity.activity().description()
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn test_toml_storage_event_subscribers_see_synced_activity_log_passes() -> TestResult<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = empty_activity_log(temp_dir.path())?;
+
+    let store = ActivityStore::with_storage(Arc::new(TomlActivityStorage::new(&path)?.into()))?;
+
+    let statuses = Arc::new(Mutex::new(vec![]));
+
+    let recorded_statuses = Arc::clone(&statuses);
+    let subscriber_path = path.clone();
+
+    // Like a hook running `pace`, the subscriber needs a shared lock on the activity log,
+    // it doesn't wait for it, so the test fails instead of blocking if the lock is still held
+    store
+        .event_bus()
+        .subscribe(vec![], move |event: &PaceEvent| -> PaceResult<()> {
+            let lock_file = File::open(subscriber_path.with_extension("toml.lock"))?;
+
+            let status = if lock_file.try_lock_shared().is_ok() {
+                FileExt::unlock(&lock_file)?;
+
+                Some(
+                    *TomlActivityStorage::new(&subscriber_path)?
+                        .read_activity(event.activity_id())?
+                        .activity()
+                        .status(),
+                )
+            } else {
+                None
+            };
+
+            recorded_statuses.lock().push(status);
+
+            Ok(())
+        });
+
+    let activity = store.begin_activity(
+        Activity::builder()
+            .description("Hooked".to_string())
+            .build(),
+    )?;
+    store.sync()?;
+
+    let _ = store.hold_activity(*activity.guid(), HoldOptions::default())?;
+    store.sync()?;
+
+    assert_eq!(
+        *statuses.lock(),
+        vec![
+            Some(ActivityStatusKind::InProgress),
+            Some(ActivityStatusKind::Paused),
+        ],
+        "Subscribers should be able to lock the activity log and see the synced changes."
+    );
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+//! Error types for the pace server
+
+use displaydoc::Display;
+use pace_core::prelude::PaceError;
+use pace_time::error::PaceTimeErrorKind;
+use thiserror::Error;
+
+pub type PaceServerResult<T> = Result<T, PaceServerErrorKind>;
+
+/// [`PaceServerErrorKind`] describes the errors that can happen while serving the api.
+#[non_exhaustive]
+#[derive(Error, Debug, Display)]
+pub enum PaceServerErrorKind {
+    /// {0}
+    #[error(transparent)]
+    Pace(#[from] PaceError),
+
+    /// {0}
+    #[error(transparent)]
+    Time(#[from] PaceTimeErrorKind),
+
+    /// {0}
+    #[error(transparent)]
+    StdIo(#[from] std::io::Error),
+
+    /// {0}
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+
+    /// Failed to listen on '{address}': {message}
+    BindingFailed { address: String, message: String },
+
+    /// Invalid request body: {0}
+    InvalidRequestBody(String),
+
+    /// Invalid query parameter '{0}': '{1}'
+    InvalidQueryParameter(String, String),
+
+    /// Unknown query parameter: '{0}'
+    UnknownQueryParameter(String),
+
+    /// No endpoint for {0} {1}
+    EndpointNotFound(String, String),
+
+    /// Invalid host '{0}', the server only answers requests to 127.0.0.1 and localhost
+    InvalidHost(String),
+
+    /// Requests from other origins are not allowed: '{0}'
+    OriginNotAllowed(String),
+
+    /// Missing or invalid token, send it as `Authorization: Bearer <token>`
+    InvalidToken,
+
+    /// Unsupported content type '{0}', expected 'application/json'
+    UnsupportedContentType(String),
+
+    /// There are no unfinished activities to end.
+    NoActivityToEnd,
+
+    /// There are no unfinished activities to hold.
+    NoActivityToHold,
+
+    /// There is no held activity to resume.
+    NoActivityToResume,
+}
+
+impl PaceServerErrorKind {
+    /// The HTTP status code the error is answered with
+    #[must_use]
+    pub const fn status_code(&self) -> u16 {
+        match self {
+            Self::Time(_)
+            | Self::InvalidRequestBody(_)
+            | Self::InvalidQueryParameter(..)
+            | Self::UnknownQueryParameter(_) => 400,
+            Self::InvalidToken => 401,
+            Self::InvalidHost(_) | Self::OriginNotAllowed(_) => 403,
+            Self::EndpointNotFound(..) => 404,
+            Self::NoActivityToEnd | Self::NoActivityToHold | Self::NoActivityToResume => 409,
+            Self::UnsupportedContentType(_) => 415,
+            Self::Pace(_) | Self::StdIo(_) | Self::SerdeJson(_) | Self::BindingFailed { .. } => 500,
+        }
+    }
+}
@@ -1,3 +1,17 @@
 //! Server Library for pace
+//!
+//! The server exposes the activity store over a JSON api on the loopback interface,
+//! so editor plugins, status bars and scripts can drive pace without racing on the
+//! activity log.
 
-pub(crate) mod event;
+pub mod error;
+pub mod request;
+pub mod server;
+
+pub mod prelude {
+    pub use crate::{
+        error::{PaceServerErrorKind, PaceServerResult},
+        request::{BeginRequest, EndRequest, HoldRequest, RequestHeaders, ResumeRequest},
+        server::{ApiResponse, PaceServer},
+    };
+}
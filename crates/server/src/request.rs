@@ -0,0 +1,84 @@
+//! Headers and bodies of the requests the api accepts
+
+use pace_time::date_time::PaceDateTime;
+use serde_derive::Deserialize;
+
+/// Body of `POST /begin`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct BeginRequest {
+    /// The description of the activity
+    pub description: String,
+
+    /// The category of the activity, e.g. `development::pace`
+    pub category: Option<String>,
+
+    /// The tags of the activity
+    pub tags: Option<Vec<String>>,
+
+    /// The begin time of the activity, defaults to now
+    pub at: Option<PaceDateTime>,
+}
+
+/// Body of `POST /end`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct EndRequest {
+    /// The end time of the activities, defaults to now
+    pub at: Option<PaceDateTime>,
+}
+
+/// Body of `POST /hold`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct HoldRequest {
+    /// The begin time of the intermission, defaults to now
+    pub at: Option<PaceDateTime>,
+
+    /// The reason for holding the activity
+    pub reason: Option<String>,
+}
+
+/// Body of `POST /resume`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ResumeRequest {
+    /// The time the activity is resumed at, defaults to now
+    pub at: Option<PaceDateTime>,
+}
+
+/// The headers of a request the server checks before handling it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestHeaders {
+    /// The `Host` header
+    pub host: Option<String>,
+
+    /// The `Origin` header, browsers send it with cross-origin requests
+    pub origin: Option<String>,
+
+    /// The `Content-Type` header
+    pub content_type: Option<String>,
+
+    /// The `Authorization` header
+    pub authorization: Option<String>,
+}
+
+impl<'a> FromIterator<(&'a str, &'a str)> for RequestHeaders {
+    fn from_iter<T: IntoIterator<Item = (&'a str, &'a str)>>(headers: T) -> Self {
+        let mut request_headers = Self::default();
+
+        for (name, value) in headers {
+            let header = match name.to_ascii_lowercase().as_str() {
+                "host" => &mut request_headers.host,
+                "origin" => &mut request_headers.origin,
+                "content-type" => &mut request_headers.content_type,
+                "authorization" => &mut request_headers.authorization,
+                _ => continue,
+            };
+
+            *header = Some(value.to_string());
+        }
+
+        request_headers
+    }
+}
@@ -0,0 +1,857 @@
+//! The local pace server, exposing the activity store over a JSON api
+
+use std::{
+    collections::HashSet,
+    io::Read,
+    net::{Ipv4Addr, SocketAddr},
+};
+
+use chrono::NaiveDate;
+use getset::Getters;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+use tracing::{debug, warn};
+
+use pace_core::prelude::{
+    Activity, ActivityFilterKind, ActivityItem, ActivityKind, ActivityQuerying, ActivityReadOps,
+    ActivityStateManagement, ActivityStore, ActivityTracker, EndOptions, ExpensiveFlags,
+    FilterOptions, HoldOptions, PaceConfig, PaceResult, ResumeOptions, SyncStorage,
+};
+use pace_time::{
+    date::PaceDate, date_time::PaceDateTime, time_frame::PaceTimeFrame,
+    time_range::TimeRangeOptions, time_zone::PaceTimeZoneKind, Validate,
+};
+
+use crate::{
+    error::{PaceServerErrorKind, PaceServerResult},
+    request::{BeginRequest, EndRequest, HoldRequest, RequestHeaders, ResumeRequest},
+};
+
+/// The maximum size of a request body in bytes
+const MAX_REQUEST_BODY_SIZE: u64 = 64 * 1024;
+
+/// A response of the api, before it is sent to the client
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct ApiResponse {
+    /// The HTTP status code
+    status_code: u16,
+
+    /// The JSON body
+    body: Value,
+}
+
+impl ApiResponse {
+    /// Create a `200 OK` response with the given body
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body cannot be serialized to JSON
+    pub fn ok(body: &impl Serialize) -> PaceServerResult<Self> {
+        Self::with_status_code(200, body)
+    }
+
+    /// Create a `201 Created` response with the given body
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body cannot be serialized to JSON
+    pub fn created(body: &impl Serialize) -> PaceServerResult<Self> {
+        Self::with_status_code(201, body)
+    }
+
+    fn with_status_code(status_code: u16, body: &impl Serialize) -> PaceServerResult<Self> {
+        Ok(Self {
+            status_code,
+            body: serde_json::to_value(body)?,
+        })
+    }
+}
+
+impl From<&PaceServerErrorKind> for ApiResponse {
+    fn from(err: &PaceServerErrorKind) -> Self {
+        Self {
+            status_code: err.status_code(),
+            body: json!({ "error": err.to_string() }),
+        }
+    }
+}
+
+/// The local pace server
+///
+/// The server owns the only [`ActivityStore`] and handles one request after another,
+/// so it is the single writer of the activity log while it is running.
+pub struct PaceServer {
+    /// The activity tracker, holding the activity store
+    activity_tracker: ActivityTracker,
+
+    /// The pace configuration
+    config: PaceConfig,
+}
+
+impl PaceServer {
+    /// Create a new server for the given activity store
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The pace configuration
+    /// * `activity_store` - The activity store the server owns
+    #[must_use]
+    pub fn new(config: PaceConfig, activity_store: ActivityStore) -> Self {
+        Self {
+            activity_tracker: ActivityTracker::with_activity_store(activity_store),
+            config,
+        }
+    }
+
+    /// Create a new server with the activity log storage from the configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The pace configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activity log cannot be loaded
+    ///
+    /// # Returns
+    ///
+    /// The server, ready to be started with [`PaceServer::serve`]
+    pub fn from_config(config: PaceConfig) -> PaceServerResult<Self> {
+        let activity_store = ActivityStore::from_config(&config)?;
+
+        Ok(Self::new(config, activity_store))
+    }
+
+    /// Listen on the loopback interface and answer requests until the process is stopped
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - The port to listen on
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server cannot listen on the port
+    pub fn serve(&self, port: u16) -> PaceServerResult<()> {
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+
+        let server = Server::http(address).map_err(|err| PaceServerErrorKind::BindingFailed {
+            address: address.to_string(),
+            message: err.to_string(),
+        })?;
+
+        debug!("Listening on http://{address}");
+
+        for mut request in server.incoming_requests() {
+            let response = self.respond_to(port, &mut request);
+
+            let mut http_response = Response::from_string(response.body().to_string())
+                .with_status_code(*response.status_code());
+
+            if let Ok(content_type) = Header::from_bytes("Content-Type", "application/json") {
+                http_response.add_header(content_type);
+            }
+
+            if let Err(err) = request.respond(http_response) {
+                warn!("Failed to send response: {err}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check the headers of a request, read its body and handle it
+    fn respond_to(&self, port: u16, request: &mut Request) -> ApiResponse {
+        let headers = request
+            .headers()
+            .iter()
+            .map(|header| (header.field.as_str().as_str(), header.value.as_str()))
+            .collect::<RequestHeaders>();
+
+        if let Err(err) = self.authorize(port, request.method(), &headers) {
+            debug!("Request rejected: {err}");
+            return ApiResponse::from(&err);
+        }
+
+        let mut body = String::new();
+
+        if let Err(err) = request
+            .as_reader()
+            .take(MAX_REQUEST_BODY_SIZE)
+            .read_to_string(&mut body)
+        {
+            return ApiResponse::from(&PaceServerErrorKind::InvalidRequestBody(err.to_string()));
+        }
+
+        self.handle(request.method(), request.url(), &body)
+    }
+
+    /// Check that a request is allowed to use the api
+    ///
+    /// Any website open in a browser can send requests to the loopback interface, so
+    /// requests to other hosts than `127.0.0.1` and `localhost`, requests with an
+    /// `Origin` header and `POST` requests without a JSON content type are rejected.
+    /// If the configuration has a token, requests have to send it as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - The port the server listens on
+    /// * `method` - The HTTP method of the request
+    /// * `headers` - The headers of the request
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request isn't allowed
+    pub fn authorize(
+        &self,
+        port: u16,
+        method: &Method,
+        headers: &RequestHeaders,
+    ) -> PaceServerResult<()> {
+        let host = headers.host.as_deref().unwrap_or_default();
+
+        if host != format!("127.0.0.1:{port}") && host != format!("localhost:{port}") {
+            return Err(PaceServerErrorKind::InvalidHost(host.to_string()));
+        }
+
+        if let Some(origin) = &headers.origin {
+            return Err(PaceServerErrorKind::OriginNotAllowed(origin.clone()));
+        }
+
+        if let Some(token) = self
+            .config
+            .server()
+            .as_ref()
+            .and_then(|server_config| server_config.token().as_deref())
+        {
+            let authorization = headers.authorization.as_deref().unwrap_or_default();
+
+            if !constant_time_eq(
+                authorization.as_bytes(),
+                format!("Bearer {token}").as_bytes(),
+            ) {
+                return Err(PaceServerErrorKind::InvalidToken);
+            }
+        }
+
+        if *method == Method::Post {
+            let content_type = headers.content_type.as_deref().unwrap_or_default();
+
+            // Ignore parameters like `; charset=utf-8`
+            let media_type = content_type.split(';').next().unwrap_or_default().trim();
+
+            if !media_type.eq_ignore_ascii_case("application/json") {
+                return Err(PaceServerErrorKind::UnsupportedContentType(
+                    content_type.to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a request to the api
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The HTTP method of the request
+    /// * `url` - The url of the request, including the query
+    /// * `body` - The body of the request, may be empty
+    ///
+    /// # Returns
+    ///
+    /// The response, errors are answered with `{"error": "..."}` and a matching status code
+    #[tracing::instrument(skip(self, body))]
+    pub fn handle(&self, method: &Method, url: &str, body: &str) -> ApiResponse {
+        match self.route(method, url, body) {
+            Ok(response) => response,
+            Err(err) => {
+                debug!("Request failed: {err}");
+                ApiResponse::from(&err)
+            }
+        }
+    }
+
+    fn route(&self, method: &Method, url: &str, body: &str) -> PaceServerResult<ApiResponse> {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+
+        match (method, path) {
+            (Method::Get, "/now") => self.now(),
+            (Method::Get, "/activities") => self.activities(),
+            (Method::Get, "/reflect") => self.reflect(query),
+            (Method::Post, "/begin") => self.begin(parse_body(body)?),
+            (Method::Post, "/end") => self.end(parse_body(body)?),
+            (Method::Post, "/hold") => self.hold(parse_body(body)?),
+            (Method::Post, "/resume") => self.resume(parse_body(body)?),
+            _ => Err(PaceServerErrorKind::EndpointNotFound(
+                method.to_string(),
+                path.to_string(),
+            )),
+        }
+    }
+
+    /// `GET /now`: the currently active activities
+    fn now(&self) -> PaceServerResult<ApiResponse> {
+        let activity_store = &self.activity_tracker.store;
+
+        let activity_items = activity_store
+            .list_current_activities(ActivityFilterKind::Active)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|activity_id| activity_store.read_activity(activity_id))
+            .collect::<Result<Vec<ActivityItem>, _>>()?;
+
+        ApiResponse::ok(&activity_items)
+    }
+
+    /// `GET /activities`: all activities in the activity log, oldest first
+    fn activities(&self) -> PaceServerResult<ApiResponse> {
+        let activity_items = self
+            .activity_tracker
+            .store
+            .list_activities_by_id()?
+            .unwrap_or_default()
+            .into_iter()
+            .map(ActivityItem::from)
+            .collect::<Vec<_>>();
+
+        ApiResponse::ok(&activity_items)
+    }
+
+    /// Change the activities and write the changes
+    ///
+    /// The activity store is synced even if the change fails or changes nothing,
+    /// because the file storages lock the activity log until the changes are
+    /// written and the long-running server would block every `pace` command otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `change` - Changes the activities in the activity store
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the change fails or the changes cannot be written
+    fn change_activities<T>(
+        &self,
+        change: impl FnOnce(&ActivityStore) -> PaceResult<T>,
+    ) -> PaceServerResult<T> {
+        let activity_store = &self.activity_tracker.store;
+
+        let changed = change(activity_store);
+
+        let synced = activity_store.sync();
+
+        let value = changed?;
+
+        synced?;
+
+        Ok(value)
+    }
+
+    /// `POST /begin`: begin a new activity, ending all unfinished ones
+    fn begin(&self, request: BeginRequest) -> PaceServerResult<ApiResponse> {
+        let BeginRequest {
+            description,
+            category,
+            tags,
+            at,
+        } = request;
+
+        if description.trim().is_empty() {
+            return Err(PaceServerErrorKind::InvalidRequestBody(
+                "`description` must not be empty".to_string(),
+            ));
+        }
+
+        let activity = Activity::builder()
+            .description(description)
+            .begin(validated_time(at)?)
+            .kind(ActivityKind::default())
+            .category(category)
+            .tags(tags.map(|tags| tags.into_iter().collect::<HashSet<String>>()))
+            .build();
+
+        let activity_item =
+            self.change_activities(|activity_store| activity_store.begin_activity(activity))?;
+
+        ApiResponse::created(&activity_item)
+    }
+
+    /// `POST /end`: end all unfinished activities
+    fn end(&self, EndRequest { at }: EndRequest) -> PaceServerResult<ApiResponse> {
+        let end_opts = EndOptions::builder().end_time(validated_time(at)?).build();
+
+        let ended_activities = self
+            .change_activities(|activity_store| activity_store.end_all_activities(end_opts))?
+            .ok_or(PaceServerErrorKind::NoActivityToEnd)?;
+
+        ApiResponse::ok(&ended_activities)
+    }
+
+    /// `POST /hold`: pause the most recent active activity
+    fn hold(&self, request: HoldRequest) -> PaceServerResult<ApiResponse> {
+        let hold_opts = HoldOptions::builder()
+            .begin_time(validated_time(request.at)?)
+            .reason(request.reason)
+            .build();
+
+        let held_activity = self
+            .change_activities(|activity_store| {
+                activity_store.hold_most_recent_active_activity(hold_opts)
+            })?
+            .ok_or(PaceServerErrorKind::NoActivityToHold)?;
+
+        ApiResponse::ok(&held_activity)
+    }
+
+    /// `POST /resume`: resume the most recent held activity
+    fn resume(&self, ResumeRequest { at }: ResumeRequest) -> PaceServerResult<ApiResponse> {
+        let resume_opts = ResumeOptions::builder()
+            .resume_time(validated_time(at)?)
+            .build();
+
+        let resumed_activity = self
+            .change_activities(|activity_store| {
+                activity_store.resume_most_recent_activity(resume_opts)
+            })?
+            .ok_or(PaceServerErrorKind::NoActivityToResume)?;
+
+        ApiResponse::ok(&resumed_activity)
+    }
+
+    /// `GET /reflect`: the reflection for a time frame, `null` if there are no activities
+    ///
+    /// Without a query the reflection is for today. `date=YYYY-MM-DD` selects a specific date,
+    /// `from=YYYY-MM-DD` and `to=YYYY-MM-DD` a date range. `detailed`, `comparative` and
+    /// `recommendations` can be set to `true` to add the more expensive parts of a reflection.
+    fn reflect(&self, query: &str) -> PaceServerResult<ApiResponse> {
+        let time_zone = PaceTimeZoneKind::from(self.config.general().default_time_zone().as_ref());
+
+        let (mut date, mut from, mut to) = (None, None, None);
+        let (mut detailed, mut comparative, mut recommendations) = (false, false, false);
+
+        for (key, value) in query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+        {
+            match key {
+                "date" => date = Some(parse_query_value::<NaiveDate>(key, value)?),
+                "from" => from = Some(parse_query_value::<NaiveDate>(key, value)?),
+                "to" => to = Some(parse_query_value::<NaiveDate>(key, value)?),
+                "detailed" => detailed = parse_query_value(key, value)?,
+                "comparative" => comparative = parse_query_value(key, value)?,
+                "recommendations" => recommendations = parse_query_value(key, value)?,
+                _ => return Err(PaceServerErrorKind::UnknownQueryParameter(key.to_string())),
+            }
+        }
+
+        let time_frame = match (date, from, to) {
+            (Some(date), None, None) => PaceTimeFrame::SpecificDate(PaceDate::from(date)),
+            (None, None, None) => PaceTimeFrame::Today,
+            (None, from, to) => PaceTimeFrame::DateRange(
+                TimeRangeOptions::builder()
+                    .start(
+                        from.map(|from| PaceDateTime::try_from((from, time_zone))?.start_of_day())
+                            .transpose()?
+                            .unwrap_or_else(PaceDateTime::now),
+                    )
+                    .end(
+                        to.map(|to| PaceDateTime::try_from((to, time_zone))?.end_of_day())
+                            .transpose()?
+                            .unwrap_or_else(PaceDateTime::now),
+                    )
+                    .build(),
+            ),
+            (Some(date), ..) => {
+                return Err(PaceServerErrorKind::InvalidQueryParameter(
+                    "date".to_string(),
+                    format!("{date}, it can't be combined with `from` and `to`"),
+                ))
+            }
+        };
+
+        let expensive_flags = ExpensiveFlags::builder()
+            .detailed(detailed)
+            .comparative(comparative)
+            .recommendations(recommendations)
+            .build();
+
+        let reflection = self.activity_tracker.generate_reflection(
+            FilterOptions::default(),
+            time_frame,
+            &expensive_flags,
+            &self.config.duration_range_options()?,
+        )?;
+
+        ApiResponse::ok(&reflection)
+    }
+}
+
+/// Parse the JSON body of a request, an empty body is the default request
+fn parse_body<T: DeserializeOwned + Default>(body: &str) -> PaceServerResult<T> {
+    if body.trim().is_empty() {
+        return Ok(T::default());
+    }
+
+    serde_json::from_str(body)
+        .map_err(|err| PaceServerErrorKind::InvalidRequestBody(err.to_string()))
+}
+
+/// Parse the value of a query parameter
+fn parse_query_value<T: std::str::FromStr>(key: &str, value: &str) -> PaceServerResult<T> {
+    value
+        .parse()
+        .map_err(|_| PaceServerErrorKind::InvalidQueryParameter(key.to_string(), value.to_string()))
+}
+
+/// Compare two byte strings in a time that doesn't depend on where they differ,
+/// so the token can't be guessed byte by byte from the response times
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0, |difference, (left, right)| difference | (left ^ right))
+            == 0
+}
+
+/// Use the given time or now, the time must not be in the future
+fn validated_time(at: Option<PaceDateTime>) -> PaceServerResult<PaceDateTime> {
+    Ok(at.unwrap_or_else(PaceDateTime::now).validate()?)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::{fs::File, sync::Arc};
+
+    use fs4::FileExt;
+    use pace_core::prelude::{InMemoryActivityStorage, TestResult, TomlActivityStorage};
+
+    use super::*;
+
+    fn in_memory_server() -> TestResult<PaceServer> {
+        let activity_store =
+            ActivityStore::with_storage(Arc::new(InMemoryActivityStorage::new().into()))?;
+
+        Ok(PaceServer::new(PaceConfig::default(), activity_store))
+    }
+
+    #[test]
+    fn test_server_activity_lifecycle_passes() -> TestResult<()> {
+        let server = in_memory_server()?;
+
+        let response = server.handle(
+            &Method::Post,
+            "/begin",
+            r#"{"description": "Write the server", "category": "development::pace", "tags": ["rust"]}"#,
+        );
+        assert_eq!(*response.status_code(), 201);
+        assert_eq!(
+            response.body()["activity"]["description"],
+            "Write the server"
+        );
+
+        let response = server.handle(&Method::Get, "/now", "");
+        assert_eq!(*response.status_code(), 200);
+        assert_eq!(response.body().as_array().map(Vec::len), Some(1));
+
+        let response = server.handle(&Method::Post, "/hold", r#"{"reason": "Lunch"}"#);
+        assert_eq!(*response.status_code(), 200);
+
+        let response = server.handle(&Method::Post, "/resume", "");
+        assert_eq!(*response.status_code(), 200);
+
+        let response = server.handle(&Method::Post, "/end", "");
+        assert_eq!(*response.status_code(), 200);
+
+        let response = server.handle(&Method::Post, "/end", "");
+        assert_eq!(*response.status_code(), 409);
+        assert!(response.body()["error"].is_string());
+
+        let response = server.handle(&Method::Get, "/now", "");
+        assert_eq!(response.body().as_array().map(Vec::len), Some(0));
+
+        let response = server.handle(&Method::Get, "/activities", "");
+        assert_eq!(*response.status_code(), 200);
+        assert!(response
+            .body()
+            .as_array()
+            .is_some_and(|activities| activities.len() > 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_releases_activity_log_lock_on_every_path_passes() -> TestResult<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("activities.pace.toml");
+        std::fs::write(&path, "")?;
+
+        let activity_store =
+            ActivityStore::with_storage(Arc::new(TomlActivityStorage::new(&path)?.into()))?;
+
+        let server = PaceServer::new(PaceConfig::default(), activity_store);
+
+        let lock_is_free = || -> TestResult<bool> {
+            let lock_file = File::open(path.with_extension("toml.lock"))?;
+
+            let is_free = lock_file.try_lock_exclusive().is_ok();
+
+            if is_free {
+                FileExt::unlock(&lock_file)?;
+            }
+
+            Ok(is_free)
+        };
+
+        for (endpoint, status_code) in [("/end", 409), ("/hold", 409), ("/resume", 409)] {
+            assert_eq!(
+                *server.handle(&Method::Post, endpoint, "").status_code(),
+                status_code
+            );
+            assert!(
+                lock_is_free()?,
+                "The lock should be released after `{endpoint}` changed nothing."
+            );
+        }
+
+        assert_eq!(
+            *server
+                .handle(&Method::Post, "/begin", r#"{"description": "Locked"}"#)
+                .status_code(),
+            201
+        );
+        assert!(
+            lock_is_free()?,
+            "The lock should be released after a change."
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_rejects_invalid_requests_passes() -> TestResult<()> {
+        let server = in_memory_server()?;
+
+        assert_eq!(
+            *server.handle(&Method::Get, "/unknown", "").status_code(),
+            404
+        );
+        assert_eq!(
+            *server.handle(&Method::Get, "/begin", "").status_code(),
+            404
+        );
+        assert_eq!(
+            *server.handle(&Method::Post, "/begin", "{}").status_code(),
+            400
+        );
+        assert_eq!(
+            *server
+                .handle(&Method::Post, "/begin", r#"{"description": 42}"#)
+                .status_code(),
+            400
+        );
+        assert_eq!(
+            *server
+                .handle(
+                    &Method::Post,
+                    "/end",
+                    r#"{"at": "2999-01-01T12:00:00+00:00"}"#
+                )
+                .status_code(),
+            400
+        );
+        assert_eq!(
+            *server.handle(&Method::Post, "/hold", "").status_code(),
+            409
+        );
+        assert_eq!(
+            *server
+                .handle(&Method::Get, "/reflect?date=yesterday", "")
+                .status_code(),
+            400
+        );
+        assert_eq!(
+            *server
+                .handle(&Method::Get, "/reflect?unknown=true", "")
+                .status_code(),
+            400
+        );
+
+        Ok(())
+    }
+
+    fn local_headers(port: u16) -> RequestHeaders {
+        RequestHeaders {
+            host: Some(format!("127.0.0.1:{port}")),
+            content_type: Some("application/json".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_server_authorizes_local_requests_passes() -> TestResult<()> {
+        let server = in_memory_server()?;
+
+        server.authorize(7878, &Method::Post, &local_headers(7878))?;
+        server.authorize(
+            7878,
+            &Method::Post,
+            &[
+                ("host", "localhost:7878"),
+                ("Content-Type", "application/json; charset=utf-8"),
+            ]
+            .into_iter()
+            .collect(),
+        )?;
+        server.authorize(
+            7878,
+            &Method::Get,
+            &RequestHeaders {
+                content_type: None,
+                ..local_headers(7878)
+            },
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_rejects_foreign_hosts_fails() -> TestResult<()> {
+        let server = in_memory_server()?;
+
+        for host in [None, Some("evil.example:7878"), Some("127.0.0.1:8080")] {
+            let headers = RequestHeaders {
+                host: host.map(ToString::to_string),
+                ..local_headers(7878)
+            };
+
+            let result = server.authorize(7878, &Method::Get, &headers);
+
+            assert!(
+                matches!(result, Err(PaceServerErrorKind::InvalidHost(_))),
+                "Should reject requests to {host:?}."
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_rejects_requests_with_origin_fails() -> TestResult<()> {
+        let server = in_memory_server()?;
+
+        let headers = RequestHeaders {
+            origin: Some("https://evil.example".to_string()),
+            ..local_headers(7878)
+        };
+
+        let result = server.authorize(7878, &Method::Post, &headers);
+
+        assert!(matches!(
+            result,
+            Err(PaceServerErrorKind::OriginNotAllowed(_))
+        ));
+        assert_eq!(result.map_err(|err| err.status_code()).err(), Some(403));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_rejects_posts_without_json_fails() -> TestResult<()> {
+        let server = in_memory_server()?;
+
+        for content_type in [
+            None,
+            Some("text/plain"),
+            Some("application/x-www-form-urlencoded"),
+        ] {
+            let headers = RequestHeaders {
+                content_type: content_type.map(ToString::to_string),
+                ..local_headers(7878)
+            };
+
+            let result = server.authorize(7878, &Method::Post, &headers);
+
+            assert!(
+                matches!(result, Err(PaceServerErrorKind::UnsupportedContentType(_))),
+                "Should reject posts with content type {content_type:?}."
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_rejects_requests_without_token_fails() -> TestResult<()> {
+        let activity_store =
+            ActivityStore::with_storage(Arc::new(InMemoryActivityStorage::new().into()))?;
+
+        let mut config = PaceConfig::default();
+        *config.server_mut() = Some(serde_json::from_value(json!({ "token": "secret" }))?);
+
+        let server = PaceServer::new(config, activity_store);
+
+        for authorization in [None, Some("Bearer wrong"), Some("secret")] {
+            let headers = RequestHeaders {
+                authorization: authorization.map(ToString::to_string),
+                ..local_headers(7878)
+            };
+
+            let result = server.authorize(7878, &Method::Get, &headers);
+
+            assert!(
+                matches!(result, Err(PaceServerErrorKind::InvalidToken)),
+                "Should reject requests with authorization {authorization:?}."
+            );
+        }
+
+        server.authorize(
+            7878,
+            &Method::Get,
+            &RequestHeaders {
+                authorization: Some("Bearer secret".to_string()),
+                ..local_headers(7878)
+            },
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_reflect_passes() -> TestResult<()> {
+        let server = in_memory_server()?;
+
+        let response = server.handle(&Method::Get, "/reflect", "");
+        assert_eq!(*response.status_code(), 200);
+        assert!(response.body().is_null());
+
+        let response = server.handle(
+            &Method::Post,
+            "/begin",
+            r#"{"description": "Reflect on the server", "at": "2024-03-05T09:00:00+00:00"}"#,
+        );
+        assert_eq!(*response.status_code(), 201);
+
+        let response = server.handle(
+            &Method::Post,
+            "/end",
+            r#"{"at": "2024-03-05T10:30:00+00:00"}"#,
+        );
+        assert_eq!(*response.status_code(), 200);
+
+        let response = server.handle(
+            &Method::Get,
+            "/reflect?from=2024-03-01&to=2024-03-31&detailed=true",
+            "",
+        );
+        assert_eq!(*response.status_code(), 200);
+        assert_eq!(response.body()["total_time_spent"], 5400);
+        assert_eq!(response.body()["statistics"]["session_count"], 1);
+
+        Ok(())
+    }
+}
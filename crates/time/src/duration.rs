@@ -7,9 +7,11 @@ use std::{
 use chrono::{DateTime, Local};
 
 use derive_more::{Add, AddAssign};
+use getset::Getters;
 use humantime::format_duration;
 use serde_derive::{Deserialize, Serialize};
 use tracing::debug;
+use typed_builder::TypedBuilder;
 
 use crate::{
     date_time::PaceDateTime,
@@ -64,6 +66,76 @@ pub enum PaceDurationRange {
     Long,
 }
 
+/// The boundaries between short, medium and long durations
+///
+/// Durations below `short_below_minutes` are short, durations of at least
+/// `long_from_minutes` are long, everything in between is medium.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Getters, TypedBuilder)]
+#[getset(get = "pub")]
+#[serde(default, rename_all = "kebab-case")]
+pub struct PaceDurationRangeOptions {
+    /// Durations below this are short (in minutes)
+    /// Default: `15`
+    short_below_minutes: u64,
+
+    /// Durations of at least this are long (in minutes)
+    /// Default: `60`
+    long_from_minutes: u64,
+}
+
+impl Default for PaceDurationRangeOptions {
+    fn default() -> Self {
+        Self {
+            short_below_minutes: 15,
+            long_from_minutes: 60,
+        }
+    }
+}
+
+impl PaceDurationRangeOptions {
+    /// Get the duration range a duration falls into
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - The duration to get the range for
+    ///
+    /// # Returns
+    ///
+    /// The duration range, if both boundaries are the same, no duration is medium
+    #[must_use]
+    pub const fn range_of(&self, duration: PaceDuration) -> PaceDurationRange {
+        let minutes = duration.as_secs() / 60;
+
+        if minutes < self.short_below_minutes {
+            PaceDurationRange::Short
+        } else if minutes < self.long_from_minutes {
+            PaceDurationRange::Medium
+        } else {
+            PaceDurationRange::Long
+        }
+    }
+
+    /// Validate that the boundary for long durations is not below the one for short durations
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the boundary for long durations is below the one for short durations
+    ///
+    /// # Returns
+    ///
+    /// The validated duration range options
+    pub const fn validate(self) -> PaceTimeResult<Self> {
+        if self.long_from_minutes < self.short_below_minutes {
+            return Err(PaceTimeErrorKind::InvalidDurationRanges(
+                self.short_below_minutes,
+                self.long_from_minutes,
+            ));
+        }
+
+        Ok(self)
+    }
+}
+
 /// The duration of an activity
 #[derive(
     Debug,
@@ -464,4 +536,27 @@ mod tests {
 
         Ok(())
     }
+
+    #[rstest]
+    #[case(0, PaceDurationRange::Short)]
+    #[case(14 * 60 + 59, PaceDurationRange::Short)]
+    #[case(15 * 60, PaceDurationRange::Medium)]
+    #[case(59 * 60 + 59, PaceDurationRange::Medium)]
+    #[case(60 * 60, PaceDurationRange::Long)]
+    fn test_duration_range_of_passes(#[case] seconds: u64, #[case] expected: PaceDurationRange) {
+        assert_eq!(
+            PaceDurationRangeOptions::default().range_of(PaceDuration::new(seconds)),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_duration_range_options_validate_fails() {
+        let duration_range_opts = PaceDurationRangeOptions::builder()
+            .short_below_minutes(30)
+            .long_from_minutes(20)
+            .build();
+
+        assert!(duration_range_opts.validate().is_err());
+    }
 }
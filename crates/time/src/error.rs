@@ -74,4 +74,7 @@ pub enum PaceTimeErrorKind {
 
     /// Failed to convert PaceDuration to Standard Duration: '{0}'
     ConversionToDurationFailed(String),
+
+    /// Invalid duration ranges: short durations end at {0} minutes, but long durations begin at {1} minutes
+    InvalidDurationRanges(u64, u64),
 }
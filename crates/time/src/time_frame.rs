@@ -1,3 +1,4 @@
+use chrono::{DateTime, Days, FixedOffset, Months, TimeDelta};
 use displaydoc::Display;
 use serde_derive::{Deserialize, Serialize};
 use tracing::debug;
@@ -5,7 +6,7 @@ use tracing::debug;
 use crate::{
     date::PaceDate,
     date_time::PaceDateTime,
-    error::PaceTimeErrorKind,
+    error::{PaceTimeErrorKind, PaceTimeResult},
     flags::{DateFlags, TimeFlags},
     time_range::TimeRangeOptions,
     time_zone::PaceTimeZoneKind,
@@ -45,6 +46,64 @@ pub enum PaceTimeFrame {
     Yesterday,
 }
 
+impl PaceTimeFrame {
+    /// Get the time range of the previous equivalent time frame
+    ///
+    /// Calendar time frames move back by one day, week, month, or year. Time frames
+    /// that run until now, e.g. the current week, are compared to the same part of
+    /// the previous period. Date ranges are compared to the range of the same length
+    /// right before them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the time range of the time frame or the previous time frame
+    /// cannot be calculated
+    ///
+    /// # Returns
+    ///
+    /// Returns the time range of the previous time frame
+    pub fn previous_time_range(&self) -> PaceTimeResult<TimeRangeOptions> {
+        let time_range = TimeRangeOptions::try_from(self.clone())?;
+
+        let start = time_range.start().inner();
+        let end = time_range.end().inner();
+
+        let one_second = TimeDelta::try_seconds(1).ok_or(PaceTimeErrorKind::InvalidUserInput)?;
+
+        let shift = |date_time: DateTime<FixedOffset>| match self {
+            Self::Today | Self::Yesterday | Self::SpecificDate(_) => {
+                date_time.checked_sub_days(Days::new(1))
+            }
+            Self::CurrentWeek | Self::LastWeek => date_time.checked_sub_days(Days::new(7)),
+            Self::CurrentMonth | Self::LastMonth => date_time.checked_sub_months(Months::new(1)),
+            Self::CurrentYear | Self::LastYear => date_time.checked_sub_months(Months::new(12)),
+            Self::DateRange(_) => date_time.checked_sub_signed((end - start) + one_second),
+        };
+
+        let runs_until_now = matches!(
+            self,
+            Self::Today | Self::CurrentWeek | Self::CurrentMonth | Self::CurrentYear
+        );
+
+        let previous_start =
+            shift(start).ok_or_else(|| PaceTimeErrorKind::InvalidDate(start.to_string()))?;
+
+        let previous_end = if runs_until_now {
+            shift(end)
+        } else {
+            start.checked_sub_signed(one_second)
+        }
+        .ok_or_else(|| PaceTimeErrorKind::InvalidDate(end.to_string()))?;
+
+        debug!("Previous time range: {previous_start} - {previous_end}");
+
+        Ok(TimeRangeOptions::builder()
+            .start(PaceDateTime::with_date_time_fixed_offset(previous_start))
+            .end(PaceDateTime::with_date_time_fixed_offset(previous_end))
+            .build())
+    }
+}
+
 impl
     TryFrom<(
         Option<&TimeFlags>,
@@ -299,4 +358,40 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_previous_time_range_of_date_range_passes() -> Result<()> {
+        let time_range = TimeRangeOptions::builder()
+            .start("2024-03-11T00:00:00+01:00".parse::<PaceDateTime>()?)
+            .end("2024-03-17T23:59:59+01:00".parse::<PaceDateTime>()?)
+            .build();
+
+        let previous = PaceTimeFrame::DateRange(time_range).previous_time_range()?;
+
+        assert_eq!(
+            previous,
+            TimeRangeOptions::builder()
+                .start("2024-03-04T00:00:00+01:00".parse::<PaceDateTime>()?)
+                .end("2024-03-10T23:59:59+01:00".parse::<PaceDateTime>()?)
+                .build()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_previous_time_range_of_today_passes() -> Result<()> {
+        let today = TimeRangeOptions::today()?;
+
+        let previous = PaceTimeFrame::Today.previous_time_range()?;
+
+        let one_day = TimeDelta::try_days(1).ok_or_else(|| eyre::eyre!("Invalid time delta."))?;
+
+        assert_eq!(previous.start().inner(), today.start().inner() - one_day);
+        // The previous time range runs until the same time yesterday
+        assert!(previous.end() < today.start());
+        assert!(previous.end() > previous.start());
+
+        Ok(())
+    }
 }
@@ -11,18 +11,32 @@
 //! application's configuration file.
 
 pub mod adjust;
+pub mod archive;
 pub mod begin;
 pub mod docs;
 pub mod end;
+pub mod export;
 pub mod hold;
+pub mod import;
+pub mod inbox;
+pub mod invoice;
+pub mod log;
 pub mod now;
+pub mod pomo;
+pub mod redo;
 pub mod reflect;
 pub mod resume;
+pub mod serve;
 pub mod settings;
 pub mod setup;
+pub mod sync;
+pub mod tag;
+pub mod tasks;
+pub mod undo;
 
 use abscissa_core::{
-    config::Override, status_warn, tracing::debug, Command, Configurable, FrameworkError, Runnable,
+    config::Override, status_info, status_warn, tracing::debug, Application, Command, Configurable,
+    FrameworkError, Runnable,
 };
 use clap::builder::{styling::AnsiColor, Styles};
 use human_panic::setup_panic;
@@ -30,9 +44,11 @@ use std::path::PathBuf;
 
 use pace_core::{
     constants::PACE_CONFIG_FILENAME,
-    prelude::{get_config_paths, ActivityLogFormatKind, PaceConfig},
+    prelude::{get_config_paths, ActivityLogFormatKind, ArchiveCommandOptions, PaceConfig},
 };
 
+use crate::prelude::PACE_APP;
+
 /// Pace Subcommands
 /// Subcommands need to be listed in an enum.
 #[derive(clap::Parser, Command, Debug, Runnable)]
@@ -75,14 +91,47 @@ pub enum PaceCmd {
     /// 📚 Open the online documentation for pace.
     #[clap(visible_alias = "d")]
     Docs(docs::DocsCmd),
-    // /// Exports your tracked data and reflections in JSON or CSV format, suitable for analysis or record-keeping.
-    // Export(export::ExportCmd),
-
-    // /// Starts a Pomodoro session for the specified task, integrating the Pomodoro technique directly with your tasks.
-    // Pomo(pomo::PomoCmd),
-    //
-    // /// Lists all tasks with optional filters. Use this to view active, completed, or today's tasks.
-    // Tasks(tasks::TasksCmd),
+
+    /// 📤 Exports your tracked activities in JSON, CSV, or iCalendar format, suitable for analysis or record-keeping.
+    Export(export::ExportCmd),
+
+    /// 📥 Imports your history from other time trackers like Timewarrior or Watson, or from CSV files.
+    Import(import::ImportCmd),
+
+    /// 🧾 Renders an invoice for the billable time spent on a client, with the rates of your configuration.
+    Invoice(invoice::InvoiceCmd),
+
+    /// 🍅 Starts a Pomodoro session, alternating between work sessions and breaks with a countdown.
+    Pomo(pomo::PomoCmd),
+
+    /// ✅ Manages your tasks, which you can track time for with `pace begin --task`.
+    #[clap(visible_alias = "t")]
+    Tasks(tasks::TasksCmd),
+
+    /// 📬 Quickly captures ideas and to-dos in your inbox, to promote them to tasks or activities later.
+    #[clap(visible_alias = "in")]
+    Inbox(inbox::InboxCmd),
+
+    /// 🗄️  Moves activities that have ended a while ago to the archive, to keep your activity log small.
+    Archive(archive::ArchiveCmd),
+
+    /// 🏷️  Adds, removes, renames, or lists the tags of your activities.
+    Tag(tag::TagCmd),
+
+    /// 🛰️  Runs a local server, so editor plugins, status bars and scripts can control pace over a JSON API.
+    Serve(serve::ServeCmd),
+
+    /// ↩️  Undoes the most recent change to your activities, e.g. a mistaken `pace adjust` or `pace end --all`.
+    Undo(undo::UndoCmd),
+
+    /// ↪️  Redoes the most recently undone change to your activities.
+    Redo(redo::RedoCmd),
+
+    /// 📜 Shows the most recent changes to your activities, or every change to a single activity.
+    Log(log::LogCmd),
+
+    /// 🔄 Merges the activity logs of your devices, from a shared folder or a git repository.
+    Sync(sync::SyncCmd),
 }
 
 /// Define CLI colour styles for the application
@@ -122,6 +171,30 @@ pub struct EntryPoint {
 impl Runnable for EntryPoint {
     fn run(&self) {
         setup_panic!();
+
+        // Setting up pace and reading the docs shouldn't touch the activity log. The
+        // archival is recorded in the history, so it mustn't come between `pace undo`
+        // or `pace redo` and the change they are meant for, and the server keeps running
+        if !matches!(
+            self.cmd,
+            PaceCmd::Setup(_)
+                | PaceCmd::Settings(_)
+                | PaceCmd::Docs(_)
+                | PaceCmd::Archive(_)
+                | PaceCmd::Undo(_)
+                | PaceCmd::Redo(_)
+                | PaceCmd::Log(_)
+                | PaceCmd::Serve(_)
+        ) {
+            match ArchiveCommandOptions::handle_auto_archive(&PACE_APP.config()) {
+                Ok(Some(count)) if count > 0 => {
+                    status_info!("Archived", "{count} activities that have ended a while ago");
+                }
+                Ok(_) => {}
+                Err(err) => status_warn!("Auto-archival failed: {}", err),
+            }
+        }
+
         self.cmd.run();
     }
 }
@@ -147,12 +220,13 @@ impl Override<PaceConfig> for EntryPoint {
             *config.general_mut().activity_log_options_mut().path_mut() =
                 activity_log_file.to_path_buf();
 
-            // Set the activity log format to TOML
-            // TODO: This should be configurable
+            // Set the activity log format, journals are stored as JSON lines
+            let format_kind = ActivityLogFormatKind::from_path(activity_log_file);
+
             *config
                 .general_mut()
                 .activity_log_options_mut()
-                .format_kind_mut() = Some(ActivityLogFormatKind::Toml);
+                .format_kind_mut() = Some(format_kind);
         };
 
         debug!("Overridden config: {:?}", config);
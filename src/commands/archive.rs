@@ -0,0 +1,27 @@
+//! `archive` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::ArchiveCommandOptions;
+
+/// `archive` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct ArchiveCmd {
+    #[clap(flatten)]
+    archive_opts: ArchiveCommandOptions,
+}
+
+impl Runnable for ArchiveCmd {
+    fn run(&self) {
+        match self.archive_opts.handle_archive(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -1,31 +1,27 @@
 //! `export` subcommand
 
-use abscissa_core::{Command, Runnable};
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
 use clap::Parser;
 
+use pace_core::prelude::ExportCommandOptions;
+
+use crate::prelude::PACE_APP;
+
 /// `export` subcommand
-///
-/// The `Parser` proc macro generates an option parser based on the struct
-/// definition, and is defined in the `clap` crate. See their documentation
-/// for a more comprehensive example:
-///
-/// <https://docs.rs/clap/>
 #[derive(Command, Debug, Parser)]
 pub struct ExportCmd {
-    // /// Option foobar. Doc comments are the help description
-    // #[clap(short)]
-    // foobar: Option<PathBuf>
-
-    // /// Baz path
-    // #[clap(long)]
-    // baz: Option<PathBuf>
-
-    // "free" arguments don't need a macro
-    // free_args: Vec<String>,
+    #[clap(flatten)]
+    export_opts: ExportCommandOptions,
 }
 
 impl Runnable for ExportCmd {
     fn run(&self) {
-        // Your code goes here
+        match self.export_opts.handle_export(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
     }
 }
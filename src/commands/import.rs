@@ -0,0 +1,27 @@
+//! `import` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use pace_core::prelude::ImportCommandOptions;
+
+use crate::prelude::PACE_APP;
+
+/// `import` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct ImportCmd {
+    #[clap(flatten)]
+    import_opts: ImportCommandOptions,
+}
+
+impl Runnable for ImportCmd {
+    fn run(&self) {
+        match self.import_opts.handle_import(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -0,0 +1,31 @@
+//! `inbox` subcommand
+
+use abscissa_core::{Command, Runnable};
+use clap::{Parser, Subcommand};
+
+mod add;
+mod list;
+mod promote;
+
+/// `inbox` subcommand
+#[derive(Subcommand, Command, Debug, Runnable)]
+pub enum InboxSubCmd {
+    /// Capture a new item in the inbox
+    #[clap(alias = "a", alias = "capture")]
+    Add(add::AddInboxItemSubCmd),
+
+    /// List the items in the inbox, ordered by priority
+    #[clap(alias = "l", alias = "ls")]
+    List(list::ListInboxSubCmd),
+
+    /// Promote an item to a task, or start an activity for it with `--begin`
+    #[clap(alias = "p")]
+    Promote(promote::PromoteInboxItemSubCmd),
+}
+
+/// `inbox` subcommand
+#[derive(Command, Debug, Parser, Runnable)]
+pub struct InboxCmd {
+    #[clap(subcommand)]
+    commands: InboxSubCmd,
+}
@@ -0,0 +1,27 @@
+//! `inbox add` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::AddInboxItemCommandOptions;
+
+/// `inbox add` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct AddInboxItemSubCmd {
+    #[clap(flatten)]
+    add_opts: AddInboxItemCommandOptions,
+}
+
+impl Runnable for AddInboxItemSubCmd {
+    fn run(&self) {
+        match self.add_opts.handle_add_inbox_item(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
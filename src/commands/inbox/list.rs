@@ -0,0 +1,27 @@
+//! `inbox list` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::ListInboxCommandOptions;
+
+/// `inbox list` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct ListInboxSubCmd {
+    #[clap(flatten)]
+    list_opts: ListInboxCommandOptions,
+}
+
+impl Runnable for ListInboxSubCmd {
+    fn run(&self) {
+        match self.list_opts.handle_list_inbox(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
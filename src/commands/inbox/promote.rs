@@ -0,0 +1,30 @@
+//! `inbox promote` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::PromoteInboxItemCommandOptions;
+
+/// `inbox promote` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct PromoteInboxItemSubCmd {
+    #[clap(flatten)]
+    promote_opts: PromoteInboxItemCommandOptions,
+}
+
+impl Runnable for PromoteInboxItemSubCmd {
+    fn run(&self) {
+        match self
+            .promote_opts
+            .handle_promote_inbox_item(&PACE_APP.config())
+        {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -0,0 +1,27 @@
+//! `invoice` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use pace_core::prelude::InvoiceCommandOptions;
+
+use crate::prelude::PACE_APP;
+
+/// `invoice` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct InvoiceCmd {
+    #[clap(flatten)]
+    invoice_opts: InvoiceCommandOptions,
+}
+
+impl Runnable for InvoiceCmd {
+    fn run(&self) {
+        match self.invoice_opts.handle_invoice(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -0,0 +1,27 @@
+//! `log` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::LogCommandOptions;
+
+/// `log` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct LogCmd {
+    #[clap(flatten)]
+    log_opts: LogCommandOptions,
+}
+
+impl Runnable for LogCmd {
+    fn run(&self) {
+        match self.log_opts.handle_log(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -1,35 +1,70 @@
 //! `pomo` subcommand
 
-use abscissa_core::{Command, Runnable};
+use std::{
+    io::{stdout, Write},
+    thread,
+    time::Duration,
+};
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
 use clap::Parser;
 
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::{ActivityItem, PaceResult, PomoCommandOptions, PomodoroPhase};
+use pace_time::date_time::PaceDateTime;
+
 /// `pomo` subcommand
-///
-/// The `Parser` proc macro generates an option parser based on the struct
-/// definition, and is defined in the `clap` crate. See their documentation
-/// for a more comprehensive example:
-///
-/// <https://docs.rs/clap/>
 #[derive(Command, Debug, Parser)]
 pub struct PomoCmd {
-    // /// Option foobar. Doc comments are the help description
-    // #[clap(short)]
-    // foobar: Option<PathBuf>
-
-    // /// Baz path
-    // #[clap(long)]
-    // baz: Option<PathBuf>
-
-    // "free" arguments don't need a macro
-    // free_args: Vec<String>,
-
-    // /// Allow human-readable durations
-    // #[arg(long)]
-    // break: Option<humantime::Duration>,
+    #[clap(flatten)]
+    pomo_opts: PomoCommandOptions,
 }
 
 impl Runnable for PomoCmd {
     fn run(&self) {
-        // Your code goes here
+        match self.pomo_opts.handle_pomo(&PACE_APP.config(), countdown) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
+
+/// Count down the remaining time of a pomodoro phase on a single line
+fn countdown(activity_item: &ActivityItem, phase: &PomodoroPhase) -> PaceResult<()> {
+    let activity = activity_item.activity();
+
+    let symbol = activity.kind().as_symbol();
+
+    let mut stdout = stdout();
+
+    loop {
+        let remaining = phase
+            .remaining(activity.begin(), &PaceDateTime::now())
+            .as_secs();
+
+        write!(
+            stdout,
+            "\r{symbol}  \"{}\": {phase}, {:02}:{:02} left",
+            activity.description(),
+            remaining / 60,
+            remaining % 60
+        )?;
+
+        stdout.flush()?;
+
+        if remaining == 0 {
+            break;
+        }
+
+        thread::sleep(Duration::from_secs(1));
     }
+
+    // Ring the terminal bell, so the end of a phase is noticed
+    writeln!(stdout, "\x07")?;
+
+    Ok(())
 }
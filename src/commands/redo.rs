@@ -0,0 +1,27 @@
+//! `redo` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::RedoCommandOptions;
+
+/// `redo` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct RedoCmd {
+    #[clap(flatten)]
+    redo_opts: RedoCommandOptions,
+}
+
+impl Runnable for RedoCmd {
+    fn run(&self) {
+        match self.redo_opts.handle_redo(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -7,8 +7,8 @@ use eyre::Result;
 
 use pace_cli::{confirmation_or_break, prompt_resume_activity};
 use pace_core::prelude::{
-    get_storage_from_config, ActivityQuerying, ActivityReadOps, ActivityStateManagement,
-    ActivityStore, ResumeCommandOptions, ResumeOptions, SyncStorage, UserMessage,
+    ActivityQuerying, ActivityReadOps, ActivityStateManagement, ActivityStore,
+    ResumeCommandOptions, ResumeOptions, SyncStorage, UserMessage,
 };
 use pace_time::{date_time::PaceDateTime, time_zone::PaceTimeZoneKind, Validate};
 
@@ -53,8 +53,7 @@ impl ResumeCmd {
 
         debug!("Parsed time: {date_time:?}");
 
-        let activity_store =
-            ActivityStore::with_storage(get_storage_from_config(&PACE_APP.config())?)?;
+        let activity_store = ActivityStore::from_config(&PACE_APP.config())?;
 
         let (msg, resumed) = if let Some(resumed_activity) = activity_store
             .resume_most_recent_activity(ResumeOptions::builder().resume_time(date_time).build())?
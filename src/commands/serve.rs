@@ -0,0 +1,49 @@
+//! `serve` subcommand
+
+use abscissa_core::{status_err, status_info, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+use eyre::Result;
+
+use pace_server::prelude::PaceServer;
+
+use crate::prelude::PACE_APP;
+
+/// `serve` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct ServeCmd {
+    /// The port to listen on, overrides the port from the configuration
+    #[clap(short, long, value_name = "Port")]
+    port: Option<u16>,
+}
+
+impl Runnable for ServeCmd {
+    fn run(&self) {
+        if let Err(err) = self.inner_run() {
+            status_err!("{}", err);
+            PACE_APP.shutdown(Shutdown::Crash);
+        };
+    }
+}
+
+impl ServeCmd {
+    /// Inner run implementation for the serve command
+    pub fn inner_run(&self) -> Result<()> {
+        let config = PACE_APP.config();
+
+        let port = self
+            .port
+            .unwrap_or_else(|| *config.server().clone().unwrap_or_default().port());
+
+        let server = PaceServer::from_config(config.as_ref().clone())?;
+
+        status_info!(
+            "Starting",
+            "server on http://127.0.0.1:{}, press Ctrl+C to stop it",
+            port
+        );
+
+        server.serve(port)?;
+
+        Ok(())
+    }
+}
@@ -5,6 +5,7 @@ use clap::{Parser, Subcommand};
 
 mod completions;
 mod config;
+mod migrate;
 mod project;
 mod show;
 
@@ -24,6 +25,9 @@ pub enum SetupSubCmd {
     /// Show the current pace configuration
     Show(show::ShowSubCmd),
 
+    /// Migrate the database to the latest schema version
+    Migrate(migrate::MigrateSubCmd),
+
     /// Generate shell completions for the specified shell
     #[clap(alias = "comp")]
     Completions(completions::CompletionsCmd),
@@ -0,0 +1,27 @@
+//! `migrate` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::MigrateCommandOptions;
+
+/// `migrate` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct MigrateSubCmd {
+    #[clap(flatten)]
+    migrate_opts: MigrateCommandOptions,
+}
+
+impl Runnable for MigrateSubCmd {
+    fn run(&self) {
+        match self.migrate_opts.handle_migrate(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -0,0 +1,29 @@
+//! `sync` subcommand
+
+use abscissa_core::{Command, Runnable};
+use clap::{Parser, Subcommand};
+
+mod merge;
+mod pull;
+mod push;
+
+/// `sync` subcommand
+#[derive(Subcommand, Command, Debug, Runnable)]
+pub enum SyncSubCmd {
+    /// Merge another activity log into yours, e.g. a conflicting copy from another device
+    #[clap(alias = "m")]
+    Merge(merge::MergeSyncSubCmd),
+
+    /// Pull the activity log from the remote repository and merge it into yours
+    Pull(pull::PullSyncSubCmd),
+
+    /// Push your activity log to the remote repository
+    Push(push::PushSyncSubCmd),
+}
+
+/// `sync` subcommand
+#[derive(Command, Debug, Parser, Runnable)]
+pub struct SyncCmd {
+    #[clap(subcommand)]
+    commands: SyncSubCmd,
+}
@@ -0,0 +1,27 @@
+//! `sync merge` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::MergeSyncCommandOptions;
+
+/// `sync merge` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct MergeSyncSubCmd {
+    #[clap(flatten)]
+    merge_opts: MergeSyncCommandOptions,
+}
+
+impl Runnable for MergeSyncSubCmd {
+    fn run(&self) {
+        match self.merge_opts.handle_merge(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -0,0 +1,27 @@
+//! `sync pull` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::PullSyncCommandOptions;
+
+/// `sync pull` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct PullSyncSubCmd {
+    #[clap(flatten)]
+    pull_opts: PullSyncCommandOptions,
+}
+
+impl Runnable for PullSyncSubCmd {
+    fn run(&self) {
+        match self.pull_opts.handle_pull(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
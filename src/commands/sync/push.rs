@@ -0,0 +1,27 @@
+//! `sync push` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::PushSyncCommandOptions;
+
+/// `sync push` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct PushSyncSubCmd {
+    #[clap(flatten)]
+    push_opts: PushSyncCommandOptions,
+}
+
+impl Runnable for PushSyncSubCmd {
+    fn run(&self) {
+        match self.push_opts.handle_push(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -0,0 +1,36 @@
+//! `tag` subcommand
+
+use abscissa_core::{Command, Runnable};
+use clap::{Parser, Subcommand};
+
+mod add;
+mod list;
+mod rename;
+mod rm;
+
+/// `tag` subcommand
+#[derive(Subcommand, Command, Debug, Runnable)]
+pub enum TagSubCmd {
+    /// Add tags to the currently active activity, or another one with `--activity`
+    #[clap(alias = "a")]
+    Add(add::AddTagSubCmd),
+
+    /// Remove tags from the currently active activity, or another one with `--activity`
+    #[clap(alias = "remove")]
+    Rm(rm::RemoveTagSubCmd),
+
+    /// Rename a tag on all activities
+    #[clap(alias = "mv")]
+    Rename(rename::RenameTagSubCmd),
+
+    /// List all tags and how many activities use them
+    #[clap(alias = "l", alias = "ls")]
+    List(list::ListTagsSubCmd),
+}
+
+/// `tag` subcommand
+#[derive(Command, Debug, Parser, Runnable)]
+pub struct TagCmd {
+    #[clap(subcommand)]
+    commands: TagSubCmd,
+}
@@ -0,0 +1,27 @@
+//! `tag add` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::AddTagCommandOptions;
+
+/// `tag add` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct AddTagSubCmd {
+    #[clap(flatten)]
+    add_opts: AddTagCommandOptions,
+}
+
+impl Runnable for AddTagSubCmd {
+    fn run(&self) {
+        match self.add_opts.handle_add_tag(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
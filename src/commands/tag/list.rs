@@ -0,0 +1,27 @@
+//! `tag list` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::ListTagsCommandOptions;
+
+/// `tag list` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct ListTagsSubCmd {
+    #[clap(flatten)]
+    list_opts: ListTagsCommandOptions,
+}
+
+impl Runnable for ListTagsSubCmd {
+    fn run(&self) {
+        match self.list_opts.handle_list_tags(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -0,0 +1,27 @@
+//! `tag rename` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::RenameTagCommandOptions;
+
+/// `tag rename` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct RenameTagSubCmd {
+    #[clap(flatten)]
+    rename_opts: RenameTagCommandOptions,
+}
+
+impl Runnable for RenameTagSubCmd {
+    fn run(&self) {
+        match self.rename_opts.handle_rename_tag(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
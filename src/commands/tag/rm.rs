@@ -0,0 +1,27 @@
+//! `tag rm` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::RemoveTagCommandOptions;
+
+/// `tag rm` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct RemoveTagSubCmd {
+    #[clap(flatten)]
+    remove_opts: RemoveTagCommandOptions,
+}
+
+impl Runnable for RemoveTagSubCmd {
+    fn run(&self) {
+        match self.remove_opts.handle_remove_tag(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -1,31 +1,41 @@
 //! `tasks` subcommand
 
 use abscissa_core::{Command, Runnable};
-use clap::Parser;
+use clap::{Parser, Subcommand};
+
+mod add;
+mod done;
+mod edit;
+mod list;
+mod rm;
 
 /// `tasks` subcommand
-///
-/// The `Parser` proc macro generates an option parser based on the struct
-/// definition, and is defined in the `clap` crate. See their documentation
-/// for a more comprehensive example:
-///
-/// <https://docs.rs/clap/>
-#[derive(Command, Debug, Parser)]
-pub struct TasksCmd {
-    // /// Option foobar. Doc comments are the help description
-    // #[clap(short)]
-    // foobar: Option<PathBuf>
+#[derive(Subcommand, Command, Debug, Runnable)]
+pub enum TasksSubCmd {
+    /// Add a new task
+    #[clap(alias = "a", alias = "new")]
+    Add(add::AddTaskSubCmd),
+
+    /// List the open tasks, filtered by status, priority or tags
+    #[clap(alias = "l", alias = "ls")]
+    List(list::ListTasksSubCmd),
 
-    // /// Baz path
-    // #[clap(long)]
-    // baz: Option<PathBuf>
+    /// Mark a task as completed
+    #[clap(alias = "d", alias = "complete")]
+    Done(done::DoneTaskSubCmd),
 
-    // "free" arguments don't need a macro
-    // free_args: Vec<String>,
+    /// Edit the title, description, priority, status or tags of a task
+    #[clap(alias = "e")]
+    Edit(edit::EditTaskSubCmd),
+
+    /// Remove a task
+    #[clap(alias = "remove")]
+    Rm(rm::RemoveTaskSubCmd),
 }
 
-impl Runnable for TasksCmd {
-    fn run(&self) {
-        // Your code goes here
-    }
+/// `tasks` subcommand
+#[derive(Command, Debug, Parser, Runnable)]
+pub struct TasksCmd {
+    #[clap(subcommand)]
+    commands: TasksSubCmd,
 }
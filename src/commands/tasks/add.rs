@@ -0,0 +1,27 @@
+//! `tasks add` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::AddTaskCommandOptions;
+
+/// `tasks add` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct AddTaskSubCmd {
+    #[clap(flatten)]
+    add_opts: AddTaskCommandOptions,
+}
+
+impl Runnable for AddTaskSubCmd {
+    fn run(&self) {
+        match self.add_opts.handle_add_task(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -0,0 +1,27 @@
+//! `tasks done` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::DoneTaskCommandOptions;
+
+/// `tasks done` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct DoneTaskSubCmd {
+    #[clap(flatten)]
+    done_opts: DoneTaskCommandOptions,
+}
+
+impl Runnable for DoneTaskSubCmd {
+    fn run(&self) {
+        match self.done_opts.handle_done_task(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -0,0 +1,27 @@
+//! `tasks edit` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::EditTaskCommandOptions;
+
+/// `tasks edit` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct EditTaskSubCmd {
+    #[clap(flatten)]
+    edit_opts: EditTaskCommandOptions,
+}
+
+impl Runnable for EditTaskSubCmd {
+    fn run(&self) {
+        match self.edit_opts.handle_edit_task(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -0,0 +1,27 @@
+//! `tasks list` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::ListTasksCommandOptions;
+
+/// `tasks list` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct ListTasksSubCmd {
+    #[clap(flatten)]
+    list_opts: ListTasksCommandOptions,
+}
+
+impl Runnable for ListTasksSubCmd {
+    fn run(&self) {
+        match self.list_opts.handle_list_tasks(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -0,0 +1,27 @@
+//! `tasks rm` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::RemoveTaskCommandOptions;
+
+/// `tasks rm` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct RemoveTaskSubCmd {
+    #[clap(flatten)]
+    remove_opts: RemoveTaskCommandOptions,
+}
+
+impl Runnable for RemoveTaskSubCmd {
+    fn run(&self) {
+        match self.remove_opts.handle_remove_task(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}
@@ -0,0 +1,27 @@
+//! `undo` subcommand
+
+use abscissa_core::{status_err, Application, Command, Runnable, Shutdown};
+use clap::Parser;
+
+use crate::prelude::PACE_APP;
+
+use pace_core::prelude::UndoCommandOptions;
+
+/// `undo` subcommand
+#[derive(Command, Debug, Parser)]
+pub struct UndoCmd {
+    #[clap(flatten)]
+    undo_opts: UndoCommandOptions,
+}
+
+impl Runnable for UndoCmd {
+    fn run(&self) {
+        match self.undo_opts.handle_undo(&PACE_APP.config()) {
+            Ok(user_message) => user_message.display(),
+            Err(err) => {
+                status_err!("{}", err);
+                PACE_APP.shutdown(Shutdown::Crash);
+            }
+        };
+    }
+}